@@ -137,6 +137,7 @@ async fn main() -> Result<()> {
                 bench_setup.bank,
                 &opts,
                 system_state_observer.clone(),
+                &registry_clone,
             )
             .await?;
             let interval = opts.run_duration;
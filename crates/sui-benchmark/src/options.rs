@@ -178,6 +178,9 @@ pub enum RunSpec {
         // relative weight of adversarial transactions in the benchmark workload
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
         shared_deletion: Vec<u32>,
+        // relative weight of hot object transactions in the benchmark workload
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        hot_object: Vec<u32>,
 
         // --- workload-specific options --- (TODO: use subcommands or similar)
         // 100 for max hotness i.e all requests target
@@ -207,6 +210,21 @@ pub enum RunSpec {
         // Default is (0-0.5) implying random load at 50% load. See `AdversarialPayloadType` enum for `adversarial_type`
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = ["0-1.0".to_string()])]
         adversarial_cfg: Vec<String>,
+        // distribution of the accesses of hot object transactions to shared and owned objects.
+        // Format is "uniform", "zipf:{exponent}" or "hotset:{hot_fraction}:{hot_probability}",
+        // e.g. "hotset:0.1:0.9" sends 90% of the accesses to 10% of the objects.
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = ["zipf:1.0".to_string()])]
+        hot_object_distribution: Vec<String>,
+        // number of shared objects the hot object workload creates
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [100])]
+        num_hot_objects: Vec<u64>,
+        // number of distinct shared objects each hot object transaction mutates
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [2])]
+        hot_objects_per_tx: Vec<u64>,
+        // number of owned objects each hot object payload creates, one of which each of its
+        // transactions mutates. 0 for hot object transactions to only take shared objects.
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        hot_object_owned_pool_size: Vec<u64>,
 
         // --- generic options ---
         // Target qps
@@ -0,0 +1,710 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Workload with skewed accesses to a pool of shared objects.
+//!
+//! Every transaction increments several distinct shared counters drawn from a configurable
+//! distribution (uniform, Zipfian or hot-set), so that transactions contend on the hottest
+//! counters the way production traffic contends on popular pools or markets. Each transaction can
+//! also mutate an owned object. Owned objects can't be accessed by concurrent transactions without
+//! equivocating, so every payload draws them from its own pool with the same distribution.
+//!
+//! Per-object metrics, labeled by the rank of the object in the distribution (0 being the hottest),
+//! measure how contended each shared object is: the number of transactions accessing it, how many
+//! of them were submitted while another transaction accessing it was still in flight, and their
+//! latency.
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::publish_basics_package;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ValidatorProxy};
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use futures::future::join_all;
+use move_core_types::identifier::Identifier;
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Registry,
+};
+use rand::Rng;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+use sui_types::crypto::get_key_pair;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{CallArg, ObjectArg, Transaction};
+use tracing::{error, info};
+
+const LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.1, 0.25, 0.5, 0.75, 1., 1.25, 1.5, 1.75, 2., 2.5, 5., 10., 20., 30., 60., 90.,
+];
+
+/// Distribution of the accesses to a pool of objects, ranked from the hottest to the coldest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccessDistribution {
+    /// All objects are equally likely to be accessed.
+    Uniform,
+    /// The object of rank `k` (starting from 1) is accessed with a probability proportional to
+    /// `1 / k^exponent`.
+    Zipf { exponent: f64 },
+    /// A fraction `hot_fraction` of the objects receives a fraction `hot_probability` of the
+    /// accesses, uniformly. The remaining accesses are spread uniformly over the other objects.
+    HotSet {
+        hot_fraction: f64,
+        hot_probability: f64,
+    },
+}
+
+impl FromStr for AccessDistribution {
+    type Err = anyhow::Error;
+
+    /// Parses `uniform`, `zipf:<exponent>` or `hotset:<hot_fraction>:<hot_probability>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.split(':').collect();
+        let parse = |value: &str| {
+            f64::from_str(value).map_err(|e| anyhow!("Invalid number {value} in {s}: {e}"))
+        };
+        match parts[..] {
+            ["uniform"] => Ok(Self::Uniform),
+            ["zipf", exponent] => {
+                let exponent = parse(exponent)?;
+                if exponent.is_nan() || exponent < 0.0 {
+                    bail!("Zipf exponent must be non-negative, got {exponent}");
+                }
+                Ok(Self::Zipf { exponent })
+            }
+            ["hotset", hot_fraction, hot_probability] => {
+                let hot_fraction = parse(hot_fraction)?;
+                let hot_probability = parse(hot_probability)?;
+                if !(0.0..=1.0).contains(&hot_fraction) || hot_fraction == 0.0 {
+                    bail!("Hot set fraction must be in (0, 1], got {hot_fraction}");
+                }
+                if !(0.0..=1.0).contains(&hot_probability) {
+                    bail!("Hot set probability must be in [0, 1], got {hot_probability}");
+                }
+                Ok(Self::HotSet {
+                    hot_fraction,
+                    hot_probability,
+                })
+            }
+            _ => Err(anyhow!(
+                "Invalid access distribution {s}. Valid values are `uniform`, `zipf:<exponent>` \
+                and `hotset:<hot_fraction>:<hot_probability>`"
+            )),
+        }
+    }
+}
+
+/// Samples object ranks according to an [AccessDistribution].
+#[derive(Debug)]
+pub struct AccessSampler {
+    /// Cumulative access weight of the objects, by rank.
+    cumulative_weights: Vec<f64>,
+}
+
+impl AccessSampler {
+    pub fn new(distribution: AccessDistribution, num_objects: usize) -> Self {
+        assert!(num_objects > 0, "Cannot sample from an empty pool");
+        let num_hot = match distribution {
+            AccessDistribution::HotSet { hot_fraction, .. } => {
+                ((num_objects as f64 * hot_fraction).ceil() as usize).clamp(1, num_objects)
+            }
+            _ => num_objects,
+        };
+        let weight = |rank: usize| match distribution {
+            AccessDistribution::Uniform => 1.0,
+            AccessDistribution::Zipf { exponent } => 1.0 / ((rank + 1) as f64).powf(exponent),
+            AccessDistribution::HotSet {
+                hot_probability, ..
+            } => {
+                if rank < num_hot {
+                    hot_probability / num_hot as f64
+                } else {
+                    (1.0 - hot_probability) / (num_objects - num_hot) as f64
+                }
+            }
+        };
+        let cumulative_weights = (0..num_objects)
+            .scan(0.0, |total, rank| {
+                *total += weight(rank);
+                Some(*total)
+            })
+            .collect();
+        Self { cumulative_weights }
+    }
+
+    pub fn num_objects(&self) -> usize {
+        self.cumulative_weights.len()
+    }
+
+    /// Returns the rank of a randomly accessed object.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let total = *self.cumulative_weights.last().unwrap();
+        let target = rng.gen::<f64>() * total;
+        self.cumulative_weights
+            .partition_point(|weight| *weight <= target)
+            .min(self.num_objects() - 1)
+    }
+
+    /// Returns the ranks of `count` distinct randomly accessed objects.
+    pub fn sample_distinct<R: Rng + ?Sized>(&self, rng: &mut R, count: usize) -> Vec<usize> {
+        assert!(count <= self.num_objects());
+        let mut ranks = Vec::with_capacity(count);
+        for _ in 0..count * 16 {
+            if ranks.len() == count {
+                return ranks;
+            }
+            let rank = self.sample(rng);
+            if !ranks.contains(&rank) {
+                ranks.push(rank);
+            }
+        }
+        // With very skewed distributions, the hottest objects are sampled over and over, so fill
+        // up with the hottest objects not sampled yet.
+        for rank in 0..self.num_objects() {
+            if ranks.len() == count {
+                break;
+            }
+            if !ranks.contains(&rank) {
+                ranks.push(rank);
+            }
+        }
+        ranks
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HotObjectWorkloadCfg {
+    pub distribution: AccessDistribution,
+    /// Number of shared objects to create.
+    pub num_shared_objects: u64,
+    /// Number of distinct shared objects each transaction mutates.
+    pub shared_objects_per_tx: u64,
+    /// Number of owned objects of each payload, one of which each transaction mutates. 0 for
+    /// transactions to only take shared objects.
+    pub owned_objects_per_payload: u64,
+}
+
+pub struct HotObjectMetrics {
+    accesses: IntCounterVec,
+    contended_accesses: IntCounterVec,
+    in_flight: IntGaugeVec,
+    latency_s: HistogramVec,
+}
+
+impl HotObjectMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            accesses: register_int_counter_vec_with_registry!(
+                "hot_object_accesses",
+                "Number of transactions submitted with the shared object of the given rank as input",
+                &["rank"],
+                registry,
+            )
+            .unwrap(),
+            contended_accesses: register_int_counter_vec_with_registry!(
+                "hot_object_contended_accesses",
+                "Number of transactions submitted with the shared object of the given rank as \
+                input while another transaction with it as input was in flight",
+                &["rank"],
+                registry,
+            )
+            .unwrap(),
+            in_flight: register_int_gauge_vec_with_registry!(
+                "hot_object_in_flight",
+                "Number of transactions in flight with the shared object of the given rank as input",
+                &["rank"],
+                registry,
+            )
+            .unwrap(),
+            latency_s: register_histogram_vec_with_registry!(
+                "hot_object_latency_s",
+                "Latency of transactions with the shared object of the given rank as input",
+                &["rank"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    fn for_objects(&self, num_objects: u64) -> Vec<ObjectMetrics> {
+        (0..num_objects)
+            .map(|rank| {
+                let rank = rank.to_string();
+                ObjectMetrics {
+                    accesses: self.accesses.with_label_values(&[&rank]),
+                    contended_accesses: self.contended_accesses.with_label_values(&[&rank]),
+                    in_flight: self.in_flight.with_label_values(&[&rank]),
+                    latency_s: self.latency_s.with_label_values(&[&rank]),
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for HotObjectMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HotObjectMetrics").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct ObjectMetrics {
+    accesses: IntCounter,
+    contended_accesses: IntCounter,
+    in_flight: IntGauge,
+    latency_s: Histogram,
+}
+
+#[derive(Debug)]
+pub struct HotObjectTestPayload {
+    package_id: ObjectID,
+    /// Shared objects by rank, as (id, initial shared version).
+    shared_objects: Arc<Vec<(ObjectID, SequenceNumber)>>,
+    shared_object_sampler: Arc<AccessSampler>,
+    shared_objects_per_tx: usize,
+    shared_object_metrics: Arc<Vec<ObjectMetrics>>,
+    /// Owned objects of the payload, by rank.
+    owned_objects: Vec<ObjectRef>,
+    owned_object_sampler: Option<Arc<AccessSampler>>,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+    /// Ranks of the shared objects and of the owned object accessed by the transaction in flight,
+    /// and when it was created.
+    in_flight: Option<(Vec<usize>, Option<usize>, Instant)>,
+}
+
+impl HotObjectTestPayload {
+    /// Marks the transaction in flight as done, recording its latency if it executed.
+    fn finish_in_flight(&mut self, executed: bool) -> Option<usize> {
+        let (shared_ranks, owned_rank, start) = self.in_flight.take()?;
+        let latency = start.elapsed().as_secs_f64();
+        for rank in shared_ranks {
+            let metrics = &self.shared_object_metrics[rank];
+            metrics.in_flight.dec();
+            if executed {
+                metrics.latency_s.observe(latency);
+            }
+        }
+        owned_rank
+    }
+}
+
+impl std::fmt::Display for HotObjectTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "hot_object")
+    }
+}
+
+impl Payload for HotObjectTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("Hot object tx failed... Status: {:?}", effects.status());
+        }
+        if let Some(owned_rank) = self.finish_in_flight(true) {
+            let owned_object = &mut self.owned_objects[owned_rank];
+            if let Some((new_ref, _)) = effects
+                .mutated()
+                .into_iter()
+                .find(|(object_ref, _)| object_ref.0 == owned_object.0)
+            {
+                *owned_object = new_ref;
+            }
+        }
+        self.gas.0 = effects.gas_object().0;
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        self.finish_in_flight(false);
+        let rgp = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let mut rng = rand::thread_rng();
+
+        let shared_ranks = self
+            .shared_object_sampler
+            .sample_distinct(&mut rng, self.shared_objects_per_tx);
+        let mut builder = ProgrammableTransactionBuilder::new();
+        for rank in &shared_ranks {
+            let metrics = &self.shared_object_metrics[*rank];
+            metrics.accesses.inc();
+            if metrics.in_flight.get() > 0 {
+                metrics.contended_accesses.inc();
+            }
+            metrics.in_flight.inc();
+
+            let (id, initial_shared_version) = self.shared_objects[*rank];
+            builder
+                .move_call(
+                    self.package_id,
+                    Identifier::new("counter").unwrap(),
+                    Identifier::new("increment").unwrap(),
+                    vec![],
+                    vec![CallArg::Object(ObjectArg::SharedObject {
+                        id,
+                        initial_shared_version,
+                        mutable: true,
+                    })],
+                )
+                .unwrap();
+        }
+
+        let owned_rank = self.owned_object_sampler.as_ref().map(|sampler| {
+            let rank = sampler.sample(&mut rng);
+            builder
+                .move_call(
+                    self.package_id,
+                    Identifier::new("object_basics").unwrap(),
+                    Identifier::new("set_value").unwrap(),
+                    vec![],
+                    vec![
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(self.owned_objects[rank])),
+                        CallArg::Pure(bcs::to_bytes(&rng.gen::<u64>()).unwrap()),
+                    ],
+                )
+                .unwrap();
+            rank
+        });
+
+        self.in_flight = Some((shared_ranks, owned_rank, Instant::now()));
+        TestTransactionBuilder::new(self.gas.1, self.gas.0, rgp)
+            .programmable(builder.finish())
+            .build_and_sign(self.gas.2.as_ref())
+    }
+}
+
+impl Drop for HotObjectTestPayload {
+    fn drop(&mut self) {
+        // Payloads whose transaction permanently failed are dropped.
+        self.finish_in_flight(false);
+    }
+}
+
+#[derive(Debug)]
+pub struct HotObjectWorkloadBuilder {
+    num_payloads: u64,
+    cfg: HotObjectWorkloadCfg,
+    metrics: Arc<HotObjectMetrics>,
+}
+
+impl HotObjectWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        cfg: HotObjectWorkloadCfg,
+        metrics: Arc<HotObjectMetrics>,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 || cfg.num_shared_objects == 0 {
+            return None;
+        }
+        assert!(
+            cfg.shared_objects_per_tx <= cfg.num_shared_objects,
+            "Transactions cannot access {} distinct objects out of {}",
+            cfg.shared_objects_per_tx,
+            cfg.num_shared_objects
+        );
+        let workload_params = WorkloadParams {
+            group,
+            target_qps,
+            num_workers,
+            max_ops,
+            duration,
+        };
+        let workload_builder =
+            Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(HotObjectWorkloadBuilder {
+                num_payloads: max_ops,
+                cfg,
+                metrics,
+            }));
+        Some(WorkloadBuilderInfo {
+            workload_params,
+            workload_builder,
+        })
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for HotObjectWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the package, and one for creating each shared object.
+        (0..self.cfg.num_shared_objects + 1)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        (0..self.num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(HotObjectWorkload {
+            basics_package_id: None,
+            shared_objects: Arc::new(vec![]),
+            owned_objects: vec![],
+            init_gas,
+            payload_gas,
+            cfg: self.cfg,
+            shared_object_metrics: Arc::new(self.metrics.for_objects(self.cfg.num_shared_objects)),
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct HotObjectWorkload {
+    pub basics_package_id: Option<ObjectID>,
+    /// Shared objects, as (id, initial shared version), ranked in creation order.
+    pub shared_objects: Arc<Vec<(ObjectID, SequenceNumber)>>,
+    /// Owned objects of each payload, in the order of `payload_gas`.
+    pub owned_objects: Vec<Vec<ObjectRef>>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+    pub cfg: HotObjectWorkloadCfg,
+    shared_object_metrics: Arc<Vec<ObjectMetrics>>,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for HotObjectWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.basics_package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize hot object workload");
+
+        info!("Publishing basics package");
+        let package_id = publish_basics_package(head.0, proxy.clone(), head.1, &head.2, gas_price)
+            .await
+            .0;
+        self.basics_package_id = Some(package_id);
+
+        info!("Creating {} shared objects", tail.len());
+        let futures = tail.iter().map(|(gas, sender, keypair)| {
+            let transaction = TestTransactionBuilder::new(*sender, *gas, gas_price)
+                .call_counter_create(package_id)
+                .build_and_sign(keypair.as_ref());
+            let proxy = proxy.clone();
+            async move {
+                let effects = proxy
+                    .execute_transaction_block(transaction)
+                    .await
+                    .expect("Failed to create shared object");
+                let (id, initial_shared_version, _) = effects.created()[0].0;
+                (id, initial_shared_version)
+            }
+        });
+        self.shared_objects = Arc::new(join_all(futures).await);
+
+        if self.cfg.owned_objects_per_payload == 0 {
+            return;
+        }
+        info!(
+            "Creating {} owned objects for each of {} payloads",
+            self.cfg.owned_objects_per_payload,
+            self.payload_gas.len()
+        );
+        let futures = self.payload_gas.iter().map(|(gas, sender, keypair)| {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            for _ in 0..self.cfg.owned_objects_per_payload {
+                builder
+                    .move_call(
+                        package_id,
+                        Identifier::new("object_basics").unwrap(),
+                        Identifier::new("create").unwrap(),
+                        vec![],
+                        vec![
+                            CallArg::Pure(bcs::to_bytes(&0u64).unwrap()),
+                            CallArg::Pure(bcs::to_bytes(sender).unwrap()),
+                        ],
+                    )
+                    .unwrap();
+            }
+            let transaction = TestTransactionBuilder::new(*sender, *gas, gas_price)
+                .programmable(builder.finish())
+                .build_and_sign(keypair.as_ref());
+            let proxy = proxy.clone();
+            async move {
+                let effects = proxy
+                    .execute_transaction_block(transaction)
+                    .await
+                    .expect("Failed to create owned objects");
+                let owned_objects: Vec<_> = effects
+                    .created()
+                    .into_iter()
+                    .map(|(object_ref, _)| object_ref)
+                    .collect();
+                (owned_objects, effects.gas_object().0)
+            }
+        });
+        let results = join_all(futures).await;
+        for ((owned_objects, new_gas), gas) in results.into_iter().zip(self.payload_gas.iter_mut())
+        {
+            gas.0 = new_gas;
+            self.owned_objects.push(owned_objects);
+        }
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating hot object txn payloads, hang tight..");
+        let shared_object_sampler = Arc::new(AccessSampler::new(
+            self.cfg.distribution,
+            self.shared_objects.len(),
+        ));
+        let owned_object_sampler = (self.cfg.owned_objects_per_payload > 0).then(|| {
+            Arc::new(AccessSampler::new(
+                self.cfg.distribution,
+                self.cfg.owned_objects_per_payload as usize,
+            ))
+        });
+        self.payload_gas
+            .iter()
+            .enumerate()
+            .map(|(i, gas)| {
+                Box::new(HotObjectTestPayload {
+                    package_id: self.basics_package_id.unwrap(),
+                    shared_objects: self.shared_objects.clone(),
+                    shared_object_sampler: shared_object_sampler.clone(),
+                    shared_objects_per_tx: self.cfg.shared_objects_per_tx as usize,
+                    shared_object_metrics: self.shared_object_metrics.clone(),
+                    owned_objects: self.owned_objects.get(i).cloned().unwrap_or_default(),
+                    owned_object_sampler: owned_object_sampler.clone(),
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                    in_flight: None,
+                }) as Box<dyn Payload>
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const NUM_SAMPLES: usize = 100_000;
+
+    /// Fraction of `NUM_SAMPLES` samples that hit each rank.
+    fn frequencies(sampler: &AccessSampler) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = vec![0; sampler.num_objects()];
+        for _ in 0..NUM_SAMPLES {
+            counts[sampler.sample(&mut rng)] += 1;
+        }
+        counts
+            .into_iter()
+            .map(|count| count as f64 / NUM_SAMPLES as f64)
+            .collect()
+    }
+
+    fn distribution(s: &str) -> AccessDistribution {
+        AccessDistribution::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_access_distributions() {
+        let uniform = frequencies(&AccessSampler::new(distribution("uniform"), 4));
+        assert!(
+            uniform.iter().all(|f| (f - 0.25).abs() < 0.01),
+            "{uniform:?}"
+        );
+
+        // Rank r is accessed with a probability proportional to 1 / (r + 1).
+        let zipf = frequencies(&AccessSampler::new(distribution("zipf:1.0"), 10));
+        let harmonic: f64 = (1..=10).map(|r| 1.0 / r as f64).sum();
+        for (rank, f) in zipf.iter().enumerate() {
+            let expected = 1.0 / ((rank + 1) as f64 * harmonic);
+            assert!((f - expected).abs() < 0.01, "{zipf:?}");
+        }
+
+        // The 10 hottest objects get 90% of the accesses, evenly.
+        let hotset = frequencies(&AccessSampler::new(distribution("hotset:0.1:0.9"), 100));
+        let hot: f64 = hotset[..10].iter().sum();
+        assert!((hot - 0.9).abs() < 0.01, "{hotset:?}");
+        assert!(hotset[..10].iter().all(|f| (f - 0.09).abs() < 0.01));
+        assert!(hotset[10..].iter().all(|f| (f - 0.1 / 90.0).abs() < 0.002));
+    }
+
+    #[test]
+    fn test_zero_weight_objects_are_never_sampled() {
+        let only_hot = frequencies(&AccessSampler::new(distribution("hotset:0.5:1.0"), 4));
+        assert_eq!(only_hot[2..], [0.0, 0.0]);
+
+        let only_cold = frequencies(&AccessSampler::new(distribution("hotset:0.5:0.0"), 4));
+        assert_eq!(only_cold[..2], [0.0, 0.0]);
+        assert!(only_cold[2..].iter().all(|f| (f - 0.5).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_single_object() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for s in ["uniform", "zipf:2.0", "hotset:0.5:0.9", "hotset:1.0:0.0"] {
+            let sampler = AccessSampler::new(distribution(s), 1);
+            for _ in 0..100 {
+                assert_eq!(sampler.sample(&mut rng), 0);
+            }
+            assert_eq!(sampler.sample_distinct(&mut rng, 1), vec![0]);
+        }
+    }
+
+    #[test]
+    fn test_sample_distinct() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // Even when the hottest object gets almost all accesses.
+        let sampler = AccessSampler::new(distribution("zipf:10.0"), 5);
+        for count in 0..=5 {
+            let mut ranks = sampler.sample_distinct(&mut rng, count);
+            ranks.sort();
+            ranks.dedup();
+            assert_eq!(ranks.len(), count);
+        }
+    }
+}
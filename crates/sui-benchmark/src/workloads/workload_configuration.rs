@@ -7,10 +7,14 @@ use crate::options::{Opts, RunSpec};
 use crate::system_state_observer::SystemStateObserver;
 use crate::workloads::batch_payment::BatchPaymentWorkloadBuilder;
 use crate::workloads::delegation::DelegationWorkloadBuilder;
+use crate::workloads::hot_object::{
+    AccessDistribution, HotObjectMetrics, HotObjectWorkloadBuilder, HotObjectWorkloadCfg,
+};
 use crate::workloads::shared_counter::SharedCounterWorkloadBuilder;
 use crate::workloads::transfer_object::TransferObjectWorkloadBuilder;
 use crate::workloads::{GroupID, WorkloadBuilderInfo, WorkloadInfo};
 use anyhow::Result;
+use prometheus::Registry;
 use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -26,6 +30,7 @@ impl WorkloadConfiguration {
         bank: BenchmarkBank,
         opts: &Opts,
         system_state_observer: Arc<SystemStateObserver>,
+        registry: &Registry,
     ) -> Result<BTreeMap<GroupID, Vec<WorkloadInfo>>> {
         let mut workload_builders = vec![];
 
@@ -39,11 +44,16 @@ impl WorkloadConfiguration {
                 delegation,
                 batch_payment,
                 adversarial,
+                hot_object,
                 shared_counter_hotness_factor,
                 num_shared_counters,
                 shared_counter_max_tip,
                 batch_payment_size,
                 adversarial_cfg,
+                hot_object_distribution,
+                num_hot_objects,
+                hot_objects_per_tx,
+                hot_object_owned_pool_size,
                 target_qps,
                 num_workers,
                 in_flight_ratio,
@@ -53,6 +63,7 @@ impl WorkloadConfiguration {
                     "Number of benchmark groups to run: {}",
                     num_of_benchmark_groups
                 );
+                let hot_object_metrics = Arc::new(HotObjectMetrics::new(registry));
 
                 // Creating the workload builders for each benchmark group. The workloads for each
                 // benchmark group will run in the same time for the same duration.
@@ -69,6 +80,15 @@ impl WorkloadConfiguration {
                         shared_deletion[i],
                         adversarial[i],
                         AdversarialPayloadCfg::from_str(&adversarial_cfg[i]).unwrap(),
+                        hot_object[i],
+                        HotObjectWorkloadCfg {
+                            distribution: AccessDistribution::from_str(&hot_object_distribution[i])
+                                .unwrap(),
+                            num_shared_objects: num_hot_objects[i],
+                            shared_objects_per_tx: hot_objects_per_tx[i],
+                            owned_objects_per_payload: hot_object_owned_pool_size[i],
+                        },
+                        hot_object_metrics.clone(),
                         batch_payment_size[i],
                         shared_counter_hotness_factor[i],
                         num_shared_counters.as_ref().map(|n| n[i]),
@@ -146,6 +166,9 @@ impl WorkloadConfiguration {
         shared_deletion_weight: u32,
         adversarial_weight: u32,
         adversarial_cfg: AdversarialPayloadCfg,
+        hot_object_weight: u32,
+        hot_object_cfg: HotObjectWorkloadCfg,
+        hot_object_metrics: Arc<HotObjectMetrics>,
         batch_payment_size: u32,
         shared_counter_hotness_factor: u32,
         num_shared_counters: Option<u64>,
@@ -159,7 +182,8 @@ impl WorkloadConfiguration {
             + transfer_object_weight
             + delegation_weight
             + batch_payment_weight
-            + adversarial_weight;
+            + adversarial_weight
+            + hot_object_weight;
         let reference_gas_price = system_state_observer.state.borrow().reference_gas_price;
         let mut workload_builders = vec![];
         let shared_workload = SharedCounterWorkloadBuilder::from(
@@ -226,6 +250,17 @@ impl WorkloadConfiguration {
             workload_group,
         );
         workload_builders.push(adversarial_workload);
+        let hot_object_workload = HotObjectWorkloadBuilder::from(
+            hot_object_weight as f32 / total_weight as f32,
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            hot_object_cfg,
+            hot_object_metrics,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(hot_object_workload);
 
         workload_builders
     }
@@ -13,6 +13,9 @@ mod test {
     use sui_benchmark::bank::BenchmarkBank;
     use sui_benchmark::system_state_observer::SystemStateObserver;
     use sui_benchmark::workloads::adversarial::AdversarialPayloadCfg;
+    use sui_benchmark::workloads::hot_object::{
+        AccessDistribution, HotObjectMetrics, HotObjectWorkloadCfg,
+    };
     use sui_benchmark::workloads::workload_configuration::WorkloadConfiguration;
     use sui_benchmark::{
         drivers::{bench_driver::BenchDriver, driver::Driver, Interval},
@@ -83,6 +86,21 @@ mod test {
         test_simulated_load(TestInitData::new(&test_cluster).await, 15).await;
     }
 
+    #[sim_test(config = "test_config()")]
+    async fn test_simulated_load_hot_objects() {
+        sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
+        let test_cluster = build_test_cluster(4, 0).await;
+        test_simulated_load_with_weights(
+            TestInitData::new(&test_cluster).await,
+            30,
+            WorkloadWeights {
+                hot_object: 1,
+                ..WorkloadWeights::none()
+            },
+        )
+        .await;
+    }
+
     #[sim_test(config = "test_config()")]
     async fn test_simulated_load_restarts() {
         sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
@@ -512,7 +530,52 @@ mod test {
         }
     }
 
+    /// Relative weights of the workloads run by [test_simulated_load_with_weights].
+    struct WorkloadWeights {
+        shared_counter: u32,
+        transfer_object: u32,
+        delegation: u32,
+        batch_payment: u32,
+        shared_object_deletion: u32,
+        hot_object: u32,
+    }
+
+    impl WorkloadWeights {
+        fn none() -> Self {
+            Self {
+                shared_counter: 0,
+                transfer_object: 0,
+                delegation: 0,
+                batch_payment: 0,
+                shared_object_deletion: 0,
+                hot_object: 0,
+            }
+        }
+    }
+
+    impl Default for WorkloadWeights {
+        fn default() -> Self {
+            Self {
+                shared_counter: 1,
+                transfer_object: 1,
+                delegation: 1,
+                batch_payment: 1,
+                shared_object_deletion: 1,
+                ..Self::none()
+            }
+        }
+    }
+
     async fn test_simulated_load(init_data: TestInitData, test_duration_secs: u64) {
+        test_simulated_load_with_weights(init_data, test_duration_secs, WorkloadWeights::default())
+            .await
+    }
+
+    async fn test_simulated_load_with_weights(
+        init_data: TestInitData,
+        test_duration_secs: u64,
+        weights: WorkloadWeights,
+    ) {
         let TestInitData {
             keystore_path,
             genesis,
@@ -543,12 +606,15 @@ mod test {
         let num_workers = get_var("SIM_STRESS_TEST_WORKERS", 10);
         let in_flight_ratio = get_var("SIM_STRESS_TEST_IFR", 2);
         let batch_payment_size = get_var("SIM_BATCH_PAYMENT_SIZE", 15);
-        let shared_counter_weight = 1;
-        let transfer_object_weight = 1;
+        let WorkloadWeights {
+            shared_counter: shared_counter_weight,
+            transfer_object: transfer_object_weight,
+            delegation: delegation_weight,
+            batch_payment: batch_payment_weight,
+            shared_object_deletion: shared_object_deletion_weight,
+            hot_object: hot_object_weight,
+        } = weights;
         let num_transfer_accounts = 2;
-        let delegation_weight = 1;
-        let batch_payment_weight = 1;
-        let shared_object_deletion_weight = 1;
 
         // Run random payloads at 100% load
         let adversarial_cfg = AdversarialPayloadCfg::from_str("0-1.0").unwrap();
@@ -558,6 +624,14 @@ mod test {
         // tests run for ever
         let adversarial_weight = 0;
 
+        let hot_object_cfg = HotObjectWorkloadCfg {
+            distribution: AccessDistribution::from_str("zipf:1.0").unwrap(),
+            num_shared_objects: 10,
+            shared_objects_per_tx: 2,
+            owned_objects_per_payload: 4,
+        };
+        let hot_object_metrics = Arc::new(HotObjectMetrics::new(&registry));
+
         let shared_counter_hotness_factor = 50;
         let num_shared_counters = Some(1);
         let shared_counter_max_tip = 0;
@@ -574,6 +648,9 @@ mod test {
             shared_object_deletion_weight,
             adversarial_weight,
             adversarial_cfg,
+            hot_object_weight,
+            hot_object_cfg,
+            hot_object_metrics,
             batch_payment_size,
             shared_counter_hotness_factor,
             num_shared_counters,
@@ -40,6 +40,24 @@ impl CertificateDenyConfig {
                 .collect::<HashSet<_>>()
         })
     }
+
+    /// Adds `digest` to the certificate deny list. Returns whether it was not denied already.
+    pub fn add_certificate_deny(&mut self, digest: TransactionDigest) -> bool {
+        if self.certificate_deny_set().contains(&digest) {
+            return false;
+        }
+        self.certificate_deny_list.push(digest);
+        self.certificate_deny_set = OnceCell::new();
+        true
+    }
+
+    /// Removes `digest` from the certificate deny list. Returns whether it was denied.
+    pub fn remove_certificate_deny(&mut self, digest: &TransactionDigest) -> bool {
+        let len = self.certificate_deny_list.len();
+        self.certificate_deny_list.retain(|denied| denied != digest);
+        self.certificate_deny_set = OnceCell::new();
+        self.certificate_deny_list.len() != len
+    }
 }
 
 #[derive(Default)]
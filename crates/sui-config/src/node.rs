@@ -13,7 +13,7 @@ use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::{BTreeMap, BTreeSet};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -162,6 +162,46 @@ pub struct NodeConfig {
 
     #[serde(default = "default_overload_threshold_config")]
     pub overload_threshold_config: OverloadThresholdConfig,
+
+    /// Per-client rate limiting of the validator gRPC server. Disabled when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traffic_control_config: Option<TrafficControlConfig>,
+
+    /// File where the transaction and certificate deny configs updated through the admin API are
+    /// persisted. When the file exists at startup, its content replaces `transaction_deny_config`
+    /// and `certificate_deny_config`. Defaults to `deny-config-override.yaml` in `db_path`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny_config_override_path: Option<PathBuf>,
+
+    /// Limits on JSON-RPC batch requests. The JSON-RPC server defaults apply to the limits that
+    /// are not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_rpc_batch_config: Option<JsonRpcBatchConfig>,
+}
+
+/// Transaction and certificate deny configs updated at runtime through the admin API.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DenyConfigOverride {
+    #[serde(default)]
+    pub transaction_deny_config: TransactionDenyConfig,
+
+    #[serde(default)]
+    pub certificate_deny_config: CertificateDenyConfig,
+}
+
+impl Config for DenyConfigOverride {}
+
+/// Limits on JSON-RPC batch requests, i.e. arrays of calls sent in a single request.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcBatchConfig {
+    /// Maximum number of calls in a batch request. Batch requests are rejected if 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_batch_size: Option<usize>,
+    /// Maximum number of calls of a batch request executed concurrently.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_calls: Option<usize>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -289,6 +329,12 @@ impl NodeConfig {
         self.db_path.join("db_checkpoints")
     }
 
+    pub fn deny_config_override_path(&self) -> PathBuf {
+        self.deny_config_override_path
+            .clone()
+            .unwrap_or_else(|| self.db_path.join("deny-config-override.yaml"))
+    }
+
     pub fn archive_path(&self) -> PathBuf {
         self.db_path.join("archive")
     }
@@ -680,16 +726,60 @@ pub struct TransactionKeyValueStoreWriteConfig {
 /// stop processing new transactions and/or certificates until the congestion
 /// resolves.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct OverloadThresholdConfig {
+    /// Reject a transaction if one of its input objects has had a transaction pending in the
+    /// transaction manager for longer than this.
     pub max_txn_age_in_queue: Duration,
-    // TODO: Move other thresholds here as well, including `MAX_TM_QUEUE_LENGTH`
-    // and `MAX_PER_OBJECT_QUEUE_LENGTH`.
+
+    /// Reject a transaction if the transaction manager queue length is above this threshold.
+    pub max_transaction_manager_queue_length: usize,
+
+    /// Reject a transaction if the number of pending transactions depending on one of its input
+    /// objects is above this threshold.
+    pub max_transaction_manager_per_object_queue_length: usize,
+
+    /// When enabled, the node rejects a fraction of new transactions that rises with the measured
+    /// execution queueing latency and CPU utilization, instead of only rejecting them once one of
+    /// the hard thresholds above is crossed.
+    pub adaptive_load_shedding: bool,
+
+    /// How often the overload monitor samples execution queueing latency and CPU utilization.
+    pub overload_monitor_interval: Duration,
+
+    /// Load shedding starts once the execution queueing latency is above this limit.
+    pub execution_queue_latency_soft_limit: Duration,
+
+    /// At and above this execution queueing latency, `max_load_shedding_percentage` of new
+    /// transactions are rejected.
+    pub execution_queue_latency_hard_limit: Duration,
+
+    /// Load shedding starts once CPU utilization, in percent, is above this limit.
+    pub cpu_utilization_soft_limit: u32,
+
+    /// At and above this CPU utilization, in percent, `max_load_shedding_percentage` of new
+    /// transactions are rejected.
+    pub cpu_utilization_hard_limit: u32,
+
+    /// Upper bound on the percentage of new transactions rejected by load shedding, so that some
+    /// transactions are always let through to measure recovery.
+    pub max_load_shedding_percentage: u32,
 }
 
 impl Default for OverloadThresholdConfig {
     fn default() -> Self {
         Self {
             max_txn_age_in_queue: Duration::from_secs(1), // 1 second
+            // 100_000 = 10k TPS * 5s resident time in transaction manager (pending + executing) * 2.
+            max_transaction_manager_queue_length: 100_000,
+            max_transaction_manager_per_object_queue_length: 200,
+            adaptive_load_shedding: false,
+            overload_monitor_interval: Duration::from_secs(1),
+            execution_queue_latency_soft_limit: Duration::from_secs(1),
+            execution_queue_latency_hard_limit: Duration::from_secs(10),
+            cpu_utilization_soft_limit: 90,
+            cpu_utilization_hard_limit: 99,
+            max_load_shedding_percentage: 95,
         }
     }
 }
@@ -698,6 +788,62 @@ fn default_overload_threshold_config() -> OverloadThresholdConfig {
     OverloadThresholdConfig::default()
 }
 
+/// Policy applied by the validator gRPC server to the traffic of each source, where a source is
+/// either a client IP address or a transaction sender address. Each source is allowed a sustained
+/// request rate with some burst, and sources that keep sending failed or invalid transactions are
+/// blocked for a while. Blocking the same source again before its past offenses have decayed
+/// doubles the block duration, up to `max_deny_duration`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct TrafficControlConfig {
+    /// Sustained number of transactions per second accepted from one client IP address.
+    /// Certificates are not limited per client IP, as fullnodes relay them for many users.
+    pub client_ip_requests_per_sec: u32,
+    /// Number of transactions one client IP address can send at once above the sustained rate.
+    pub client_ip_request_burst: u32,
+    /// Sustained number of transactions and certificates per second accepted from one sender.
+    pub sender_requests_per_sec: u32,
+    /// Number of transactions one sender can send at once above the sustained rate.
+    pub sender_request_burst: u32,
+    /// Sustained number of failed or invalid transactions per minute tolerated from one source.
+    pub errors_per_min: u32,
+    /// Number of failed or invalid transactions one source can send at once above the sustained
+    /// rate before being blocked.
+    pub error_burst: u32,
+    /// How long a source is blocked on its first offense.
+    pub deny_duration: Duration,
+    /// Upper bound on how long a repeat offender is blocked.
+    pub max_deny_duration: Duration,
+    /// One past offense of a source is forgotten for each period without a new one.
+    pub deny_decay_period: Duration,
+    /// Maximum number of sources whose request and error rates are tracked at once. The least
+    /// recently seen sources are forgotten first.
+    pub max_tracked_sources: usize,
+    /// Addresses of the fullnodes trusted to report the IP address of the client that submitted
+    /// a transaction, as the last entry of the `x-forwarded-for` header. Transactions are only
+    /// limited per client IP when relayed by one of them: most transactions reach a validator
+    /// through a few fullnodes, and limiting the connecting peer would throttle all their users.
+    pub trusted_forwarders: Vec<IpAddr>,
+}
+
+impl Default for TrafficControlConfig {
+    fn default() -> Self {
+        Self {
+            client_ip_requests_per_sec: 100,
+            client_ip_request_burst: 200,
+            sender_requests_per_sec: 20,
+            sender_request_burst: 40,
+            errors_per_min: 60,
+            error_burst: 20,
+            deny_duration: Duration::from_secs(30),
+            max_deny_duration: Duration::from_secs(60 * 60),
+            deny_decay_period: Duration::from_secs(10 * 60),
+            max_tracked_sources: 100_000,
+            trusted_forwarders: vec![],
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq)]
 pub struct Genesis {
     #[serde(flatten)]
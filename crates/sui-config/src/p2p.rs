@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
+use std::{net::SocketAddr, num::NonZeroU32, path::PathBuf, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use sui_types::{
@@ -29,6 +29,12 @@ pub struct P2pConfig {
     pub state_sync: Option<StateSyncConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub discovery: Option<DiscoveryConfig>,
+    /// Peer scoring shared by state sync and discovery.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_scoring: Option<PeerScoringConfig>,
+    /// Serving of state snapshots to peers bootstrapping from them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_snapshot: Option<StateSnapshotConfig>,
     /// Size in bytes above which network messages are considered excessively large. Excessively
     /// large messages will still be handled, but logged and reported in metrics for debugging.
     ///
@@ -50,6 +56,8 @@ impl Default for P2pConfig {
             anemo_config: Default::default(),
             state_sync: None,
             discovery: None,
+            peer_scoring: None,
+            state_snapshot: None,
             excessive_message_size: None,
         }
     }
@@ -239,6 +247,142 @@ impl StateSyncConfig {
     }
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PeerScoringConfig {
+    /// How long a peer that served invalid data is excluded from state sync, and disconnected
+    /// from by discovery. The duration doubles with each repeated offense.
+    ///
+    /// If unspecified, this will default to `300,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ban_duration_ms: Option<u64>,
+
+    /// Upper bound on the ban duration of repeat offenders.
+    ///
+    /// If unspecified, this will default to `3,600,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_ban_duration_ms: Option<u64>,
+
+    /// Weight of the most recent sample in the moving averages of peer latency, throughput and
+    /// success rate, between 0 and 1.
+    ///
+    /// If unspecified, this will default to `0.2`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moving_average_weight: Option<f64>,
+
+    /// Statistics of peers we haven't exchanged any request with for this long are dropped.
+    ///
+    /// If unspecified, this will default to `86,400,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_retention_ms: Option<u64>,
+}
+
+impl PeerScoringConfig {
+    pub fn ban_duration(&self) -> Duration {
+        const BAN_DURATION_MS: u64 = 300_000; // 5 minutes
+
+        Duration::from_millis(self.ban_duration_ms.unwrap_or(BAN_DURATION_MS))
+    }
+
+    pub fn max_ban_duration(&self) -> Duration {
+        const MAX_BAN_DURATION_MS: u64 = 3_600_000; // 1 hour
+
+        Duration::from_millis(self.max_ban_duration_ms.unwrap_or(MAX_BAN_DURATION_MS))
+    }
+
+    pub fn moving_average_weight(&self) -> f64 {
+        const MOVING_AVERAGE_WEIGHT: f64 = 0.2;
+
+        self.moving_average_weight
+            .unwrap_or(MOVING_AVERAGE_WEIGHT)
+            .clamp(0.0, 1.0)
+    }
+
+    pub fn stats_retention(&self) -> Duration {
+        const STATS_RETENTION_MS: u64 = 86_400_000; // 1 day
+
+        Duration::from_millis(self.stats_retention_ms.unwrap_or(STATS_RETENTION_MS))
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StateSnapshotConfig {
+    /// Local directory of state snapshots, laid out as written by the state snapshot writer, that
+    /// are served to peers.
+    ///
+    /// If unspecified, snapshots are served from the object store the node writes state snapshots
+    /// to, if it is a local directory. Otherwise no snapshots are served.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_dir: Option<PathBuf>,
+
+    /// Maximum number of bytes of a snapshot file sent in a single response. Snapshot files are
+    /// downloaded in chunks of this size.
+    ///
+    /// If unspecified, this will default to `16,777,216` bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_chunk_size_bytes: Option<u64>,
+
+    /// Per-peer rate-limit (in requests/sec) for the GetStateSnapshotFileChunk RPC.
+    ///
+    /// If unspecified, this will default to `10` requests/sec.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_file_chunk_rate_limit: Option<NonZeroU32>,
+
+    /// Per-peer inflight limit for the GetStateSnapshotFileChunk RPC.
+    ///
+    /// If unspecified, this will default to `4`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_file_chunk_inflight_limit: Option<usize>,
+
+    /// Maximum size of a snapshot file downloaded from a peer. Peers announcing larger files are
+    /// treated as failing.
+    ///
+    /// If unspecified, this will default to `4,294,967,296` bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_file_size_bytes: Option<u64>,
+
+    /// Timeout for downloading a single chunk of a snapshot file from a peer.
+    ///
+    /// If unspecified, this will default to `60,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_timeout_ms: Option<u64>,
+}
+
+impl StateSnapshotConfig {
+    pub fn max_chunk_size_bytes(&self) -> u64 {
+        const MAX_CHUNK_SIZE_BYTES: u64 = 16 << 20;
+
+        self.max_chunk_size_bytes.unwrap_or(MAX_CHUNK_SIZE_BYTES)
+    }
+
+    pub fn chunk_timeout(&self) -> Duration {
+        const CHUNK_TIMEOUT_MS: u64 = 60_000;
+
+        Duration::from_millis(self.chunk_timeout_ms.unwrap_or(CHUNK_TIMEOUT_MS))
+    }
+
+    pub fn get_file_chunk_rate_limit(&self) -> NonZeroU32 {
+        const GET_FILE_CHUNK_RATE_LIMIT: u32 = 10;
+
+        self.get_file_chunk_rate_limit
+            .unwrap_or(NonZeroU32::new(GET_FILE_CHUNK_RATE_LIMIT).unwrap())
+    }
+
+    pub fn get_file_chunk_inflight_limit(&self) -> usize {
+        const GET_FILE_CHUNK_INFLIGHT_LIMIT: usize = 4;
+
+        self.get_file_chunk_inflight_limit
+            .unwrap_or(GET_FILE_CHUNK_INFLIGHT_LIMIT)
+    }
+
+    pub fn max_file_size_bytes(&self) -> u64 {
+        const MAX_FILE_SIZE_BYTES: u64 = 4 << 30;
+
+        self.max_file_size_bytes.unwrap_or(MAX_FILE_SIZE_BYTES)
+    }
+}
+
 /// Access Type of a node.
 /// AccessType info is shared in the discovery process.
 /// * If the node marks itself as Public, other nodes may try to connect to it.
@@ -118,6 +118,72 @@ impl TransactionDenyConfig {
     pub fn zklogin_disabled_providers(&self) -> &HashSet<String> {
         &self.zklogin_disabled_providers
     }
+
+    /// Adds `id` to the object deny list. Returns whether it was not denied already.
+    pub fn add_denied_object(&mut self, id: ObjectID) -> bool {
+        if self.get_object_deny_set().contains(&id) {
+            return false;
+        }
+        self.object_deny_list.push(id);
+        self.object_deny_set = OnceCell::new();
+        true
+    }
+
+    /// Removes `id` from the object deny list. Returns whether it was denied.
+    pub fn remove_denied_object(&mut self, id: &ObjectID) -> bool {
+        let len = self.object_deny_list.len();
+        self.object_deny_list.retain(|denied| denied != id);
+        self.object_deny_set = OnceCell::new();
+        self.object_deny_list.len() != len
+    }
+
+    /// Adds `id` to the package deny list. Returns whether it was not denied already.
+    pub fn add_denied_package(&mut self, id: ObjectID) -> bool {
+        if self.get_package_deny_set().contains(&id) {
+            return false;
+        }
+        self.package_deny_list.push(id);
+        self.package_deny_set = OnceCell::new();
+        true
+    }
+
+    /// Removes `id` from the package deny list. Returns whether it was denied.
+    pub fn remove_denied_package(&mut self, id: &ObjectID) -> bool {
+        let len = self.package_deny_list.len();
+        self.package_deny_list.retain(|denied| denied != id);
+        self.package_deny_set = OnceCell::new();
+        self.package_deny_list.len() != len
+    }
+
+    /// Adds `address` to the address deny list. Returns whether it was not denied already.
+    pub fn add_denied_address(&mut self, address: SuiAddress) -> bool {
+        if self.get_address_deny_set().contains(&address) {
+            return false;
+        }
+        self.address_deny_list.push(address);
+        self.address_deny_set = OnceCell::new();
+        true
+    }
+
+    /// Removes `address` from the address deny list. Returns whether it was denied.
+    pub fn remove_denied_address(&mut self, address: &SuiAddress) -> bool {
+        let len = self.address_deny_list.len();
+        self.address_deny_list.retain(|denied| denied != address);
+        self.address_deny_set = OnceCell::new();
+        self.address_deny_list.len() != len
+    }
+
+    pub fn set_package_publish_disabled(&mut self, disabled: bool) {
+        self.package_publish_disabled = disabled;
+    }
+
+    pub fn set_package_upgrade_disabled(&mut self, disabled: bool) {
+        self.package_upgrade_disabled = disabled;
+    }
+
+    pub fn set_shared_object_disabled(&mut self, disabled: bool) {
+        self.shared_object_disabled = disabled;
+    }
 }
 
 #[derive(Default)]
@@ -31,7 +31,7 @@ use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use std::{
     collections::{HashMap, HashSet},
     fs,
@@ -135,6 +135,7 @@ use crate::consensus_adapter::ConsensusAdapter;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
+use crate::overload_monitor::{overload_monitor, AuthorityOverloadInfo};
 use crate::stake_aggregator::StakeAggregator;
 use crate::state_accumulator::{StateAccumulator, WrappedObject};
 use crate::subscription_handler::SubscriptionHandler;
@@ -226,6 +227,9 @@ pub struct AuthorityMetrics {
 
     pub(crate) execution_driver_executed_transactions: IntCounter,
     pub(crate) execution_driver_dispatch_queue: IntGauge,
+    pub(crate) execution_queueing_latency_s: Histogram,
+
+    pub(crate) load_shedding_percentage: IntGauge,
 
     pub(crate) skipped_consensus_txns: IntCounter,
     pub(crate) skipped_consensus_txns_cache_hit: IntCounter,
@@ -495,6 +499,19 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            execution_queueing_latency_s: register_histogram_with_registry!(
+                "execution_queueing_latency_s",
+                "Time a ready transaction waits before its execution starts",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            load_shedding_percentage: register_int_gauge_with_registry!(
+                "authority_load_shedding_percentage",
+                "Percentage of new transactions rejected by adaptive load shedding",
+                registry,
+            )
+            .unwrap(),
             skipped_consensus_txns: register_int_counter_with_registry!(
                 "skipped_consensus_txns",
                 "Total number of consensus transactions skipped",
@@ -646,15 +663,19 @@ pub struct AuthorityState {
     /// Config controlling what kind of expensive safety checks to perform.
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
 
-    transaction_deny_config: TransactionDenyConfig,
+    /// Deny configs can be replaced at runtime, and take effect for the next transaction.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
 
-    certificate_deny_config: CertificateDenyConfig,
+    certificate_deny_config: ArcSwap<CertificateDenyConfig>,
 
     /// Config for state dumping on forks
     debug_dump_config: StateDebugDumpConfig,
 
     /// Config for when we consider the node overloaded.
     overload_threshold_config: OverloadThresholdConfig,
+
+    /// Current overload state, maintained by the overload monitor.
+    overload_info: AuthorityOverloadInfo,
 }
 
 /// The authority state encapsulates all state, drives execution, and ensures safety.
@@ -680,8 +701,28 @@ impl AuthorityState {
         self.committee_store.clone()
     }
 
-    pub fn max_txn_age_in_queue(&self) -> Duration {
-        self.overload_threshold_config.max_txn_age_in_queue
+    pub fn overload_threshold_config(&self) -> &OverloadThresholdConfig {
+        &self.overload_threshold_config
+    }
+
+    pub fn overload_info(&self) -> &AuthorityOverloadInfo {
+        &self.overload_info
+    }
+
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.transaction_deny_config.load_full()
+    }
+
+    pub fn set_transaction_deny_config(&self, config: TransactionDenyConfig) {
+        self.transaction_deny_config.store(Arc::new(config));
+    }
+
+    pub fn certificate_deny_config(&self) -> Arc<CertificateDenyConfig> {
+        self.certificate_deny_config.load_full()
+    }
+
+    pub fn set_certificate_deny_config(&self, config: CertificateDenyConfig) {
+        self.certificate_deny_config.store(Arc::new(config));
     }
 
     pub fn get_epoch_state_commitments(
@@ -728,7 +769,7 @@ impl AuthorityState {
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             &self.database,
         )?;
 
@@ -839,11 +880,21 @@ impl AuthorityState {
         tx_data: &SenderSignedData,
     ) -> SuiResult {
         self.transaction_manager
-            .check_execution_overload(self.max_txn_age_in_queue(), tx_data)?;
+            .check_execution_overload(self.overload_threshold_config(), tx_data)?;
         consensus_adapter.check_consensus_overload()?;
         Ok(())
     }
 
+    /// Rejects a fraction of new transactions while the node is overloaded, when adaptive load
+    /// shedding is enabled.
+    pub(crate) fn check_load_shedding(&self) -> SuiResult {
+        if !self.overload_threshold_config.adaptive_load_shedding {
+            return Ok(());
+        }
+        self.overload_info
+            .check_load_shedding(&self.overload_threshold_config)
+    }
+
     /// Executes a transaction that's known to have correct effects.
     /// For such transaction, we don't have to wait for consensus to set shared object
     /// locks because we already know the shared object versions based on the effects.
@@ -1382,7 +1433,7 @@ impl AuthorityState {
                 // cyclic dependency w/ sui-adapter
                 self.expensive_safety_check_config
                     .enable_deep_per_tx_sui_conservation_check(),
-                self.certificate_deny_config.certificate_deny_set(),
+                self.certificate_deny_config.load().certificate_deny_set(),
                 &epoch_store.epoch_start_config().epoch_data().epoch_id(),
                 epoch_store
                     .epoch_start_config()
@@ -1439,7 +1490,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.transaction_deny_config,
+            &self.transaction_deny_config.load(),
             &self.database,
         )?;
 
@@ -1509,7 +1560,7 @@ impl AuthorityState {
                 protocol_config,
                 self.metrics.limits_metrics.clone(),
                 expensive_checks,
-                self.certificate_deny_config.certificate_deny_set(),
+                self.certificate_deny_config.load().certificate_deny_set(),
                 &epoch_store.epoch_start_config().epoch_data().epoch_id(),
                 epoch_store
                     .epoch_start_config()
@@ -1662,7 +1713,7 @@ impl AuthorityState {
             protocol_config,
             self.metrics.limits_metrics.clone(),
             expensive_checks,
-            self.certificate_deny_config.certificate_deny_set(),
+            self.certificate_deny_config.load().certificate_deny_set(),
             &epoch_store.epoch_start_config().epoch_data().epoch_id(),
             epoch_store
                 .epoch_start_config()
@@ -2274,10 +2325,11 @@ impl AuthorityState {
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             expensive_safety_check_config,
-            transaction_deny_config,
-            certificate_deny_config,
+            transaction_deny_config: ArcSwap::from_pointee(transaction_deny_config),
+            certificate_deny_config: ArcSwap::from_pointee(certificate_deny_config),
             debug_dump_config,
-            overload_threshold_config,
+            overload_threshold_config: overload_threshold_config.clone(),
+            overload_info: AuthorityOverloadInfo::default(),
         });
 
         // Start a task to execute ready certificates.
@@ -2288,6 +2340,11 @@ impl AuthorityState {
             rx_execution_shutdown
         ));
 
+        if overload_threshold_config.adaptive_load_shedding {
+            let authority_state = Arc::downgrade(&state);
+            spawn_monitored_task!(overload_monitor(authority_state, overload_threshold_config));
+        }
+
         // TODO: This doesn't belong to the constructor of AuthorityState.
         state
             .create_owner_index_if_empty(genesis_objects, &epoch_store)
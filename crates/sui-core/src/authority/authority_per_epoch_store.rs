@@ -51,7 +51,8 @@ use crate::checkpoints::{
 };
 use crate::consensus_handler::{
     SequencedConsensusTransaction, SequencedConsensusTransactionKey,
-    SequencedConsensusTransactionKind, VerifiedSequencedConsensusTransaction,
+    SequencedConsensusTransactionKind, SharedObjectCongestionTracker,
+    VerifiedSequencedConsensusTransaction,
 };
 use crate::epoch::epoch_metrics::EpochMetrics;
 use crate::epoch::reconfiguration::ReconfigState;
@@ -456,7 +457,7 @@ impl DeferralKey {
         }
     }
 
-    fn new_for_consensus_round(future_round: Round, deferred_from_round: Round) -> Self {
+    pub(crate) fn new_for_consensus_round(future_round: Round, deferred_from_round: Round) -> Self {
         Self::ConsensusRound {
             future_round,
             deferred_from_round,
@@ -476,18 +477,33 @@ impl DeferralKey {
         )
     }
 
-    fn range_for_consensus_round(future_round: Round) -> (Self, Self) {
+    // Consensus commit rounds are not contiguous, so every transaction deferred to a round up to
+    // and including `consensus_round` is ready once that round is committed.
+    fn range_for_up_to_consensus_round(consensus_round: Round) -> (Self, Self) {
         (
             Self::ConsensusRound {
-                future_round,
+                future_round: 0,
                 deferred_from_round: 0,
             },
             Self::ConsensusRound {
-                future_round: future_round.checked_add(1).unwrap(),
+                future_round: consensus_round.checked_add(1).unwrap(),
                 deferred_from_round: 0,
             },
         )
     }
+
+    pub(crate) fn deferred_from_round(&self) -> Round {
+        match self {
+            Self::RandomnessRound {
+                deferred_from_round,
+                ..
+            }
+            | Self::ConsensusRound {
+                deferred_from_round,
+                ..
+            } => *deferred_from_round,
+        }
+    }
 }
 
 #[tokio::test]
@@ -1334,17 +1350,17 @@ impl AuthorityPerEpochStore {
         &self,
         batch: &mut DBBatch,
         randomness_round: RandomnessRound,
-    ) -> SuiResult<Vec<VerifiedSequencedConsensusTransaction>> {
+    ) -> SuiResult<Vec<(DeferralKey, VerifiedSequencedConsensusTransaction)>> {
         let (min, max) = DeferralKey::range_for_randomness_round(randomness_round);
         self.load_deferred_transactions(batch, min, max)
     }
 
-    fn load_deferred_transactions_for_consensus_round(
+    fn load_deferred_transactions_for_up_to_consensus_round(
         &self,
         batch: &mut DBBatch,
         consensus_round: u64,
-    ) -> SuiResult<Vec<VerifiedSequencedConsensusTransaction>> {
-        let (min, max) = DeferralKey::range_for_consensus_round(consensus_round);
+    ) -> SuiResult<Vec<(DeferralKey, VerifiedSequencedConsensusTransaction)>> {
+        let (min, max) = DeferralKey::range_for_up_to_consensus_round(consensus_round);
         self.load_deferred_transactions(batch, min, max)
     }
 
@@ -1354,7 +1370,7 @@ impl AuthorityPerEpochStore {
         batch: &mut DBBatch,
         min: DeferralKey,
         max: DeferralKey,
-    ) -> SuiResult<Vec<VerifiedSequencedConsensusTransaction>> {
+    ) -> SuiResult<Vec<(DeferralKey, VerifiedSequencedConsensusTransaction)>> {
         let mut keys = Vec::new();
         let txns: Vec<_> = self
             .tables()?
@@ -1362,7 +1378,7 @@ impl AuthorityPerEpochStore {
             .iter_with_bounds(Some(min), Some(max))
             .flat_map(|(key, txns)| {
                 keys.push(key);
-                txns
+                txns.into_iter().map(move |txn| (key, txn))
             })
             .collect();
 
@@ -1371,7 +1387,7 @@ impl AuthorityPerEpochStore {
         #[cfg(debug_assertions)]
         {
             let mut seen = HashSet::new();
-            for txn in &txns {
+            for (_, txn) in &txns {
                 assert!(seen.insert(txn.0.key()));
             }
         }
@@ -1388,7 +1404,7 @@ impl AuthorityPerEpochStore {
         &self,
         cert: &VerifiedExecutableTransaction,
         commit_round: Round,
-        previously_deferred_tx_digests: &HashSet<TransactionDigest>,
+        previously_deferred_tx_digests: &HashMap<TransactionDigest, DeferralKey>,
         last_randomness_round: RandomnessRound,
     ) -> Option<DeferralKey> {
         // Defer transaction if it depends on Random object.
@@ -1397,7 +1413,7 @@ impl AuthorityPerEpochStore {
             .any(|obj| obj.id() == SUI_RANDOMNESS_STATE_OBJECT_ID)
         {
             // Don't re-defer randomness-using tx.
-            if previously_deferred_tx_digests.contains(cert.digest()) {
+            if previously_deferred_tx_digests.contains_key(cert.digest()) {
                 return None;
             }
             return Some(DeferralKey::new_for_randomness_round(
@@ -1409,9 +1425,6 @@ impl AuthorityPerEpochStore {
             ));
         }
 
-        // placeholder construction to silence lints
-        let _ = DeferralKey::new_for_consensus_round(0, 0);
-
         None
     }
 
@@ -2171,24 +2184,24 @@ impl AuthorityPerEpochStore {
         // Load transactions deferred from prevous commits.
         // We do this after updating the last_randomness_round_written above so that every deferred
         // transaction that can be run with this commit is loaded.
-        let deferred_tx: Vec<VerifiedSequencedConsensusTransaction> = self
-            .load_deferred_transactions_for_consensus_round(&mut batch, commit_round)?
+        let deferred_tx: Vec<(DeferralKey, VerifiedSequencedConsensusTransaction)> = self
+            .load_deferred_transactions_for_up_to_consensus_round(&mut batch, commit_round)?
             .into_iter()
             .chain(self.load_deferred_transactions_for_randomness_round(
                 &mut batch,
                 last_randomness_round_written,
             )?)
             .collect();
-        let previously_deferred_tx_digests: HashSet<_> = deferred_tx
+        let previously_deferred_tx_digests: HashMap<_, _> = deferred_tx
             .iter()
-            .map(|tx| match tx.0.transaction.key() {
+            .map(|(deferral_key, tx)| match tx.0.transaction.key() {
                 SequencedConsensusTransactionKey::External(
                     ConsensusTransactionKey::Certificate(digest),
-                ) => digest,
+                ) => (digest, *deferral_key),
                 _ => panic!("deferred transaction was not a user certificate: {tx:?}"),
             })
             .collect();
-        sequenced_transactions.extend(deferred_tx.into_iter());
+        sequenced_transactions.extend(deferred_tx.into_iter().map(|(_, tx)| tx));
 
         PostConsensusTxReorder::reorder(
             &mut sequenced_transactions,
@@ -2317,7 +2330,7 @@ impl AuthorityPerEpochStore {
         checkpoint_service: &Arc<C>,
         object_store: impl ObjectStore,
         commit_round: Round,
-        previously_deferred_tx_digests: HashSet<TransactionDigest>,
+        previously_deferred_tx_digests: HashMap<TransactionDigest, DeferralKey>,
         last_randomness_round: RandomnessRound,
     ) -> SuiResult<(
         Vec<VerifiedExecutableTransaction>,
@@ -2356,6 +2369,12 @@ impl AuthorityPerEpochStore {
 
         let mut deferred_txns: BTreeMap<DeferralKey, Vec<VerifiedSequencedConsensusTransaction>> =
             BTreeMap::new();
+        let mut shared_object_congestion_tracker = SharedObjectCongestionTracker::new(
+            &self.protocol_config,
+            !self
+                .get_reconfig_state_read_lock_guard()
+                .should_accept_user_certs(),
+        );
 
         for tx in transactions {
             let key = tx.0.transaction.key();
@@ -2369,6 +2388,7 @@ impl AuthorityPerEpochStore {
                     commit_round,
                     &previously_deferred_tx_digests,
                     last_randomness_round,
+                    &mut shared_object_congestion_tracker,
                 )
                 .await?
             {
@@ -2511,8 +2531,9 @@ impl AuthorityPerEpochStore {
         transaction: &VerifiedSequencedConsensusTransaction,
         checkpoint_service: &Arc<C>,
         commit_round: Round,
-        previously_deferred_tx_digests: &HashSet<TransactionDigest>,
+        previously_deferred_tx_digests: &HashMap<TransactionDigest, DeferralKey>,
         last_randomness_round: RandomnessRound,
+        shared_object_congestion_tracker: &mut SharedObjectCongestionTracker,
     ) -> SuiResult<ConsensusCertificateResult> {
         let _scope = monitored_scope("HandleConsensusTransaction");
         let VerifiedSequencedConsensusTransaction(SequencedConsensusTransaction {
@@ -2559,7 +2580,7 @@ impl AuthorityPerEpochStore {
                 if !self
                     .get_reconfig_state_read_lock_guard()
                     .should_accept_consensus_certs()
-                    && !previously_deferred_tx_digests.contains(certificate.digest())
+                    && !previously_deferred_tx_digests.contains_key(certificate.digest())
                 {
                     debug!("Ignoring consensus certificate for transaction {:?} because of end of epoch",
                     certificate.digest());
@@ -2579,6 +2600,22 @@ impl AuthorityPerEpochStore {
                     return Ok(ConsensusCertificateResult::Defered(deferral_key));
                 }
 
+                if let Some(deferral_key) = shared_object_congestion_tracker
+                    .should_defer_due_to_congestion(
+                        &certificate,
+                        commit_round,
+                        previously_deferred_tx_digests
+                            .get(certificate.digest())
+                            .map(|key| key.deferred_from_round()),
+                    )
+                {
+                    debug!(
+                        "Deferring consensus certificate for transaction {:?} until {deferral_key:?} due to shared object congestion",
+                        certificate.digest(),
+                    );
+                    return Ok(ConsensusCertificateResult::Defered(deferral_key));
+                }
+
                 if certificate.contains_shared_object() {
                     self.record_shared_object_cert_from_consensus(
                         batch,
@@ -15,6 +15,7 @@ use sui_network::{
     api::{Validator, ValidatorServer},
     tonic,
 };
+use sui_types::base_types::SuiAddress;
 use sui_types::effects::TransactionEvents;
 use sui_types::messages_consensus::ConsensusTransaction;
 use sui_types::messages_grpc::{
@@ -36,6 +37,7 @@ use tokio::task::JoinHandle;
 use tracing::{error_span, info, Instrument};
 
 use crate::consensus_adapter::{ConnectionMonitorStatusForTests, LazyNarwhalClient};
+use crate::traffic_controller::{TrafficController, TrafficSource};
 use crate::{
     authority::AuthorityState,
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
@@ -125,6 +127,7 @@ impl AuthorityServer {
                 state: self.state,
                 consensus_adapter: self.consensus_adapter,
                 metrics: self.metrics.clone(),
+                traffic_controller: None,
             }))
             .bind(&address)
             .await
@@ -240,6 +243,7 @@ pub struct ValidatorService {
     state: Arc<AuthorityState>,
     consensus_adapter: Arc<ConsensusAdapter>,
     metrics: Arc<ValidatorServiceMetrics>,
+    traffic_controller: Option<Arc<TrafficController>>,
 }
 
 impl ValidatorService {
@@ -247,11 +251,13 @@ impl ValidatorService {
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
         metrics: Arc<ValidatorServiceMetrics>,
+        traffic_controller: Option<Arc<TrafficController>>,
     ) -> Self {
         Self {
             state,
             consensus_adapter,
             metrics,
+            traffic_controller,
         }
     }
 
@@ -263,30 +269,125 @@ impl ValidatorService {
         &self,
         cert: CertifiedTransaction,
     ) -> HandleCertificateResponseV2 {
-        self.handle_certificate_v2(tonic::Request::new(cert))
+        self.try_execute_certificate_for_testing(cert)
             .await
             .unwrap()
-            .into_inner()
+    }
+
+    pub async fn try_execute_certificate_for_testing(
+        &self,
+        cert: CertifiedTransaction,
+    ) -> SuiResult<HandleCertificateResponseV2> {
+        Ok(self
+            .handle_certificate_v2(tonic::Request::new(cert))
+            .await?
+            .into_inner())
     }
 
     pub async fn handle_transaction_for_testing(
         &self,
         transaction: Transaction,
     ) -> HandleTransactionResponse {
-        self.transaction(tonic::Request::new(transaction))
+        self.try_handle_transaction_for_testing(transaction)
             .await
             .unwrap()
-            .into_inner()
+    }
+
+    pub async fn try_handle_transaction_for_testing(
+        &self,
+        transaction: Transaction,
+    ) -> SuiResult<HandleTransactionResponse> {
+        Ok(self
+            .transaction(tonic::Request::new(transaction))
+            .await?
+            .into_inner())
+    }
+
+    /// The IP address of the client that submitted a request, as reported by a trusted
+    /// forwarder. The connecting peer itself is never used: it is usually a fullnode relaying
+    /// transactions for many users.
+    fn client_ip<T>(
+        traffic_controller: &Option<Arc<TrafficController>>,
+        request: &tonic::Request<T>,
+    ) -> Option<TrafficSource> {
+        let peer = request.remote_addr()?.ip();
+        if !traffic_controller.as_ref()?.is_trusted_forwarder(&peer) {
+            return None;
+        }
+        // The trusted forwarder appends the address of the client it received the request from.
+        let forwarded_for = request.metadata().get("x-forwarded-for")?.to_str().ok()?;
+        let ip = forwarded_for.rsplit(',').next()?.trim().parse().ok()?;
+        Some(TrafficSource::ClientIp(ip))
+    }
+
+    /// Rejects a request whose known `sources` are blocked by traffic control, before doing any
+    /// work for it.
+    fn check_denied(
+        traffic_controller: &Option<Arc<TrafficController>>,
+        sources: &[TrafficSource],
+    ) -> SuiResult {
+        match traffic_controller {
+            Some(traffic_controller) => traffic_controller.check_denied(sources),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the `sender` of a request to its `sources` once its signatures are verified, and
+    /// checks that traffic control accepts a request from all of them.
+    fn admit_sender(
+        traffic_controller: &Option<Arc<TrafficController>>,
+        sources: &mut Vec<TrafficSource>,
+        sender: SuiAddress,
+    ) -> SuiResult {
+        sources.push(TrafficSource::Sender(sender));
+        match traffic_controller {
+            Some(traffic_controller) => traffic_controller.check(sources),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports a failed or invalid request back to traffic control.
+    fn report_traffic_error<T>(
+        traffic_controller: &Option<Arc<TrafficController>>,
+        sources: &[TrafficSource],
+        result: &Result<T, tonic::Status>,
+    ) {
+        let (Some(traffic_controller), Err(status)) = (traffic_controller, result) else {
+            return;
+        };
+        // Retryable errors are caused by the state of this validator, not by the client.
+        let (retryable, _) = SuiError::from(status.clone()).is_retryable();
+        if !retryable && !sources.is_empty() {
+            traffic_controller.report_error(sources);
+        }
     }
 
     async fn handle_transaction(
         self,
         request: tonic::Request<Transaction>,
+    ) -> Result<tonic::Response<HandleTransactionResponse>, tonic::Status> {
+        let traffic_controller = self.traffic_controller.clone();
+        // The sender is only added once the signature of the transaction is verified, so that
+        // clients can neither use up nor be charged for the budget of someone else's address.
+        let mut sources: Vec<_> = Self::client_ip(&traffic_controller, &request)
+            .into_iter()
+            .collect();
+        Self::check_denied(&traffic_controller, &sources)?;
+        let result = self.handle_transaction_impl(request, &mut sources).await;
+        Self::report_traffic_error(&traffic_controller, &sources, &result);
+        result
+    }
+
+    async fn handle_transaction_impl(
+        self,
+        request: tonic::Request<Transaction>,
+        traffic_sources: &mut Vec<TrafficSource>,
     ) -> Result<tonic::Response<HandleTransactionResponse>, tonic::Status> {
         let Self {
             state,
             consensus_adapter,
             metrics,
+            traffic_controller,
         } = self;
 
         let transaction = request.into_inner();
@@ -311,8 +412,9 @@ impl ValidatorService {
             .into());
         }
 
-        let overload_check_res =
-            state.check_system_overload(&consensus_adapter, transaction.data());
+        let overload_check_res = state
+            .check_load_shedding()
+            .and_then(|_| state.check_system_overload(&consensus_adapter, transaction.data()));
         if let Err(error) = overload_check_res {
             metrics
                 .num_rejected_tx_during_overload
@@ -329,6 +431,12 @@ impl ValidatorService {
         })?;
         drop(tx_verif_metrics_guard);
 
+        Self::admit_sender(
+            &traffic_controller,
+            traffic_sources,
+            transaction.data().transaction_data().sender(),
+        )?;
+
         let tx_digest = transaction.digest();
 
         // Enable Trace Propagation across spans/processes using tx_digest
@@ -347,16 +455,34 @@ impl ValidatorService {
         Ok(tonic::Response::new(info))
     }
 
-    // TODO: reject certificate if TransactionManager or Narwhal is backlogged.
     async fn handle_certificate(
         self,
         request: tonic::Request<CertifiedTransaction>,
         wait_for_effects: bool,
+    ) -> Result<Option<HandleCertificateResponseV2>, tonic::Status> {
+        let traffic_controller = self.traffic_controller.clone();
+        // Certificates are not limited per client IP: fullnodes relay them on behalf of many
+        // users, and their transactions were already rate limited when they were signed.
+        let mut sources = vec![];
+        let result = self
+            .handle_certificate_impl(request, wait_for_effects, &mut sources)
+            .await;
+        Self::report_traffic_error(&traffic_controller, &sources, &result);
+        result
+    }
+
+    // TODO: reject certificate if TransactionManager or Narwhal is backlogged.
+    async fn handle_certificate_impl(
+        self,
+        request: tonic::Request<CertifiedTransaction>,
+        wait_for_effects: bool,
+        traffic_sources: &mut Vec<TrafficSource>,
     ) -> Result<Option<HandleCertificateResponseV2>, tonic::Status> {
         let Self {
             state,
             consensus_adapter,
             metrics,
+            traffic_controller,
         } = self;
 
         let epoch_store = state.load_epoch_store_one_call_per_task();
@@ -432,6 +558,11 @@ impl ValidatorService {
                     .verify_cert(certificate)
                     .await?
             };
+            Self::admit_sender(
+                &traffic_controller,
+                traffic_sources,
+                certificate.data().transaction_data().sender(),
+            )?;
 
             let reconfiguration_lock = epoch_store.get_reconfig_state_read_lock_guard();
             if !reconfiguration_lock.should_accept_user_certs() {
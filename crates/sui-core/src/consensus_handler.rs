@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::authority::authority_per_epoch_store::{
-    AuthorityPerEpochStore, ConsensusStats, ConsensusStatsAPI, ExecutionIndicesWithStats,
+    AuthorityPerEpochStore, ConsensusStats, ConsensusStatsAPI, DeferralKey,
+    ExecutionIndicesWithStats,
 };
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::{AuthorityMetrics, AuthorityState, AuthorityStore};
@@ -19,15 +20,16 @@ use lru::LruCache;
 use mysten_metrics::{monitored_scope, spawn_monitored_task};
 use narwhal_config::Committee;
 use narwhal_executor::{ExecutionIndices, ExecutionState};
-use narwhal_types::ConsensusOutput;
+use narwhal_types::{ConsensusOutput, Round};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
+use sui_protocol_config::{PerObjectCongestionControlMode, ProtocolConfig};
 use sui_types::authenticator_state::ActiveJwk;
-use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
+use sui_types::base_types::{AuthorityName, EpochId, ObjectID, TransactionDigest};
 use sui_types::digests::ConsensusCommitDigest;
 use sui_types::executable_transaction::{
     TrustedExecutableTransaction, VerifiedExecutableTransaction,
@@ -37,7 +39,7 @@ use sui_types::messages_consensus::{
 };
 use sui_types::storage::ObjectStore;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
-use sui_types::transaction::{SenderSignedData, VerifiedTransaction};
+use sui_types::transaction::{SenderSignedData, TransactionDataAPI, VerifiedTransaction};
 use tracing::{debug, error, info, instrument, trace_span};
 
 pub struct ConsensusHandlerInitializer {
@@ -811,6 +813,107 @@ impl SequencedConsensusTransaction {
     }
 }
 
+/// Tracks the estimated execution cost of the transactions scheduled on each shared object in a
+/// consensus commit, and decides which transactions must be deferred to a later commit so that no
+/// single hot object accumulates more than `max_accumulated_txn_cost_per_object_in_checkpoint`.
+///
+/// Transactions on an object are assumed to execute sequentially after the transactions already
+/// scheduled on any of their mutable shared inputs, so a transaction starts at the largest
+/// accumulated cost among those objects. Transactions only reading a shared object do not
+/// contribute to its cost.
+///
+/// Nothing is deferred once the epoch is closing, as transactions still deferred when the epoch
+/// ends would never be executed.
+pub(crate) struct SharedObjectCongestionTracker {
+    object_execution_cost: HashMap<ObjectID, u64>,
+    mode: PerObjectCongestionControlMode,
+    max_accumulated_txn_cost_per_object: u64,
+    max_deferral_rounds: u64,
+}
+
+impl SharedObjectCongestionTracker {
+    /// A tracker for one consensus commit. `epoch_closing` is whether the epoch has stopped
+    /// accepting new user certificates, in which case every transaction is scheduled.
+    pub fn new(protocol_config: &ProtocolConfig, epoch_closing: bool) -> Self {
+        let mode = protocol_config.per_object_congestion_control_mode();
+        let (max_accumulated_txn_cost_per_object, max_deferral_rounds) =
+            if mode.is_none() || epoch_closing {
+                (u64::MAX, 0)
+            } else {
+                (
+                    protocol_config.max_accumulated_txn_cost_per_object_in_checkpoint(),
+                    protocol_config.max_deferral_rounds_for_congestion_control(),
+                )
+            };
+        Self {
+            object_execution_cost: HashMap::new(),
+            mode,
+            max_accumulated_txn_cost_per_object,
+            max_deferral_rounds,
+        }
+    }
+
+    fn tx_cost(&self, cert: &VerifiedExecutableTransaction) -> u64 {
+        match self.mode {
+            PerObjectCongestionControlMode::None => 0,
+            PerObjectCongestionControlMode::TotalGasBudget => cert.transaction_data().gas_budget(),
+            PerObjectCongestionControlMode::TotalTxCount => 1,
+        }
+    }
+
+    /// Returns the key to defer `cert` to if scheduling it in this commit would push the cost of
+    /// one of its mutable shared objects over the limit. Otherwise the transaction is accounted
+    /// for and `None` is returned.
+    ///
+    /// `previously_deferred_from_round` is the round `cert` was first deferred from, if any.
+    /// Transactions deferred for `max_deferral_rounds_for_congestion_control` rounds or more are
+    /// always scheduled, and a transaction is always scheduled if none of its objects has been
+    /// used yet in this commit, so that transactions costlier than the limit still make progress.
+    pub fn should_defer_due_to_congestion(
+        &mut self,
+        cert: &VerifiedExecutableTransaction,
+        commit_round: Round,
+        previously_deferred_from_round: Option<Round>,
+    ) -> Option<DeferralKey> {
+        if self.mode.is_none() {
+            return None;
+        }
+
+        let objects: Vec<ObjectID> = cert
+            .shared_input_objects()
+            .filter(|obj| obj.mutable)
+            .map(|obj| obj.id)
+            .collect();
+        if objects.is_empty() {
+            return None;
+        }
+
+        let tx_cost = self.tx_cost(cert);
+        let start_cost = objects
+            .iter()
+            .map(|id| self.object_execution_cost.get(id).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        let end_cost = start_cost.saturating_add(tx_cost);
+
+        let deferred_from_round = previously_deferred_from_round.unwrap_or(commit_round);
+        if start_cost > 0
+            && end_cost > self.max_accumulated_txn_cost_per_object
+            && commit_round < deferred_from_round.saturating_add(self.max_deferral_rounds)
+        {
+            return Some(DeferralKey::new_for_consensus_round(
+                commit_round + 1,
+                deferred_from_round,
+            ));
+        }
+
+        for id in objects {
+            self.object_execution_cost.insert(id, end_cost);
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -824,19 +927,21 @@ mod tests {
     use narwhal_types::{
         Batch, Certificate, CommittedSubDag, Header, HeaderV2Builder, ReputationScores,
     };
-    use prometheus::Registry;
+    use prometheus::{IntCounter, Registry};
     use shared_crypto::intent::Intent;
     use std::collections::BTreeSet;
     use sui_protocol_config::{ConsensusTransactionOrdering, SupportedProtocolVersions};
-    use sui_types::base_types::{random_object_ref, AuthorityName, SuiAddress};
+    use sui_types::base_types::{random_object_ref, AuthorityName, SequenceNumber, SuiAddress};
     use sui_types::committee::Committee;
     use sui_types::messages_consensus::{
         AuthorityCapabilities, ConsensusTransaction, ConsensusTransactionKind,
     };
     use sui_types::object::Object;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
     use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
     use sui_types::transaction::{
-        CertifiedTransaction, SenderSignedData, TransactionData, TransactionDataAPI,
+        CertifiedTransaction, ObjectArg, SenderSignedData, TransactionData, TransactionDataAPI,
+        VerifiedCertificate,
     };
 
     #[tokio::test]
@@ -1051,6 +1156,216 @@ mod tests {
         );
     }
 
+    fn congestion_control_config(
+        mode: PerObjectCongestionControlMode,
+        max_accumulated_txn_cost_per_object: u64,
+        max_deferral_rounds: u64,
+    ) -> ProtocolConfig {
+        let mut config = ProtocolConfig::get_for_max_version_UNSAFE();
+        config.set_per_object_congestion_control_mode_for_testing(mode);
+        config.set_max_accumulated_txn_cost_per_object_in_checkpoint_for_testing(
+            max_accumulated_txn_cost_per_object,
+        );
+        config.set_max_deferral_rounds_for_congestion_control_for_testing(max_deferral_rounds);
+        config
+    }
+
+    fn shared_object_txn(
+        shared_objects: &[(ObjectID, bool)],
+        gas_budget: u64,
+    ) -> VerifiedExecutableTransaction {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        for (id, mutable) in shared_objects {
+            builder
+                .obj(ObjectArg::SharedObject {
+                    id: *id,
+                    initial_shared_version: SequenceNumber::new(),
+                    mutable: *mutable,
+                })
+                .unwrap();
+        }
+        let (committee, keypairs) = Committee::new_simple_test_committee();
+        let data = SenderSignedData::new(
+            TransactionData::new_programmable(
+                SuiAddress::default(),
+                vec![random_object_ref()],
+                builder.finish(),
+                gas_budget,
+                1000,
+            ),
+            Intent::sui_transaction(),
+            vec![],
+        );
+        let certificate =
+            CertifiedTransaction::new_from_keypairs_for_testing(data, &keypairs, &committee);
+        VerifiedExecutableTransaction::new_from_certificate(VerifiedCertificate::new_unchecked(
+            certificate,
+        ))
+    }
+
+    #[test]
+    fn test_congestion_control_disabled() {
+        let config = congestion_control_config(PerObjectCongestionControlMode::None, 1, 0);
+        let mut tracker = SharedObjectCongestionTracker::new(&config, false);
+        let object = ObjectID::random();
+        for _ in 0..10 {
+            let txn = shared_object_txn(&[(object, true)], 1_000_000);
+            assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+        }
+    }
+
+    #[test]
+    fn test_congestion_control_tx_count() {
+        let config = congestion_control_config(PerObjectCongestionControlMode::TotalTxCount, 2, 5);
+        let mut tracker = SharedObjectCongestionTracker::new(&config, false);
+        let hot = ObjectID::random();
+        let other = ObjectID::random();
+
+        // The first two transactions on the hot object fit in the commit.
+        for _ in 0..2 {
+            let txn = shared_object_txn(&[(hot, true)], 1_000);
+            assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+        }
+
+        // The third one is deferred to the next round.
+        let txn = shared_object_txn(&[(hot, true)], 1_000);
+        assert_eq!(
+            tracker.should_defer_due_to_congestion(&txn, 10, None),
+            Some(DeferralKey::new_for_consensus_round(11, 10))
+        );
+
+        // A transaction that also touches the hot object starts after it and is deferred too.
+        let txn = shared_object_txn(&[(other, true), (hot, true)], 1_000);
+        assert_eq!(
+            tracker.should_defer_due_to_congestion(&txn, 10, None),
+            Some(DeferralKey::new_for_consensus_round(11, 10))
+        );
+
+        // Reading the hot object does not count against it.
+        let txn = shared_object_txn(&[(other, true), (hot, false)], 1_000);
+        assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+
+        // A re-deferred transaction keeps the round it was first deferred from.
+        let txn = shared_object_txn(&[(hot, true)], 1_000);
+        assert_eq!(
+            tracker.should_defer_due_to_congestion(&txn, 12, Some(8)),
+            Some(DeferralKey::new_for_consensus_round(13, 8))
+        );
+
+        // Once deferred for the maximum number of rounds, it is scheduled regardless.
+        assert_eq!(
+            tracker.should_defer_due_to_congestion(&txn, 13, Some(8)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_congestion_control_gas_budget() {
+        let config =
+            congestion_control_config(PerObjectCongestionControlMode::TotalGasBudget, 1_000, 5);
+        let mut tracker = SharedObjectCongestionTracker::new(&config, false);
+        let object = ObjectID::random();
+
+        // A transaction over the limit on an unused object is still scheduled.
+        let txn = shared_object_txn(&[(object, true)], 5_000);
+        assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+
+        let txn = shared_object_txn(&[(object, true)], 1);
+        assert_eq!(
+            tracker.should_defer_due_to_congestion(&txn, 10, None),
+            Some(DeferralKey::new_for_consensus_round(11, 10))
+        );
+
+        let object = ObjectID::random();
+        let txn = shared_object_txn(&[(object, true)], 600);
+        assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+        let txn = shared_object_txn(&[(object, true)], 400);
+        assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+        let txn = shared_object_txn(&[(object, true)], 1);
+        assert_eq!(
+            tracker.should_defer_due_to_congestion(&txn, 10, None),
+            Some(DeferralKey::new_for_consensus_round(11, 10))
+        );
+    }
+
+    #[test]
+    fn test_congestion_control_epoch_closing() {
+        let config = congestion_control_config(PerObjectCongestionControlMode::TotalTxCount, 1, 5);
+        let mut tracker = SharedObjectCongestionTracker::new(&config, true);
+        let object = ObjectID::random();
+        for _ in 0..10 {
+            let txn = shared_object_txn(&[(object, true)], 1_000);
+            assert_eq!(tracker.should_defer_due_to_congestion(&txn, 10, None), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_congestion_control_across_epoch_boundary() {
+        let mut objects = test_gas_objects();
+        objects.push(Object::shared_for_testing());
+        let network_config =
+            sui_swarm_config::network_config_builder::ConfigBuilder::new_with_temp_dir()
+                .with_objects(objects)
+                .build();
+        let state = TestAuthorityBuilder::new()
+            .with_network_config(&network_config)
+            .with_protocol_config(congestion_control_config(
+                PerObjectCongestionControlMode::TotalTxCount,
+                1,
+                100,
+            ))
+            .build()
+            .await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+        let checkpoint_service = Arc::new(CheckpointServiceNoop {});
+        let skipped_consensus_txns = IntCounter::new("skipped", "skipped").unwrap();
+
+        // Every certificate mutates the same shared object, so only one of them fits in a commit.
+        let certificates = test_certificates(&state).await;
+        let transactions = certificates
+            .iter()
+            .map(|certificate| {
+                SequencedConsensusTransaction::new_test(
+                    ConsensusTransaction::new_certificate_message(&state.name, certificate.clone()),
+                )
+            })
+            .collect();
+        let scheduled = epoch_store
+            .process_consensus_transactions_and_commit_boundary(
+                transactions,
+                &ExecutionIndicesWithStats::default(),
+                &checkpoint_service,
+                state.db(),
+                10,
+                0,
+                &skipped_consensus_txns,
+            )
+            .await
+            .unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert!(!epoch_store.deferred_transactions_empty());
+
+        // Once the epoch is closing, all deferred certificates are scheduled in the next commit,
+        // rather than being carried past the end of the epoch.
+        epoch_store
+            .get_reconfig_state_write_lock_guard()
+            .close_user_certs();
+        let scheduled = epoch_store
+            .process_consensus_transactions_and_commit_boundary(
+                vec![],
+                &ExecutionIndicesWithStats::default(),
+                &checkpoint_service,
+                state.db(),
+                11,
+                0,
+                &skipped_consensus_txns,
+            )
+            .await
+            .unwrap();
+        assert_eq!(scheduled.len(), certificates.len() - 1);
+        assert!(epoch_store.deferred_transactions_empty());
+    }
+
     fn extract(v: Vec<VerifiedSequencedConsensusTransaction>) -> Vec<String> {
         v.into_iter().map(extract_one).collect()
     }
@@ -3,7 +3,7 @@
 
 use std::{
     sync::{Arc, Weak},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use mysten_metrics::{monitored_scope, spawn_monitored_task};
@@ -35,6 +35,7 @@ pub async fn execution_process(
     mut rx_ready_certificates: UnboundedReceiver<(
         VerifiedExecutableTransaction,
         Option<TransactionEffectsDigest>,
+        Instant,
     )>,
     mut rx_execution_shutdown: oneshot::Receiver<()>,
 ) {
@@ -49,11 +50,13 @@ pub async fn execution_process(
 
         let certificate;
         let expected_effects_digest;
+        let ready_time;
         tokio::select! {
             result = rx_ready_certificates.recv() => {
-                if let Some((cert, fx_digest)) = result {
+                if let Some((cert, fx_digest, ready)) = result {
                     certificate = cert;
                     expected_effects_digest = fx_digest;
+                    ready_time = ready;
                 } else {
                     // Should only happen after the AuthorityState has shut down and tx_ready_certificate
                     // has been dropped by TransactionManager.
@@ -86,7 +89,22 @@ pub async fn execution_process(
         let limit = limit.clone();
         // hold semaphore permit until task completes. unwrap ok because we never close
         // the semaphore in this context.
+        authority
+            .overload_info()
+            .report_oldest_queued_ready_time(Some(ready_time));
         let permit = limit.acquire_owned().await.unwrap();
+        authority
+            .overload_info()
+            .report_oldest_queued_ready_time(None);
+
+        let queueing_latency = ready_time.elapsed();
+        authority
+            .metrics
+            .execution_queueing_latency_s
+            .observe(queueing_latency.as_secs_f64());
+        authority
+            .overload_info()
+            .report_execution_queueing_latency(queueing_latency);
 
         // Certificate execution can take significant time, so run it in a separate task.
         spawn_monitored_task!(async move {
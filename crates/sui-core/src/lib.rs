@@ -21,6 +21,7 @@ mod execution_driver;
 pub mod metrics;
 pub mod module_cache_metrics;
 pub mod mysticeti_adapter;
+pub mod overload_monitor;
 pub(crate) mod post_consensus_tx_reorder;
 pub mod quorum_driver;
 pub mod safe_client;
@@ -30,6 +31,7 @@ pub mod state_accumulator;
 pub mod storage;
 pub mod streamer;
 pub mod subscription_handler;
+pub mod traffic_controller;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 mod transaction_input_loader;
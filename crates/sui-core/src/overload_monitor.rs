@@ -0,0 +1,186 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Weak;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rand::Rng;
+use sui_config::node::OverloadThresholdConfig;
+use sui_types::error::{SuiError, SuiResult};
+use sysinfo::{CpuExt, System, SystemExt};
+use tokio::time::sleep;
+use tracing::{debug, info};
+
+use crate::authority::AuthorityState;
+
+#[cfg(test)]
+#[path = "unit_tests/overload_monitor_tests.rs"]
+mod overload_monitor_tests;
+
+#[derive(Default)]
+struct QueueingLatencySamples {
+    total: Duration,
+    count: u32,
+}
+
+/// Overload state of the authority, updated by the overload monitor and consulted when handling
+/// new transactions.
+#[derive(Default)]
+pub struct AuthorityOverloadInfo {
+    /// Execution queueing latency of the transactions that started executing since the last
+    /// time the overload monitor sampled it.
+    execution_queueing_latency: Mutex<QueueingLatencySamples>,
+
+    /// Time at which the oldest ready transaction still waiting to start executing became ready,
+    /// if any transaction is waiting.
+    oldest_queued_ready_time: Mutex<Option<Instant>>,
+
+    /// Percentage of new transactions currently rejected, between 0 and 100.
+    load_shedding_percentage: AtomicU32,
+}
+
+impl AuthorityOverloadInfo {
+    /// Records how long a ready transaction waited before its execution started.
+    pub fn report_execution_queueing_latency(&self, latency: Duration) {
+        let mut samples = self.execution_queueing_latency.lock();
+        samples.total += latency;
+        samples.count += 1;
+    }
+
+    /// Records when the oldest ready transaction that has not started executing yet became
+    /// ready, or `None` once no ready transaction is waiting anymore.
+    pub fn report_oldest_queued_ready_time(&self, ready_time: Option<Instant>) {
+        *self.oldest_queued_ready_time.lock() = ready_time;
+    }
+
+    /// Returns the average execution queueing latency since the previous call, or `None` if no
+    /// transaction started executing in the meantime.
+    fn take_average_execution_queueing_latency(&self) -> Option<Duration> {
+        let samples = std::mem::take(&mut *self.execution_queueing_latency.lock());
+        (samples.count > 0).then(|| samples.total / samples.count)
+    }
+
+    /// How long the oldest ready transaction still waiting to start executing has been queued,
+    /// or zero if no transaction is waiting.
+    fn oldest_queued_latency(&self) -> Duration {
+        self.oldest_queued_ready_time
+            .lock()
+            .map(|ready_time| ready_time.elapsed())
+            .unwrap_or_default()
+    }
+
+    pub fn load_shedding_percentage(&self) -> u32 {
+        self.load_shedding_percentage.load(Ordering::Relaxed)
+    }
+
+    fn set_load_shedding_percentage(&self, percentage: u32) {
+        self.load_shedding_percentage
+            .store(percentage, Ordering::Relaxed);
+    }
+
+    /// Rejects a new transaction with probability `load_shedding_percentage` percent.
+    pub fn check_load_shedding(&self, config: &OverloadThresholdConfig) -> SuiResult {
+        let load_shedding_percentage = self.load_shedding_percentage();
+        if load_shedding_percentage > 0
+            && rand::thread_rng().gen_range(0..100) < load_shedding_percentage
+        {
+            return Err(SuiError::ValidatorOverloadedRetryAfter {
+                load_shedding_percentage,
+                retry_after_secs: config.overload_monitor_interval.as_secs().max(1),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Fraction in [0, 1] of how far `value` is between `soft_limit` and `hard_limit`.
+fn pressure(value: f64, soft_limit: f64, hard_limit: f64) -> f64 {
+    if value <= soft_limit {
+        0.0
+    } else if value >= hard_limit || hard_limit <= soft_limit {
+        1.0
+    } else {
+        (value - soft_limit) / (hard_limit - soft_limit)
+    }
+}
+
+/// Computes the next load shedding percentage. The percentage rises proportionally to how far
+/// the execution queueing latency or the CPU utilization is between its soft and hard limits,
+/// and decays by half per sample once the pressure goes down, so that the node does not
+/// oscillate between shedding and accepting all transactions.
+pub(crate) fn calculate_load_shedding_percentage(
+    config: &OverloadThresholdConfig,
+    execution_queueing_latency: Duration,
+    cpu_utilization: f32,
+    current_percentage: u32,
+) -> u32 {
+    let latency_pressure = pressure(
+        execution_queueing_latency.as_secs_f64(),
+        config.execution_queue_latency_soft_limit.as_secs_f64(),
+        config.execution_queue_latency_hard_limit.as_secs_f64(),
+    );
+    let cpu_pressure = pressure(
+        cpu_utilization as f64,
+        config.cpu_utilization_soft_limit as f64,
+        config.cpu_utilization_hard_limit as f64,
+    );
+    let max_percentage = config.max_load_shedding_percentage.min(100);
+    let target = (latency_pressure.max(cpu_pressure) * max_percentage as f64).round() as u32;
+    target.max(current_percentage / 2).min(max_percentage)
+}
+
+/// Periodically samples the execution queueing latency and the CPU utilization of the node, and
+/// updates the percentage of new transactions the authority rejects. Exits once the authority
+/// state is dropped.
+pub async fn overload_monitor(
+    authority_state: Weak<AuthorityState>,
+    config: OverloadThresholdConfig,
+) {
+    info!("Starting system overload monitor.");
+    let mut system = System::new();
+    // Keep the last average when no transaction started executing during a sample interval, so
+    // that a stalled executor is not mistaken for an idle one.
+    let mut average_execution_queueing_latency = Duration::ZERO;
+
+    loop {
+        sleep(config.overload_monitor_interval).await;
+
+        let Some(authority_state) = authority_state.upgrade() else {
+            info!("Authority state has shutdown. Exiting overload monitor ...");
+            return;
+        };
+
+        system.refresh_cpu();
+        let cpu_utilization = system.global_cpu_info().cpu_usage();
+        let overload_info = authority_state.overload_info();
+        if let Some(latency) = overload_info.take_average_execution_queueing_latency() {
+            average_execution_queueing_latency = latency;
+        }
+        // Transactions that are still queued count as well, otherwise a stall that prevents any
+        // transaction from starting execution would never register as overload.
+        let execution_queueing_latency =
+            average_execution_queueing_latency.max(overload_info.oldest_queued_latency());
+
+        let current_percentage = overload_info.load_shedding_percentage();
+        let new_percentage = calculate_load_shedding_percentage(
+            &config,
+            execution_queueing_latency,
+            cpu_utilization,
+            current_percentage,
+        );
+        if new_percentage != current_percentage {
+            debug!(
+                ?execution_queueing_latency,
+                cpu_utilization,
+                "Load shedding percentage changed from {current_percentage} to {new_percentage}"
+            );
+        }
+        overload_info.set_load_shedding_percentage(new_percentage);
+        authority_state
+            .metrics
+            .load_shedding_percentage
+            .set(new_percentage as i64);
+    }
+}
@@ -0,0 +1,395 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use parking_lot::{Mutex, RwLock};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_with_registry, IntCounter, IntCounterVec, IntGauge, Registry,
+};
+use serde::Serialize;
+use sui_config::node::TrafficControlConfig;
+use sui_types::base_types::SuiAddress;
+use sui_types::error::{SuiError, SuiResult};
+use tracing::{info, warn};
+
+#[cfg(test)]
+#[path = "unit_tests/traffic_controller_tests.rs"]
+mod traffic_controller_tests;
+
+/// A source of traffic to the validator gRPC server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum TrafficSource {
+    ClientIp(IpAddr),
+    Sender(SuiAddress),
+}
+
+impl fmt::Display for TrafficSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrafficSource::ClientIp(ip) => write!(f, "client {ip}"),
+            TrafficSource::Sender(sender) => write!(f, "sender {sender}"),
+        }
+    }
+}
+
+impl FromStr for TrafficSource {
+    type Err = anyhow::Error;
+
+    /// Parses either an IP address or a Sui address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ip) = IpAddr::from_str(s) {
+            return Ok(TrafficSource::ClientIp(ip));
+        }
+        SuiAddress::from_str(s)
+            .map(TrafficSource::Sender)
+            .map_err(|_| anyhow::anyhow!("{s} is neither an IP address nor a Sui address"))
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(burst: u32, now: Instant) -> Self {
+        Self {
+            tokens: burst as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Refills the bucket at `rate_per_sec`, up to `burst` tokens.
+    fn refill(&mut self, rate_per_sec: f64, burst: u32, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate_per_sec).min(burst as f64);
+        self.last_refill = now;
+    }
+
+    fn has_token(&self) -> bool {
+        self.tokens >= 1.0
+    }
+
+    /// Takes one token if available, after refilling the bucket at `rate_per_sec` up to `burst`.
+    fn try_acquire(&mut self, rate_per_sec: f64, burst: u32, now: Instant) -> bool {
+        self.refill(rate_per_sec, burst, now);
+        if self.has_token() {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct SourceState {
+    requests: TokenBucket,
+    errors: TokenBucket,
+}
+
+struct DenyEntry {
+    until: Instant,
+    offenses: u32,
+    last_offense: Instant,
+}
+
+impl DenyEntry {
+    /// Number of offenses still remembered at `now`.
+    fn decayed_offenses(&self, decay_period: Duration, now: Instant) -> u32 {
+        if decay_period.is_zero() {
+            return 0;
+        }
+        let periods = now
+            .saturating_duration_since(self.last_offense)
+            .as_secs_f64()
+            / decay_period.as_secs_f64();
+        self.offenses.saturating_sub(periods as u32)
+    }
+}
+
+/// A blocked traffic source, as reported by `TrafficController::deny_list`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DeniedSource {
+    pub source: TrafficSource,
+    pub remaining: Duration,
+    pub offenses: u32,
+}
+
+struct Inner {
+    sources: LruCache<TrafficSource, SourceState>,
+    deny_list: HashMap<TrafficSource, DenyEntry>,
+}
+
+pub struct TrafficControllerMetrics {
+    requests_rejected: IntCounterVec,
+    sources_blocked: IntCounter,
+    deny_list_size: IntGauge,
+}
+
+impl TrafficControllerMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            requests_rejected: register_int_counter_vec_with_registry!(
+                "traffic_control_requests_rejected",
+                "Number of requests rejected by traffic control",
+                &["reason"],
+                registry,
+            )
+            .unwrap(),
+            sources_blocked: register_int_counter_with_registry!(
+                "traffic_control_sources_blocked",
+                "Number of times a traffic source has been added to the deny list",
+                registry,
+            )
+            .unwrap(),
+            deny_list_size: register_int_gauge_with_registry!(
+                "traffic_control_deny_list_size",
+                "Number of traffic sources in the deny list, including expired entries whose offenses have not decayed yet",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn new_for_tests() -> Self {
+        Self::new(&Registry::new())
+    }
+}
+
+/// Tracks the request rate and the rate of failed or invalid transactions of each client IP
+/// address and transaction sender, rate limits them with token buckets, and blocks abusive
+/// sources for a duration that grows with repeated offenses and decays over time.
+pub struct TrafficController {
+    policy: RwLock<TrafficControlConfig>,
+    inner: Mutex<Inner>,
+    metrics: TrafficControllerMetrics,
+}
+
+impl TrafficController {
+    pub fn new(policy: TrafficControlConfig, metrics: TrafficControllerMetrics) -> Self {
+        let capacity = NonZeroUsize::new(policy.max_tracked_sources.max(1)).unwrap();
+        Self {
+            policy: RwLock::new(policy),
+            inner: Mutex::new(Inner {
+                sources: LruCache::new(capacity),
+                deny_list: HashMap::new(),
+            }),
+            metrics,
+        }
+    }
+
+    pub fn policy(&self) -> TrafficControlConfig {
+        self.policy.read().clone()
+    }
+
+    /// Replaces the policy. Rates already accumulated by each source are kept.
+    pub fn set_policy(&self, policy: TrafficControlConfig) {
+        let capacity = NonZeroUsize::new(policy.max_tracked_sources.max(1)).unwrap();
+        self.inner.lock().sources.resize(capacity);
+        info!(?policy, "Traffic control policy updated");
+        *self.policy.write() = policy;
+    }
+
+    /// Whether `peer` is trusted to report the IP address of the clients it relays requests for.
+    pub fn is_trusted_forwarder(&self, peer: &IpAddr) -> bool {
+        self.policy.read().trusted_forwarders.contains(peer)
+    }
+
+    /// Checks whether a request from all of `sources` can be served, and accounts for it. The
+    /// request is only accounted for if all sources accept it.
+    pub fn check(&self, sources: &[TrafficSource]) -> SuiResult {
+        self.check_at(sources, Instant::now())
+    }
+
+    /// Checks that none of `sources` is blocked, without accounting for a request.
+    pub fn check_denied(&self, sources: &[TrafficSource]) -> SuiResult {
+        self.check_denied_at(&self.inner.lock(), sources, Instant::now())
+    }
+
+    /// Records a failed or invalid transaction from `sources`, blocking the ones that send too
+    /// many of them.
+    pub fn report_error(&self, sources: &[TrafficSource]) {
+        self.report_error_at(sources, Instant::now())
+    }
+
+    /// Blocks `source` for `duration`, counting as one offense.
+    pub fn block(&self, source: TrafficSource, duration: Duration) {
+        let now = Instant::now();
+        let mut inner = self.inner.lock();
+        let offenses = inner
+            .deny_list
+            .get(&source)
+            .map_or(0, |entry| entry.offenses)
+            + 1;
+        inner.deny_list.insert(
+            source,
+            DenyEntry {
+                until: now + duration,
+                offenses,
+                last_offense: now,
+            },
+        );
+        self.metrics
+            .deny_list_size
+            .set(inner.deny_list.len() as i64);
+        info!("Traffic from {source} blocked for {duration:?}");
+    }
+
+    /// Removes `source` from the deny list and forgets its past offenses. Returns whether it was
+    /// in the deny list.
+    pub fn unblock(&self, source: &TrafficSource) -> bool {
+        let mut inner = self.inner.lock();
+        let removed = inner.deny_list.remove(source).is_some();
+        self.metrics
+            .deny_list_size
+            .set(inner.deny_list.len() as i64);
+        removed
+    }
+
+    /// Returns the currently blocked sources.
+    pub fn deny_list(&self) -> Vec<DeniedSource> {
+        let now = Instant::now();
+        let policy = self.policy();
+        let inner = self.inner.lock();
+        let mut denied: Vec<_> = inner
+            .deny_list
+            .iter()
+            .filter(|(_, entry)| entry.until > now)
+            .map(|(source, entry)| DeniedSource {
+                source: *source,
+                remaining: entry.until - now,
+                offenses: entry.decayed_offenses(policy.deny_decay_period, now),
+            })
+            .collect();
+        denied.sort_by_key(|denied| denied.source);
+        denied
+    }
+
+    pub(crate) fn check_at(&self, sources: &[TrafficSource], now: Instant) -> SuiResult {
+        let policy = self.policy();
+        let mut inner = self.inner.lock();
+
+        self.check_denied_at(&inner, sources, now)?;
+
+        // Only take a token from any source once all of them are known to have one.
+        for source in sources {
+            let (rate, burst) = Self::request_rate(&policy, source);
+            let state = Self::source_state(&mut inner, &policy, *source, now);
+            state.requests.refill(rate as f64, burst, now);
+            if !state.requests.has_token() {
+                self.metrics
+                    .requests_rejected
+                    .with_label_values(&["rate_limited"])
+                    .inc();
+                return Err(SuiError::TooManyRequests {
+                    traffic_source: source.to_string(),
+                });
+            }
+        }
+        for source in sources {
+            Self::source_state(&mut inner, &policy, *source, now)
+                .requests
+                .tokens -= 1.0;
+        }
+        Ok(())
+    }
+
+    fn check_denied_at(&self, inner: &Inner, sources: &[TrafficSource], now: Instant) -> SuiResult {
+        for source in sources {
+            if let Some(entry) = inner.deny_list.get(source) {
+                if entry.until > now {
+                    self.metrics
+                        .requests_rejected
+                        .with_label_values(&["denied"])
+                        .inc();
+                    return Err(SuiError::TrafficControlDenied {
+                        traffic_source: source.to_string(),
+                        retry_after_secs: (entry.until - now).as_secs().max(1),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn report_error_at(&self, sources: &[TrafficSource], now: Instant) {
+        let policy = self.policy();
+        let mut inner = self.inner.lock();
+
+        for source in sources {
+            let state = Self::source_state(&mut inner, &policy, *source, now);
+            if state.errors.try_acquire(
+                policy.errors_per_min as f64 / 60.0,
+                policy.error_burst,
+                now,
+            ) {
+                continue;
+            }
+
+            // Refill the error budget of the source, so that once it is unblocked it is only
+            // blocked again after sending another burst of errors.
+            state.errors = TokenBucket::new(policy.error_burst, now);
+
+            let offenses = inner.deny_list.get(source).map_or(0, |entry| {
+                entry.decayed_offenses(policy.deny_decay_period, now)
+            }) + 1;
+            let duration = policy
+                .deny_duration
+                .saturating_mul(1 << (offenses - 1).min(16))
+                .min(policy.max_deny_duration);
+            warn!("Blocking traffic from {source} for {duration:?} after {offenses} offenses");
+            inner.deny_list.insert(
+                *source,
+                DenyEntry {
+                    until: now + duration,
+                    offenses,
+                    last_offense: now,
+                },
+            );
+            self.metrics.sources_blocked.inc();
+        }
+
+        // Forget sources that are no longer blocked and whose offenses have fully decayed.
+        inner.deny_list.retain(|_, entry| {
+            entry.until > now || entry.decayed_offenses(policy.deny_decay_period, now) > 0
+        });
+        self.metrics
+            .deny_list_size
+            .set(inner.deny_list.len() as i64);
+    }
+
+    fn request_rate(policy: &TrafficControlConfig, source: &TrafficSource) -> (u32, u32) {
+        match source {
+            TrafficSource::ClientIp(_) => (
+                policy.client_ip_requests_per_sec,
+                policy.client_ip_request_burst,
+            ),
+            TrafficSource::Sender(_) => {
+                (policy.sender_requests_per_sec, policy.sender_request_burst)
+            }
+        }
+    }
+
+    fn source_state<'a>(
+        inner: &'a mut Inner,
+        policy: &TrafficControlConfig,
+        source: TrafficSource,
+        now: Instant,
+    ) -> &'a mut SourceState {
+        let (_, burst) = Self::request_rate(policy, &source);
+        inner.sources.get_or_insert_mut(source, || SourceState {
+            requests: TokenBucket::new(burst, now),
+            errors: TokenBucket::new(policy.error_burst, now),
+        })
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn test_config() -> OverloadThresholdConfig {
+    OverloadThresholdConfig {
+        execution_queue_latency_soft_limit: Duration::from_secs(1),
+        execution_queue_latency_hard_limit: Duration::from_secs(11),
+        cpu_utilization_soft_limit: 80,
+        cpu_utilization_hard_limit: 100,
+        max_load_shedding_percentage: 90,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_no_load_shedding_below_soft_limits() {
+    let config = test_config();
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_millis(500), 50.0, 0),
+        0
+    );
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_secs(1), 80.0, 0),
+        0
+    );
+}
+
+#[test]
+fn test_load_shedding_rises_with_pressure() {
+    let config = test_config();
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_secs(6), 0.0, 0),
+        45
+    );
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_secs(11), 0.0, 0),
+        90
+    );
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_secs(60), 0.0, 0),
+        90
+    );
+
+    // The highest of latency and CPU pressure wins.
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_secs(6), 95.0, 0),
+        68
+    );
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::ZERO, 100.0, 0),
+        90
+    );
+}
+
+#[test]
+fn test_load_shedding_decays_gradually() {
+    let config = test_config();
+    let mut percentage = 90;
+    let mut history = vec![];
+    while percentage > 0 {
+        percentage = calculate_load_shedding_percentage(&config, Duration::ZERO, 0.0, percentage);
+        history.push(percentage);
+    }
+    assert_eq!(history, vec![45, 22, 11, 5, 2, 1, 0]);
+
+    // A new spike takes over the decay right away.
+    assert_eq!(
+        calculate_load_shedding_percentage(&config, Duration::from_secs(11), 0.0, 22),
+        90
+    );
+}
+
+#[test]
+fn test_check_load_shedding() {
+    let config = test_config();
+    let overload_info = AuthorityOverloadInfo::default();
+    for _ in 0..100 {
+        overload_info.check_load_shedding(&config).unwrap();
+    }
+
+    overload_info.set_load_shedding_percentage(100);
+    for _ in 0..100 {
+        let err = overload_info.check_load_shedding(&config).unwrap_err();
+        assert!(err.is_overload());
+        assert!(matches!(
+            err,
+            SuiError::ValidatorOverloadedRetryAfter {
+                load_shedding_percentage: 100,
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+fn test_average_execution_queueing_latency() {
+    let overload_info = AuthorityOverloadInfo::default();
+    assert_eq!(
+        overload_info.take_average_execution_queueing_latency(),
+        None
+    );
+
+    overload_info.report_execution_queueing_latency(Duration::from_millis(100));
+    overload_info.report_execution_queueing_latency(Duration::from_millis(300));
+    assert_eq!(
+        overload_info.take_average_execution_queueing_latency(),
+        Some(Duration::from_millis(200))
+    );
+
+    // Samples are consumed by each read.
+    assert_eq!(
+        overload_info.take_average_execution_queueing_latency(),
+        None
+    );
+}
+
+#[test]
+fn test_oldest_queued_latency() {
+    let overload_info = AuthorityOverloadInfo::default();
+    assert_eq!(overload_info.oldest_queued_latency(), Duration::ZERO);
+
+    // A transaction stuck in the queue counts even though none started executing.
+    let ready_time = Instant::now() - Duration::from_secs(5);
+    overload_info.report_oldest_queued_ready_time(Some(ready_time));
+    assert!(overload_info.oldest_queued_latency() >= Duration::from_secs(5));
+    assert_eq!(
+        overload_info.take_average_execution_queueing_latency(),
+        None
+    );
+
+    overload_info.report_oldest_queued_ready_time(None);
+    assert_eq!(overload_info.oldest_queued_latency(), Duration::ZERO);
+}
@@ -0,0 +1,227 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::net::Ipv4Addr;
+
+fn test_policy() -> TrafficControlConfig {
+    TrafficControlConfig {
+        client_ip_requests_per_sec: 10,
+        client_ip_request_burst: 5,
+        sender_requests_per_sec: 1,
+        sender_request_burst: 2,
+        errors_per_min: 60,
+        error_burst: 3,
+        deny_duration: Duration::from_secs(10),
+        max_deny_duration: Duration::from_secs(25),
+        deny_decay_period: Duration::from_secs(100),
+        max_tracked_sources: 100,
+        trusted_forwarders: vec![IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1))],
+    }
+}
+
+fn client(last_octet: u8) -> TrafficSource {
+    TrafficSource::ClientIp(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet)))
+}
+
+#[test]
+fn test_request_rate_limit() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    let now = Instant::now();
+    let ip = client(1);
+
+    // The burst is accepted, then requests are rate limited.
+    for _ in 0..5 {
+        controller.check_at(&[ip], now).unwrap();
+    }
+    assert!(matches!(
+        controller.check_at(&[ip], now),
+        Err(SuiError::TooManyRequests { .. })
+    ));
+
+    // Another client is not affected.
+    controller.check_at(&[client(2)], now).unwrap();
+
+    // Tokens are refilled at the sustained rate.
+    let later = now + Duration::from_millis(200);
+    controller.check_at(&[ip], later).unwrap();
+    controller.check_at(&[ip], later).unwrap();
+    assert!(controller.check_at(&[ip], later).is_err());
+}
+
+#[test]
+fn test_sender_rate_limit() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    let now = Instant::now();
+    let sender = TrafficSource::Sender(SuiAddress::random_for_testing_only());
+
+    // The sender limit applies across client IPs.
+    controller.check_at(&[client(1), sender], now).unwrap();
+    controller.check_at(&[client(2), sender], now).unwrap();
+    let err = controller.check_at(&[client(3), sender], now).unwrap_err();
+    assert_eq!(
+        err,
+        SuiError::TooManyRequests {
+            traffic_source: sender.to_string()
+        }
+    );
+}
+
+#[test]
+fn test_rejected_request_does_not_use_other_budgets() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    let now = Instant::now();
+    let ip = client(1);
+    let sender = TrafficSource::Sender(SuiAddress::random_for_testing_only());
+
+    // Once the sender is rate limited, its requests do not use up the budget of the client IP.
+    controller.check_at(&[ip, sender], now).unwrap();
+    controller.check_at(&[ip, sender], now).unwrap();
+    for _ in 0..5 {
+        assert!(controller.check_at(&[ip, sender], now).is_err());
+    }
+    for _ in 0..3 {
+        controller.check_at(&[ip], now).unwrap();
+    }
+    assert!(controller.check_at(&[ip], now).is_err());
+}
+
+#[test]
+fn test_check_denied_does_not_use_budget() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    let ip = client(1);
+
+    for _ in 0..10 {
+        controller.check_denied(&[ip]).unwrap();
+    }
+    for _ in 0..5 {
+        controller.check(&[ip]).unwrap();
+    }
+
+    controller.block(ip, Duration::from_secs(60));
+    assert!(matches!(
+        controller.check_denied(&[ip]),
+        Err(SuiError::TrafficControlDenied { .. })
+    ));
+}
+
+#[test]
+fn test_errors_block_source_with_decaying_deny_list() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    let now = Instant::now();
+    let ip = client(1);
+
+    // Errors within the burst are tolerated.
+    for _ in 0..3 {
+        controller.report_error_at(&[ip], now);
+    }
+    controller.check_at(&[ip], now).unwrap();
+
+    // One more blocks the source for the base duration.
+    controller.report_error_at(&[ip], now);
+    let err = controller
+        .check_at(&[ip], now + Duration::from_secs(1))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        SuiError::TrafficControlDenied {
+            traffic_source: ip.to_string(),
+            retry_after_secs: 9,
+        }
+    );
+    controller
+        .check_at(&[ip], now + Duration::from_secs(10))
+        .unwrap();
+
+    // A repeat offense doubles the block duration.
+    let now = now + Duration::from_secs(11);
+    for _ in 0..4 {
+        controller.report_error_at(&[ip], now);
+    }
+    assert!(controller
+        .check_at(&[ip], now + Duration::from_secs(15))
+        .is_err());
+    controller
+        .check_at(&[ip], now + Duration::from_secs(20))
+        .unwrap();
+
+    // And is capped by the maximum duration.
+    let now = now + Duration::from_secs(21);
+    for _ in 0..4 {
+        controller.report_error_at(&[ip], now);
+    }
+    assert!(controller
+        .check_at(&[ip], now + Duration::from_secs(24))
+        .is_err());
+    controller
+        .check_at(&[ip], now + Duration::from_secs(25))
+        .unwrap();
+
+    // Once past offenses have decayed, the base duration applies again.
+    let now = now + Duration::from_secs(1000);
+    for _ in 0..4 {
+        controller.report_error_at(&[ip], now);
+    }
+    controller
+        .check_at(&[ip], now + Duration::from_secs(10))
+        .unwrap();
+}
+
+#[test]
+fn test_manual_block_and_policy_update() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    let sender = TrafficSource::Sender(SuiAddress::random_for_testing_only());
+
+    controller.block(sender, Duration::from_secs(60));
+    assert!(matches!(
+        controller.check(&[client(1), sender]),
+        Err(SuiError::TrafficControlDenied { .. })
+    ));
+    let deny_list = controller.deny_list();
+    assert_eq!(deny_list.len(), 1);
+    assert_eq!(deny_list[0].source, sender);
+    assert_eq!(deny_list[0].offenses, 1);
+
+    assert!(controller.unblock(&sender));
+    assert!(!controller.unblock(&sender));
+    controller.check(&[client(1), sender]).unwrap();
+    assert!(controller.deny_list().is_empty());
+
+    let policy = TrafficControlConfig {
+        sender_request_burst: 100,
+        ..test_policy()
+    };
+    controller.set_policy(policy.clone());
+    assert_eq!(controller.policy(), policy);
+}
+
+#[test]
+fn test_trusted_forwarders() {
+    let controller =
+        TrafficController::new(test_policy(), TrafficControllerMetrics::new_for_tests());
+    assert!(controller.is_trusted_forwarder(&IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1))));
+    assert!(!controller.is_trusted_forwarder(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
+
+    controller.set_policy(TrafficControlConfig {
+        trusted_forwarders: vec![],
+        ..test_policy()
+    });
+    assert!(!controller.is_trusted_forwarder(&IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1))));
+}
+
+#[test]
+fn test_parse_traffic_source() {
+    assert_eq!("10.0.0.1".parse::<TrafficSource>().unwrap(), client(1));
+    let sender = SuiAddress::random_for_testing_only();
+    assert_eq!(
+        sender.to_string().parse::<TrafficSource>().unwrap(),
+        TrafficSource::Sender(sender)
+    );
+    assert!("not-a-source".parse::<TrafficSource>().is_err());
+}
@@ -240,6 +240,27 @@ async fn test_signer_denied() {
     assert_denied(&transfer_with_account(&accounts[2], &accounts[1], &state).await);
 }
 
+#[tokio::test]
+async fn test_deny_config_updated_at_runtime() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    // Denying an address takes effect for the next transaction, without reloading the state.
+    let mut config = (*state.transaction_deny_config()).clone();
+    assert!(config.add_denied_address(accounts[0].0));
+    assert!(!config.add_denied_address(accounts[0].0));
+    state.set_transaction_deny_config(config.clone());
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+
+    // And so does removing it.
+    assert!(config.remove_denied_address(&accounts[0].0));
+    assert!(!config.remove_denied_address(&accounts[0].0));
+    state.set_transaction_deny_config(config);
+    transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_shared_object_transaction_disabled() {
     let (network_config, state) = setup_test(
@@ -3,6 +3,8 @@
 
 use thiserror::Error;
 
+use crate::QuotaExceeded;
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum FaucetError {
     #[error("Faucet cannot read objects from fullnode: {0}")]
@@ -40,6 +42,15 @@ pub enum FaucetError {
     #[error("Coin amounts sent are incorrect:`{0}`")]
     CoinAmountTransferredIncorrect(String),
 
+    #[error("{0}")]
+    QuotaExceeded(QuotaExceeded),
+
+    #[error("Request verification failed: {0}")]
+    VerificationFailed(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -6,11 +6,16 @@ use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
+use crate::VerificationMode;
+
 mod simple_faucet;
 mod write_ahead_log;
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -114,6 +119,58 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Maximum number of requests for the same recipient address in each recipient quota window.
+    /// Unlimited if not set.
+    #[clap(long)]
+    pub recipient_quota: Option<u64>,
+
+    #[clap(long, default_value_t = 86400)]
+    pub recipient_quota_window_secs: u64,
+
+    /// Maximum number of requests from the same IP address in each IP quota window. Unlimited if
+    /// not set.
+    #[clap(long)]
+    pub ip_quota: Option<u64>,
+
+    #[clap(long, default_value_t = 86400)]
+    pub ip_quota_window_secs: u64,
+
+    /// IP addresses whose requests are not subject to quotas, e.g. CI runners.
+    #[clap(long, value_delimiter = ',')]
+    pub quota_allowlist: Vec<IpAddr>,
+
+    /// Header to read the client's IP address from, for faucets behind a proxy (e.g.
+    /// X-Forwarded-For). If not set, the address of the connecting peer is used.
+    #[clap(long)]
+    pub client_ip_header: Option<String>,
+
+    /// Number of trusted proxies appending to `client_ip_header`. The client's IP address is the
+    /// one appended by the outermost of them, counting from the end of the header, as entries
+    /// before it are controlled by the client.
+    #[clap(long, default_value_t = 1)]
+    pub client_ip_trusted_hops: usize,
+
+    /// How requests are verified before they are served.
+    #[clap(long, value_enum, default_value_t = VerificationMode::None)]
+    pub verification: VerificationMode,
+
+    /// Number of leading zero bits required in solutions to proof-of-work challenges.
+    #[clap(long, default_value_t = 20)]
+    pub pow_difficulty: u8,
+
+    #[clap(long, default_value_t = 300)]
+    pub challenge_ttl_secs: u64,
+
+    /// File containing the hex-encoded key authenticating proof-of-work challenges, to share it
+    /// across restarts and replicas. If not set, a random key is generated when the faucet starts.
+    #[clap(long)]
+    pub pow_key_path: Option<PathBuf>,
+
+    /// File containing the hex-encoded key shared with the frontend issuing tokens, for token
+    /// verification.
+    #[clap(long)]
+    pub token_key_path: Option<PathBuf>,
 }
 
 impl Default for FaucetConfig {
@@ -132,6 +189,18 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            recipient_quota: None,
+            recipient_quota_window_secs: 86400,
+            ip_quota: None,
+            ip_quota_window_secs: 86400,
+            quota_allowlist: vec![],
+            client_ip_header: None,
+            client_ip_trusted_hops: 1,
+            verification: VerificationMode::None,
+            pow_difficulty: 20,
+            challenge_ttl_secs: 300,
+            pow_key_path: None,
+            token_key_path: None,
         }
     }
 }
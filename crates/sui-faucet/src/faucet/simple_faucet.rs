@@ -1111,11 +1111,12 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: None,
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: Some(10),
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(&mut context)
         .await
@@ -1239,11 +1240,12 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: None,
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: Some(10),
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(&mut context)
         .await
@@ -1398,9 +1400,10 @@ mod tests {
         let res = SuiClientCommands::PayAllSui {
             input_coins: vec![*bad_gas.id()],
             recipient: KeyIdentity::Address(SuiAddress::random_for_testing_only()),
-            gas_budget: 2_000_000,
+            gas_budget: Some(2_000_000),
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(faucet.wallet_mut())
         .await
@@ -1521,11 +1524,12 @@ mod tests {
         let res = SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![tiny_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(&mut context)
         .await;
@@ -1606,11 +1610,12 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![reasonable_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(&mut context)
         .await
@@ -1622,10 +1627,11 @@ mod tests {
             SuiClientCommands::TransferSui {
                 to: KeyIdentity::Address(destination_address),
                 sui_coin_object_id: *gas.id(),
-                gas_budget: 50000000,
+                gas_budget: Some(50000000),
                 amount: None,
                 serialize_unsigned_transaction: false,
                 serialize_signed_transaction: false,
+                dry_run: false,
             }
             .execute(&mut context)
             .await
@@ -1679,11 +1685,12 @@ mod tests {
         let _res = SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![tiny_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(&mut context)
         .await;
@@ -1695,10 +1702,11 @@ mod tests {
             SuiClientCommands::TransferSui {
                 to: KeyIdentity::Address(destination_address),
                 sui_coin_object_id: *gas.id(),
-                gas_budget: 50000000,
+                gas_budget: Some(50000000),
                 amount: None,
                 serialize_unsigned_transaction: false,
                 serialize_signed_transaction: false,
+                dry_run: false,
             }
             .execute(&mut context)
             .await
@@ -1813,11 +1821,12 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: None,
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: Some(10),
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
+            dry_run: false,
         }
         .execute(&mut context)
         .await
@@ -4,13 +4,17 @@
 mod errors;
 mod faucet;
 mod metrics;
+mod quota;
 mod requests;
 mod responses;
+mod verification;
 
 pub mod metrics_layer;
 pub use metrics_layer::*;
 
 pub use errors::FaucetError;
 pub use faucet::*;
+pub use quota::*;
 pub use requests::*;
 pub use responses::*;
+pub use verification::*;
@@ -3,8 +3,8 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::Path,
-    http::StatusCode,
+    extract::{ConnectInfo, Path},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -21,10 +21,12 @@ use std::{
 };
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
 use sui_faucet::{
-    BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet,
+    new_verifier, BatchFaucetResponse, BatchStatusFaucetResponse, ChallengeResponse, Faucet,
+    FaucetConfig, FaucetError, FaucetRequest, FaucetResponse, QuotaReservation,
+    QuotaStatusResponse, Quotas, RequestMetricsLayer, RequestVerifier, SimpleFaucet,
 };
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
@@ -34,6 +36,8 @@ const CONCURRENCY_LIMIT: usize = 30;
 
 struct AppState<F = Arc<SimpleFaucet>> {
     faucet: F,
+    quotas: Quotas,
+    verifier: Box<dyn RequestVerifier>,
     config: FaucetConfig,
 }
 
@@ -79,6 +83,8 @@ async fn main() -> Result<(), anyhow::Error> {
         )
         .await
         .unwrap(),
+        quotas: Quotas::new(&config),
+        verifier: new_verifier(&config)?,
         config,
     });
 
@@ -93,6 +99,8 @@ async fn main() -> Result<(), anyhow::Error> {
         .route("/gas", post(request_gas))
         .route("/v1/gas", post(batch_request_gas))
         .route("/v1/status/:task_id", get(request_status))
+        .route("/v1/quota/:recipient", get(request_quota))
+        .route("/challenge", get(request_challenge))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -115,13 +123,16 @@ async fn main() -> Result<(), anyhow::Error> {
             // Every config.wal_retry_interval (Default: 300 seconds) we try to clear the wal coins
             tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
             app_state.faucet.retry_wal_coins().await.unwrap();
+            if let Err(e) = with_quotas(&app_state, Quotas::prune).await {
+                warn!("Failed to prune quota ledger: {e}");
+            }
         }
     });
 
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -134,6 +145,8 @@ async fn health() -> &'static str {
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let id = Uuid::new_v4();
@@ -149,14 +162,34 @@ async fn batch_request_gas(
         );
     };
 
+    // The quota is checked first, so that a request it rejects does not use up the client's
+    // single-use proof.
+    let reservation = match acquire_quota(&state, &headers, peer, request.recipient).await {
+        Ok(reservation) => reservation,
+        Err(v) => {
+            warn!(uuid =?id, "Rejected gas request: {:?}", v);
+            return (error_status(&v), Json(BatchFaucetResponse::from(v)));
+        }
+    };
+
+    if let Err(v) = state
+        .verifier
+        .verify(request.recipient, request.proof.as_ref())
+    {
+        warn!(uuid =?id, "Rejected gas request: {:?}", v);
+        release_quota(&state, reservation).await;
+        return (error_status(&v), Json(BatchFaucetResponse::from(v)));
+    }
+
     if state.config.batch_enabled {
+        let task_state = state.clone();
         let result = spawn_monitored_task!(async move {
-            state
+            task_state
                 .faucet
                 .batch_send(
                     id,
                     request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
+                    &vec![task_state.config.amount; task_state.config.num_coins],
                 )
                 .await
         })
@@ -170,6 +203,7 @@ async fn batch_request_gas(
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                release_quota(&state, reservation).await;
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
@@ -179,13 +213,14 @@ async fn batch_request_gas(
     } else {
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
+        let task_state = state.clone();
         let result = spawn_monitored_task!(async move {
-            state
+            task_state
                 .faucet
                 .send(
                     id,
                     request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
+                    &vec![task_state.config.amount; task_state.config.num_coins],
                 )
                 .await
         })
@@ -199,6 +234,7 @@ async fn batch_request_gas(
             }
             Err(v) => {
                 warn!(uuid =?id, "Failed to request gas: {:?}", v);
+                release_quota(&state, reservation).await;
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(BatchFaucetResponse::from(v)),
@@ -236,40 +272,109 @@ async fn request_status(
     }
 }
 
+/// handler for quota status requests, reporting the quotas left for a recipient and the client's
+/// IP address.
+async fn request_quota(
+    Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(recipient): Path<String>,
+) -> impl IntoResponse {
+    let recipient = match recipient.parse::<SuiAddress>() {
+        Ok(recipient) => recipient,
+        Err(e) => {
+            let v = FaucetError::InvalidRequest(format!("Invalid recipient address: {e}"));
+            return (error_status(&v), Json(QuotaStatusResponse::from(v)));
+        }
+    };
+
+    let ip = match client_ip(&state.config, &headers, peer) {
+        Ok(ip) => Some(ip),
+        Err(v) => return (error_status(&v), Json(QuotaStatusResponse::from(v))),
+    };
+
+    match with_quotas(&state, move |quotas| quotas.status(recipient, ip)).await {
+        Ok((recipient, ip_quota)) => (
+            StatusCode::OK,
+            Json(QuotaStatusResponse {
+                allowlisted: state.quotas.is_allowlisted(ip),
+                recipient,
+                ip: ip_quota,
+                error: None,
+            }),
+        ),
+        Err(v) => (error_status(&v), Json(QuotaStatusResponse::from(v))),
+    }
+}
+
+/// handler for challenge requests, issuing a challenge to solve before requesting gas, if the
+/// faucet uses challenges.
+async fn request_challenge(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
+    match state.verifier.challenge() {
+        Some(challenge) => (StatusCode::OK, Json(ChallengeResponse::from(challenge))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ChallengeResponse::from(FaucetError::Internal(
+                "This faucet does not issue challenges.".to_string(),
+            ))),
+        ),
+    }
+}
+
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
-    let result = match payload {
-        FaucetRequest::FixedAmountRequest(requests) => {
-            // We spawn a tokio task for this such that connection drop will not interrupt
-            // it and impact the recycling of coins
-            spawn_monitored_task!(async move {
-                state
-                    .faucet
-                    .send(
-                        id,
-                        requests.recipient,
-                        &vec![state.config.amount; state.config.num_coins],
-                    )
-                    .await
-            })
-            .await
-            .unwrap()
-        }
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(FaucetResponse::from(FaucetError::Internal(
-                    "Input Error.".to_string(),
-                ))),
-            )
+    let FaucetRequest::FixedAmountRequest(requests) = payload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(FaucetResponse::from(FaucetError::Internal(
+                "Input Error.".to_string(),
+            ))),
+        );
+    };
+
+    // The quota is checked first, so that a request it rejects does not use up the client's
+    // single-use proof.
+    let reservation = match acquire_quota(&state, &headers, peer, requests.recipient).await {
+        Ok(reservation) => reservation,
+        Err(v) => {
+            warn!(uuid =?id, "Rejected gas request: {:?}", v);
+            return (error_status(&v), Json(FaucetResponse::from(v)));
         }
     };
+
+    if let Err(v) = state
+        .verifier
+        .verify(requests.recipient, requests.proof.as_ref())
+    {
+        warn!(uuid =?id, "Rejected gas request: {:?}", v);
+        release_quota(&state, reservation).await;
+        return (error_status(&v), Json(FaucetResponse::from(v)));
+    }
+
+    // We spawn a tokio task for this such that connection drop will not interrupt
+    // it and impact the recycling of coins
+    let task_state = state.clone();
+    let result = spawn_monitored_task!(async move {
+        task_state
+            .faucet
+            .send(
+                id,
+                requests.recipient,
+                &vec![task_state.config.amount; task_state.config.num_coins],
+            )
+            .await
+    })
+    .await
+    .unwrap();
+
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
@@ -277,6 +382,7 @@ async fn request_gas(
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            release_quota(&state, reservation).await;
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
@@ -285,6 +391,72 @@ async fn request_gas(
     }
 }
 
+/// The IP address of the client that sent a request, read from the configured header if there is
+/// one, or the address of the connecting peer otherwise. Behind proxies, only the address
+/// appended by the outermost trusted proxy is used, as the client can set any entries before it.
+fn client_ip(
+    config: &FaucetConfig,
+    headers: &HeaderMap,
+    peer: SocketAddr,
+) -> Result<IpAddr, FaucetError> {
+    let Some(header) = &config.client_ip_header else {
+        return Ok(peer.ip());
+    };
+
+    headers
+        .get_all(header)
+        .iter()
+        // Unreadable values still count as an entry, so that they cannot shift the others.
+        .flat_map(|value| value.to_str().unwrap_or_default().split(','))
+        .rev()
+        .nth(config.client_ip_trusted_hops.saturating_sub(1))
+        .and_then(|ip| ip.trim().parse().ok())
+        .ok_or_else(|| {
+            FaucetError::InvalidRequest(format!(
+                "Could not determine the client's IP address from header {header}"
+            ))
+        })
+}
+
+/// Record a request for `recipient` against the quotas of the recipient and the client's IP
+/// address.
+async fn acquire_quota(
+    state: &Arc<AppState>,
+    headers: &HeaderMap,
+    peer: SocketAddr,
+    recipient: SuiAddress,
+) -> Result<QuotaReservation, FaucetError> {
+    let ip = client_ip(&state.config, headers, peer)?;
+    with_quotas(state, move |quotas| quotas.acquire(recipient, Some(ip))).await
+}
+
+/// Stop counting a failed request towards the quotas it was recorded against.
+async fn release_quota(state: &Arc<AppState>, reservation: QuotaReservation) {
+    if let Err(e) = with_quotas(state, move |quotas| quotas.release(reservation)).await {
+        warn!("Failed to release quota reservation: {e}");
+    }
+}
+
+/// Run `f` against the quotas on a blocking thread, as it reads and writes the quota ledger.
+async fn with_quotas<T: Send + 'static>(
+    state: &Arc<AppState>,
+    f: impl FnOnce(&Quotas) -> Result<T, FaucetError> + Send + 'static,
+) -> Result<T, FaucetError> {
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || f(&state.quotas))
+        .await
+        .map_err(|e| FaucetError::Internal(e.to_string()))?
+}
+
+fn error_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+        FaucetError::VerificationFailed(_) => StatusCode::FORBIDDEN,
+        FaucetError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -309,3 +481,47 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(trusted_hops: usize) -> FaucetConfig {
+        FaucetConfig {
+            client_ip_header: Some("x-forwarded-for".to_string()),
+            client_ip_trusted_hops: trusted_hops,
+            ..Default::default()
+        }
+    }
+
+    fn headers(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let peer: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let ip = |addr: &str| addr.parse::<IpAddr>().unwrap();
+
+        // Without a header, the peer is the client.
+        let direct = FaucetConfig::default();
+        assert_eq!(
+            client_ip(&direct, &headers(&["1.1.1.1"]), peer),
+            Ok(peer.ip())
+        );
+
+        // Entries before the one appended by the trusted proxy are ignored.
+        let spoofed = headers(&["1.1.1.1, 2.2.2.2", "3.3.3.3"]);
+        assert_eq!(client_ip(&config(1), &spoofed, peer), Ok(ip("3.3.3.3")));
+        assert_eq!(client_ip(&config(2), &spoofed, peer), Ok(ip("2.2.2.2")));
+
+        // Requests whose IP cannot be determined are rejected.
+        assert!(client_ip(&config(1), &headers(&[]), peer).is_err());
+        assert!(client_ip(&config(1), &headers(&["unknown"]), peer).is_err());
+        assert!(client_ip(&config(3), &headers(&["1.1.1.1, 2.2.2.2"]), peer).is_err());
+    }
+}
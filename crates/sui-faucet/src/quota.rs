@@ -0,0 +1,497 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use typed_store::rocks::DBMap;
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+use typed_store_derive::DBMapUtils;
+
+use crate::{FaucetConfig, FaucetError};
+
+/// Persistent ledger of the requests served for each recipient address and source IP, used to
+/// enforce quotas across restarts. Each entry holds the times (in milliseconds since the Unix
+/// epoch, in ascending order) of the requests made within the quota's window.
+#[derive(DBMapUtils)]
+pub struct QuotaLedger {
+    pub usage: DBMap<QuotaKey, Vec<u64>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum QuotaKey {
+    Recipient(SuiAddress),
+    Ip(IpAddr),
+}
+
+/// At most `limit` requests are allowed in any `window`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub limit: u64,
+    pub window: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaScope {
+    Recipient,
+    Ip,
+}
+
+/// Details of the quota a request exceeded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaExceeded {
+    pub scope: QuotaScope,
+    pub limit: u64,
+    pub window_secs: u64,
+    pub retry_after_secs: u64,
+}
+
+/// How much of a quota is left.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaStatus {
+    pub limit: u64,
+    pub remaining: u64,
+    pub window_secs: u64,
+    /// Seconds until the oldest request in the window stops counting towards the quota.
+    pub resets_in_secs: u64,
+}
+
+/// A request recorded against the quotas, which can be released if the request fails.
+#[derive(Debug)]
+pub struct QuotaReservation {
+    keys: Vec<QuotaKey>,
+    at: u64,
+}
+
+/// Per-recipient and per-IP quotas on faucet requests, backed by a [`QuotaLedger`].
+pub struct Quotas {
+    ledger: Mutex<QuotaLedger>,
+    recipient: Option<Quota>,
+    ip: Option<Quota>,
+    allowlist: HashSet<IpAddr>,
+}
+
+impl fmt::Display for QuotaScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaScope::Recipient => write!(f, "recipient address"),
+            QuotaScope::Ip => write!(f, "IP address"),
+        }
+    }
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Too many requests from this {}: at most {} requests are allowed every {} seconds. \
+             Please try again in {} seconds.",
+            self.scope, self.limit, self.window_secs, self.retry_after_secs,
+        )
+    }
+}
+
+impl QuotaLedger {
+    pub(crate) fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::new("faucet_quota_ledger"),
+            None,
+            None,
+        )
+    }
+}
+
+impl Quotas {
+    /// Set up the quotas configured in `config`, with the ledger stored next to the write-ahead
+    /// log.
+    pub fn new(config: &FaucetConfig) -> Self {
+        let quota = |limit: Option<u64>, window_secs| {
+            limit.map(|limit| Quota {
+                limit,
+                window: Duration::from_secs(window_secs),
+            })
+        };
+
+        Self::open(
+            &config.write_ahead_log.with_extension("quota"),
+            quota(config.recipient_quota, config.recipient_quota_window_secs),
+            quota(config.ip_quota, config.ip_quota_window_secs),
+            config.quota_allowlist.iter().copied().collect(),
+        )
+    }
+
+    pub fn open(
+        path: &Path,
+        recipient: Option<Quota>,
+        ip: Option<Quota>,
+        allowlist: HashSet<IpAddr>,
+    ) -> Self {
+        Self {
+            ledger: Mutex::new(QuotaLedger::open(path)),
+            recipient,
+            ip,
+            allowlist,
+        }
+    }
+
+    /// Record a request for `recipient` from `ip`, if it is within all quotas. Requests from
+    /// allowlisted IPs are not subject to quotas, and are not recorded.
+    pub fn acquire(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+    ) -> Result<QuotaReservation, FaucetError> {
+        self.acquire_at(recipient, ip, now_ms())
+    }
+
+    /// Forget a request recorded by [`Quotas::acquire`], so that it no longer counts towards the
+    /// quotas (e.g. because it failed).
+    pub fn release(&self, reservation: QuotaReservation) -> Result<(), FaucetError> {
+        let ledger = self.ledger.lock();
+        for key in &reservation.keys {
+            let Some(mut usage) = ledger.usage.get(key).map_err(FaucetError::internal)? else {
+                continue;
+            };
+
+            if let Some(pos) = usage.iter().position(|t| *t == reservation.at) {
+                usage.remove(pos);
+                ledger
+                    .usage
+                    .insert(key, &usage)
+                    .map_err(FaucetError::internal)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// How much of the recipient's and IP's quotas are left. Quotas that are not configured, or
+    /// that do not apply to allowlisted IPs, are reported as `None`.
+    pub fn status(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+    ) -> Result<(Option<QuotaStatus>, Option<QuotaStatus>), FaucetError> {
+        self.status_at(recipient, ip, now_ms())
+    }
+
+    /// Whether requests from `ip` are exempt from quotas.
+    pub fn is_allowlisted(&self, ip: Option<IpAddr>) -> bool {
+        ip.is_some_and(|ip| self.allowlist.contains(&ip))
+    }
+
+    /// Remove requests from the ledger that no longer count towards any quota.
+    pub fn prune(&self) -> Result<(), FaucetError> {
+        let now = now_ms();
+        let ledger = self.ledger.lock();
+        let mut batch = ledger.usage.batch();
+        let mut updates = vec![];
+        let mut deletes = vec![];
+
+        for item in ledger.usage.safe_iter() {
+            let (key, usage) = item.map_err(FaucetError::internal)?;
+            let live = match self.quota(&key) {
+                Some(quota) => live_usage(&usage, &quota, now),
+                None => vec![],
+            };
+
+            if live.is_empty() {
+                deletes.push(key);
+            } else if live.len() < usage.len() {
+                updates.push((key, live));
+            }
+        }
+
+        batch
+            .delete_batch(&ledger.usage, deletes)
+            .map_err(FaucetError::internal)?;
+        batch
+            .insert_batch(&ledger.usage, updates)
+            .map_err(FaucetError::internal)?;
+        batch.write().map_err(FaucetError::internal)
+    }
+
+    fn acquire_at(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+        now: u64,
+    ) -> Result<QuotaReservation, FaucetError> {
+        let keys = self.keys(recipient, ip);
+        let ledger = self.ledger.lock();
+
+        // Check every quota before recording the request against any of them.
+        let mut usages = vec![];
+        for (key, quota) in keys {
+            let usage = ledger.usage.get(&key).map_err(FaucetError::internal)?;
+            let live = live_usage(&usage.unwrap_or_default(), &quota, now);
+
+            if live.len() as u64 >= quota.limit {
+                return Err(FaucetError::QuotaExceeded(QuotaExceeded {
+                    scope: key.scope(),
+                    limit: quota.limit,
+                    window_secs: quota.window.as_secs(),
+                    retry_after_secs: resets_in_secs(&live, &quota, now),
+                }));
+            }
+
+            usages.push((key, live));
+        }
+
+        let mut batch = ledger.usage.batch();
+        let mut keys = vec![];
+        for (key, mut live) in usages {
+            live.push(now);
+            batch
+                .insert_batch(&ledger.usage, [(key, live)])
+                .map_err(FaucetError::internal)?;
+            keys.push(key);
+        }
+        batch.write().map_err(FaucetError::internal)?;
+
+        Ok(QuotaReservation { keys, at: now })
+    }
+
+    fn status_at(
+        &self,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+        now: u64,
+    ) -> Result<(Option<QuotaStatus>, Option<QuotaStatus>), FaucetError> {
+        let ledger = self.ledger.lock();
+        let (mut recipient_status, mut ip_status) = (None, None);
+
+        for (key, quota) in self.keys(recipient, ip) {
+            let usage = ledger.usage.get(&key).map_err(FaucetError::internal)?;
+            let live = live_usage(&usage.unwrap_or_default(), &quota, now);
+            let status = Some(QuotaStatus {
+                limit: quota.limit,
+                remaining: quota.limit.saturating_sub(live.len() as u64),
+                window_secs: quota.window.as_secs(),
+                resets_in_secs: resets_in_secs(&live, &quota, now),
+            });
+
+            match key {
+                QuotaKey::Recipient(_) => recipient_status = status,
+                QuotaKey::Ip(_) => ip_status = status,
+            }
+        }
+
+        Ok((recipient_status, ip_status))
+    }
+
+    /// The ledger entries, and the quotas on them, that a request for `recipient` from `ip` is
+    /// subject to.
+    fn keys(&self, recipient: SuiAddress, ip: Option<IpAddr>) -> Vec<(QuotaKey, Quota)> {
+        if self.is_allowlisted(ip) {
+            return vec![];
+        }
+
+        let recipient = Some(QuotaKey::Recipient(recipient));
+        let ip = ip.map(QuotaKey::Ip);
+        [recipient, ip]
+            .into_iter()
+            .flatten()
+            .filter_map(|key| Some((key, self.quota(&key)?)))
+            .collect()
+    }
+
+    fn quota(&self, key: &QuotaKey) -> Option<Quota> {
+        match key {
+            QuotaKey::Recipient(_) => self.recipient,
+            QuotaKey::Ip(_) => self.ip,
+        }
+    }
+}
+
+impl QuotaKey {
+    fn scope(&self) -> QuotaScope {
+        match self {
+            QuotaKey::Recipient(_) => QuotaScope::Recipient,
+            QuotaKey::Ip(_) => QuotaScope::Ip,
+        }
+    }
+}
+
+/// The requests in `usage` that still count towards `quota` at `now`.
+fn live_usage(usage: &[u64], quota: &Quota, now: u64) -> Vec<u64> {
+    let window_start = now.saturating_sub(quota.window.as_millis() as u64);
+    usage
+        .iter()
+        .copied()
+        .filter(|t| *t > window_start)
+        .collect()
+}
+
+/// Seconds until the oldest request in `live` stops counting towards `quota`, rounded up.
+fn resets_in_secs(live: &[u64], quota: &Quota, now: u64) -> u64 {
+    let Some(oldest) = live.first() else {
+        return 0;
+    };
+
+    let resets_at = oldest + quota.window.as_millis() as u64;
+    (resets_at.saturating_sub(now) + 999) / 1000
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the Unix epoch")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000;
+
+    fn quotas(path: &Path, allowlist: &[IpAddr]) -> Quotas {
+        Quotas::open(
+            path,
+            Some(Quota {
+                limit: 2,
+                window: Duration::from_secs(60),
+            }),
+            Some(Quota {
+                limit: 3,
+                window: Duration::from_secs(3600),
+            }),
+            allowlist.iter().copied().collect(),
+        )
+    }
+
+    fn ip(addr: &str) -> Option<IpAddr> {
+        Some(addr.parse().unwrap())
+    }
+
+    fn exceeded(result: Result<QuotaReservation, FaucetError>) -> QuotaExceeded {
+        match result {
+            Err(FaucetError::QuotaExceeded(exceeded)) => exceeded,
+            other => panic!("Expected quota to be exceeded, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_recipient_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = quotas(&tmp.path().join("quota"), &[]);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.acquire_at(recipient, None, MINUTE).unwrap();
+        quotas.acquire_at(recipient, None, MINUTE + 1000).unwrap();
+
+        let exceeded = exceeded(quotas.acquire_at(recipient, None, MINUTE + 2000));
+        assert_eq!(exceeded.scope, QuotaScope::Recipient);
+        assert_eq!(exceeded.limit, 2);
+        assert_eq!(exceeded.retry_after_secs, 58);
+
+        // Other recipients are unaffected.
+        let other = SuiAddress::random_for_testing_only();
+        quotas.acquire_at(other, None, MINUTE + 2000).unwrap();
+
+        // Once the first request leaves the window, there is room for another.
+        quotas.acquire_at(recipient, None, 2 * MINUTE).unwrap();
+    }
+
+    #[test]
+    fn test_ip_quota_across_recipients() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = quotas(&tmp.path().join("quota"), &[]);
+        let client = ip("10.0.0.1");
+
+        // Rotating recipients does not get around the IP's quota.
+        for _ in 0..3 {
+            let recipient = SuiAddress::random_for_testing_only();
+            quotas.acquire_at(recipient, client, MINUTE).unwrap();
+        }
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let exceeded = exceeded(quotas.acquire_at(recipient, client, MINUTE));
+        assert_eq!(exceeded.scope, QuotaScope::Ip);
+
+        // The rejected request is not recorded against its recipient.
+        let (recipient_status, ip_status) = quotas.status_at(recipient, client, MINUTE).unwrap();
+        assert_eq!(recipient_status.unwrap().remaining, 2);
+        assert_eq!(ip_status.unwrap().remaining, 0);
+
+        // Requests from other IPs are unaffected.
+        quotas
+            .acquire_at(recipient, ip("10.0.0.2"), MINUTE)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let runner = ip("192.168.0.1");
+        let quotas = quotas(&tmp.path().join("quota"), &[runner.unwrap()]);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        for _ in 0..10 {
+            quotas.acquire_at(recipient, runner, MINUTE).unwrap();
+        }
+
+        assert!(quotas.is_allowlisted(runner));
+        assert_eq!(
+            quotas.status_at(recipient, runner, MINUTE).unwrap(),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_release() {
+        let tmp = tempfile::tempdir().unwrap();
+        let quotas = quotas(&tmp.path().join("quota"), &[]);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        quotas.acquire_at(recipient, None, MINUTE).unwrap();
+        let reservation = quotas.acquire_at(recipient, None, MINUTE + 1).unwrap();
+        exceeded(quotas.acquire_at(recipient, None, MINUTE + 2));
+
+        quotas.release(reservation).unwrap();
+        quotas.acquire_at(recipient, None, MINUTE + 3).unwrap();
+    }
+
+    #[test]
+    fn test_ledger_persists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("quota");
+        let recipient = SuiAddress::random_for_testing_only();
+        let now = now_ms();
+
+        {
+            let quotas = quotas(&path, &[]);
+            quotas.acquire_at(recipient, None, now).unwrap();
+            quotas.acquire_at(recipient, None, now).unwrap();
+        }
+
+        let quotas = quotas(&path, &[]);
+        exceeded(quotas.acquire_at(recipient, None, now));
+
+        let (recipient_status, _) = quotas.status_at(recipient, None, now).unwrap();
+        assert_eq!(
+            recipient_status,
+            Some(QuotaStatus {
+                limit: 2,
+                remaining: 0,
+                window_secs: 60,
+                resets_in_secs: 60,
+            })
+        );
+
+        // Pruning keeps requests that still count towards the quota.
+        quotas.prune().unwrap();
+        exceeded(quotas.acquire_at(recipient, None, now));
+    }
+}
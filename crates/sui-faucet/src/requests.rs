@@ -13,6 +13,19 @@ pub enum FaucetRequest {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FixedAmountRequest {
     pub recipient: SuiAddress,
+    /// Proof that the request is allowed, for faucets that verify requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<RequestProof>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RequestProof {
+    /// A solution to a proof-of-work challenge issued by the faucet's `/challenge` endpoint.
+    ProofOfWork { challenge: String, nonce: u64 },
+    /// A token issued by a trusted frontend, valid until `expires_at_ms`.
+    #[serde(rename_all = "camelCase")]
+    Token { token: String, expires_at_ms: u64 },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,6 +37,7 @@ impl FaucetRequest {
     pub fn new_fixed_amount_request(recipient: impl Into<SuiAddress>) -> Self {
         Self::FixedAmountRequest(FixedAmountRequest {
             recipient: recipient.into(),
+            proof: None,
         })
     }
 
@@ -10,6 +10,9 @@ use uuid::Uuid;
 pub struct FaucetResponse {
     pub transferred_gas_objects: Vec<CoinInfo>,
     pub error: Option<String>,
+    /// Set if the request was rejected because it exceeded a quota.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_exceeded: Option<QuotaExceeded>,
 }
 
 impl From<FaucetError> for FaucetResponse {
@@ -17,6 +20,7 @@ impl From<FaucetError> for FaucetResponse {
         Self {
             error: Some(e.to_string()),
             transferred_gas_objects: vec![],
+            quota_exceeded: quota_exceeded(e),
         }
     }
 }
@@ -26,6 +30,7 @@ impl From<FaucetReceipt> for FaucetResponse {
         Self {
             transferred_gas_objects: v.sent,
             error: None,
+            quota_exceeded: None,
         }
     }
 }
@@ -36,6 +41,9 @@ pub struct BatchFaucetResponse {
     // This string is the Uuid for the req
     pub task: Option<String>,
     pub error: Option<String>,
+    /// Set if the request was rejected because it exceeded a quota.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_exceeded: Option<QuotaExceeded>,
 }
 
 impl From<FaucetError> for BatchFaucetResponse {
@@ -43,6 +51,7 @@ impl From<FaucetError> for BatchFaucetResponse {
         Self {
             error: Some(e.to_string()),
             task: None,
+            quota_exceeded: quota_exceeded(e),
         }
     }
 }
@@ -52,6 +61,7 @@ impl From<BatchFaucetReceipt> for BatchFaucetResponse {
         Self {
             task: Some(v.task),
             error: None,
+            quota_exceeded: None,
         }
     }
 }
@@ -61,6 +71,7 @@ impl From<Uuid> for BatchFaucetResponse {
         Self {
             task: Some(v.to_string()),
             error: None,
+            quota_exceeded: None,
         }
     }
 }
@@ -90,3 +101,56 @@ impl From<BatchSendStatus> for BatchStatusFaucetResponse {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaStatusResponse {
+    /// Whether requests from the client's IP address are exempt from quotas.
+    pub allowlisted: bool,
+    /// The recipient's quota, if there is one.
+    pub recipient: Option<QuotaStatus>,
+    /// The client IP address's quota, if there is one.
+    pub ip: Option<QuotaStatus>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for QuotaStatusResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeResponse {
+    pub challenge: Option<ProofOfWorkChallenge>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for ChallengeResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            challenge: None,
+        }
+    }
+}
+
+impl From<ProofOfWorkChallenge> for ChallengeResponse {
+    fn from(v: ProofOfWorkChallenge) -> Self {
+        Self {
+            challenge: Some(v),
+            error: None,
+        }
+    }
+}
+
+fn quota_exceeded(e: FaucetError) -> Option<QuotaExceeded> {
+    match e {
+        FaucetError::QuotaExceeded(exceeded) => Some(exceeded),
+        _ => None,
+    }
+}
@@ -0,0 +1,530 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::path::Path;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::hmac::{hmac_sha3_256, HmacKey};
+use fastcrypto::traits::ToFromBytes;
+use parking_lot::Mutex;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+
+use crate::quota::now_ms;
+use crate::{FaucetConfig, FaucetError, RequestProof};
+
+/// Maximum number of solved challenges, or used tokens, remembered (until they expire) to prevent
+/// replays.
+const MAX_USED_PROOFS: usize = 100_000;
+
+const SALT_LENGTH: usize = 16;
+const MAC_LENGTH: usize = 32;
+const CHALLENGE_LENGTH: usize = SALT_LENGTH + 8 + MAC_LENGTH;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerificationMode {
+    /// Serve every well-formed request, e.g. on local networks.
+    #[default]
+    None,
+    /// Require a solution to a proof-of-work challenge issued by the faucet.
+    ProofOfWork,
+    /// Require an HMAC-signed token issued by a trusted frontend.
+    Token,
+}
+
+/// Checks that a faucet request is allowed to be served, before any coins are sent for it.
+pub trait RequestVerifier: Send + Sync {
+    /// A new challenge for a client to solve before making a request, if this verifier uses
+    /// challenges.
+    fn challenge(&self) -> Option<ProofOfWorkChallenge> {
+        None
+    }
+
+    /// Check the `proof` accompanying a request for coins to be sent to `recipient`.
+    fn verify(
+        &self,
+        recipient: SuiAddress,
+        proof: Option<&RequestProof>,
+    ) -> Result<(), FaucetError>;
+}
+
+/// A proof-of-work challenge. To solve it, a client needs to find a `nonce` such that the SHA-256
+/// hash of the challenge's bytes (hex-decoded), followed by the recipient's address bytes and the
+/// nonce (as 8 little-endian bytes), starts with at least `difficulty` zero bits.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofOfWorkChallenge {
+    pub challenge: String,
+    pub difficulty: u8,
+    pub expires_at_ms: u64,
+}
+
+/// Verifier that accepts every request.
+pub struct NoopVerifier;
+
+/// Verifier for solutions to proof-of-work challenges. Challenges are stateless: they carry their
+/// expiry time, authenticated with the faucet's key, so that only solved challenges need to be
+/// remembered, to stop them from being used more than once. Faucets sharing a key (e.g. replicas
+/// behind a load balancer) accept each other's challenges, but each remembers only the challenges
+/// solved through it.
+pub struct ProofOfWorkVerifier {
+    key: HmacKey,
+    difficulty: u8,
+    ttl: Duration,
+    solved: UsedProofs<[u8; SALT_LENGTH]>,
+}
+
+/// Verifier for tokens issued by a trusted frontend, which shares a key with the faucet. A token
+/// is the hex-encoded HMAC-SHA3-256, under the shared key, of the recipient's address bytes
+/// followed by the token's expiry time (milliseconds since the Unix epoch, as 8 big-endian bytes).
+/// Each token can only be used once.
+pub struct TokenVerifier {
+    key: HmacKey,
+    used: UsedProofs<[u8; MAC_LENGTH]>,
+}
+
+/// Proofs that have already been used, remembered until they expire. Once full, no new proofs are
+/// accepted until old ones expire, as forgetting unexpired proofs would allow them to be replayed.
+struct UsedProofs<K> {
+    expiries: Mutex<HashMap<K, u64>>,
+    capacity: usize,
+}
+
+/// The verifier selected by `config`.
+pub fn new_verifier(config: &FaucetConfig) -> anyhow::Result<Box<dyn RequestVerifier>> {
+    Ok(match config.verification {
+        VerificationMode::None => Box::new(NoopVerifier),
+        VerificationMode::ProofOfWork => {
+            let ttl = Duration::from_secs(config.challenge_ttl_secs);
+            Box::new(match &config.pow_key_path {
+                Some(path) => ProofOfWorkVerifier::from_file(path, config.pow_difficulty, ttl)?,
+                None => ProofOfWorkVerifier::new(config.pow_difficulty, ttl),
+            })
+        }
+        VerificationMode::Token => {
+            let Some(path) = &config.token_key_path else {
+                anyhow::bail!("Token verification requires --token-key-path");
+            };
+            Box::new(TokenVerifier::from_file(path)?)
+        }
+    })
+}
+
+impl RequestVerifier for NoopVerifier {
+    fn verify(&self, _: SuiAddress, _: Option<&RequestProof>) -> Result<(), FaucetError> {
+        Ok(())
+    }
+}
+
+impl ProofOfWorkVerifier {
+    /// A verifier with a random key, which only accepts challenges it issued itself.
+    pub fn new(difficulty: u8, ttl: Duration) -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self::with_key(&key, difficulty, ttl)
+    }
+
+    pub fn with_key(key: &[u8], difficulty: u8, ttl: Duration) -> Self {
+        Self {
+            key: HmacKey::from_bytes(key).expect("HMAC key can be of any length"),
+            difficulty,
+            ttl,
+            solved: UsedProofs::new(MAX_USED_PROOFS),
+        }
+    }
+
+    /// Read the hex-encoded key from the file at `path`, so that challenges stay valid across
+    /// restarts and replicas using the same key.
+    pub fn from_file(path: &Path, difficulty: u8, ttl: Duration) -> anyhow::Result<Self> {
+        Ok(Self::with_key(&read_key(path)?, difficulty, ttl))
+    }
+
+    fn challenge_at(&self, now: u64) -> ProofOfWorkChallenge {
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let expires_at_ms = now + self.ttl.as_millis() as u64;
+
+        let mut bytes = salt.to_vec();
+        bytes.extend_from_slice(&expires_at_ms.to_be_bytes());
+        let mac = hmac_sha3_256(&self.key, &bytes);
+        bytes.extend_from_slice(&mac.digest);
+
+        ProofOfWorkChallenge {
+            challenge: hex::encode(bytes),
+            difficulty: self.difficulty,
+            expires_at_ms,
+        }
+    }
+
+    fn verify_at(
+        &self,
+        recipient: SuiAddress,
+        proof: Option<&RequestProof>,
+        now: u64,
+    ) -> Result<(), FaucetError> {
+        let Some(RequestProof::ProofOfWork { challenge, nonce }) = proof else {
+            return Err(failed(
+                "Request requires a solution to a proof-of-work challenge",
+            ));
+        };
+
+        let bytes = hex::decode(challenge).map_err(|_| failed("Malformed challenge"))?;
+        if bytes.len() != CHALLENGE_LENGTH {
+            return Err(failed("Malformed challenge"));
+        }
+
+        let (message, mac) = bytes.split_at(SALT_LENGTH + 8);
+        if !constant_time_eq(&hmac_sha3_256(&self.key, message).digest, mac) {
+            return Err(failed("Challenge was not issued by this faucet"));
+        }
+
+        let (salt, expires_at_ms) = message.split_at(SALT_LENGTH);
+        let expires_at_ms = u64::from_be_bytes(expires_at_ms.try_into().unwrap());
+        if expires_at_ms <= now {
+            return Err(failed("Challenge has expired"));
+        }
+
+        let mut solution = bytes.clone();
+        solution.extend_from_slice(recipient.as_ref());
+        solution.extend_from_slice(&nonce.to_le_bytes());
+        if leading_zero_bits(&Sha256::digest(&solution).digest) < self.difficulty as u32 {
+            return Err(failed("Incorrect solution to challenge"));
+        }
+
+        let salt: [u8; SALT_LENGTH] = salt.try_into().unwrap();
+        self.solved
+            .use_once(salt, expires_at_ms, now)
+            .map_err(|e| failed(&format!("Challenge {e}")))
+    }
+}
+
+impl RequestVerifier for ProofOfWorkVerifier {
+    fn challenge(&self) -> Option<ProofOfWorkChallenge> {
+        Some(self.challenge_at(now_ms()))
+    }
+
+    fn verify(
+        &self,
+        recipient: SuiAddress,
+        proof: Option<&RequestProof>,
+    ) -> Result<(), FaucetError> {
+        self.verify_at(recipient, proof, now_ms())
+    }
+}
+
+impl TokenVerifier {
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: HmacKey::from_bytes(key).expect("HMAC key can be of any length"),
+            used: UsedProofs::new(MAX_USED_PROOFS),
+        }
+    }
+
+    /// Read the hex-encoded shared key from the file at `path`.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        Ok(Self::new(&read_key(path)?))
+    }
+
+    /// The token for a request to send coins to `recipient`, that is valid until `expires_at_ms`.
+    pub fn token(&self, recipient: SuiAddress, expires_at_ms: u64) -> String {
+        hex::encode(self.mac(recipient, expires_at_ms))
+    }
+
+    fn mac(&self, recipient: SuiAddress, expires_at_ms: u64) -> [u8; MAC_LENGTH] {
+        let mut message = recipient.to_vec();
+        message.extend_from_slice(&expires_at_ms.to_be_bytes());
+        hmac_sha3_256(&self.key, &message).digest
+    }
+
+    fn verify_at(
+        &self,
+        recipient: SuiAddress,
+        proof: Option<&RequestProof>,
+        now: u64,
+    ) -> Result<(), FaucetError> {
+        let Some(RequestProof::Token {
+            token,
+            expires_at_ms,
+        }) = proof
+        else {
+            return Err(failed("Request requires a token"));
+        };
+
+        let token = hex::decode(token).map_err(|_| failed("Malformed token"))?;
+        let mac = self.mac(recipient, *expires_at_ms);
+        if !constant_time_eq(&mac, &token) {
+            return Err(failed("Invalid token"));
+        }
+
+        if *expires_at_ms <= now {
+            return Err(failed("Token has expired"));
+        }
+
+        self.used
+            .use_once(mac, *expires_at_ms, now)
+            .map_err(|e| failed(&format!("Token {e}")))
+    }
+}
+
+impl RequestVerifier for TokenVerifier {
+    fn verify(
+        &self,
+        recipient: SuiAddress,
+        proof: Option<&RequestProof>,
+    ) -> Result<(), FaucetError> {
+        self.verify_at(recipient, proof, now_ms())
+    }
+}
+
+impl<K: Eq + Hash> UsedProofs<K> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            expiries: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Remember `proof` until it expires at `expires_at_ms`, unless it has already been used. The
+    /// error completes a sentence about the proof.
+    fn use_once(&self, proof: K, expires_at_ms: u64, now: u64) -> Result<(), &'static str> {
+        let mut expiries = self.expiries.lock();
+        if expiries.contains_key(&proof) {
+            return Err("has already been used");
+        }
+
+        if expiries.len() >= self.capacity {
+            expiries.retain(|_, expires_at_ms| *expires_at_ms > now);
+            if expiries.len() >= self.capacity {
+                return Err("cannot be accepted right now, please try again later");
+            }
+        }
+
+        expiries.insert(proof, expires_at_ms);
+        Ok(())
+    }
+}
+
+/// Read a hex-encoded key from the file at `path`.
+fn read_key(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let key = hex::decode(std::fs::read_to_string(path)?.trim())?;
+    if key.is_empty() {
+        anyhow::bail!("Key in {} is empty", path.display());
+    }
+    Ok(key)
+}
+
+fn failed(reason: &str) -> FaucetError {
+    FaucetError::VerificationFailed(reason.to_string())
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut zeros = 0;
+    for byte in bytes {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
+}
+
+/// Compare `a` and `b` in time that depends only on their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn solve(challenge: &ProofOfWorkChallenge, recipient: SuiAddress) -> RequestProof {
+        let mut prefix = hex::decode(&challenge.challenge).unwrap();
+        prefix.extend_from_slice(recipient.as_ref());
+        let nonce = (0u64..)
+            .find(|nonce| {
+                let mut solution = prefix.clone();
+                solution.extend_from_slice(&nonce.to_le_bytes());
+                leading_zero_bits(&Sha256::digest(&solution).digest) >= challenge.difficulty as u32
+            })
+            .unwrap();
+
+        RequestProof::ProofOfWork {
+            challenge: challenge.challenge.clone(),
+            nonce,
+        }
+    }
+
+    fn reason(result: Result<(), FaucetError>) -> String {
+        match result {
+            Err(FaucetError::VerificationFailed(reason)) => reason,
+            other => panic!("Expected verification to fail, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0xff]), 0);
+        assert_eq!(leading_zero_bits(&[0x00, 0x10, 0x00]), 11);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00]), 16);
+    }
+
+    #[test]
+    fn test_noop() {
+        let recipient = SuiAddress::random_for_testing_only();
+        assert!(NoopVerifier.challenge().is_none());
+        assert!(NoopVerifier.verify(recipient, None).is_ok());
+    }
+
+    #[test]
+    fn test_proof_of_work() {
+        let verifier = ProofOfWorkVerifier::new(8, Duration::from_secs(60));
+        let recipient = SuiAddress::random_for_testing_only();
+        let challenge = verifier.challenge_at(NOW);
+        assert_eq!(challenge.expires_at_ms, NOW + 60_000);
+
+        let proof = solve(&challenge, recipient);
+        verifier.verify_at(recipient, Some(&proof), NOW).unwrap();
+
+        // Challenges can only be used once.
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&proof), NOW + 1)),
+            "Challenge has already been used",
+        );
+    }
+
+    #[test]
+    fn test_proof_of_work_rejected() {
+        // A high enough difficulty that a solution for one recipient is very unlikely to also be
+        // a solution for another.
+        let verifier = ProofOfWorkVerifier::new(16, Duration::from_secs(60));
+        let recipient = SuiAddress::random_for_testing_only();
+        let challenge = verifier.challenge_at(NOW);
+        let proof = solve(&challenge, recipient);
+
+        assert_eq!(
+            reason(verifier.verify_at(recipient, None, NOW)),
+            "Request requires a solution to a proof-of-work challenge",
+        );
+
+        // Solutions are bound to their recipient.
+        let other = SuiAddress::random_for_testing_only();
+        assert_eq!(
+            reason(verifier.verify_at(other, Some(&proof), NOW)),
+            "Incorrect solution to challenge",
+        );
+
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&proof), NOW + 60_000)),
+            "Challenge has expired",
+        );
+
+        // Challenges from another faucet (with a different key) are not accepted.
+        let other_faucet = ProofOfWorkVerifier::new(16, Duration::from_secs(60));
+        assert_eq!(
+            reason(other_faucet.verify_at(recipient, Some(&proof), NOW)),
+            "Challenge was not issued by this faucet",
+        );
+
+        let malformed = RequestProof::ProofOfWork {
+            challenge: "abcd".to_string(),
+            nonce: 0,
+        };
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&malformed), NOW)),
+            "Malformed challenge",
+        );
+    }
+
+    #[test]
+    fn test_proof_of_work_shared_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("pow.key");
+        std::fs::write(&path, hex::encode([7u8; 32])).unwrap();
+
+        // Challenges issued before a restart, or by another replica, are still accepted.
+        let issuer = ProofOfWorkVerifier::from_file(&path, 8, Duration::from_secs(60)).unwrap();
+        let replica = ProofOfWorkVerifier::from_file(&path, 8, Duration::from_secs(60)).unwrap();
+        let recipient = SuiAddress::random_for_testing_only();
+        let proof = solve(&issuer.challenge_at(NOW), recipient);
+        replica.verify_at(recipient, Some(&proof), NOW).unwrap();
+
+        std::fs::write(&path, "").unwrap();
+        assert!(ProofOfWorkVerifier::from_file(&path, 8, Duration::from_secs(60)).is_err());
+    }
+
+    #[test]
+    fn test_used_proofs_full() {
+        let used = UsedProofs::new(2);
+        used.use_once(1, NOW + 10, NOW).unwrap();
+        used.use_once(2, NOW + 20, NOW).unwrap();
+
+        // Unexpired proofs are not forgotten to make room for new ones.
+        assert!(used.use_once(3, NOW + 30, NOW + 5).is_err());
+        assert!(used.use_once(1, NOW + 10, NOW + 5).is_err());
+
+        // Expired proofs are.
+        used.use_once(3, NOW + 30, NOW + 10).unwrap();
+        assert!(used.use_once(2, NOW + 20, NOW + 10).is_err());
+    }
+
+    #[test]
+    fn test_token() {
+        let verifier = TokenVerifier::new(b"shared secret");
+        let recipient = SuiAddress::random_for_testing_only();
+        let expires_at_ms = NOW + 60_000;
+
+        let proof = RequestProof::Token {
+            token: verifier.token(recipient, expires_at_ms),
+            expires_at_ms,
+        };
+        verifier.verify_at(recipient, Some(&proof), NOW).unwrap();
+
+        // Tokens can only be used once.
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&proof), NOW + 1)),
+            "Token has already been used",
+        );
+
+        assert_eq!(
+            reason(verifier.verify_at(recipient, None, NOW)),
+            "Request requires a token",
+        );
+
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&proof), expires_at_ms)),
+            "Token has expired",
+        );
+
+        // Tokens are bound to their recipient, expiry time, and key.
+        let other = SuiAddress::random_for_testing_only();
+        assert_eq!(
+            reason(verifier.verify_at(other, Some(&proof), NOW)),
+            "Invalid token",
+        );
+
+        let extended = RequestProof::Token {
+            token: verifier.token(recipient, expires_at_ms),
+            expires_at_ms: expires_at_ms + 1,
+        };
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&extended), NOW)),
+            "Invalid token",
+        );
+
+        let forged = RequestProof::Token {
+            token: TokenVerifier::new(b"guess").token(recipient, expires_at_ms),
+            expires_at_ms,
+        };
+        assert_eq!(
+            reason(verifier.verify_at(recipient, Some(&forged), NOW)),
+            "Invalid token",
+        );
+    }
+}
@@ -4,7 +4,11 @@
 use crate::{error::Error as SuiGraphQLError, types::big_int::BigInt};
 use async_graphql::*;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeSet, path::PathBuf, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    time::Duration,
+};
 use sui_json_rpc::name_service::NameServiceConfig;
 
 use crate::functional_group::FunctionalGroup;
@@ -23,6 +27,10 @@ const MAX_TYPE_NODES: u32 = 256;
 const MAX_MOVE_VALUE_DEPTH: u32 = 128;
 
 const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 40_000;
+const DEFAULT_SUBSCRIPTION_POLL_INTERVAL_MS: u64 = 1_000;
+const DEFAULT_MAX_SUBSCRIPTIONS: u32 = 1_000;
+const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: u32 = 10;
+const DEFAULT_CLIENT_BUDGET_WINDOW_MS: u64 = 60_000;
 
 const DEFAULT_IDE_TITLE: &str = "Sui GraphQL IDE";
 
@@ -61,6 +69,12 @@ pub struct ServiceConfig {
 
     #[serde(default)]
     pub(crate) experiments: Experiments,
+
+    #[serde(default)]
+    pub(crate) client_budgets: ClientBudgets,
+
+    #[serde(default)]
+    pub(crate) persisted_queries: PersistedQueries,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Copy)]
@@ -82,6 +96,12 @@ pub struct Limits {
     pub max_page_size: u64,
     #[serde(default)]
     pub request_timeout_ms: u64,
+    #[serde(default = "default_subscription_poll_interval_ms")]
+    pub subscription_poll_interval_ms: u64,
+    #[serde(default = "default_max_subscriptions")]
+    pub max_subscriptions: u32,
+    #[serde(default = "default_max_subscriptions_per_connection")]
+    pub max_subscriptions_per_connection: u32,
     #[serde(default)]
     pub max_type_argument_depth: u32,
     #[serde(default)]
@@ -113,6 +133,43 @@ impl Limits {
     }
 }
 
+/// Budgets of query cost (measured in query nodes) that clients can spend over a rolling window.
+/// Clients are identified by their API key, if they send a known one, and otherwise by
+/// `client_header` if it is configured, or by IP address.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClientBudgets {
+    /// Length of the window that budgets are spent over, in milliseconds.  Budgets are replenished
+    /// continuously, so that a client that has spent its budget regains all of it after a window.
+    #[serde(default = "default_client_budget_window_ms")]
+    pub window_ms: u64,
+    /// Budget for clients identified by their IP address, or `client_header`.  If absent, their
+    /// queries are not metered.
+    #[serde(default)]
+    pub default_budget: Option<u64>,
+    /// Header identifying clients without an API key, e.g. `x-forwarded-for`, when the service is
+    /// behind a proxy, which must set it.  If it holds a comma-separated list, the last entry is
+    /// used, as it is the one added by the proxy.  If absent, or a request does not have it, the
+    /// client is identified by the IP address it connected from.
+    #[serde(default)]
+    pub client_header: Option<String>,
+    /// Budgets for clients identified by their API key, keyed by API key.
+    #[serde(default)]
+    pub api_keys: BTreeMap<String, u64>,
+}
+
+/// Registry of queries that clients can run by their SHA-256 hash, rather than by their text.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct PersistedQueries {
+    /// Reject queries from clients that are not in the registry.
+    #[serde(default)]
+    pub only_persisted: bool,
+    /// Text of the queries in the registry.
+    #[serde(default)]
+    pub queries: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Ide {
@@ -251,6 +308,22 @@ impl ServiceConfig {
         self.limits.request_timeout_ms
     }
 
+    /// Time in milliseconds between two polls of the latest checkpoint on behalf of subscriptions,
+    /// which query the database for new elements whenever it changes.
+    async fn subscription_poll_interval_ms(&self) -> u64 {
+        self.limits.subscription_poll_interval_ms
+    }
+
+    /// Maximum number of subscriptions served at once, across all connections.
+    async fn max_subscriptions(&self) -> u32 {
+        self.limits.max_subscriptions
+    }
+
+    /// Maximum number of subscriptions served at once over a single WebSocket connection.
+    async fn max_subscriptions_per_connection(&self) -> u32 {
+        self.limits.max_subscriptions_per_connection
+    }
+
     /// Maximum length of a query payload string.
     async fn max_query_payload_size(&self) -> u32 {
         self.limits.max_query_payload_size
@@ -303,6 +376,9 @@ impl Default for Limits {
             default_page_size: DEFAULT_PAGE_SIZE,
             max_page_size: MAX_PAGE_SIZE,
             request_timeout_ms: DEFAULT_REQUEST_TIMEOUT_MS,
+            subscription_poll_interval_ms: DEFAULT_SUBSCRIPTION_POLL_INTERVAL_MS,
+            max_subscriptions: DEFAULT_MAX_SUBSCRIPTIONS,
+            max_subscriptions_per_connection: DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION,
             max_type_argument_depth: MAX_TYPE_ARGUMENT_DEPTH,
             max_type_argument_width: MAX_TYPE_ARGUMENT_WIDTH,
             max_type_nodes: MAX_TYPE_NODES,
@@ -311,11 +387,42 @@ impl Default for Limits {
     }
 }
 
+impl Default for ClientBudgets {
+    fn default() -> Self {
+        Self {
+            window_ms: default_client_budget_window_ms(),
+            default_budget: None,
+            client_header: None,
+            api_keys: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_subscription_poll_interval_ms() -> u64 {
+    DEFAULT_SUBSCRIPTION_POLL_INTERVAL_MS
+}
+
+fn default_max_subscriptions() -> u32 {
+    DEFAULT_MAX_SUBSCRIPTIONS
+}
+
+fn default_max_subscriptions_per_connection() -> u32 {
+    DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION
+}
+
+fn default_client_budget_window_ms() -> u64 {
+    DEFAULT_CLIENT_BUDGET_WINDOW_MS
+}
+
 #[derive(Serialize, Clone, Deserialize, Debug, Eq, PartialEq)]
 pub struct InternalFeatureConfig {
     #[serde(default)]
     pub(crate) query_limits_checker: bool,
     #[serde(default)]
+    pub(crate) client_budget: bool,
+    #[serde(default)]
+    pub(crate) persisted_queries: bool,
+    #[serde(default)]
     pub(crate) feature_gate: bool,
     #[serde(default)]
     pub(crate) logger: bool,
@@ -335,6 +442,8 @@ impl Default for InternalFeatureConfig {
     fn default() -> Self {
         Self {
             query_limits_checker: true,
+            client_budget: true,
+            persisted_queries: true,
             feature_gate: true,
             logger: true,
             query_timeout: true,
@@ -427,6 +536,9 @@ mod tests {
                 default-page-size = 20
                 max-page-size = 50
                 request-timeout-ms = 27000
+                subscription-poll-interval-ms = 500
+                max-subscriptions = 100
+                max-subscriptions-per-connection = 5
                 max-type-argument-depth = 32
                 max-type-argument-width = 64
                 max-type-nodes = 128
@@ -445,6 +557,9 @@ mod tests {
                 default_page_size: 20,
                 max_page_size: 50,
                 request_timeout_ms: 27_000,
+                subscription_poll_interval_ms: 500,
+                max_subscriptions: 100,
+                max_subscriptions_per_connection: 5,
                 max_type_argument_depth: 32,
                 max_type_argument_width: 64,
                 max_type_nodes: 128,
@@ -456,6 +571,27 @@ mod tests {
         assert_eq!(actual, expect)
     }
 
+    #[test]
+    fn test_read_partial_limits_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [limits]
+                max-query-depth = 100
+            "#,
+        )
+        .unwrap();
+
+        // Subscription limits missing from the table keep their defaults.
+        assert_eq!(
+            actual.limits.subscription_poll_interval_ms,
+            DEFAULT_SUBSCRIPTION_POLL_INTERVAL_MS
+        );
+        assert_eq!(actual.limits.max_subscriptions, DEFAULT_MAX_SUBSCRIPTIONS);
+        assert_eq!(
+            actual.limits.max_subscriptions_per_connection,
+            DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION
+        );
+    }
+
     #[test]
     fn test_read_enabled_features_in_service_config() {
         let actual = ServiceConfig::read(
@@ -471,7 +607,7 @@ mod tests {
         let expect = ServiceConfig {
             limits: Limits::default(),
             disabled_features: BTreeSet::from([G::Coins, G::NameService]),
-            experiments: Experiments::default(),
+            ..Default::default()
         };
 
         assert_eq!(actual, expect)
@@ -494,6 +630,63 @@ mod tests {
         assert_eq!(actual, expect)
     }
 
+    #[test]
+    fn test_read_client_budgets_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [client-budgets]
+                default-budget = 5000
+                client-header = "x-forwarded-for"
+
+                [client-budgets.api-keys]
+                explorer = 100000
+                wallet = 50000
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            client_budgets: ClientBudgets {
+                window_ms: 60_000,
+                default_budget: Some(5000),
+                client_header: Some("x-forwarded-for".to_string()),
+                api_keys: BTreeMap::from([
+                    ("explorer".to_string(), 100_000),
+                    ("wallet".to_string(), 50_000),
+                ]),
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
+    #[test]
+    fn test_read_persisted_queries_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [persisted-queries]
+                only-persisted = true
+                queries = [
+                  "{ chainIdentifier }",
+                  "query Epoch { epoch { epochId } }",
+                ]
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            persisted_queries: PersistedQueries {
+                only_persisted: true,
+                queries: vec![
+                    "{ chainIdentifier }".to_string(),
+                    "query Epoch { epoch { epochId } }".to_string(),
+                ],
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
     #[test]
     fn test_read_everything_in_service_config() {
         let actual = ServiceConfig::read(
@@ -508,6 +701,9 @@ mod tests {
                 default-page-size = 10
                 max-page-size = 20
                 request-timeout-ms = 30000
+                subscription-poll-interval-ms = 2000
+                max-subscriptions = 1000
+                max-subscriptions-per-connection = 10
                 max-type-argument-depth = 32
                 max-type-argument-width = 64
                 max-type-nodes = 128
@@ -515,6 +711,16 @@ mod tests {
 
                 [experiments]
                 test-flag = true
+
+                [client-budgets]
+                window-ms = 30000
+                default-budget = 1000
+
+                [client-budgets.api-keys]
+                explorer = 20000
+
+                [persisted-queries]
+                queries = ["{ chainIdentifier }"]
             "#,
         )
         .unwrap();
@@ -529,6 +735,9 @@ mod tests {
                 default_page_size: 10,
                 max_page_size: 20,
                 request_timeout_ms: 30_000,
+                subscription_poll_interval_ms: 2_000,
+                max_subscriptions: 1_000,
+                max_subscriptions_per_connection: 10,
                 max_type_argument_depth: 32,
                 max_type_argument_width: 64,
                 max_type_nodes: 128,
@@ -536,6 +745,16 @@ mod tests {
             },
             disabled_features: BTreeSet::from([FunctionalGroup::Analytics]),
             experiments: Experiments { test_flag: true },
+            client_budgets: ClientBudgets {
+                window_ms: 30_000,
+                default_budget: Some(1000),
+                client_header: None,
+                api_keys: BTreeMap::from([("explorer".to_string(), 20_000)]),
+            },
+            persisted_queries: PersistedQueries {
+                only_persisted: false,
+                queries: vec!["{ chainIdentifier }".to_string()],
+            },
         };
 
         assert_eq!(actual, expect);
@@ -7,13 +7,19 @@ use diesel::{
 };
 use sui_indexer::{
     models_v2::epoch::QueryableEpochInfo,
-    schema_v2::{checkpoints, display, epochs, events, objects, transactions},
+    schema_v2::{
+        address_metrics, checkpoints, display, epochs, events, move_call_metrics, objects,
+        objects_history, transactions, tx_count_metrics,
+    },
     types_v2::OwnerType,
 };
 
 use crate::{
     error::Error,
-    types::{event::EventFilter, object::ObjectFilter, transaction_block::TransactionBlockFilter},
+    types::{
+        event::EventFilter, network_metrics::MetricsWindow, object::ObjectFilter,
+        transaction_block::TransactionBlockFilter,
+    },
 };
 use diesel::{
     query_builder::{BoxedSelectStatement, FromClause, QueryId},
@@ -34,6 +40,18 @@ pub(crate) type BalanceQuery<'a, DB> = BoxedSelectStatement<
     objects::dsl::coin_type,
 >;
 
+pub(crate) type HistoryBalanceQuery<'a, DB> = BoxedSelectStatement<
+    'a,
+    (
+        diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
+        diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
+        diesel::sql_types::Nullable<diesel::sql_types::Text>,
+    ),
+    FromClause<objects_history::table>,
+    DB,
+    objects_history::dsl::coin_type,
+>;
+
 pub type QueryableEpochInfoType<DB> = SqlTypeOf<AsSelect<QueryableEpochInfo, DB>>;
 
 pub(crate) trait GenericQueryBuilder<DB: Backend> {
@@ -76,6 +94,38 @@ pub(crate) trait GenericQueryBuilder<DB: Backend> {
     ) -> Result<objects::BoxedQuery<'static, DB>, Error>;
     fn multi_get_balances(address: Vec<u8>) -> BalanceQuery<'static, DB>;
     fn get_balance(address: Vec<u8>, coin_type: String) -> BalanceQuery<'static, DB>;
+    /// The latest version of the object at `address` (or the version `version`) written at or
+    /// before `checkpoint`, which may record that the object was wrapped or deleted.
+    fn get_obj_at_checkpoint(
+        address: Vec<u8>,
+        version: Option<i64>,
+        checkpoint: i64,
+    ) -> objects_history::BoxedQuery<'static, DB>;
+    fn multi_get_coins_at_checkpoint(
+        before: Option<Vec<u8>>,
+        after: Option<Vec<u8>>,
+        limit: PageLimit,
+        address: Option<Vec<u8>>,
+        coin_type: String,
+        checkpoint: i64,
+    ) -> objects_history::BoxedQuery<'static, DB>;
+    fn multi_get_objs_at_checkpoint(
+        before: Option<Vec<u8>>,
+        after: Option<Vec<u8>>,
+        limit: PageLimit,
+        filter: Option<ObjectFilter>,
+        owner_type: Option<OwnerType>,
+        checkpoint: i64,
+    ) -> Result<objects_history::BoxedQuery<'static, DB>, Error>;
+    fn multi_get_balances_at_checkpoint(
+        address: Vec<u8>,
+        checkpoint: i64,
+    ) -> HistoryBalanceQuery<'static, DB>;
+    fn get_balance_at_checkpoint(
+        address: Vec<u8>,
+        coin_type: String,
+        checkpoint: i64,
+    ) -> HistoryBalanceQuery<'static, DB>;
     fn multi_get_checkpoints(
         before: Option<i64>,
         after: Option<i64>,
@@ -88,6 +138,20 @@ pub(crate) trait GenericQueryBuilder<DB: Backend> {
         limit: PageLimit,
         filter: Option<EventFilter>,
     ) -> Result<events::BoxedQuery<'static, DB>, Error>;
+    fn get_address_metrics(checkpoint: i64) -> address_metrics::BoxedQuery<'static, DB>;
+    fn multi_get_address_metrics(
+        before: Option<i64>,
+        after: Option<i64>,
+        limit: PageLimit,
+        window: Option<MetricsWindow>,
+    ) -> address_metrics::BoxedQuery<'static, DB>;
+    fn multi_get_tx_count_metrics(
+        before: Option<i64>,
+        after: Option<i64>,
+        limit: PageLimit,
+        window: Option<MetricsWindow>,
+    ) -> tx_count_metrics::BoxedQuery<'static, DB>;
+    fn get_move_call_metrics(epoch: i64) -> move_call_metrics::BoxedQuery<'static, DB>;
 }
 
 /// The struct returned for query.explain()
@@ -7,6 +7,7 @@ use crate::{
     error::Error,
     types::{
         address::{Address, AddressTransactionBlockRelationship},
+        address_metrics::AddressMetrics,
         balance::Balance,
         big_int::BigInt,
         checkpoint::Checkpoint,
@@ -20,11 +21,13 @@ use crate::{
         epoch::Epoch,
         event::{Event, EventFilter},
         gas::GasCostSummary,
+        move_call_metrics::MoveCallMetrics,
         move_function::MoveFunction,
         move_module::MoveModule,
         move_object::MoveObject,
         move_package::MovePackage,
         move_type::MoveType,
+        network_metrics::{MetricsWindow, NetworkMetrics, TransactionCountMetrics},
         object::{Object, ObjectFilter},
         protocol_config::{ProtocolConfigAttr, ProtocolConfigFeatureFlag, ProtocolConfigs},
         stake::StakedSui,
@@ -42,11 +45,19 @@ use sui_indexer::{
     apis::GovernanceReadApiV2,
     indexer_reader::IndexerReader,
     models_v2::{
-        checkpoints::StoredCheckpoint, display::StoredDisplay, epoch::QueryableEpochInfo,
-        events::StoredEvent, objects::StoredObject, transactions::StoredTransaction,
+        address_metrics::StoredAddressMetrics,
+        checkpoints::StoredCheckpoint,
+        display::StoredDisplay,
+        epoch::QueryableEpochInfo,
+        events::StoredEvent,
+        move_call_metrics::QueriedMoveCallMetrics,
+        network_metrics::StoredNetworkMetrics,
+        objects::{StoredHistoryObject, StoredObject},
+        transactions::StoredTransaction,
+        tx_count_metrics::StoredTxCountMetrics,
     },
-    schema_v2::transactions,
-    types_v2::OwnerType,
+    schema_v2::{move_call_metrics, transactions},
+    types_v2::{ObjectStatus, OwnerType},
     PgConnectionPoolConfig,
 };
 use sui_json_rpc::{
@@ -105,6 +116,10 @@ pub enum DbValidationError {
     PageSizeExceeded(u64, u64),
     #[error("Invalid type provided as filter: {0}")]
     InvalidType(String),
+    #[error("Checkpoint {0} has not been indexed yet. The latest checkpoint indexed is {1}")]
+    CheckpointNotIndexed(u64, u64),
+    #[error("Checkpoint {0} is no longer available. The earliest checkpoint available is {1}")]
+    CheckpointPruned(u64, u64),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -122,6 +137,7 @@ pub(crate) enum PageLimit {
     Last(i64),
 }
 
+#[derive(Clone)]
 pub(crate) struct PgManager {
     pub inner: IndexerReader,
     pub limits: Limits,
@@ -179,6 +195,34 @@ impl PgManager {
         .await
     }
 
+    /// Fetch the object at `address` as it was at `checkpoint` (optionally at a specific
+    /// `version`), from the object history.  Returns `None` if the object did not exist, or had
+    /// been wrapped or deleted, by then.
+    async fn get_obj_at_checkpoint(
+        &self,
+        address: Vec<u8>,
+        version: Option<i64>,
+        checkpoint: i64,
+    ) -> Result<Option<StoredObject>, Error> {
+        let stored: Option<StoredHistoryObject> = self
+            .run_query_async_with_cost(
+                move || {
+                    Ok(QueryBuilder::get_obj_at_checkpoint(
+                        address.clone(),
+                        version,
+                        checkpoint,
+                    ))
+                },
+                |query| move |conn| query.get_result::<StoredHistoryObject>(conn).optional(),
+            )
+            .await?;
+
+        Ok(stored
+            .filter(|o| o.object_status == ObjectStatus::Active as i16)
+            .map(StoredObject::try_from)
+            .transpose()?)
+    }
+
     async fn get_obj_by_type(&self, object_type: String) -> Result<Option<StoredObject>, Error> {
         self.run_query_async_with_cost(
             move || Ok(QueryBuilder::get_obj_by_type(object_type.clone())),
@@ -263,6 +307,7 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
+        checkpoint: Option<i64>,
     ) -> Result<Option<(Vec<StoredObject>, bool)>, Error> {
         let limit = self.validate_page_limit(first, last)?;
         let before = before
@@ -274,8 +319,27 @@ impl PgManager {
         let coin_type = parse_to_type_tag(Some(coin_type))
             .map_err(|e| Error::InvalidCoinType(e.to_string()))?
             .to_canonical_string(/* with_prefix */ true);
-        let result: Option<Vec<StoredObject>> = self
-            .run_query_async_with_cost(
+
+        let result: Option<Vec<StoredObject>> = if let Some(checkpoint) = checkpoint {
+            let result: Option<Vec<StoredHistoryObject>> = self
+                .run_query_async_with_cost(
+                    move || {
+                        Ok(QueryBuilder::multi_get_coins_at_checkpoint(
+                            before.clone(),
+                            after.clone(),
+                            limit,
+                            address.clone(),
+                            coin_type.clone(),
+                            checkpoint,
+                        ))
+                    },
+                    |query| move |conn| query.load(conn).optional(),
+                )
+                .await?;
+
+            result.map(live_objects).transpose()?
+        } else {
+            self.run_query_async_with_cost(
                 move || {
                     Ok(QueryBuilder::multi_get_coins(
                         before.clone(),
@@ -287,7 +351,8 @@ impl PgManager {
                 },
                 |query| move |conn| query.load(conn).optional(),
             )
-            .await?;
+            .await?
+        };
 
         result
             .map(|mut stored_objs| {
@@ -309,7 +374,23 @@ impl PgManager {
         &self,
         address: Vec<u8>,
         coin_type: String,
+        checkpoint: Option<i64>,
     ) -> Result<Option<(Option<i64>, Option<i64>, Option<String>)>, Error> {
+        if let Some(checkpoint) = checkpoint {
+            return self
+                .run_query_async_with_cost(
+                    move || {
+                        Ok(QueryBuilder::get_balance_at_checkpoint(
+                            address.clone(),
+                            coin_type.clone(),
+                            checkpoint,
+                        ))
+                    },
+                    |query| move |conn| query.get_result(conn).optional(),
+                )
+                .await;
+        }
+
         self.run_query_async_with_cost(
             move || {
                 Ok(QueryBuilder::get_balance(
@@ -329,6 +410,7 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
+        checkpoint: Option<i64>,
     ) -> Result<Option<Vec<(Option<i64>, Option<i64>, Option<String>)>>, Error> {
         // Todo (wlmyng): paginating on balances does not really make sense
         // We'll always need to calculate all balances first
@@ -336,6 +418,20 @@ impl PgManager {
             return Err(DbValidationError::PaginationDisabledOnBalances.into());
         }
 
+        if let Some(checkpoint) = checkpoint {
+            return self
+                .run_query_async_with_cost(
+                    move || {
+                        Ok(QueryBuilder::multi_get_balances_at_checkpoint(
+                            address.clone(),
+                            checkpoint,
+                        ))
+                    },
+                    |query| move |conn| query.load(conn).optional(),
+                )
+                .await;
+        }
+
         self.run_query_async_with_cost(
             move || Ok(QueryBuilder::multi_get_balances(address.clone())),
             |query| move |conn| query.load(conn).optional(),
@@ -547,6 +643,7 @@ impl PgManager {
         before: Option<String>,
         filter: Option<ObjectFilter>,
         owner_type: Option<OwnerType>,
+        checkpoint: Option<i64>,
     ) -> Result<Option<(Vec<StoredObject>, bool)>, Error> {
         let limit = self.validate_page_limit(first, last)?;
         let before = before
@@ -556,19 +653,37 @@ impl PgManager {
             .map(|cursor| self.parse_obj_cursor(&cursor))
             .transpose()?;
 
-        let query = move || {
-            QueryBuilder::multi_get_objs(
-                before.clone(),
-                after.clone(),
-                limit,
-                filter.clone(),
-                owner_type,
-            )
-        };
+        let result: Option<Vec<StoredObject>> = if let Some(checkpoint) = checkpoint {
+            let query = move || {
+                QueryBuilder::multi_get_objs_at_checkpoint(
+                    before.clone(),
+                    after.clone(),
+                    limit,
+                    filter.clone(),
+                    owner_type,
+                    checkpoint,
+                )
+            };
 
-        let result: Option<Vec<StoredObject>> = self
-            .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
-            .await?;
+            let result: Option<Vec<StoredHistoryObject>> = self
+                .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
+                .await?;
+
+            result.map(live_objects).transpose()?
+        } else {
+            let query = move || {
+                QueryBuilder::multi_get_objs(
+                    before.clone(),
+                    after.clone(),
+                    limit,
+                    filter.clone(),
+                    owner_type,
+                )
+            };
+
+            self.run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
+                .await?
+        };
 
         result
             .map(|mut stored_objs| {
@@ -585,6 +700,169 @@ impl PgManager {
             })
             .transpose()
     }
+
+    async fn get_address_metrics(
+        &self,
+        checkpoint: i64,
+    ) -> Result<Option<StoredAddressMetrics>, Error> {
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::get_address_metrics(checkpoint)),
+            |query| move |conn| query.get_result::<StoredAddressMetrics>(conn).optional(),
+        )
+        .await
+    }
+
+    async fn multi_get_address_metrics(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        window: Option<MetricsWindow>,
+    ) -> Result<Option<(Vec<StoredAddressMetrics>, bool)>, Error> {
+        self.multi_get_checkpoint_metrics(
+            first,
+            after,
+            last,
+            before,
+            move |before, after, limit| {
+                QueryBuilder::multi_get_address_metrics(before, after, limit, window.clone())
+            },
+        )
+        .await
+    }
+
+    async fn multi_get_tx_count_metrics(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        window: Option<MetricsWindow>,
+    ) -> Result<Option<(Vec<StoredTxCountMetrics>, bool)>, Error> {
+        self.multi_get_checkpoint_metrics(
+            first,
+            after,
+            last,
+            before,
+            move |before, after, limit| {
+                QueryBuilder::multi_get_tx_count_metrics(before, after, limit, window.clone())
+            },
+        )
+        .await
+    }
+
+    /// Fetches a page of metrics that are keyed by checkpoint, and whether there is a next page.
+    /// `build_query` builds the query for the page, given the checkpoints it is bounded by and its
+    /// limit, and must fetch an extra row to detect the next page.
+    async fn multi_get_checkpoint_metrics<T, Q, B>(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        build_query: B,
+    ) -> Result<Option<(Vec<T>, bool)>, Error>
+    where
+        T: Send + 'static,
+        B: Fn(Option<i64>, Option<i64>, PageLimit) -> Q + Send + 'static,
+        Q: diesel::query_builder::QueryFragment<diesel::pg::Pg>
+            + diesel::query_builder::Query
+            + diesel::query_builder::QueryId
+            + diesel::query_dsl::LoadQuery<'static, diesel::PgConnection, T>
+            + RunQueryDsl<diesel::PgConnection>
+            + Send
+            + 'static,
+    {
+        validate_cursor_pagination(&first, &after, &last, &before)?;
+        let limit = self.validate_page_limit(first, last)?;
+        let before = before
+            .map(|cursor| self.parse_checkpoint_cursor(&cursor))
+            .transpose()?;
+        let after = after
+            .map(|cursor| self.parse_checkpoint_cursor(&cursor))
+            .transpose()?;
+
+        let result: Option<Vec<T>> = self
+            .run_query_async_with_cost(
+                move || Ok(build_query(before, after, limit)),
+                |query| move |conn| query.load(conn).optional(),
+            )
+            .await?;
+
+        Ok(result.map(|mut stored_metrics| {
+            let has_next_page = stored_metrics.len() as i64 > limit.value();
+            if has_next_page {
+                stored_metrics.pop();
+            }
+
+            if last.is_some() {
+                stored_metrics.reverse();
+            }
+
+            (stored_metrics, has_next_page)
+        }))
+    }
+
+    async fn get_network_metrics(&self) -> Result<Option<StoredNetworkMetrics>, Error> {
+        // `network_metrics` is a view maintained by the indexer, rather than a table. Its metrics
+        // are NULL until the indexer's analytics have been computed, and its estimates are
+        // negative for tables that have never been analyzed, so default them to 0.
+        self.run_query_async(|conn| {
+            diesel::sql_query(
+                "SELECT \
+                    COALESCE(current_tps, 0) AS current_tps, \
+                    COALESCE(tps_30_days, 0) AS tps_30_days, \
+                    GREATEST(COALESCE(total_packages, 0), 0) AS total_packages, \
+                    GREATEST(COALESCE(total_addresses, 0), 0) AS total_addresses, \
+                    GREATEST(COALESCE(total_objects, 0), 0) AS total_objects, \
+                    COALESCE(current_epoch, 0) AS current_epoch, \
+                    COALESCE(current_checkpoint, 0) AS current_checkpoint \
+                FROM network_metrics;",
+            )
+            .get_result::<StoredNetworkMetrics>(conn)
+            .optional()
+        })
+        .await
+    }
+
+    /// Fetches the move call metrics computed in `epoch`, or in the latest epoch they were
+    /// computed in, if `epoch` is not provided.
+    async fn get_move_call_metrics(
+        &self,
+        epoch: Option<i64>,
+    ) -> Result<Option<(i64, Vec<QueriedMoveCallMetrics>)>, Error> {
+        let epoch = match epoch {
+            Some(epoch) => epoch,
+            None => {
+                let subquery = move_call_metrics::dsl::move_call_metrics
+                    .order(move_call_metrics::dsl::epoch.desc())
+                    .select(move_call_metrics::dsl::epoch)
+                    .limit(1)
+                    .into_boxed();
+
+                let latest = self
+                    .run_query_async(|conn| subquery.get_result::<i64>(conn).optional())
+                    .await?;
+
+                // Metrics have not been computed for any epoch yet.
+                let Some(latest) = latest else {
+                    return Ok(None);
+                };
+
+                latest
+            }
+        };
+
+        let stored_metrics: Vec<QueriedMoveCallMetrics> = self
+            .run_query_async_with_cost(
+                move || Ok(QueryBuilder::get_move_call_metrics(epoch)),
+                |query| move |conn| query.load(conn),
+            )
+            .await?;
+
+        Ok(Some((epoch, stored_metrics)))
+    }
 }
 
 /// Implement methods to be used by graphql resolvers
@@ -863,24 +1141,41 @@ impl PgManager {
         ))
     }
 
+    /// Fetch the object at `address` (optionally at a specific `version`).  If a `checkpoint` is
+    /// provided, the object is fetched as it was at that checkpoint, and so are any other objects
+    /// reached through it.
     pub(crate) async fn fetch_obj(
         &self,
         address: SuiAddress,
         version: Option<u64>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Object>, Error> {
         let address = address.into_vec();
         let version = version.map(|v| v as i64);
 
-        let stored_obj = self.get_obj(address, version).await?;
-        stored_obj.map(Object::try_from).transpose()
+        let stored_obj = if let Some(checkpoint) = checkpoint {
+            self.get_obj_at_checkpoint(address, version, checkpoint as i64)
+                .await?
+        } else {
+            self.get_obj(address, version).await?
+        };
+
+        stored_obj
+            .map(|stored_obj| {
+                let mut object = Object::try_from(stored_obj)?;
+                object.checkpoint_viewed_at = checkpoint;
+                Ok(object)
+            })
+            .transpose()
     }
 
     pub(crate) async fn fetch_move_obj(
         &self,
         address: SuiAddress,
         version: Option<u64>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<MoveObject>, Error> {
-        let Some(object) = self.fetch_obj(address, version).await? else {
+        let Some(object) = self.fetch_obj(address, version, checkpoint).await? else {
             return Ok(None);
         };
 
@@ -894,7 +1189,7 @@ impl PgManager {
         address: SuiAddress,
         version: Option<u64>,
     ) -> Result<Option<MovePackage>, Error> {
-        let Some(object) = self.fetch_obj(address, version).await? else {
+        let Some(object) = self.fetch_obj(address, version, None).await? else {
             return Ok(None);
         };
 
@@ -939,6 +1234,7 @@ impl PgManager {
         before: Option<String>,
         filter: Option<ObjectFilter>,
         owner: SuiAddress,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, Object>>, Error> {
         let filter = filter
             .map(|mut f| {
@@ -949,7 +1245,7 @@ impl PgManager {
                 owner: Some(owner),
                 ..Default::default()
             });
-        self.fetch_objs(first, after, last, before, Some(filter))
+        self.fetch_objs(first, after, last, before, Some(filter), checkpoint)
             .await
     }
 
@@ -960,13 +1256,22 @@ impl PgManager {
         last: Option<u64>,
         before: Option<String>,
         filter: Option<ObjectFilter>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, Object>>, Error> {
         validate_cursor_pagination(&first, &after, &last, &before)?;
         if let Some(filter) = &filter {
             self.validate_obj_filter(filter)?;
         }
         let objects = self
-            .multi_get_objs(first, after, last, before, filter, None)
+            .multi_get_objs(
+                first,
+                after,
+                last,
+                before,
+                filter,
+                None,
+                checkpoint.map(|c| c as i64),
+            )
             .await?;
 
         if let Some((stored_objs, has_next_page)) = objects {
@@ -977,7 +1282,10 @@ impl PgManager {
                     Object::try_from(stored_obj)
                         .map_err(|e| eprintln!("Error converting object: {:?}", e))
                         .ok()
-                        .map(|obj| Edge::new(obj.address.to_string(), obj))
+                        .map(|mut obj| {
+                            obj.checkpoint_viewed_at = checkpoint;
+                            Edge::new(obj.address.to_string(), obj)
+                        })
                 }));
             Ok(Some(connection))
         } else {
@@ -1022,6 +1330,7 @@ impl PgManager {
         &self,
         address: SuiAddress,
         coin_type: Option<String>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Balance>, Error> {
         let address = address.into_vec();
         let coin_type = parse_to_type_tag(coin_type.clone())
@@ -1030,6 +1339,7 @@ impl PgManager {
             .get_balance(
                 address,
                 coin_type.to_canonical_string(/* with_prefix */ true),
+                checkpoint.map(|c| c as i64),
             )
             .await?;
 
@@ -1055,10 +1365,18 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, Balance>>, Error> {
         let address = address.into_vec();
         let Some(balances) = self
-            .multi_get_balances(address, first, after, last, before)
+            .multi_get_balances(
+                address,
+                first,
+                after,
+                last,
+                before,
+                checkpoint.map(|c| c as i64),
+            )
             .await?
         else {
             return Ok(None);
@@ -1099,6 +1417,7 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, Coin>>, Error> {
         let address = address.map(|addr| addr.into_vec());
         let coin_type = coin_type.unwrap_or_else(|| {
@@ -1106,7 +1425,15 @@ impl PgManager {
         });
 
         let coins = self
-            .multi_get_coins(address, coin_type, first, after, last, before)
+            .multi_get_coins(
+                address,
+                coin_type,
+                first,
+                after,
+                last,
+                before,
+                checkpoint.map(|c| c as i64),
+            )
             .await?;
 
         let Some((stored_objs, has_next_page)) = coins else {
@@ -1115,7 +1442,8 @@ impl PgManager {
 
         let mut connection = Connection::new(false, has_next_page);
         for stored_obj in stored_objs {
-            let object = Object::try_from(stored_obj)?;
+            let mut object = Object::try_from(stored_obj)?;
+            object.checkpoint_viewed_at = checkpoint;
 
             let move_object = MoveObject::try_from(&object).map_err(|_| {
                 Error::Internal(format!(
@@ -1163,6 +1491,7 @@ impl PgManager {
 
         Ok(record.target_address.map(|address| Address {
             address: SuiAddress::from_array(address.to_inner()),
+            checkpoint_viewed_at: None,
         }))
     }
 
@@ -1174,6 +1503,22 @@ impl PgManager {
             .map(|(start, end)| (start as u64, end as u64))?)
     }
 
+    /// Check that `checkpoint` can be used to fix a consistent view of objects: It must have been
+    /// indexed already, so that the view does not change as more checkpoints are indexed, and it
+    /// must not have been pruned, so that the history of objects at that checkpoint is complete.
+    pub(crate) async fn validate_checkpoint_viewed_at(&self, checkpoint: u64) -> Result<(), Error> {
+        let (earliest, latest) = self.available_range().await?;
+        if checkpoint > latest {
+            return Err(DbValidationError::CheckpointNotIndexed(checkpoint, latest).into());
+        }
+
+        if checkpoint < earliest {
+            return Err(DbValidationError::CheckpointPruned(checkpoint, earliest).into());
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn default_name_service_name(
         &self,
         name_service_config: &NameServiceConfig,
@@ -1271,6 +1616,7 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, StakedSui>>, Error> {
         let obj_filter = ObjectFilter {
             type_: Some(MoveObjectType::staked_sui().to_canonical_string(/* with_prefix */ true)),
@@ -1287,6 +1633,7 @@ impl PgManager {
                 before,
                 Some(obj_filter),
                 Some(OwnerType::Address),
+                checkpoint.map(|c| c as i64),
             )
             .await?;
 
@@ -1296,7 +1643,8 @@ impl PgManager {
 
         let mut connection = Connection::new(false, has_next_page);
         for stored_obj in stored_objs {
-            let object = Object::try_from(stored_obj)?;
+            let mut object = Object::try_from(stored_obj)?;
+            object.checkpoint_viewed_at = checkpoint;
 
             let move_object = MoveObject::try_from(&object).map_err(|_| {
                 Error::Internal(format!(
@@ -1376,6 +1724,83 @@ impl PgManager {
         }
     }
 
+    pub(crate) async fn fetch_checkpoint_address_metrics(
+        &self,
+        checkpoint: u64,
+    ) -> Result<Option<AddressMetrics>, Error> {
+        self.get_address_metrics(checkpoint as i64)
+            .await?
+            .map(AddressMetrics::try_from)
+            .transpose()
+    }
+
+    pub(crate) async fn fetch_address_metrics(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<MetricsWindow>,
+    ) -> Result<Option<Connection<String, AddressMetrics>>, Error> {
+        let Some((stored_metrics, has_next_page)) = self
+            .multi_get_address_metrics(first, after, last, before, filter)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut connection = Connection::new(false, has_next_page);
+        for stored in stored_metrics {
+            let cursor = stored.checkpoint.to_string();
+            connection
+                .edges
+                .push(Edge::new(cursor, AddressMetrics::try_from(stored)?));
+        }
+
+        Ok(Some(connection))
+    }
+
+    pub(crate) async fn fetch_tx_count_metrics(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<MetricsWindow>,
+    ) -> Result<Option<Connection<String, TransactionCountMetrics>>, Error> {
+        let Some((stored_metrics, has_next_page)) = self
+            .multi_get_tx_count_metrics(first, after, last, before, filter)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut connection = Connection::new(false, has_next_page);
+        for stored in stored_metrics {
+            let cursor = stored.checkpoint_sequence_number.to_string();
+            connection.edges.push(Edge::new(
+                cursor,
+                TransactionCountMetrics::try_from(stored)?,
+            ));
+        }
+
+        Ok(Some(connection))
+    }
+
+    pub(crate) async fn fetch_network_metrics(&self) -> Result<Option<NetworkMetrics>, Error> {
+        Ok(self.get_network_metrics().await?.map(NetworkMetrics::from))
+    }
+
+    pub(crate) async fn fetch_move_call_metrics(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<Option<MoveCallMetrics>, Error> {
+        self.get_move_call_metrics(epoch.map(|e| e as i64))
+            .await?
+            .map(|(epoch, stored)| MoveCallMetrics::new(epoch as u64, stored))
+            .transpose()
+    }
+
     pub(crate) async fn fetch_dynamic_fields(
         &self,
         first: Option<u64>,
@@ -1383,6 +1808,7 @@ impl PgManager {
         last: Option<u64>,
         before: Option<String>,
         address: SuiAddress,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, DynamicField>>, Error> {
         let filter = ObjectFilter {
             owner: Some(address),
@@ -1397,6 +1823,7 @@ impl PgManager {
                 before,
                 Some(filter),
                 Some(OwnerType::Object),
+                checkpoint.map(|c| c as i64),
             )
             .await?;
 
@@ -1427,6 +1854,7 @@ impl PgManager {
                     stored_object: stored_obj,
                     df_object_id: cursor,
                     df_kind,
+                    checkpoint_viewed_at: checkpoint,
                 },
             ));
         }
@@ -1438,6 +1866,7 @@ impl PgManager {
         address: SuiAddress,
         name: DynamicFieldName,
         kind: DynamicFieldType,
+        checkpoint: Option<u64>,
     ) -> Result<Option<DynamicField>, Error> {
         let name_bcs_value = &name.bcs.0;
         let parent_object_id =
@@ -1458,7 +1887,12 @@ impl PgManager {
         )
         .map_err(|e| Error::Internal(format!("Deriving dynamic field id cannot fail: {e}")))?;
 
-        let stored_obj = self.get_obj(id.to_vec(), None).await?;
+        let stored_obj = if let Some(checkpoint) = checkpoint {
+            self.get_obj_at_checkpoint(id.to_vec(), None, checkpoint as i64)
+                .await?
+        } else {
+            self.get_obj(id.to_vec(), None).await?
+        };
         if let Some(stored_object) = stored_obj {
             let df_object_id = stored_object.df_object_id.as_ref().ok_or_else(|| {
                 Error::Internal("Dynamic field does not have df_object_id".to_string())
@@ -1469,6 +1903,7 @@ impl PgManager {
                 stored_object,
                 df_object_id,
                 df_kind: kind,
+                checkpoint_viewed_at: checkpoint,
             }));
         }
         Ok(None)
@@ -1540,6 +1975,7 @@ impl PgManager {
         before: Option<String>,
         name_service_config: &NameServiceConfig,
         owner: SuiAddress,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, SuinsRegistration>>, Error> {
         let suins_registration_type = format!(
             "{}::suins_registration::SuinsRegistration",
@@ -1563,6 +1999,7 @@ impl PgManager {
                 before,
                 Some(obj_filter),
                 Some(OwnerType::Address),
+                checkpoint.map(|c| c as i64),
             )
             .await?;
 
@@ -1572,7 +2009,8 @@ impl PgManager {
 
         let mut connection = Connection::new(false, has_next_page);
         for stored_obj in stored_objs {
-            let object = Object::try_from(stored_obj)?;
+            let mut object = Object::try_from(stored_obj)?;
+            object.checkpoint_viewed_at = checkpoint;
 
             let move_object = MoveObject::try_from(&object).map_err(|_| {
                 Error::Internal(format!(
@@ -1682,6 +2120,14 @@ impl TryFrom<StoredCheckpoint> for Checkpoint {
 }
 
 /// TODO: enfroce limits on first and last
+/// Convert rows from the object history into the live representation of those objects.
+fn live_objects(history: Vec<StoredHistoryObject>) -> Result<Vec<StoredObject>, Error> {
+    Ok(history
+        .into_iter()
+        .map(StoredObject::try_from)
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
 pub(crate) fn validate_cursor_pagination(
     first: &Option<u64>,
     after: &Option<String>,
@@ -1745,7 +2191,10 @@ impl From<Address> for SuiAddress {
 
 impl From<SuiAddress> for Address {
     fn from(a: SuiAddress) -> Self {
-        Address { address: a }
+        Address {
+            address: a,
+            checkpoint_viewed_at: None,
+        }
     }
 }
 
@@ -1765,6 +2214,7 @@ impl From<&NativeSuiAddress> for Address {
     fn from(a: &NativeSuiAddress) -> Self {
         Self {
             address: SuiAddress::from_array(a.to_inner()),
+            checkpoint_viewed_at: None,
         }
     }
 }
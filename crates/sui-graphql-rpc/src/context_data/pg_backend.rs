@@ -2,21 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
-    db_backend::{BalanceQuery, Explain, Explained, GenericQueryBuilder, QueryableEpochInfoType},
+    db_backend::{
+        BalanceQuery, Explain, Explained, GenericQueryBuilder, HistoryBalanceQuery,
+        QueryableEpochInfoType,
+    },
     db_data_provider::{DbValidationError, PageLimit, TypeFilterError},
 };
 use crate::{
     context_data::db_data_provider::PgManager,
     error::Error,
     types::{
-        digest::Digest, event::EventFilter, object::ObjectFilter, sui_address::SuiAddress,
-        transaction_block::TransactionBlockFilter,
+        digest::Digest, event::EventFilter, network_metrics::MetricsWindow, object::ObjectFilter,
+        sui_address::SuiAddress, transaction_block::TransactionBlockFilter,
     },
 };
 use async_trait::async_trait;
 use diesel::{
+    expression::SqlLiteral,
     pg::Pg,
     query_builder::{AstPass, QueryFragment},
+    sql_types::Bool,
     BoolExpressionMethods, ExpressionMethods, PgConnection, QueryDsl, QueryResult, RunQueryDsl,
     TextExpressionMethods,
 };
@@ -24,10 +29,11 @@ use std::str::FromStr;
 use sui_indexer::{
     models_v2::epoch::QueryableEpochInfo,
     schema_v2::{
-        checkpoints, display, epochs, events, objects, transactions, tx_calls, tx_changed_objects,
+        address_metrics, checkpoints, display, epochs, events, move_call_metrics, objects,
+        objects_history, transactions, tx_calls, tx_changed_objects, tx_count_metrics,
         tx_input_objects, tx_recipients, tx_senders,
     },
-    types_v2::OwnerType,
+    types_v2::{ObjectStatus, OwnerType},
 };
 use sui_types::parse_sui_struct_tag;
 use tap::TapFallible;
@@ -313,58 +319,17 @@ impl GenericQueryBuilder<Pg> for PgQueryBuilder {
         }
 
         if let Some(object_type) = filter.type_ {
-            let format = "package[::module[::type[<type_params>]]]";
-            let parts: Vec<_> = object_type.splitn(3, "::").collect();
-
-            if parts.iter().any(|&part| part.is_empty()) {
-                return Err(DbValidationError::InvalidType(
-                    TypeFilterError::MissingComponents(object_type, format).to_string(),
-                ))?;
-            }
-
-            if parts.len() == 1 {
-                // We check for a leading 0x to determine if it is an address
-                // And otherwise process it as a primitive type
-                if parts[0].starts_with("0x") {
-                    let package = SuiAddress::from_str(parts[0])
-                        .map_err(|e| DbValidationError::InvalidType(e.to_string()))?;
-                    query = query.filter(objects::dsl::object_type.like(format!("{}::%", package)));
-                } else {
-                    query = query.filter(objects::dsl::object_type.eq(parts[0].to_string()));
-                }
-            } else if parts.len() == 2 {
-                // Only package addresses are allowed if there are two or more parts
-                let package = SuiAddress::from_str(parts[0])
-                    .map_err(|e| DbValidationError::InvalidType(e.to_string()))?;
-                query = query.filter(
-                    objects::dsl::object_type.like(format!("{}::{}::%", package, parts[1])),
-                );
-            } else if parts.len() == 3 {
-                let validated_type = parse_sui_struct_tag(&object_type)
-                    .map_err(|e| DbValidationError::InvalidType(e.to_string()))?;
-
-                if validated_type.type_params.is_empty() {
-                    query = query.filter(
-                        objects::dsl::object_type
-                            .like(format!(
-                                "{}<%",
-                                validated_type.to_canonical_string(/* with_prefix */ true)
-                            ))
-                            .or(objects::dsl::object_type
-                                .eq(validated_type.to_canonical_string(/* with_prefix */ true))),
-                    );
-                } else {
-                    query = query.filter(
-                        objects::dsl::object_type
-                            .eq(validated_type.to_canonical_string(/* with_prefix */ true)),
-                    );
+            query = match ObjectTypeFilter::parse(object_type)? {
+                ObjectTypeFilter::Exact(type_) => query.filter(objects::dsl::object_type.eq(type_)),
+                ObjectTypeFilter::Prefix(prefix) => {
+                    query.filter(objects::dsl::object_type.like(format!("{prefix}%")))
                 }
-            } else {
-                return Err(DbValidationError::InvalidType(
-                    TypeFilterError::TooManyComponents(object_type, 3, format).to_string(),
-                )
-                .into());
-            }
+                ObjectTypeFilter::Uninstantiated(type_) => query.filter(
+                    objects::dsl::object_type
+                        .like(format!("{type_}<%"))
+                        .or(objects::dsl::object_type.eq(type_)),
+                ),
+            };
         }
 
         Ok(query)
@@ -392,6 +357,138 @@ impl GenericQueryBuilder<Pg> for PgQueryBuilder {
         let query = PgQueryBuilder::multi_get_balances(address);
         query.filter(objects::dsl::coin_type.eq(coin_type))
     }
+    fn get_obj_at_checkpoint(
+        address: Vec<u8>,
+        version: Option<i64>,
+        checkpoint: i64,
+    ) -> objects_history::BoxedQuery<'static, Pg> {
+        let mut query = objects_history::dsl::objects_history
+            .filter(objects_history::dsl::object_id.eq(address))
+            .filter(objects_history::dsl::checkpoint_sequence_number.le(checkpoint))
+            .into_boxed();
+
+        if let Some(version) = version {
+            query = query.filter(objects_history::dsl::object_version.eq(version));
+        }
+
+        query
+            .order(objects_history::dsl::object_version.desc())
+            .limit(1)
+    }
+    fn multi_get_coins_at_checkpoint(
+        before: Option<Vec<u8>>,
+        after: Option<Vec<u8>>,
+        limit: PageLimit,
+        address: Option<Vec<u8>>,
+        coin_type: String,
+        checkpoint: i64,
+    ) -> objects_history::BoxedQuery<'static, Pg> {
+        let mut query = order_objs_at_checkpoint(before, after, &limit, checkpoint);
+        query = query.limit(limit.value() + 1);
+
+        if let Some(address) = address {
+            query = query
+                .filter(objects_history::dsl::owner_id.eq(address))
+                .filter(objects_history::dsl::owner_type.eq(OwnerType::Address as i16));
+        }
+        query = query.filter(objects_history::dsl::coin_type.eq(coin_type));
+
+        query
+    }
+    fn multi_get_objs_at_checkpoint(
+        before: Option<Vec<u8>>,
+        after: Option<Vec<u8>>,
+        limit: PageLimit,
+        filter: Option<ObjectFilter>,
+        owner_type: Option<OwnerType>,
+        checkpoint: i64,
+    ) -> Result<objects_history::BoxedQuery<'static, Pg>, Error> {
+        let mut query = order_objs_at_checkpoint(before, after, &limit, checkpoint);
+        query = query.limit(limit.value() + 1);
+
+        let Some(filter) = filter else {
+            return Ok(query);
+        };
+
+        if let Some(object_ids) = filter.object_ids {
+            query = query.filter(
+                objects_history::dsl::object_id.eq_any(
+                    object_ids
+                        .into_iter()
+                        .map(|id| id.into_vec())
+                        .collect::<Vec<_>>(),
+                ),
+            );
+        }
+
+        if let Some(owner) = filter.owner {
+            query = query.filter(objects_history::dsl::owner_id.eq(owner.into_vec()));
+
+            query = match owner_type {
+                Some(OwnerType::Address) => {
+                    query.filter(objects_history::dsl::owner_type.eq(OwnerType::Address as i16))
+                }
+                Some(OwnerType::Object) => {
+                    query.filter(objects_history::dsl::owner_type.eq(OwnerType::Object as i16))
+                }
+                None => query.filter(
+                    objects_history::dsl::owner_type
+                        .eq(OwnerType::Address as i16)
+                        .or(objects_history::dsl::owner_type.eq(OwnerType::Object as i16)),
+                ),
+                _ => Err(DbValidationError::InvalidOwnerType)?,
+            };
+        }
+
+        if let Some(object_type) = filter.type_ {
+            query = match ObjectTypeFilter::parse(object_type)? {
+                ObjectTypeFilter::Exact(type_) => {
+                    query.filter(objects_history::dsl::object_type.eq(type_))
+                }
+                ObjectTypeFilter::Prefix(prefix) => {
+                    query.filter(objects_history::dsl::object_type.like(format!("{prefix}%")))
+                }
+                ObjectTypeFilter::Uninstantiated(type_) => query.filter(
+                    objects_history::dsl::object_type
+                        .like(format!("{type_}<%"))
+                        .or(objects_history::dsl::object_type.eq(type_)),
+                ),
+            };
+        }
+
+        Ok(query)
+    }
+    fn multi_get_balances_at_checkpoint(
+        address: Vec<u8>,
+        checkpoint: i64,
+    ) -> HistoryBalanceQuery<'static, Pg> {
+        objects_history::dsl::objects_history
+            .group_by(objects_history::dsl::coin_type)
+            .select((
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "CAST(SUM(coin_balance) AS BIGINT)",
+                ),
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "COUNT(*)",
+                ),
+                objects_history::dsl::coin_type,
+            ))
+            .filter(objects_history::dsl::checkpoint_sequence_number.le(checkpoint))
+            .filter(objects_history::dsl::object_status.eq(ObjectStatus::Active as i16))
+            .filter(latest_version_at_checkpoint(checkpoint))
+            .filter(objects_history::dsl::owner_id.eq(address))
+            .filter(objects_history::dsl::owner_type.eq(OwnerType::Address as i16))
+            .filter(objects_history::dsl::coin_type.is_not_null())
+            .into_boxed()
+    }
+    fn get_balance_at_checkpoint(
+        address: Vec<u8>,
+        coin_type: String,
+        checkpoint: i64,
+    ) -> HistoryBalanceQuery<'static, Pg> {
+        let query = PgQueryBuilder::multi_get_balances_at_checkpoint(address, checkpoint);
+        query.filter(objects_history::dsl::coin_type.eq(coin_type))
+    }
     fn multi_get_checkpoints(
         before: Option<i64>,
         after: Option<i64>,
@@ -530,6 +627,72 @@ impl GenericQueryBuilder<Pg> for PgQueryBuilder {
 
         Ok(query)
     }
+    fn get_address_metrics(checkpoint: i64) -> address_metrics::BoxedQuery<'static, Pg> {
+        address_metrics::dsl::address_metrics
+            .filter(address_metrics::dsl::checkpoint.eq(checkpoint))
+            .into_boxed()
+    }
+    fn multi_get_address_metrics(
+        before: Option<i64>,
+        after: Option<i64>,
+        limit: PageLimit,
+        window: Option<MetricsWindow>,
+    ) -> address_metrics::BoxedQuery<'static, Pg> {
+        let mut query = order_address_metrics(before, after, &limit);
+        query = query.limit(limit.value() + 1);
+
+        let Some(window) = window else {
+            return query;
+        };
+
+        if let Some(epoch) = window.epoch {
+            query = query.filter(address_metrics::dsl::epoch.eq(epoch as i64));
+        }
+
+        if let Some(first) = window.first_checkpoint {
+            query = query.filter(address_metrics::dsl::checkpoint.ge(first as i64));
+        }
+
+        if let Some(last) = window.last_checkpoint {
+            query = query.filter(address_metrics::dsl::checkpoint.le(last as i64));
+        }
+
+        query
+    }
+    fn multi_get_tx_count_metrics(
+        before: Option<i64>,
+        after: Option<i64>,
+        limit: PageLimit,
+        window: Option<MetricsWindow>,
+    ) -> tx_count_metrics::BoxedQuery<'static, Pg> {
+        let mut query = order_tx_count_metrics(before, after, &limit);
+        query = query.limit(limit.value() + 1);
+
+        let Some(window) = window else {
+            return query;
+        };
+
+        if let Some(epoch) = window.epoch {
+            query = query.filter(tx_count_metrics::dsl::epoch.eq(epoch as i64));
+        }
+
+        if let Some(first) = window.first_checkpoint {
+            query =
+                query.filter(tx_count_metrics::dsl::checkpoint_sequence_number.ge(first as i64));
+        }
+
+        if let Some(last) = window.last_checkpoint {
+            query = query.filter(tx_count_metrics::dsl::checkpoint_sequence_number.le(last as i64));
+        }
+
+        query
+    }
+    fn get_move_call_metrics(epoch: i64) -> move_call_metrics::BoxedQuery<'static, Pg> {
+        move_call_metrics::dsl::move_call_metrics
+            .filter(move_call_metrics::dsl::epoch.eq(epoch))
+            .order(move_call_metrics::dsl::count.desc())
+            .into_boxed()
+    }
 }
 
 /// Allows methods like load(), get_result(), etc. on an Explained query
@@ -697,6 +860,113 @@ fn order_objs(
     query
 }
 
+/// Like `order_objs`, but over the versions of objects that were live as of `checkpoint`.
+fn order_objs_at_checkpoint(
+    before: Option<Vec<u8>>,
+    after: Option<Vec<u8>>,
+    limit: &PageLimit,
+    checkpoint: i64,
+) -> objects_history::BoxedQuery<'static, Pg> {
+    let mut query = objects_history::dsl::objects_history
+        .filter(objects_history::dsl::checkpoint_sequence_number.le(checkpoint))
+        .filter(objects_history::dsl::object_status.eq(ObjectStatus::Active as i16))
+        .filter(latest_version_at_checkpoint(checkpoint))
+        .into_boxed();
+
+    match limit {
+        PageLimit::First(_) => {
+            if let Some(after) = after {
+                query = query.filter(objects_history::dsl::object_id.gt(after));
+            }
+            query = query.order(objects_history::dsl::object_id.asc());
+        }
+        PageLimit::Last(_) => {
+            if let Some(before) = before {
+                query = query.filter(objects_history::dsl::object_id.lt(before));
+            }
+            query = query.order(objects_history::dsl::object_id.desc());
+        }
+    }
+    query
+}
+
+/// Filter for rows of `objects_history` that hold the latest version of their object as of
+/// `checkpoint`: No other row for the same object has a later version, written at or before that
+/// checkpoint.  Rows recording that an object was wrapped or deleted also count as versions, so
+/// objects that were no longer live at `checkpoint` are excluded by combining this with a filter
+/// on `object_status`.
+fn latest_version_at_checkpoint(checkpoint: i64) -> SqlLiteral<Bool> {
+    diesel::dsl::sql::<Bool>(&format!(
+        "NOT EXISTS (\
+            SELECT 1 FROM objects_history newer \
+            WHERE newer.object_id = objects_history.object_id \
+            AND newer.checkpoint_sequence_number <= {checkpoint} \
+            AND newer.object_version > objects_history.object_version\
+        )"
+    ))
+}
+
+/// Restriction on the types of objects returned by a query, parsed from an `ObjectFilter`.
+enum ObjectTypeFilter {
+    /// Matches this type exactly.
+    Exact(String),
+    /// Matches types whose canonical representations start with this prefix (a package or module).
+    Prefix(String),
+    /// Matches this type (which has no type parameters), or any instantiation of it.
+    Uninstantiated(String),
+}
+
+impl ObjectTypeFilter {
+    /// Objects can be filtered by their type's package, package::module, or their fully qualified
+    /// type name, with or without type parameters.  Types without a leading `0x` are treated as
+    /// primitives.
+    fn parse(object_type: String) -> Result<Self, Error> {
+        let format = "package[::module[::type[<type_params>]]]";
+        let parts: Vec<_> = object_type.splitn(3, "::").collect();
+
+        if parts.iter().any(|&part| part.is_empty()) {
+            return Err(DbValidationError::InvalidType(
+                TypeFilterError::MissingComponents(object_type, format).to_string(),
+            ))?;
+        }
+
+        match parts.len() {
+            1 => {
+                // We check for a leading 0x to determine if it is an address
+                // And otherwise process it as a primitive type
+                if parts[0].starts_with("0x") {
+                    let package = SuiAddress::from_str(parts[0])
+                        .map_err(|e| DbValidationError::InvalidType(e.to_string()))?;
+                    Ok(Self::Prefix(format!("{}::", package)))
+                } else {
+                    Ok(Self::Exact(parts[0].to_string()))
+                }
+            }
+            2 => {
+                // Only package addresses are allowed if there are two or more parts
+                let package = SuiAddress::from_str(parts[0])
+                    .map_err(|e| DbValidationError::InvalidType(e.to_string()))?;
+                Ok(Self::Prefix(format!("{}::{}::", package, parts[1])))
+            }
+            3 => {
+                let validated_type = parse_sui_struct_tag(&object_type)
+                    .map_err(|e| DbValidationError::InvalidType(e.to_string()))?;
+                let canonical = validated_type.to_canonical_string(/* with_prefix */ true);
+
+                if validated_type.type_params.is_empty() {
+                    Ok(Self::Uninstantiated(canonical))
+                } else {
+                    Ok(Self::Exact(canonical))
+                }
+            }
+            _ => Err(DbValidationError::InvalidType(
+                TypeFilterError::TooManyComponents(object_type, 3, format).to_string(),
+            )
+            .into()),
+        }
+    }
+}
+
 fn order_checkpoints(
     before: Option<i64>,
     after: Option<i64>,
@@ -720,6 +990,52 @@ fn order_checkpoints(
     query
 }
 
+fn order_address_metrics(
+    before: Option<i64>,
+    after: Option<i64>,
+    limit: &PageLimit,
+) -> address_metrics::BoxedQuery<'static, Pg> {
+    let mut query = address_metrics::dsl::address_metrics.into_boxed();
+    match limit {
+        PageLimit::First(_) => {
+            if let Some(after) = after {
+                query = query.filter(address_metrics::dsl::checkpoint.gt(after));
+            }
+            query = query.order(address_metrics::dsl::checkpoint.asc());
+        }
+        PageLimit::Last(_) => {
+            if let Some(before) = before {
+                query = query.filter(address_metrics::dsl::checkpoint.lt(before));
+            }
+            query = query.order(address_metrics::dsl::checkpoint.desc());
+        }
+    }
+    query
+}
+
+fn order_tx_count_metrics(
+    before: Option<i64>,
+    after: Option<i64>,
+    limit: &PageLimit,
+) -> tx_count_metrics::BoxedQuery<'static, Pg> {
+    let mut query = tx_count_metrics::dsl::tx_count_metrics.into_boxed();
+    match limit {
+        PageLimit::First(_) => {
+            if let Some(after) = after {
+                query = query.filter(tx_count_metrics::dsl::checkpoint_sequence_number.gt(after));
+            }
+            query = query.order(tx_count_metrics::dsl::checkpoint_sequence_number.asc());
+        }
+        PageLimit::Last(_) => {
+            if let Some(before) = before {
+                query = query.filter(tx_count_metrics::dsl::checkpoint_sequence_number.lt(before));
+            }
+            query = query.order(tx_count_metrics::dsl::checkpoint_sequence_number.desc());
+        }
+    }
+    query
+}
+
 fn order_events(
     before: Option<(i64, i64)>,
     after: Option<(i64, i64)>,
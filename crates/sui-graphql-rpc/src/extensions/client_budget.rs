@@ -0,0 +1,399 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextParseQuery},
+    parser::types::ExecutableDocument,
+    ServerResult, Variables,
+};
+use axum::{
+    headers,
+    http::{HeaderMap, HeaderName, HeaderValue},
+};
+use lru::LruCache;
+
+use crate::{
+    config::{ClientBudgets, ServiceConfig},
+    error::{code, graphql_error},
+    extensions::query_limits_checker::analyze_query,
+};
+
+pub static API_KEY_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-api-key");
+
+/// Budgets are tracked for this many of the most recently seen clients. Forgetting a client
+/// restores its full budget.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// API key sent by the client, used to identify it for the purposes of metering.
+pub(crate) struct ApiKey(pub String);
+
+/// Charges the cost of each query (the number of nodes in it) against the budget of the client
+/// that sent it. Budgets replenish continuously over a rolling window, and queries that cost more
+/// than their client has left are rejected before they are executed.
+///
+/// Requests that did not come from a client over the network (e.g. health checks), or from a
+/// client without a budget, are not metered.
+#[derive(Clone, Debug)]
+pub(crate) struct ClientBudget {
+    buckets: Arc<Mutex<LruCache<Client, Bucket>>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Client {
+    ApiKey(String),
+    Header(String),
+    Ip(IpAddr),
+}
+
+#[derive(Clone, Debug)]
+struct Bucket {
+    /// Total budget of the client, which `remaining` replenishes up to.
+    budget: u64,
+    remaining: f64,
+    last_update: Instant,
+}
+
+impl headers::Header for ApiKey {
+    fn name() -> &'static HeaderName {
+        &API_KEY_HEADER
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let key = value.to_str().map_err(|_| headers::Error::invalid())?;
+        Ok(ApiKey(key.to_string()))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        if let Ok(value) = HeaderValue::from_str(&self.0) {
+            values.extend(std::iter::once(value));
+        }
+    }
+}
+
+impl Default for ClientBudget {
+    fn default() -> Self {
+        let capacity = NonZeroUsize::new(MAX_TRACKED_CLIENTS).unwrap();
+        Self {
+            buckets: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+}
+
+impl ExtensionFactory for ClientBudget {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ClientBudget {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let doc = next.run(ctx, query, variables).await?;
+
+        let Some(addr) = ctx.data_opt::<SocketAddr>() else {
+            return Ok(doc);
+        };
+
+        let cfg = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data");
+
+        let budgets = &cfg.client_budgets;
+        let api_key = ctx.data_opt::<ApiKey>();
+        let headers = ctx.data_opt::<HeaderMap>();
+        let Some((client, budget)) = identify(budgets, api_key, headers, addr) else {
+            return Ok(doc);
+        };
+
+        let cost = analyze_query(&cfg.limits, &doc)?.num_nodes as u64;
+        if cost == 0 {
+            return Ok(doc);
+        }
+
+        let window = Duration::from_millis(budgets.window_ms);
+        self.charge(client, budget, window, cost, Instant::now())?;
+        Ok(doc)
+    }
+}
+
+impl ClientBudget {
+    /// Deduct `cost` from `client`'s budget as of `now`, failing (without deducting anything) if
+    /// the client does not have enough of its budget left.
+    fn charge(
+        &self,
+        client: Client,
+        budget: u64,
+        window: Duration,
+        cost: u64,
+        now: Instant,
+    ) -> ServerResult<()> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if !buckets.contains(&client) {
+            buckets.put(
+                client.clone(),
+                Bucket {
+                    budget,
+                    remaining: budget as f64,
+                    last_update: now,
+                },
+            );
+        }
+
+        let bucket = buckets.get_mut(&client).expect("Bucket was just inserted");
+
+        // The budget may have changed since the bucket was created.
+        bucket.budget = budget;
+        bucket.replenish(window, now);
+
+        if cost as f64 > bucket.remaining {
+            return Err(graphql_error(
+                code::BAD_REQUEST,
+                format!(
+                    "Query cost {cost} exceeds the remaining budget for this client ({} of {}, \
+                     replenished every {}ms)",
+                    bucket.remaining as u64,
+                    bucket.budget,
+                    window.as_millis(),
+                ),
+            ));
+        }
+
+        bucket.remaining -= cost as f64;
+        Ok(())
+    }
+}
+
+impl Bucket {
+    /// Top up the remaining budget in proportion to the time elapsed since it was last updated,
+    /// so that an empty bucket is full again after `window` has passed.
+    fn replenish(&mut self, window: Duration, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_update);
+        let budget = self.budget as f64;
+
+        self.remaining = if window.is_zero() {
+            budget
+        } else {
+            let refill = budget * elapsed.as_secs_f64() / window.as_secs_f64();
+            (self.remaining + refill).min(budget)
+        };
+
+        self.last_update = now;
+    }
+}
+
+/// Identify the client by its API key if it sent one that has a budget, and otherwise by the
+/// configured client header, if the request has it, or by its IP address. Returns `None` if the
+/// client has no budget to meter against.
+fn identify(
+    budgets: &ClientBudgets,
+    api_key: Option<&ApiKey>,
+    headers: Option<&HeaderMap>,
+    addr: &SocketAddr,
+) -> Option<(Client, u64)> {
+    if let Some(ApiKey(key)) = api_key {
+        if let Some(budget) = budgets.api_keys.get(key) {
+            return Some((Client::ApiKey(key.clone()), *budget));
+        }
+    }
+
+    let budget = budgets.default_budget?;
+    let from_header = budgets
+        .client_header
+        .as_ref()
+        .zip(headers)
+        .and_then(|(name, headers)| headers.get(name.as_str()))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(str::trim)
+        .filter(|id| !id.is_empty());
+
+    match from_header {
+        Some(id) => Some((Client::Header(id.to_string()), budget)),
+        None => Some((Client::Ip(addr.ip()), budget)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use async_graphql::{EmptySubscription, Request, Schema};
+    use expect_test::expect;
+
+    use crate::{mutation::Mutation, types::query::Query};
+
+    use super::*;
+
+    fn addr() -> SocketAddr {
+        "1.2.3.4:5678".parse().unwrap()
+    }
+
+    #[test]
+    fn test_identify_client() {
+        let budgets = ClientBudgets {
+            default_budget: Some(10),
+            api_keys: BTreeMap::from([("known".to_string(), 100)]),
+            ..Default::default()
+        };
+
+        let known = ApiKey("known".to_string());
+        let unknown = ApiKey("unknown".to_string());
+
+        assert_eq!(
+            identify(&budgets, Some(&known), None, &addr()),
+            Some((Client::ApiKey("known".to_string()), 100)),
+        );
+
+        assert_eq!(
+            identify(&budgets, Some(&unknown), None, &addr()),
+            Some((Client::Ip(addr().ip()), 10)),
+        );
+
+        assert_eq!(
+            identify(&budgets, None, None, &addr()),
+            Some((Client::Ip(addr().ip()), 10)),
+        );
+
+        let unmetered = ClientBudgets::default();
+        assert_eq!(identify(&unmetered, None, None, &addr()), None);
+    }
+
+    #[test]
+    fn test_identify_client_by_header() {
+        let budgets = ClientBudgets {
+            default_budget: Some(10),
+            client_header: Some("x-forwarded-for".to_string()),
+            api_keys: BTreeMap::from([("known".to_string(), 100)]),
+            ..Default::default()
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 5.6.7.8"),
+        );
+
+        // The last entry is the one added by the proxy in front of the service.
+        assert_eq!(
+            identify(&budgets, None, Some(&headers), &addr()),
+            Some((Client::Header("5.6.7.8".to_string()), 10)),
+        );
+
+        // API keys take precedence.
+        let known = ApiKey("known".to_string());
+        assert_eq!(
+            identify(&budgets, Some(&known), Some(&headers), &addr()),
+            Some((Client::ApiKey("known".to_string()), 100)),
+        );
+
+        // Requests without the header are identified by IP address.
+        assert_eq!(
+            identify(&budgets, None, Some(&HeaderMap::new()), &addr()),
+            Some((Client::Ip(addr().ip()), 10)),
+        );
+    }
+
+    #[test]
+    fn test_budget_replenishes() {
+        let budget = ClientBudget::default();
+        let client = Client::Ip(addr().ip());
+        let window = Duration::from_secs(10);
+        let start = Instant::now();
+
+        budget.charge(client.clone(), 10, window, 6, start).unwrap();
+        budget.charge(client.clone(), 10, window, 4, start).unwrap();
+        budget
+            .charge(client.clone(), 10, window, 1, start)
+            .unwrap_err();
+
+        // Half the window has passed, so half the budget is back.
+        let later = start + window / 2;
+        budget.charge(client.clone(), 10, window, 5, later).unwrap();
+        budget
+            .charge(client.clone(), 10, window, 1, later)
+            .unwrap_err();
+
+        // Budgets never replenish past their limit.
+        let much_later = later + window * 10;
+        budget
+            .charge(client.clone(), 10, window, 11, much_later)
+            .unwrap_err();
+        budget.charge(client, 10, window, 10, much_later).unwrap();
+    }
+
+    #[test]
+    fn test_least_recent_clients_are_forgotten() {
+        let budget = ClientBudget::default();
+        let window = Duration::from_secs(10);
+        let now = Instant::now();
+
+        let client = |i: usize| Client::Header(i.to_string());
+        budget.charge(client(0), 10, window, 10, now).unwrap();
+        for i in 1..=MAX_TRACKED_CLIENTS {
+            budget.charge(client(i), 10, window, 10, now).unwrap();
+        }
+
+        // The first client was forgotten to make room for the others, so its budget is full.
+        budget.charge(client(0), 10, window, 10, now).unwrap();
+        budget
+            .charge(client(MAX_TRACKED_CLIENTS), 10, window, 1, now)
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_query_exceeds_budget() {
+        let schema = Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig {
+                client_budgets: ClientBudgets {
+                    default_budget: Some(3),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .extension(ClientBudget::default())
+            .finish();
+
+        let query = "{ a: __typename b: __typename }";
+
+        // Not metered, because the request did not come from a client.
+        for _ in 0..3 {
+            assert!(schema.execute(query).await.is_ok());
+        }
+
+        let resp = schema.execute(Request::new(query).data(addr())).await;
+        assert!(resp.is_ok());
+
+        let errs: Vec<_> = schema
+            .execute(Request::new(query).data(addr()))
+            .await
+            .into_result()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+
+        let expect = expect![[r#"
+            [
+                "Query cost 2 exceeds the remaining budget for this client (1 of 3, replenished every 60000ms)",
+            ]"#]];
+        expect.assert_eq(&format!("{errs:#?}"));
+    }
+}
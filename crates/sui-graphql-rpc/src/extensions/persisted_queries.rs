@@ -0,0 +1,277 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use async_graphql::{
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextPrepareRequest,
+    },
+    parser::{parse_query, types::ExecutableDocument},
+    Request, ServerResult, Value, Variables,
+};
+use fastcrypto::hash::{HashFunction, Sha256};
+
+use crate::{
+    config::{PersistedQueries as PersistedQueriesConfig, ServiceConfig},
+    error::{code, graphql_error, Error},
+};
+
+/// Registry of allow-listed queries, which clients can run by sending the SHA-256 hash of the
+/// query (hex-encoded) in the request's extensions, following Apollo's convention:
+///
+/// ```json
+/// { "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "..." } } }
+/// ```
+///
+/// Persisted queries are parsed once, when the service starts, rather than on every request. If
+/// the service is configured to only allow persisted queries, any other query sent by a client is
+/// rejected.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PersistedQueries {
+    registry: Arc<HashMap<String, PersistedQuery>>,
+}
+
+#[derive(Debug)]
+struct PersistedQuery {
+    query: String,
+    doc: ExecutableDocument,
+}
+
+impl PersistedQueries {
+    /// Build the registry from the queries in `config`, failing if any of them do not parse.
+    pub(crate) fn new(config: &PersistedQueriesConfig) -> Result<Self, Error> {
+        let mut registry = HashMap::new();
+        for query in &config.queries {
+            let doc = parse_query(query).map_err(|e| {
+                Error::Internal(format!("Failed to parse persisted query {query:?}: {e}"))
+            })?;
+
+            registry.insert(
+                hash(query),
+                PersistedQuery {
+                    query: query.clone(),
+                    doc,
+                },
+            );
+        }
+
+        Ok(Self {
+            registry: Arc::new(registry),
+        })
+    }
+}
+
+impl ExtensionFactory for PersistedQueries {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for PersistedQueries {
+    /// Swap in the text and pre-parsed document of the persisted query the request refers to, if
+    /// it refers to one that is registered.
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let Some(Value::Object(persisted)) = request.extensions.get("persistedQuery") else {
+            return next.run(ctx, request).await;
+        };
+
+        let Some(Value::String(sha256)) = persisted.get("sha256Hash") else {
+            return Err(graphql_error(
+                code::BAD_REQUEST,
+                "Persisted query is missing its 'sha256Hash'",
+            ));
+        };
+
+        let Some(persisted) = self.registry.get(&sha256.to_lowercase()) else {
+            // Clients may send the text of the query along with its hash, in which case it is
+            // run as any other query, unless only persisted queries are allowed.
+            let cfg = ctx
+                .data::<ServiceConfig>()
+                .expect("No service config provided in schema data");
+            if !request.query.is_empty() && !cfg.persisted_queries.only_persisted {
+                return next.run(ctx, request).await;
+            }
+
+            return Err(graphql_error(
+                code::PERSISTED_QUERY_NOT_FOUND,
+                format!("Persisted query {sha256} not found"),
+            ));
+        };
+
+        request.query = persisted.query.clone();
+        request.set_parsed_query(persisted.doc.clone());
+        next.run(ctx, request).await
+    }
+
+    /// Reject queries from clients that are not in the registry, if only persisted queries are
+    /// allowed.
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let cfg = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data");
+
+        // Requests that did not come from a client (e.g. health checks) are always allowed.
+        let from_client = ctx.data_opt::<SocketAddr>().is_some();
+        if from_client
+            && cfg.persisted_queries.only_persisted
+            && !self.registry.contains_key(&hash(query))
+        {
+            return Err(graphql_error(
+                code::BAD_REQUEST,
+                "Only persisted queries are allowed",
+            ));
+        }
+
+        next.run(ctx, query, variables).await
+    }
+}
+
+/// Hex-encoded SHA-256 hash of `query`, which identifies it in the registry.
+fn hash(query: &str) -> String {
+    hex::encode(Sha256::digest(query.as_bytes()).digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{value, EmptySubscription, Name, Schema};
+    use expect_test::expect;
+
+    use crate::{mutation::Mutation, types::query::Query};
+
+    use super::*;
+
+    const QUERY: &str = "{ __typename }";
+
+    fn schema(only_persisted: bool) -> Schema<Query, Mutation, EmptySubscription> {
+        let config = PersistedQueriesConfig {
+            only_persisted,
+            queries: vec![QUERY.to_string()],
+        };
+
+        Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig {
+                persisted_queries: config.clone(),
+                ..Default::default()
+            })
+            .extension(PersistedQueries::new(&config).unwrap())
+            .finish()
+    }
+
+    fn persisted(sha256: String) -> Request {
+        with_hash(Request::new(""), sha256)
+    }
+
+    fn with_hash(mut request: Request, sha256: String) -> Request {
+        request.extensions.insert(
+            "persistedQuery".to_string(),
+            Value::Object(
+                [
+                    (Name::new("version"), Value::from(1)),
+                    (Name::new("sha256Hash"), Value::from(sha256)),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+        );
+        request
+    }
+
+    fn client(request: Request) -> Request {
+        request.data("1.2.3.4:5678".parse::<SocketAddr>().unwrap())
+    }
+
+    fn errors(resp: async_graphql::Response) -> String {
+        let errs: Vec<_> = resp
+            .into_result()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        format!("{errs:#?}")
+    }
+
+    #[test]
+    fn test_invalid_persisted_query() {
+        let config = PersistedQueriesConfig {
+            only_persisted: false,
+            queries: vec!["{ __typename".to_string()],
+        };
+
+        assert!(PersistedQueries::new(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_persisted_query() {
+        let resp = schema(false).execute(client(persisted(hash(QUERY)))).await;
+
+        assert!(resp.is_ok());
+        assert_eq!(resp.data, value!({ "__typename": "Query" }));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_persisted_query() {
+        let resp = schema(false)
+            .execute(client(persisted(hash("{ chainIdentifier }"))))
+            .await;
+
+        let expect = expect![[r#"
+            [
+                "Persisted query a2da14596e504f3f879d825b36cbf8d45f353b0224c2602323a3914e133e3aea not found",
+            ]"#]];
+        expect.assert_eq(&errors(resp));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_persisted_query_with_text() {
+        let query = "{ a: __typename }";
+        let request = || client(with_hash(Request::new(query), hash(query)));
+
+        // The text of the query is run if its hash is not registered.
+        let resp = schema(false).execute(request()).await;
+        assert!(resp.is_ok(), "{:?}", resp.errors);
+        assert_eq!(resp.data, value!({ "a": "Query" }));
+
+        // Unless only persisted queries are allowed.
+        let resp = schema(true).execute(request()).await;
+        let expect = expect![[r#"
+            [
+                "Persisted query f4f8894a5f8f85d6b0e7eab534603e8cc5376c11e15a6c5cbf04d8db3c26670f not found",
+            ]"#]];
+        expect.assert_eq(&errors(resp));
+    }
+
+    #[tokio::test]
+    async fn test_only_persisted_queries() {
+        let schema = schema(true);
+
+        // Persisted queries can be run by hash, or by their text.
+        assert!(schema.execute(client(persisted(hash(QUERY)))).await.is_ok());
+        assert!(schema.execute(client(Request::new(QUERY))).await.is_ok());
+
+        // Queries that did not come from a client are always allowed.
+        assert!(schema.execute("{ a: __typename }").await.is_ok());
+
+        let resp = schema
+            .execute(client(Request::new("{ a: __typename }")))
+            .await;
+
+        let expect = expect![[r#"
+            [
+                "Only persisted queries are allowed",
+            ]"#]];
+        expect.assert_eq(&errors(resp));
+    }
+}
@@ -78,7 +78,7 @@ impl ExtensionFactory for QueryLimitsChecker {
 }
 
 #[derive(Debug)]
-struct ComponentCost {
+pub(crate) struct ComponentCost {
     pub num_nodes: u32,
     pub depth: u32,
 }
@@ -142,33 +142,7 @@ impl Extension for QueryLimitsChecker {
         let doc = next.run(ctx, query, variables).await?;
 
         // TODO: Limit the complexity of fragments early on
-
-        let mut running_costs = ComponentCost {
-            depth: 0,
-            num_nodes: 0,
-        };
-        let mut max_depth_seen = 0;
-
-        for (count, (_name, oper)) in doc.operations.iter().enumerate() {
-            let sel_set = &oper.node.selection_set;
-
-            // If the query is pure introspection, we don't need to check the limits.
-            // Pure introspection queries are queries that only have one operation with one field
-            // and that field is a `__schema` query
-            if (count == 0) && (sel_set.node.items.len() == 1) {
-                if let Some(node) = sel_set.node.items.first() {
-                    if let Selection::Field(field) = &node.node {
-                        if field.node.name.node == "__schema" {
-                            continue;
-                        }
-                    }
-                }
-            }
-
-            running_costs.depth = 0;
-            self.analyze_selection_set(&cfg.limits, &doc.fragments, sel_set, &mut running_costs)?;
-            max_depth_seen = max_depth_seen.max(running_costs.depth);
-        }
+        let running_costs = analyze_query(&cfg.limits, &doc)?;
 
         if ctx.data_opt::<ShowUsage>().is_some() {
             *self.validation_result.lock().await = Some(ValidationRes {
@@ -188,107 +162,135 @@ impl Extension for QueryLimitsChecker {
     }
 }
 
-impl QueryLimitsChecker {
-    fn analyze_selection_set(
-        &self,
-        limits: &Limits,
-        fragment_defs: &HashMap<Name, Positioned<FragmentDefinition>>,
-        sel_set: &Positioned<SelectionSet>,
-        cost: &mut ComponentCost,
-    ) -> ServerResult<()> {
-        // Use BFS to analyze the query and count the number of nodes and the depth of the query
-
-        // Queue to store the nodes at each level
-        let mut que = VecDeque::new();
-
-        for top_level_sel in sel_set.node.items.iter() {
-            que.push_back(top_level_sel);
-            cost.num_nodes += 1;
-            check_limits(limits, cost.num_nodes, cost.depth, Some(top_level_sel.pos))?;
+/// Measure the cost of the operations in `doc`, failing if it exceeds `limits`.  Pure
+/// introspection queries are free.
+pub(crate) fn analyze_query(
+    limits: &Limits,
+    doc: &ExecutableDocument,
+) -> ServerResult<ComponentCost> {
+    let mut running_costs = ComponentCost {
+        depth: 0,
+        num_nodes: 0,
+    };
+    let mut max_depth_seen = 0;
+
+    for (count, (_name, oper)) in doc.operations.iter().enumerate() {
+        let sel_set = &oper.node.selection_set;
+
+        // If the query is pure introspection, we don't need to check the limits.
+        // Pure introspection queries are queries that only have one operation with one field
+        // and that field is a `__schema` query
+        if (count == 0) && (sel_set.node.items.len() == 1) {
+            if let Some(node) = sel_set.node.items.first() {
+                if let Selection::Field(field) = &node.node {
+                    if field.node.name.node == "__schema" {
+                        continue;
+                    }
+                }
+            }
         }
 
-        // Track the number of nodes at first level if any
-        let mut level_len = que.len();
-
-        while !que.is_empty() {
-            // Signifies the start of a new level
-            cost.depth += 1;
-            check_limits(limits, cost.num_nodes, cost.depth, None)?;
-            while level_len > 0 {
-                // Ok to unwrap since we checked for empty queue
-                // and level_len > 0
-                let curr_sel = que.pop_front().unwrap();
-
-                match &curr_sel.node {
-                    Selection::Field(f) => {
-                        if !f.node.directives.is_empty() {
-                            return Err(graphql_error_at_pos(
-                                INTERNAL_SERVER_ERROR,
-                                "Fields with directives are not supported",
-                                f.pos,
-                            ));
-                        }
-                        for field_sel in f.node.selection_set.node.items.iter() {
-                            que.push_back(field_sel);
-                            cost.num_nodes += 1;
-                            check_limits(limits, cost.num_nodes, cost.depth, Some(field_sel.pos))?;
-                        }
+        running_costs.depth = 0;
+        analyze_selection_set(limits, &doc.fragments, sel_set, &mut running_costs)?;
+        max_depth_seen = max_depth_seen.max(running_costs.depth);
+    }
+
+    Ok(running_costs)
+}
+
+fn analyze_selection_set(
+    limits: &Limits,
+    fragment_defs: &HashMap<Name, Positioned<FragmentDefinition>>,
+    sel_set: &Positioned<SelectionSet>,
+    cost: &mut ComponentCost,
+) -> ServerResult<()> {
+    // Use BFS to analyze the query and count the number of nodes and the depth of the query
+
+    // Queue to store the nodes at each level
+    let mut que = VecDeque::new();
+
+    for top_level_sel in sel_set.node.items.iter() {
+        que.push_back(top_level_sel);
+        cost.num_nodes += 1;
+        check_limits(limits, cost.num_nodes, cost.depth, Some(top_level_sel.pos))?;
+    }
+
+    // Track the number of nodes at first level if any
+    let mut level_len = que.len();
+
+    while !que.is_empty() {
+        // Signifies the start of a new level
+        cost.depth += 1;
+        check_limits(limits, cost.num_nodes, cost.depth, None)?;
+        while level_len > 0 {
+            // Ok to unwrap since we checked for empty queue
+            // and level_len > 0
+            let curr_sel = que.pop_front().unwrap();
+
+            match &curr_sel.node {
+                Selection::Field(f) => {
+                    if !f.node.directives.is_empty() {
+                        return Err(graphql_error_at_pos(
+                            INTERNAL_SERVER_ERROR,
+                            "Fields with directives are not supported",
+                            f.pos,
+                        ));
                     }
-                    Selection::FragmentSpread(fs) => {
-                        let frag_name = &fs.node.fragment_name.node;
-                        let frag_def = fragment_defs.get(frag_name).ok_or_else(|| {
-                            graphql_error_at_pos(
-                                INTERNAL_SERVER_ERROR,
-                                format!(
-                                    "Fragment {} not found but present in fragment list",
-                                    frag_name
-                                ),
-                                fs.pos,
-                            )
-                        })?;
-
-                        // TODO: this is inefficient as we might loop over same fragment multiple times
-                        // Ideally web should cache the costs of fragments we've seen before
-                        // Will do as enhancement
-                        if !frag_def.node.directives.is_empty() {
-                            return Err(graphql_error_at_pos(
-                                INTERNAL_SERVER_ERROR,
-                                "Fragments with directives are not supported",
-                                frag_def.pos,
-                            ));
-                        }
-                        for frag_sel in frag_def.node.selection_set.node.items.iter() {
-                            que.push_back(frag_sel);
-                            cost.num_nodes += 1;
-                            check_limits(limits, cost.num_nodes, cost.depth, Some(frag_sel.pos))?;
-                        }
+                    for field_sel in f.node.selection_set.node.items.iter() {
+                        que.push_back(field_sel);
+                        cost.num_nodes += 1;
+                        check_limits(limits, cost.num_nodes, cost.depth, Some(field_sel.pos))?;
                     }
-                    Selection::InlineFragment(fs) => {
-                        if !fs.node.directives.is_empty() {
-                            return Err(graphql_error_at_pos(
-                                INTERNAL_SERVER_ERROR,
-                                "Inline fragments with directives are not supported",
-                                fs.pos,
-                            ));
-                        }
-                        for in_frag_sel in fs.node.selection_set.node.items.iter() {
-                            que.push_back(in_frag_sel);
-                            cost.num_nodes += 1;
-                            check_limits(
-                                limits,
-                                cost.num_nodes,
-                                cost.depth,
-                                Some(in_frag_sel.pos),
-                            )?;
-                        }
+                }
+                Selection::FragmentSpread(fs) => {
+                    let frag_name = &fs.node.fragment_name.node;
+                    let frag_def = fragment_defs.get(frag_name).ok_or_else(|| {
+                        graphql_error_at_pos(
+                            INTERNAL_SERVER_ERROR,
+                            format!(
+                                "Fragment {} not found but present in fragment list",
+                                frag_name
+                            ),
+                            fs.pos,
+                        )
+                    })?;
+
+                    // TODO: this is inefficient as we might loop over same fragment multiple times
+                    // Ideally web should cache the costs of fragments we've seen before
+                    // Will do as enhancement
+                    if !frag_def.node.directives.is_empty() {
+                        return Err(graphql_error_at_pos(
+                            INTERNAL_SERVER_ERROR,
+                            "Fragments with directives are not supported",
+                            frag_def.pos,
+                        ));
+                    }
+                    for frag_sel in frag_def.node.selection_set.node.items.iter() {
+                        que.push_back(frag_sel);
+                        cost.num_nodes += 1;
+                        check_limits(limits, cost.num_nodes, cost.depth, Some(frag_sel.pos))?;
+                    }
+                }
+                Selection::InlineFragment(fs) => {
+                    if !fs.node.directives.is_empty() {
+                        return Err(graphql_error_at_pos(
+                            INTERNAL_SERVER_ERROR,
+                            "Inline fragments with directives are not supported",
+                            fs.pos,
+                        ));
+                    }
+                    for in_frag_sel in fs.node.selection_set.node.items.iter() {
+                        que.push_back(in_frag_sel);
+                        cost.num_nodes += 1;
+                        check_limits(limits, cost.num_nodes, cost.depth, Some(in_frag_sel.pos))?;
                     }
                 }
-                level_len -= 1;
             }
-            level_len = que.len();
+            level_len -= 1;
         }
-        Ok(())
+        level_len = que.len();
     }
+    Ok(())
 }
 
 fn check_limits(limits: &Limits, nodes: u32, depth: u32, pos: Option<Pos>) -> ServerResult<()> {
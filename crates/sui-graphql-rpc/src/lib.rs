@@ -21,11 +21,12 @@ pub mod utils;
 use async_graphql::*;
 use mutation::Mutation;
 use types::owner::ObjectOwner;
+use types::subscription::Subscription;
 
 use crate::types::query::Query;
 
 pub fn schema_sdl_export() -> String {
-    let schema = Schema::build(Query, Mutation, EmptySubscription)
+    let schema = Schema::build(Query, Mutation, Subscription)
         .register_output_type::<ObjectOwner>()
         .finish();
     schema.sdl()
@@ -9,20 +9,26 @@ use crate::{
     context_data::db_data_provider::PgManager,
     error::Error,
     extensions::{
+        client_budget::{ApiKey, ClientBudget},
         feature_gate::FeatureGate,
         logger::Logger,
+        persisted_queries::PersistedQueries,
         query_limits_checker::{QueryLimitsChecker, ShowUsage},
         timeout::Timeout,
     },
     metrics::RequestMetrics,
     server::version::{check_version_middleware, set_version_middleware},
-    types::query::{Query, SuiGraphQLSchema},
+    types::{
+        query::{Query, SuiGraphQLSchema},
+        subscription::{ConnectionSubscriptions, Subscription, SubscriptionHub},
+    },
 };
 use async_graphql::extensions::ApolloTracing;
 use async_graphql::extensions::Tracing;
-use async_graphql::EmptySubscription;
 use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{http::ALL_WEBSOCKET_PROTOCOLS, Data};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use axum::extract::WebSocketUpgrade;
 use axum::http::HeaderMap;
 use axum::response::IntoResponse;
 use axum::routing::{post, MethodRouter, Route};
@@ -30,7 +36,10 @@ use axum::{
     extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
     middleware,
 };
-use axum::{headers::Header, Router};
+use axum::{
+    headers::{Header, HeaderMapExt},
+    Router,
+};
 use http::Request;
 use hyper::server::conn::AddrIncoming as HyperAddrIncoming;
 use hyper::Body;
@@ -60,7 +69,7 @@ pub(crate) struct ServerBuilder {
     port: u16,
     host: String,
 
-    schema: SchemaBuilder<Query, Mutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
     router: Option<Router>,
 }
 
@@ -69,7 +78,8 @@ impl ServerBuilder {
         Self {
             port,
             host,
-            schema: async_graphql::Schema::build(Query, Mutation, EmptySubscription),
+            schema: async_graphql::Schema::build(Query, Mutation, Subscription)
+                .data(SubscriptionHub::default()),
             router: None,
         }
     }
@@ -88,11 +98,11 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, Mutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
-    fn build_components(self) -> (String, Schema<Query, Mutation, EmptySubscription>, Router) {
+    fn build_components(self) -> (String, Schema<Query, Mutation, Subscription>, Router) {
         let address = self.address();
         let ServerBuilder { schema, router, .. } = self;
         (
@@ -106,6 +116,7 @@ impl ServerBuilder {
         if self.router.is_none() {
             let router: Router = Router::new()
                 .route("/", post(graphql_handler))
+                .route("/subscriptions", axum::routing::get(subscription_handler))
                 .route("/health", axum::routing::get(health_checks))
                 .layer(middleware::from_fn(check_version_middleware))
                 .layer(middleware::from_fn(set_version_middleware));
@@ -219,9 +230,15 @@ impl ServerBuilder {
         if config.internal_features.logger {
             builder = builder.extension(Logger::default());
         }
+        if config.internal_features.persisted_queries {
+            builder = builder.extension(PersistedQueries::new(&config.service.persisted_queries)?);
+        }
         if config.internal_features.query_limits_checker {
             builder = builder.extension(QueryLimitsChecker::default());
         }
+        if config.internal_features.client_budget {
+            builder = builder.extension(ClientBudget::default());
+        }
         if config.internal_features.query_timeout {
             builder = builder.extension(Timeout);
         }
@@ -252,12 +269,37 @@ async fn graphql_handler(
     if headers.contains_key(ShowUsage::name()) {
         req.data.insert(ShowUsage)
     }
+    if let Some(api_key) = headers.typed_get::<ApiKey>() {
+        req.data.insert(api_key)
+    }
     // Capture the IP address of the client
     // Note: if a load balancer is used it must be configured to forward the client IP address
     req.data.insert(addr);
+    // Clients can also be identified by a header set by a load balancer, see `ClientBudgets`.
+    req.data.insert(headers);
     schema.execute(req).await.into()
 }
 
+/// Serve subscriptions over a WebSocket, using the graphql-ws protocol (or the legacy
+/// subscriptions-transport-ws protocol, for older clients).
+async fn subscription_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    schema: axum::Extension<SuiGraphQLSchema>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            let mut data = Data::default();
+            data.insert(addr);
+            data.insert(ConnectionSubscriptions::default());
+            GraphQLWebSocket::new(stream, schema.0, protocol)
+                .with_data(data)
+                .serve()
+        })
+}
+
 async fn health_checks(
     schema: axum::Extension<SuiGraphQLSchema>,
 ) -> impl axum::response::IntoResponse {
@@ -303,6 +345,7 @@ pub mod tests {
         extensions::{Extension, ExtensionContext, NextExecute},
         Response,
     };
+    use futures::StreamExt;
     use rand::{rngs::StdRng, SeedableRng};
     use simulacrum::Simulacrum;
     use std::sync::Arc;
@@ -650,4 +693,70 @@ pub mod tests {
         assert_eq!(metrics2.num_nodes.get_sample_sum(), 2. + 4.);
         assert_eq!(metrics2.query_depth.get_sample_sum(), 1. + 3.);
     }
+
+    pub async fn test_subscription_impl() {
+        let (connection_config, _cluster) = prep_cluster().await;
+
+        let db_url: String = connection_config.db_url.clone();
+        let reader = PgManager::reader(db_url).expect("Failed to create pg connection pool");
+        let limits = Limits {
+            subscription_poll_interval_ms: 100,
+            max_subscriptions: 2,
+            ..Default::default()
+        };
+        let pg_conn_pool = PgManager::new(reader, limits);
+        let schema = ServerBuilder::new(8000, "127.0.0.1".to_string())
+            .context_data(pg_conn_pool)
+            .build_schema();
+
+        // Resuming from before the first transaction streams the genesis transaction.
+        let mut first = schema.execute_stream(
+            r#"subscription { transactions(after: "-1") { cursor node { digest } } }"#,
+        );
+        let resp = first.next().await.unwrap();
+        assert!(resp.is_ok(), "{:?}", resp.errors);
+        let data = resp.data.into_json().unwrap();
+        assert_eq!(data["transactions"]["cursor"], "0");
+
+        // There is nothing after it yet, so the stream waits for new transactions.
+        let query = r#"subscription { transactions(after: "0") { cursor node { digest } } }"#;
+        let mut second = schema.execute_stream(query);
+        let resp = tokio::time::timeout(Duration::from_millis(500), second.next()).await;
+        assert!(resp.is_err(), "Unexpected transaction: {:?}", resp);
+
+        // Malformed cursors are rejected upfront.
+        let mut stream =
+            schema.execute_stream(r#"subscription { transactions(after: "abc") { cursor } }"#);
+        let errs: Vec<_> = stream
+            .next()
+            .await
+            .unwrap()
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(errs, vec!["Invalid cursor: tx".to_string()]);
+        assert!(stream.next().await.is_none());
+
+        // Only two subscriptions are served at once.
+        let mut stream = schema.execute_stream(query);
+        let errs: Vec<_> = stream
+            .next()
+            .await
+            .unwrap()
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            errs,
+            vec!["Too many subscriptions, at most 2 are served at once".to_string()]
+        );
+
+        // Ending a subscription makes room for another one.
+        drop(first);
+        let mut stream = schema.execute_stream(query);
+        let resp = tokio::time::timeout(Duration::from_millis(500), stream.next()).await;
+        assert!(resp.is_err(), "Unexpected response: {:?}", resp);
+    }
 }
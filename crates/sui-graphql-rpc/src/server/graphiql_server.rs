@@ -8,7 +8,9 @@ use crate::error::Error;
 use crate::server::builder::ServerBuilder;
 
 async fn graphiql(ide_title: axum::Extension<Option<String>>) -> impl axum::response::IntoResponse {
-    let gq = async_graphql::http::GraphiQLSource::build().endpoint("/");
+    let gq = async_graphql::http::GraphiQLSource::build()
+        .endpoint("/")
+        .subscription_endpoint("/subscriptions");
     if let axum::Extension(Some(title)) = ide_title {
         axum::response::Html(gq.title(&title).finish())
     } else {
@@ -20,6 +20,9 @@ use super::{
 #[derive(Clone, Debug, PartialEq, Eq, Copy)]
 pub(crate) struct Address {
     pub address: SuiAddress,
+    /// The checkpoint that this address's objects and balances are viewed at, or `None` to view
+    /// them at the latest checkpoint.
+    pub checkpoint_viewed_at: Option<u64>,
 }
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
@@ -77,7 +80,15 @@ impl Address {
         filter: Option<ObjectFilter>,
     ) -> Result<Option<Connection<String, Object>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_owned_objs(first, after, last, before, filter, self.address)
+            .fetch_owned_objs(
+                first,
+                after,
+                last,
+                before,
+                filter,
+                self.address,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -88,7 +99,7 @@ impl Address {
         type_: Option<String>,
     ) -> Result<Option<Balance>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_balance(self.address, type_)
+            .fetch_balance(self.address, type_, self.checkpoint_viewed_at)
             .await
             .extend()
     }
@@ -102,7 +113,14 @@ impl Address {
         before: Option<String>,
     ) -> Result<Option<Connection<String, Balance>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_balances(self.address, first, after, last, before)
+            .fetch_balances(
+                self.address,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -120,7 +138,15 @@ impl Address {
         type_: Option<String>,
     ) -> Result<Option<Connection<String, Coin>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_coins(Some(self.address), type_, first, after, last, before)
+            .fetch_coins(
+                Some(self.address),
+                type_,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -135,7 +161,14 @@ impl Address {
         before: Option<String>,
     ) -> Result<Option<Connection<String, StakedSui>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_staked_sui(self.address, first, after, last, before)
+            .fetch_staked_sui(
+                self.address,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -165,6 +198,7 @@ impl Address {
                 before,
                 ctx.data_unchecked::<NameServiceConfig>(),
                 self.address,
+                self.checkpoint_viewed_at,
             )
             .await
             .extend()
@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use sui_indexer::models_v2::address_metrics::StoredAddressMetrics;
+
+use super::date_time::DateTime;
+use crate::error::Error;
+
+/// Counts of the addresses that have been involved in transactions, as of a particular checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct AddressMetrics {
+    /// The checkpoint these metrics were computed at.
+    pub checkpoint: u64,
+    /// The epoch of the checkpoint these metrics were computed at.
+    pub epoch: u64,
+    /// The timestamp of the checkpoint these metrics were computed at.
+    pub timestamp: DateTime,
+    /// The number of addresses that have been involved in a transaction, as sender or recipient,
+    /// up to and including this checkpoint.
+    pub cumulative_addresses: u64,
+    /// The number of addresses that have sent a transaction, up to and including this checkpoint.
+    pub cumulative_active_addresses: u64,
+    /// The number of addresses that have sent a transaction in the day leading up to this
+    /// checkpoint.
+    pub daily_active_addresses: u64,
+}
+
+impl TryFrom<StoredAddressMetrics> for AddressMetrics {
+    type Error = Error;
+
+    fn try_from(stored: StoredAddressMetrics) -> Result<Self, Error> {
+        Ok(Self {
+            checkpoint: stored.checkpoint as u64,
+            epoch: stored.epoch as u64,
+            timestamp: DateTime::from_ms(stored.timestamp_ms)?,
+            cumulative_addresses: stored.cumulative_addresses as u64,
+            cumulative_active_addresses: stored.cumulative_active_addresses as u64,
+            daily_active_addresses: stored.daily_active_addresses as u64,
+        })
+    }
+}
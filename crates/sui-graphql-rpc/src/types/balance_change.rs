@@ -23,6 +23,7 @@ impl BalanceChange {
         match self.stored.owner {
             O::AddressOwner(addr) | O::ObjectOwner(addr) => Some(Owner {
                 address: SuiAddress::from(addr),
+                checkpoint_viewed_at: None,
             }),
 
             O::Shared { .. } | O::Immutable => None,
@@ -48,3 +49,9 @@ impl BalanceChange {
         Ok(Self { stored })
     }
 }
+
+impl From<StoredBalanceChange> for BalanceChange {
+    fn from(stored: StoredBalanceChange) -> Self {
+        Self { stored }
+    }
+}
@@ -4,6 +4,7 @@
 use crate::context_data::db_data_provider::PgManager;
 
 use super::{
+    address_metrics::AddressMetrics,
     base64::Base64,
     date_time::DateTime,
     end_of_epoch_data::EndOfEpochData,
@@ -82,4 +83,12 @@ impl Checkpoint {
             .await
             .extend()
     }
+
+    /// Counts of the addresses that have been involved in transactions, as of this checkpoint.
+    async fn address_metrics(&self, ctx: &Context<'_>) -> Result<Option<AddressMetrics>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_checkpoint_address_metrics(self.sequence_number)
+            .await
+            .extend()
+    }
 }
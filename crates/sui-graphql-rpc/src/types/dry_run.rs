@@ -0,0 +1,431 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use async_graphql::*;
+use fastcrypto::encoding::{Base64 as FastCryptoBase64, Encoding};
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiArgument, SuiExecutionResult,
+    SuiExecutionStatus, SuiProtocolConfigValue, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTypeTag,
+};
+use sui_sdk::SuiClient;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress as NativeSuiAddress},
+    digests::ObjectDigest,
+    effects::{IDOperation, ObjectChange as NativeObjectChange},
+    sui_serde::BigInt as NativeBigInt,
+    transaction::{
+        Argument as NativeArgument, TransactionData, TransactionDataAPI, TransactionKind,
+    },
+    TypeTag,
+};
+use tracing::warn;
+
+use super::{
+    balance_change::BalanceChange,
+    base64::Base64,
+    event::Event,
+    gas::{GasCostSummary, GasEffects},
+    move_value::MoveValue,
+    object_change::ObjectChange,
+    sui_address::SuiAddress,
+    transaction_block_effects::ExecutionStatus,
+    transaction_block_kind::programmable::TransactionArgument,
+};
+use crate::error::Error;
+
+/// The data that is missing from a `TransactionKind` to make a `TransactionData`.
+#[derive(InputObject, Debug, Default)]
+pub(crate) struct TransactionMetadata {
+    /// Defaults to `0x0`.
+    pub sender: Option<SuiAddress>,
+    /// Defaults to the reference gas price.
+    pub gas_price: Option<u64>,
+    /// Defaults to the maximum gas budget of a transaction.
+    pub gas_budget: Option<u64>,
+    /// If absent or empty, a mock Coin object is used as gas.
+    pub gas_objects: Option<Vec<ObjectRef>>,
+}
+
+/// A reference to a particular version of an object.
+#[derive(InputObject, Debug)]
+pub(crate) struct ObjectRef {
+    pub address: SuiAddress,
+    pub version: u64,
+    /// The object's digest, encoded in Base58.
+    pub digest: String,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DryRunResult {
+    /// The error that occurred during dry run execution, if any.
+    pub error: Option<String>,
+    /// The effects that running the transaction would have.
+    pub effects: DryRunTransactionBlockEffects,
+    /// The events that running the transaction would emit.
+    pub events: Vec<Event>,
+    /// The results of each command of the transaction, if it is a programmable transaction block
+    /// that ran without errors.
+    pub results: Option<Vec<DryRunEffect>>,
+}
+
+/// The effects of a transaction that was not committed on-chain.  Unlike `TransactionBlockEffects`,
+/// the objects written by the transaction do not exist, so their output state is not available.
+#[derive(SimpleObject)]
+pub(crate) struct DryRunTransactionBlockEffects {
+    /// Whether the transaction would execute successfully or not.
+    pub status: ExecutionStatus,
+    /// The reason for a transaction failure, if it would fail.
+    pub errors: Option<String>,
+    /// Effects to the gas object.
+    pub gas_effects: GasEffects,
+    /// The effect the transaction would have on objects on-chain.
+    pub object_changes: Vec<ObjectChange>,
+    /// The effect the transaction would have on the balances (sum of coin values per coin type) of
+    /// addresses and objects.
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+/// The results of a single command of a programmable transaction block.
+#[derive(SimpleObject)]
+pub(crate) struct DryRunEffect {
+    /// Changes made to arguments that were mutably borrowed by this command.
+    pub mutated_references: Vec<DryRunMutation>,
+    /// The values returned by this command.
+    pub return_values: Vec<MoveValue>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DryRunMutation {
+    /// The argument that was mutably borrowed.
+    pub input: TransactionArgument,
+    /// Its value after the command.
+    pub value: MoveValue,
+}
+
+impl DryRunResult {
+    /// Simulate running the transaction in `tx_bytes` on the fullnode behind `sui_sdk_client`.
+    /// If `tx_meta` is present, `tx_bytes` is a `TransactionKind`, completed by `tx_meta` into
+    /// a `TransactionData`, otherwise it is a `TransactionData`. If `skip_checks` is set, the
+    /// transaction is dev inspected instead of dry run.
+    pub(crate) async fn dry_run(
+        sui_sdk_client: &SuiClient,
+        tx_bytes: String,
+        tx_meta: Option<TransactionMetadata>,
+        skip_checks: bool,
+    ) -> Result<Self, Error> {
+        let tx_bytes = FastCryptoBase64::decode(&tx_bytes).map_err(|e| {
+            Error::Client(format!(
+                "Unable to deserialize transaction bytes from Base64: {e}"
+            ))
+        })?;
+
+        let tx_data = if let Some(tx_meta) = tx_meta {
+            let kind: TransactionKind = bcs::from_bytes(&tx_bytes).map_err(|e| {
+                Error::Client(format!(
+                    "Unable to deserialize transaction bytes as a BCS TransactionKind: {e}"
+                ))
+            })?;
+            tx_meta.complete(sui_sdk_client, kind).await?
+        } else {
+            bcs::from_bytes(&tx_bytes).map_err(|e| {
+                Error::Client(format!(
+                    "Unable to deserialize transaction bytes as a BCS TransactionData: {e}"
+                ))
+            })?
+        };
+
+        let read_api = sui_sdk_client.read_api();
+        let sender = tx_data.sender();
+        let gas_price = tx_data.gas_price();
+        let kind = tx_data.kind().clone();
+        let dev_inspect = read_api.dev_inspect_transaction_block(
+            sender,
+            kind.clone(),
+            Some(NativeBigInt::from(gas_price)),
+            None,
+        );
+
+        if skip_checks {
+            // TODO: use proper error type as this could be a client error or internal error
+            // depending on the specific error returned
+            let dev_inspect = dev_inspect
+                .await
+                .map_err(|e| Error::Internal(format!("Unable to dev inspect transaction: {e}")))?;
+            return Self::from_dev_inspect(dev_inspect);
+        }
+
+        // Dry runs don't report what each command returned, which dev inspect does. It is only
+        // used for that, so the dry run is still reported if it fails.
+        let dev_inspect = async {
+            if !matches!(kind, TransactionKind::ProgrammableTransaction(_)) {
+                return None;
+            }
+            dev_inspect
+                .await
+                .map_err(|e| warn!("Unable to dev inspect transaction: {e}"))
+                .ok()
+        };
+
+        let (dry_run, dev_inspect) =
+            futures::join!(read_api.dry_run_transaction_block(tx_data), dev_inspect);
+        // TODO: use proper error type as this could be a client error or internal error
+        // depending on the specific error returned
+        let dry_run =
+            dry_run.map_err(|e| Error::Internal(format!("Unable to dry run transaction: {e}")))?;
+
+        Self::from_dry_run(dry_run, dev_inspect)
+    }
+
+    fn from_dry_run(
+        dry_run: DryRunTransactionBlockResponse,
+        dev_inspect: Option<DevInspectResults>,
+    ) -> Result<Self, Error> {
+        let DryRunTransactionBlockResponse {
+            effects,
+            events,
+            balance_changes,
+            ..
+        } = dry_run;
+
+        // The execution status of the dry run is the most accurate, as dev inspect relaxes some
+        // of the checks that would fail the transaction, so its results are only reported if it
+        // succeeded, and its error never is.
+        let results = dev_inspect
+            .filter(|dev_inspect| dev_inspect.error.is_none())
+            .and_then(|dev_inspect| dev_inspect.results);
+
+        let effects = DryRunTransactionBlockEffects::new(&effects, balance_changes);
+        Ok(Self {
+            error: effects.errors.clone(),
+            effects,
+            events: events.data.into_iter().map(Event::uncommitted).collect(),
+            results: dry_run_effects(results)?,
+        })
+    }
+
+    fn from_dev_inspect(dev_inspect: DevInspectResults) -> Result<Self, Error> {
+        let DevInspectResults {
+            effects,
+            events,
+            results,
+            error,
+        } = dev_inspect;
+
+        // Dev inspect does not compute balance changes.
+        let effects = DryRunTransactionBlockEffects::new(&effects, vec![]);
+        Ok(Self {
+            error: effects.errors.clone().or(error),
+            effects,
+            events: events.data.into_iter().map(Event::uncommitted).collect(),
+            results: dry_run_effects(results)?,
+        })
+    }
+}
+
+impl TransactionMetadata {
+    /// Make a `TransactionData` out of `kind`, filling in the defaults of the missing fields.
+    async fn complete(
+        self,
+        sui_sdk_client: &SuiClient,
+        kind: TransactionKind,
+    ) -> Result<TransactionData, Error> {
+        let sender = self
+            .sender
+            .map_or(NativeSuiAddress::ZERO, NativeSuiAddress::from);
+
+        let gas_price = match self.gas_price {
+            Some(gas_price) => gas_price,
+            None => sui_sdk_client
+                .governance_api()
+                .get_reference_gas_price()
+                .await
+                .map_err(|e| {
+                    Error::Internal(format!("Unable to fetch the reference gas price: {e}"))
+                })?,
+        };
+
+        let gas_budget = match self.gas_budget {
+            Some(gas_budget) => gas_budget,
+            None => max_tx_gas(sui_sdk_client).await?,
+        };
+
+        let gas_objects = self
+            .gas_objects
+            .unwrap_or_default()
+            .into_iter()
+            .map(|object| object.to_native())
+            .collect::<Result<_, _>>()?;
+
+        Ok(TransactionData::new_with_gas_coins(
+            kind,
+            sender,
+            gas_objects,
+            gas_budget,
+            gas_price,
+        ))
+    }
+}
+
+impl ObjectRef {
+    fn to_native(&self) -> Result<(ObjectID, SequenceNumber, ObjectDigest), Error> {
+        let id = ObjectID::from_bytes(self.address.as_slice())
+            .map_err(|e| Error::Client(e.to_string()))?;
+        let digest = ObjectDigest::from_str(&self.digest)
+            .map_err(|e| Error::Client(format!("Invalid object digest {}: {e}", self.digest)))?;
+        Ok((id, SequenceNumber::from_u64(self.version), digest))
+    }
+}
+
+impl DryRunTransactionBlockEffects {
+    fn new(
+        effects: &SuiTransactionBlockEffects,
+        balance_changes: Vec<sui_json_rpc_types::BalanceChange>,
+    ) -> Self {
+        let (status, errors) = match effects.status() {
+            SuiExecutionStatus::Success => (ExecutionStatus::Success, None),
+            SuiExecutionStatus::Failure { error } => {
+                (ExecutionStatus::Failure, Some(error.clone()))
+            }
+        };
+
+        let gas_object = effects.gas_object().reference.to_object_ref();
+        let gas_effects = GasEffects {
+            summary: GasCostSummary::from(effects.gas_cost_summary()),
+            object_id: SuiAddress::from(gas_object.0),
+            object_version: gas_object.1.value(),
+        };
+
+        Self {
+            status,
+            errors,
+            gas_effects,
+            object_changes: object_changes(effects),
+            balance_changes: balance_changes
+                .into_iter()
+                .map(BalanceChange::from)
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<SuiExecutionResult> for DryRunEffect {
+    type Error = Error;
+
+    fn try_from(result: SuiExecutionResult) -> Result<Self, Error> {
+        let mut mutated_references = Vec::with_capacity(result.mutable_reference_outputs.len());
+        for (argument, bcs, type_) in result.mutable_reference_outputs {
+            mutated_references.push(DryRunMutation {
+                input: TransactionArgument::from(native_argument(argument)),
+                value: move_value(bcs, type_)?,
+            });
+        }
+
+        let return_values = result
+            .return_values
+            .into_iter()
+            .map(|(bcs, type_)| move_value(bcs, type_))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            mutated_references,
+            return_values,
+        })
+    }
+}
+
+fn dry_run_effects(
+    results: Option<Vec<SuiExecutionResult>>,
+) -> Result<Option<Vec<DryRunEffect>>, Error> {
+    results
+        .map(|results| results.into_iter().map(DryRunEffect::try_from).collect())
+        .transpose()
+}
+
+/// The maximum gas budget of a transaction, according to the fullnode's protocol config.
+async fn max_tx_gas(sui_sdk_client: &SuiClient) -> Result<u64, Error> {
+    let config = sui_sdk_client
+        .read_api()
+        .get_protocol_config(None)
+        .await
+        .map_err(|e| Error::Internal(format!("Unable to fetch the protocol config: {e}")))?;
+
+    match config.attributes.get("max_tx_gas") {
+        Some(Some(SuiProtocolConfigValue::U64(max_tx_gas))) => Ok(*max_tx_gas),
+        _ => Err(Error::Internal(
+            "Protocol config is missing max_tx_gas".to_string(),
+        )),
+    }
+}
+
+/// The changes to each object, keyed by ID.  The JSON representation of effects doesn't include
+/// the digests of the objects' input states.
+fn object_changes(effects: &SuiTransactionBlockEffects) -> Vec<ObjectChange> {
+    let mut changes = BTreeMap::new();
+
+    for (id, version) in effects.modified_at_versions() {
+        change(&mut changes, id).input_version = Some(version);
+    }
+
+    for (written, id_operation) in [
+        (effects.created(), IDOperation::Created),
+        (effects.mutated(), IDOperation::None),
+        (effects.unwrapped(), IDOperation::None),
+    ] {
+        for object in written {
+            let (id, version, digest) = object.reference.to_object_ref();
+            let change = change(&mut changes, id);
+            change.output_version = Some(version);
+            change.output_digest = Some(digest);
+            change.id_operation = id_operation;
+        }
+    }
+
+    for (removed, id_operation) in [
+        (effects.deleted(), IDOperation::Deleted),
+        (effects.unwrapped_then_deleted(), IDOperation::Deleted),
+        (effects.wrapped(), IDOperation::None),
+    ] {
+        for object in removed {
+            change(&mut changes, object.object_id).id_operation = id_operation;
+        }
+    }
+
+    changes
+        .into_values()
+        .map(|native| ObjectChange { native })
+        .collect()
+}
+
+fn change(
+    changes: &mut BTreeMap<ObjectID, NativeObjectChange>,
+    id: ObjectID,
+) -> &mut NativeObjectChange {
+    changes.entry(id).or_insert(NativeObjectChange {
+        id,
+        input_version: None,
+        input_digest: None,
+        output_version: None,
+        output_digest: None,
+        id_operation: IDOperation::None,
+    })
+}
+
+fn native_argument(argument: SuiArgument) -> NativeArgument {
+    match argument {
+        SuiArgument::GasCoin => NativeArgument::GasCoin,
+        SuiArgument::Input(ix) => NativeArgument::Input(ix),
+        SuiArgument::Result(cmd) => NativeArgument::Result(cmd),
+        SuiArgument::NestedResult(cmd, ix) => NativeArgument::NestedResult(cmd, ix),
+    }
+}
+
+fn move_value(bcs: Vec<u8>, type_: SuiTypeTag) -> Result<MoveValue, Error> {
+    let type_: TypeTag = type_
+        .try_into()
+        .map_err(|e| Error::Internal(format!("Invalid type of dry run value: {e}")))?;
+    Ok(MoveValue::new(type_, Base64::from(bcs)))
+}
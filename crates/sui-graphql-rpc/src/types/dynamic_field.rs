@@ -20,6 +20,8 @@ pub(crate) struct DynamicField {
     pub stored_object: StoredObject,
     pub df_object_id: SuiAddress,
     pub df_kind: DynamicFieldType,
+    /// The checkpoint this field is viewed at, or `None` if it is viewed at the latest checkpoint.
+    pub checkpoint_viewed_at: Option<u64>,
 }
 
 #[derive(Union)]
@@ -92,7 +94,7 @@ impl DynamicField {
         if self.df_kind == DynamicFieldType::DynamicObject {
             let obj = ctx
                 .data_unchecked::<PgManager>()
-                .fetch_move_obj(self.df_object_id, None)
+                .fetch_move_obj(self.df_object_id, None, self.checkpoint_viewed_at)
                 .await
                 .extend()?;
             Ok(obj.map(DynamicFieldValue::MoveObject))
@@ -3,6 +3,7 @@
 
 use async_graphql::*;
 use sui_indexer::models_v2::events::StoredEvent;
+use sui_json_rpc_types::SuiEvent;
 use sui_types::{parse_sui_struct_tag, TypeTag};
 
 use crate::error::Error;
@@ -14,6 +15,10 @@ use super::{
     move_value::MoveValue, sui_address::SuiAddress,
 };
 
+/// Checkpoint sequence number of events emitted by transactions that were not committed, e.g. by
+/// dry runs.
+const UNCOMMITTED_CHECKPOINT: i64 = -1;
+
 pub(crate) struct Event {
     pub stored: StoredEvent,
 }
@@ -76,13 +81,19 @@ impl Event {
             let address = SuiAddress::from_bytes(sender)
                 .map_err(|e| Error::Internal(format!("Failed to deserialize address: {e}")))
                 .extend()?;
-            addrs.push(Address { address });
+            addrs.push(Address {
+                address,
+                checkpoint_viewed_at: None,
+            });
         }
         Ok(Some(addrs))
     }
 
     /// UTC timestamp in milliseconds since epoch (1/1/1970)
     async fn timestamp(&self) -> Result<Option<DateTime>, Error> {
+        if self.stored.checkpoint_sequence_number == UNCOMMITTED_CHECKPOINT {
+            return Ok(None);
+        }
         Ok(DateTime::from_ms(self.stored.timestamp_ms).ok())
     }
 
@@ -96,3 +107,24 @@ impl Event {
         Ok(MoveValue::new(type_, Base64::from(self.stored.bcs.clone())))
     }
 }
+
+impl Event {
+    /// An event emitted by a transaction that was not committed, e.g. by a dry run. It is not part
+    /// of any checkpoint, so it has no timestamp.
+    pub(crate) fn uncommitted(event: SuiEvent) -> Self {
+        Self {
+            stored: StoredEvent {
+                tx_sequence_number: 0,
+                event_sequence_number: event.id.event_seq as i64,
+                transaction_digest: event.id.tx_digest.into_inner().to_vec(),
+                checkpoint_sequence_number: UNCOMMITTED_CHECKPOINT,
+                senders: vec![Some(event.sender.to_vec())],
+                package: event.package_id.to_vec(),
+                module: event.transaction_module.to_string(),
+                event_type: event.type_.to_canonical_string(/* with_prefix */ true),
+                timestamp_ms: 0,
+                bcs: event.bcs,
+            },
+        }
+    }
+}
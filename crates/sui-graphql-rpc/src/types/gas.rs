@@ -66,7 +66,7 @@ impl GasInput {
         };
 
         ctx.data_unchecked::<PgManager>()
-            .fetch_objs(first, after, last, before, Some(filter))
+            .fetch_objs(first, after, last, before, Some(filter), None)
             .await
             .extend()
     }
@@ -113,7 +113,7 @@ impl GasCostSummary {
 impl GasEffects {
     async fn gas_object(&self, ctx: &Context<'_>) -> Result<Option<Object>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_obj(self.object_id, Some(self.object_version))
+            .fetch_obj(self.object_id, Some(self.object_version), None)
             .await
             .extend()
     }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod address;
+pub(crate) mod address_metrics;
 pub(crate) mod available_range;
 pub(crate) mod balance;
 pub(crate) mod balance_change;
@@ -15,6 +16,7 @@ pub(crate) mod cursor;
 pub(crate) mod date_time;
 pub(crate) mod digest;
 pub(crate) mod display;
+pub(crate) mod dry_run;
 pub(crate) mod dynamic_field;
 pub(crate) mod end_of_epoch_data;
 pub(crate) mod epoch;
@@ -22,6 +24,7 @@ pub(crate) mod event;
 pub(crate) mod execution_result;
 pub(crate) mod gas;
 pub(crate) mod json;
+pub(crate) mod move_call_metrics;
 pub(crate) mod move_function;
 pub(crate) mod move_module;
 pub(crate) mod move_object;
@@ -29,6 +32,7 @@ pub(crate) mod move_package;
 pub(crate) mod move_struct;
 pub(crate) mod move_type;
 pub(crate) mod move_value;
+pub(crate) mod network_metrics;
 pub(crate) mod object;
 pub(crate) mod object_change;
 pub(crate) mod object_read;
@@ -40,6 +44,7 @@ pub(crate) mod safe_mode;
 pub(crate) mod stake;
 pub(crate) mod stake_subsidy;
 pub(crate) mod storage_fund;
+pub(crate) mod subscription;
 pub(crate) mod sui_address;
 pub(crate) mod suins_registration;
 pub(crate) mod system_parameters;
@@ -0,0 +1,91 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use async_graphql::*;
+use sui_indexer::models_v2::move_call_metrics::QueriedMoveCallMetrics;
+
+use super::sui_address::SuiAddress;
+use crate::error::Error;
+
+/// The most called Move functions, over windows of epochs ending at a particular epoch.  Each
+/// ranking holds the top functions by number of calls, in descending order.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct MoveCallMetrics {
+    /// The epoch at the end of the windows these metrics were computed over.
+    pub epoch: u64,
+    /// The most called functions over the last 3 epochs.
+    pub rank_3_days: Vec<MoveFunctionCallCount>,
+    /// The most called functions over the last 7 epochs.
+    pub rank_7_days: Vec<MoveFunctionCallCount>,
+    /// The most called functions over the last 30 epochs.
+    pub rank_30_days: Vec<MoveFunctionCallCount>,
+}
+
+/// The number of times a Move function was called, over a window of epochs.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct MoveFunctionCallCount {
+    /// The package the function is defined in.
+    pub package: SuiAddress,
+    /// The module the function is defined in.
+    pub module: String,
+    /// The name of the function.
+    pub function: String,
+    /// The number of calls to the function.
+    pub count: u64,
+}
+
+impl MoveCallMetrics {
+    /// Sort the metrics computed in `epoch` into rankings, by the size of the window they were
+    /// computed over.  Metrics for windows other than 3, 7 and 30 epochs are ignored.
+    pub(crate) fn new(epoch: u64, stored: Vec<QueriedMoveCallMetrics>) -> Result<Self, Error> {
+        let mut metrics = Self {
+            epoch,
+            rank_3_days: vec![],
+            rank_7_days: vec![],
+            rank_30_days: vec![],
+        };
+
+        for stored in stored {
+            let rank = match stored.day {
+                3 => &mut metrics.rank_3_days,
+                7 => &mut metrics.rank_7_days,
+                30 => &mut metrics.rank_30_days,
+                _ => continue,
+            };
+
+            rank.push(MoveFunctionCallCount::try_from(stored)?);
+        }
+
+        for rank in [
+            &mut metrics.rank_3_days,
+            &mut metrics.rank_7_days,
+            &mut metrics.rank_30_days,
+        ] {
+            rank.sort_by(|a, b| b.count.cmp(&a.count));
+        }
+
+        Ok(metrics)
+    }
+}
+
+impl TryFrom<QueriedMoveCallMetrics> for MoveFunctionCallCount {
+    type Error = Error;
+
+    fn try_from(stored: QueriedMoveCallMetrics) -> Result<Self, Error> {
+        let package = SuiAddress::from_str(&stored.move_package).map_err(|e| {
+            Error::Internal(format!(
+                "Failed to parse package of move call metric {}: {e}",
+                stored.move_package
+            ))
+        })?;
+
+        Ok(Self {
+            package,
+            module: stored.move_module,
+            function: stored.move_function,
+            count: stored.count as u64,
+        })
+    }
+}
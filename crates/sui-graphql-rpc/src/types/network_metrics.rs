@@ -0,0 +1,123 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{connection::Connection, *};
+use sui_indexer::models_v2::{
+    network_metrics::StoredNetworkMetrics, tx_count_metrics::StoredTxCountMetrics,
+};
+
+use super::{address_metrics::AddressMetrics, date_time::DateTime};
+use crate::{context_data::db_data_provider::PgManager, error::Error};
+
+/// Restricts metrics that are computed per checkpoint to a range of checkpoints.  All bounds are
+/// inclusive, and can be combined.
+#[derive(InputObject, Clone, Debug, Default)]
+pub(crate) struct MetricsWindow {
+    /// Only include metrics computed at checkpoints in this epoch.
+    pub epoch: Option<u64>,
+    /// Only include metrics computed at this checkpoint or after it.
+    pub first_checkpoint: Option<u64>,
+    /// Only include metrics computed at this checkpoint or before it.
+    pub last_checkpoint: Option<u64>,
+}
+
+/// Summary of the activity on the network, as of the latest checkpoint indexed.
+#[derive(Clone, Debug, PartialEq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct NetworkMetrics {
+    /// Successful transactions per second, over the latest checkpoints.
+    pub current_tps: f64,
+    /// The peak successful transactions per second, over the last 30 days.
+    pub tps_30_days: f64,
+    /// Estimated number of packages that have been published.
+    pub total_packages: u64,
+    /// Estimated number of addresses that have been involved in a transaction.
+    pub total_addresses: u64,
+    /// Estimated number of live objects.
+    pub total_objects: u64,
+    /// The latest epoch indexed.
+    pub current_epoch: u64,
+    /// The latest checkpoint indexed.
+    pub current_checkpoint: u64,
+}
+
+/// Counts of the transactions that were executed in a particular checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct TransactionCountMetrics {
+    /// The checkpoint these metrics were computed at.
+    pub checkpoint: u64,
+    /// The epoch of the checkpoint these metrics were computed at.
+    pub epoch: u64,
+    /// The timestamp of the checkpoint these metrics were computed at.
+    pub timestamp: DateTime,
+    /// The number of transaction blocks in this checkpoint.
+    pub total_transaction_blocks: u64,
+    /// The number of transaction blocks in this checkpoint that executed successfully.
+    pub total_successful_transaction_blocks: u64,
+    /// The number of commands in the successful transaction blocks of this checkpoint.
+    pub total_successful_transactions: u64,
+}
+
+#[ComplexObject]
+impl NetworkMetrics {
+    /// Address metrics computed at each checkpoint within `filter`, ordered by checkpoint.
+    async fn address_metrics_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<MetricsWindow>,
+    ) -> Result<Option<Connection<String, AddressMetrics>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_address_metrics(first, after, last, before, filter)
+            .await
+            .extend()
+    }
+
+    /// Transaction counts computed at each checkpoint within `filter`, ordered by checkpoint.
+    async fn transaction_count_metrics_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<MetricsWindow>,
+    ) -> Result<Option<Connection<String, TransactionCountMetrics>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_tx_count_metrics(first, after, last, before, filter)
+            .await
+            .extend()
+    }
+}
+
+impl From<StoredNetworkMetrics> for NetworkMetrics {
+    fn from(stored: StoredNetworkMetrics) -> Self {
+        Self {
+            current_tps: stored.current_tps,
+            tps_30_days: stored.tps_30_days,
+            total_packages: stored.total_packages as u64,
+            total_addresses: stored.total_addresses as u64,
+            total_objects: stored.total_objects as u64,
+            current_epoch: stored.current_epoch as u64,
+            current_checkpoint: stored.current_checkpoint as u64,
+        }
+    }
+}
+
+impl TryFrom<StoredTxCountMetrics> for TransactionCountMetrics {
+    type Error = Error;
+
+    fn try_from(stored: StoredTxCountMetrics) -> Result<Self, Error> {
+        Ok(Self {
+            checkpoint: stored.checkpoint_sequence_number as u64,
+            epoch: stored.epoch as u64,
+            timestamp: DateTime::from_ms(stored.timestamp_ms)?,
+            total_transaction_blocks: stored.total_transaction_blocks as u64,
+            total_successful_transaction_blocks: stored.total_successful_transaction_blocks as u64,
+            total_successful_transactions: stored.total_successful_transactions as u64,
+        })
+    }
+}
@@ -38,6 +38,10 @@ pub(crate) struct Object {
 
     /// Deserialized representation of `stored_object.serialized_object`.
     pub native: NativeObject,
+
+    /// The checkpoint this object is viewed at, or `None` if it is viewed at the latest
+    /// checkpoint.  Objects reached through this object are viewed at the same checkpoint.
+    pub checkpoint_viewed_at: Option<u64>,
 }
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
@@ -188,7 +192,10 @@ impl Object {
         };
 
         let address = SuiAddress::from(address);
-        Some(Owner { address })
+        Some(Owner {
+            address,
+            checkpoint_viewed_at: self.checkpoint_viewed_at,
+        })
     }
 
     /// Attempts to convert the object into a MoveObject
@@ -219,7 +226,15 @@ impl Object {
         filter: Option<ObjectFilter>,
     ) -> Result<Option<Connection<String, Object>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_owned_objs(first, after, last, before, filter, self.address)
+            .fetch_owned_objs(
+                first,
+                after,
+                last,
+                before,
+                filter,
+                self.address,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -231,7 +246,7 @@ impl Object {
         type_: Option<String>,
     ) -> Result<Option<Balance>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_balance(self.address, type_)
+            .fetch_balance(self.address, type_, self.checkpoint_viewed_at)
             .await
             .extend()
     }
@@ -246,7 +261,14 @@ impl Object {
         before: Option<String>,
     ) -> Result<Option<Connection<String, Balance>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_balances(self.address, first, after, last, before)
+            .fetch_balances(
+                self.address,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -265,7 +287,15 @@ impl Object {
         type_: Option<String>,
     ) -> Result<Option<Connection<String, Coin>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_coins(Some(self.address), type_, first, after, last, before)
+            .fetch_coins(
+                Some(self.address),
+                type_,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -280,7 +310,14 @@ impl Object {
         before: Option<String>,
     ) -> Result<Option<Connection<String, StakedSui>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_staked_sui(self.address, first, after, last, before)
+            .fetch_staked_sui(
+                self.address,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -311,6 +348,7 @@ impl Object {
                 before,
                 ctx.data_unchecked::<NameServiceConfig>(),
                 self.address,
+                self.checkpoint_viewed_at,
             )
             .await
             .extend()
@@ -326,7 +364,12 @@ impl Object {
         name: DynamicFieldName,
     ) -> Result<Option<DynamicField>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_dynamic_field(self.address, name, DynamicFieldType::DynamicField)
+            .fetch_dynamic_field(
+                self.address,
+                name,
+                DynamicFieldType::DynamicField,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -342,7 +385,12 @@ impl Object {
         name: DynamicFieldName,
     ) -> Result<Option<DynamicField>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_dynamic_field(self.address, name, DynamicFieldType::DynamicObject)
+            .fetch_dynamic_field(
+                self.address,
+                name,
+                DynamicFieldType::DynamicObject,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -358,7 +406,14 @@ impl Object {
         before: Option<String>,
     ) -> Result<Option<Connection<String, DynamicField>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_dynamic_fields(first, after, last, before, self.address)
+            .fetch_dynamic_fields(
+                first,
+                after,
+                last,
+                before,
+                self.address,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -371,6 +426,7 @@ impl Object {
             address,
             stored: None,
             native,
+            checkpoint_viewed_at: None,
         }
     }
 }
@@ -387,6 +443,7 @@ impl TryFrom<StoredObject> for Object {
             address,
             stored: Some(stored_object),
             native: native_object,
+            checkpoint_viewed_at: None,
         })
     }
 }
@@ -26,7 +26,7 @@ impl ObjectChange {
         };
 
         ctx.data_unchecked::<PgManager>()
-            .fetch_obj(self.native.id.into(), Some(version.value()))
+            .fetch_obj(self.native.id.into(), Some(version.value()), None)
             .await
             .extend()
     }
@@ -38,7 +38,7 @@ impl ObjectChange {
         };
 
         ctx.data_unchecked::<PgManager>()
-            .fetch_obj(self.native.id.into(), Some(version.value()))
+            .fetch_obj(self.native.id.into(), Some(version.value()), None)
             .await
             .extend()
     }
@@ -34,7 +34,7 @@ impl ObjectRead {
     /// The object at this version.  May not be available due to pruning.
     async fn object(&self, ctx: &Context<'_>) -> Result<Option<Object>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_obj(self.address_impl(), Some(self.version_impl()))
+            .fetch_obj(self.address_impl(), Some(self.version_impl()), None)
             .await
             .extend()
     }
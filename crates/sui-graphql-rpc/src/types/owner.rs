@@ -97,6 +97,9 @@ pub(crate) enum ObjectOwner {
 #[derive(Clone, Debug)]
 pub(crate) struct Owner {
     pub address: SuiAddress,
+    /// The checkpoint that this owner's objects and balances are viewed at, or `None` to view
+    /// them at the latest checkpoint.
+    pub checkpoint_viewed_at: Option<u64>,
 }
 
 #[Object]
@@ -105,6 +108,7 @@ impl Owner {
         // For now only addresses can be owners
         Some(Address {
             address: self.address,
+            checkpoint_viewed_at: self.checkpoint_viewed_at,
         })
     }
 
@@ -130,7 +134,15 @@ impl Owner {
         filter: Option<ObjectFilter>,
     ) -> Result<Option<Connection<String, Object>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_owned_objs(first, after, last, before, filter, self.address)
+            .fetch_owned_objs(
+                first,
+                after,
+                last,
+                before,
+                filter,
+                self.address,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -141,7 +153,7 @@ impl Owner {
         type_: Option<String>,
     ) -> Result<Option<Balance>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_balance(self.address, type_)
+            .fetch_balance(self.address, type_, self.checkpoint_viewed_at)
             .await
             .extend()
     }
@@ -155,7 +167,14 @@ impl Owner {
         before: Option<String>,
     ) -> Result<Option<Connection<String, Balance>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_balances(self.address, first, after, last, before)
+            .fetch_balances(
+                self.address,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -174,7 +193,15 @@ impl Owner {
         type_: Option<String>,
     ) -> Result<Option<Connection<String, Coin>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_coins(Some(self.address), type_, first, after, last, before)
+            .fetch_coins(
+                Some(self.address),
+                type_,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -189,7 +216,14 @@ impl Owner {
         before: Option<String>,
     ) -> Result<Option<Connection<String, StakedSui>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_staked_sui(self.address, first, after, last, before)
+            .fetch_staked_sui(
+                self.address,
+                first,
+                after,
+                last,
+                before,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -219,6 +253,7 @@ impl Owner {
                 before,
                 ctx.data_unchecked::<NameServiceConfig>(),
                 self.address,
+                self.checkpoint_viewed_at,
             )
             .await
             .extend()
@@ -234,7 +269,12 @@ impl Owner {
         name: DynamicFieldName,
     ) -> Result<Option<DynamicField>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_dynamic_field(self.address, name, DynamicFieldType::DynamicField)
+            .fetch_dynamic_field(
+                self.address,
+                name,
+                DynamicFieldType::DynamicField,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -250,7 +290,12 @@ impl Owner {
         name: DynamicFieldName,
     ) -> Result<Option<DynamicField>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_dynamic_field(self.address, name, DynamicFieldType::DynamicObject)
+            .fetch_dynamic_field(
+                self.address,
+                name,
+                DynamicFieldType::DynamicObject,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
@@ -266,7 +311,14 @@ impl Owner {
         before: Option<String>,
     ) -> Result<Option<Connection<String, DynamicField>>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_dynamic_fields(first, after, last, before, self.address)
+            .fetch_dynamic_fields(
+                first,
+                after,
+                last,
+                before,
+                self.address,
+                self.checkpoint_viewed_at,
+            )
             .await
             .extend()
     }
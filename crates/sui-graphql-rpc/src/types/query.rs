@@ -5,6 +5,7 @@ use std::str::FromStr;
 
 use async_graphql::{connection::Connection, *};
 use sui_json_rpc::name_service::NameServiceConfig;
+use sui_sdk::SuiClient;
 use sui_types::TypeTag;
 
 use super::{
@@ -13,12 +14,16 @@ use super::{
     checkpoint::{Checkpoint, CheckpointId},
     coin::Coin,
     coin_metadata::CoinMetadata,
+    dry_run::{DryRunResult, TransactionMetadata},
     epoch::Epoch,
     event::{Event, EventFilter},
+    move_call_metrics::MoveCallMetrics,
     move_type::MoveType,
+    network_metrics::NetworkMetrics,
     object::{Object, ObjectFilter},
     owner::{ObjectOwner, Owner},
     protocol_config::ProtocolConfigs,
+    subscription::Subscription,
     sui_address::SuiAddress,
     transaction_block::{TransactionBlock, TransactionBlockFilter},
 };
@@ -28,7 +33,7 @@ use crate::{
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[Object]
 impl Query {
@@ -56,28 +61,105 @@ impl Query {
             .extend()
     }
 
+    /// Simulate running a transaction to inspect its effects without
+    /// committing to them on-chain.
+    ///
+    /// `txBytes` either a `TransactionData` struct or a `TransactionKind`
+    ///     struct, BCS-encoded and then Base64-encoded.  The expected
+    ///     type is controlled by the presence or absence of `txMeta`: If
+    ///     present, `txBytes` is assumed to be a `TransactionKind`, if
+    ///     absent, then `TransactionData`.
+    ///
+    /// `txMeta` the data that is missing from a `TransactionKind` to make
+    ///     a `TransactionData` (sender address and gas information).  All
+    ///     its fields are nullable: `sender` defaults to `0x0`, if
+    ///     `gasObjects` is not present, or is an empty list, it is
+    ///     substituted with a mock Coin object, `gasPrice` defaults to
+    ///     the reference gas price, and `gasBudget` defaults to the
+    ///     maximum gas budget of a transaction.
+    ///
+    /// `skipChecks` optional flag to disable the usual verification
+    ///     checks that prevent access to objects that are owned by
+    ///     addresses other than the sender, and calling non-public,
+    ///     non-entry functions, by dev inspecting the transaction.
+    ///     Balance changes are not computed in this mode.  Defaults to
+    ///     false.
+    async fn dry_run_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: String,
+        tx_meta: Option<TransactionMetadata>,
+        skip_checks: Option<bool>,
+    ) -> Result<DryRunResult> {
+        let sui_sdk_client: &Option<SuiClient> = ctx
+            .data()
+            .map_err(|_| Error::Internal("Unable to fetch Sui SDK client".to_string()))
+            .extend()?;
+        let sui_sdk_client = sui_sdk_client
+            .as_ref()
+            .ok_or_else(|| Error::Internal("Sui SDK client not initialized".to_string()))
+            .extend()?;
+
+        DryRunResult::dry_run(
+            sui_sdk_client,
+            tx_bytes,
+            tx_meta,
+            skip_checks.unwrap_or(false),
+        )
+        .await
+        .extend()
+    }
+
     // availableRange - pending impl. on IndexerV2
-    // dryRunTransactionBlock
     // coinMetadata
 
-    async fn owner(&self, address: SuiAddress) -> Option<ObjectOwner> {
-        Some(ObjectOwner::Owner(Owner { address }))
+    /// The owner at `address`.  If `checkpoint` is provided, the objects and balances it owns are
+    /// viewed as they were at that checkpoint (which must have been indexed already), otherwise
+    /// they are viewed at the latest checkpoint.
+    async fn owner(
+        &self,
+        ctx: &Context<'_>,
+        address: SuiAddress,
+        checkpoint: Option<u64>,
+    ) -> Result<Option<ObjectOwner>> {
+        validate_checkpoint_viewed_at(ctx, checkpoint).await?;
+        Ok(Some(ObjectOwner::Owner(Owner {
+            address,
+            checkpoint_viewed_at: checkpoint,
+        })))
     }
 
+    /// The object at `address` (optionally at a specific `version`).  If `checkpoint` is provided,
+    /// the object, and any objects reached through it, are viewed as they were at that checkpoint
+    /// (which must have been indexed already), otherwise they are viewed at the latest checkpoint.
     async fn object(
         &self,
         ctx: &Context<'_>,
         address: SuiAddress,
         version: Option<u64>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Object>> {
+        validate_checkpoint_viewed_at(ctx, checkpoint).await?;
         ctx.data_unchecked::<PgManager>()
-            .fetch_obj(address, version)
+            .fetch_obj(address, version, checkpoint)
             .await
             .extend()
     }
 
-    async fn address(&self, address: SuiAddress) -> Option<Address> {
-        Some(Address { address })
+    /// The address `address`.  If `checkpoint` is provided, the objects and balances it owns are
+    /// viewed as they were at that checkpoint (which must have been indexed already), otherwise
+    /// they are viewed at the latest checkpoint.
+    async fn address(
+        &self,
+        ctx: &Context<'_>,
+        address: SuiAddress,
+        checkpoint: Option<u64>,
+    ) -> Result<Option<Address>> {
+        validate_checkpoint_viewed_at(ctx, checkpoint).await?;
+        Ok(Some(Address {
+            address,
+            checkpoint_viewed_at: checkpoint,
+        }))
     }
 
     /// Fetch a structured representation of a concrete type, including its layout information.
@@ -149,6 +231,8 @@ impl Query {
     ///
     /// The type field is a string of the inner type of the coin by which to filter
     /// (e.g. `0x2::sui::SUI`). If no type is provided, it will default to `0x2::sui::SUI`.
+    ///
+    /// If `checkpoint` is provided, coins are viewed as they were at that checkpoint.
     async fn coin_connection(
         &self,
         ctx: &Context<'_>,
@@ -157,9 +241,11 @@ impl Query {
         last: Option<u64>,
         before: Option<String>,
         type_: Option<String>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, Coin>>> {
+        validate_checkpoint_viewed_at(ctx, checkpoint).await?;
         ctx.data_unchecked::<PgManager>()
-            .fetch_coins(None, type_, first, after, last, before)
+            .fetch_coins(None, type_, first, after, last, before, checkpoint)
             .await
             .extend()
     }
@@ -208,6 +294,8 @@ impl Query {
             .extend()
     }
 
+    /// The objects that exist in the network, matching `filter`.  If `checkpoint` is provided,
+    /// objects are viewed as they were at that checkpoint.
     async fn object_connection(
         &self,
         ctx: &Context<'_>,
@@ -216,9 +304,11 @@ impl Query {
         last: Option<u64>,
         before: Option<String>,
         filter: Option<ObjectFilter>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Connection<String, Object>>> {
+        validate_checkpoint_viewed_at(ctx, checkpoint).await?;
         ctx.data_unchecked::<PgManager>()
-            .fetch_objs(first, after, last, before, filter)
+            .fetch_objs(first, after, last, before, filter, checkpoint)
             .await
             .extend()
     }
@@ -258,4 +348,37 @@ impl Query {
             .await
             .extend()
     }
+
+    /// Summary of the activity on the network, as of the latest checkpoint indexed.
+    async fn network_metrics(&self, ctx: &Context<'_>) -> Result<Option<NetworkMetrics>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_network_metrics()
+            .await
+            .extend()
+    }
+
+    /// The most called Move functions over windows of epochs ending at `epoch` (defaults to the
+    /// latest epoch these metrics were computed for).
+    async fn move_call_metrics(
+        &self,
+        ctx: &Context<'_>,
+        epoch: Option<u64>,
+    ) -> Result<Option<MoveCallMetrics>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_move_call_metrics(epoch)
+            .await
+            .extend()
+    }
+}
+
+/// Check that the checkpoint a query asks to view objects at, if any, has been indexed.
+async fn validate_checkpoint_viewed_at(ctx: &Context<'_>, checkpoint: Option<u64>) -> Result<()> {
+    if let Some(checkpoint) = checkpoint {
+        ctx.data_unchecked::<PgManager>()
+            .validate_checkpoint_viewed_at(checkpoint)
+            .await
+            .extend()?;
+    }
+
+    Ok(())
 }
@@ -0,0 +1,270 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::VecDeque,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    *,
+};
+use futures::{stream, Stream};
+use tokio::sync::{watch, OnceCell};
+use tracing::warn;
+
+use super::{
+    event::{Event, EventFilter},
+    transaction_block::{TransactionBlock, TransactionBlockFilter},
+};
+use crate::{config::Limits, context_data::db_data_provider::PgManager, error::Error};
+
+/// Lower bound on the interval at which the latest checkpoint is polled, so that a
+/// misconfigured `subscription-poll-interval-ms` cannot turn the poll into a busy loop.
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+
+pub(crate) struct Subscription;
+
+/// State shared by all subscriptions: how many of them are being served, and the latest
+/// checkpoint, which a single task polls on behalf of all of them.
+#[derive(Default)]
+pub(crate) struct SubscriptionHub {
+    active: Arc<AtomicUsize>,
+    latest_tx: OnceCell<watch::Receiver<i64>>,
+}
+
+/// The number of subscriptions served over a WebSocket connection.
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionSubscriptions(Arc<AtomicUsize>);
+
+/// Counts a subscription towards a limit for as long as it is alive.
+struct SubscriptionGuard(Arc<AtomicUsize>);
+
+/// Subscriptions are served by polling the indexer database, over the graphql-ws protocol. The
+/// database is only queried for new elements once a new checkpoint has been indexed.
+///
+/// Every element is pushed along with its cursor. Subscribing again with the last cursor received
+/// as `after` resumes the stream without missing or repeating elements, e.g. after a disconnection.
+/// Without `after`, the stream starts with the elements indexed after the latest checkpoint.
+///
+/// The stream ends after the first error, so that clients can resume it from their last cursor.
+#[Subscription]
+impl Subscription {
+    /// Stream the events matching `filter`, in the order they were emitted.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = Result<Edge<String, Event>>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        if let Some(after) = &after {
+            pg_manager.parse_event_cursor(after).extend()?;
+        }
+        let guards = admit(ctx, &pg_manager.limits)?;
+        let latest_tx = ctx
+            .data_unchecked::<SubscriptionHub>()
+            .latest_tx(&pg_manager)
+            .await?;
+        // Events are ordered by their transaction first, so this is just after every event of
+        // the latest transaction.
+        let after = after.unwrap_or_else(|| format!("{}:{}", *latest_tx.borrow(), i64::MAX));
+
+        let page_size = pg_manager.limits.max_page_size;
+        Ok(poll_connection(after, latest_tx, guards, move |after| {
+            let pg_manager = pg_manager.clone();
+            let filter = filter.clone();
+            async move {
+                pg_manager
+                    .fetch_events(Some(page_size), Some(after), None, None, filter)
+                    .await
+            }
+        }))
+    }
+
+    /// Stream the transaction blocks matching `filter`, in the order they were executed.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<Edge<String, TransactionBlock>>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        if let Some(filter) = &filter {
+            pg_manager.validate_tx_block_filter(filter).extend()?;
+        }
+        if let Some(after) = &after {
+            pg_manager.parse_tx_cursor(after).extend()?;
+        }
+        let guards = admit(ctx, &pg_manager.limits)?;
+        let latest_tx = ctx
+            .data_unchecked::<SubscriptionHub>()
+            .latest_tx(&pg_manager)
+            .await?;
+        let after = after.unwrap_or_else(|| latest_tx.borrow().to_string());
+
+        let page_size = pg_manager.limits.max_page_size;
+        Ok(poll_connection(after, latest_tx, guards, move |after| {
+            let pg_manager = pg_manager.clone();
+            let filter = filter.clone();
+            async move {
+                pg_manager
+                    .fetch_txs(Some(page_size), Some(after), None, None, filter)
+                    .await
+            }
+        }))
+    }
+}
+
+impl SubscriptionHub {
+    /// A receiver of the sequence number of the last transaction in the latest checkpoint. The
+    /// first call fetches it and starts the task that polls it every
+    /// `subscription_poll_interval_ms`.
+    async fn latest_tx(&self, pg_manager: &PgManager) -> Result<watch::Receiver<i64>> {
+        self.latest_tx
+            .get_or_try_init(|| async {
+                let (sender, receiver) = watch::channel(latest_tx_cursor(pg_manager).await?);
+                let interval = Duration::from_millis(
+                    pg_manager
+                        .limits
+                        .subscription_poll_interval_ms
+                        .max(MIN_POLL_INTERVAL_MS),
+                );
+                tokio::spawn(poll_latest_tx(pg_manager.clone(), interval, sender));
+                Ok::<_, async_graphql::Error>(receiver)
+            })
+            .await
+            .cloned()
+    }
+}
+
+impl SubscriptionGuard {
+    /// Count a new subscription in `active`, unless `max` of them are active already.
+    fn acquire(active: &Arc<AtomicUsize>, max: u32) -> Option<Self> {
+        active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < max as usize).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Self(active.clone()))
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Count a new subscription towards the global limit, and the limit of the connection it is
+/// served over, if any, failing if either limit has been reached.
+fn admit(ctx: &Context<'_>, limits: &Limits) -> Result<Vec<SubscriptionGuard>> {
+    let hub = ctx.data_unchecked::<SubscriptionHub>();
+    let global =
+        SubscriptionGuard::acquire(&hub.active, limits.max_subscriptions).ok_or_else(|| {
+            Error::Client(format!(
+                "Too many subscriptions, at most {} are served at once",
+                limits.max_subscriptions
+            ))
+            .extend()
+        })?;
+
+    let Ok(ConnectionSubscriptions(active)) = ctx.data::<ConnectionSubscriptions>() else {
+        return Ok(vec![global]);
+    };
+    let connection = SubscriptionGuard::acquire(active, limits.max_subscriptions_per_connection)
+        .ok_or_else(|| {
+            Error::Client(format!(
+                "Too many subscriptions, at most {} are served at once per connection",
+                limits.max_subscriptions_per_connection
+            ))
+            .extend()
+        })?;
+    Ok(vec![global, connection])
+}
+
+/// The sequence number of the last transaction in the latest checkpoint, or -1 if there are no
+/// transactions yet.
+async fn latest_tx_cursor(pg_manager: &PgManager) -> Result<i64> {
+    let checkpoint = pg_manager.fetch_latest_checkpoint().await.extend()?;
+    Ok(checkpoint.network_total_transactions.unwrap_or(0) as i64 - 1)
+}
+
+/// Publish the sequence number of the last transaction in the latest checkpoint to `sender`,
+/// polling for it every `interval`, while anything subscribes to it.
+async fn poll_latest_tx(pg_manager: PgManager, interval: Duration, sender: watch::Sender<i64>) {
+    loop {
+        tokio::time::sleep(interval).await;
+        // The hub holds a receiver of its own.
+        if sender.receiver_count() <= 1 {
+            continue;
+        }
+
+        match latest_tx_cursor(&pg_manager).await {
+            Ok(latest) => {
+                sender.send_if_modified(|current| {
+                    let modified = *current != latest;
+                    *current = latest;
+                    modified
+                });
+            }
+            Err(e) => warn!("Failed to fetch the latest checkpoint for subscriptions: {e:?}"),
+        }
+    }
+}
+
+/// Turn a paginated query into an endless stream of the elements it returns, in order.  `fetch`
+/// returns the page of elements after the given cursor.  Full pages are fetched back to back, and
+/// once the stream has caught up, the query is repeated whenever `latest_tx` changes, i.e. a new
+/// checkpoint has been indexed.  `guards` are released when the stream is dropped.
+fn poll_connection<N, F, Fut>(
+    after: String,
+    latest_tx: watch::Receiver<i64>,
+    guards: Vec<SubscriptionGuard>,
+    fetch: F,
+) -> impl Stream<Item = Result<Edge<String, N>>>
+where
+    N: OutputType + Send,
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Option<Connection<String, N>>, Error>> + Send,
+{
+    // `None` as the cursor marks the end of the stream.
+    let state = (Some(after), VecDeque::new(), fetch, latest_tx, guards);
+    stream::unfold(
+        state,
+        move |(after, mut page, fetch, mut latest_tx, guards)| async move {
+            let mut after = after?;
+            loop {
+                if let Some(edge) = page.pop_front() {
+                    return Some((Ok(edge), (Some(after), page, fetch, latest_tx, guards)));
+                }
+
+                // Anything indexed up to this checkpoint is visible to the fetch below.
+                latest_tx.borrow_and_update();
+                match fetch(after.clone()).await {
+                    Ok(Some(connection)) if !connection.edges.is_empty() => {
+                        page.extend(connection.edges);
+                        if let Some(last) = page.back() {
+                            after = last.cursor.encode_cursor();
+                        }
+                    }
+                    // Caught up, or the data the filter refers to has not been indexed yet.
+                    Ok(_) => {
+                        if latest_tx.changed().await.is_err() {
+                            return None;
+                        }
+                    }
+                    Err(e) => {
+                        return Some((Err(e.extend()), (None, page, fetch, latest_tx, guards)))
+                    }
+                }
+            }
+        },
+    )
+}
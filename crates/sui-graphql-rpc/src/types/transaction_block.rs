@@ -70,6 +70,7 @@ impl TransactionBlock {
         let sender = self.native.transaction_data().sender();
         (sender != NativeSuiAddress::ZERO).then(|| Address {
             address: SuiAddress::from(sender),
+            checkpoint_viewed_at: None,
         })
     }
 
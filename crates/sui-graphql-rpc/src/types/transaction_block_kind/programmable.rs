@@ -158,7 +158,7 @@ struct MakeMoveVecTransaction {
 
 /// An argument to a programmable transaction command.
 #[derive(Union, Clone, Eq, PartialEq)]
-enum TransactionArgument {
+pub(crate) enum TransactionArgument {
     GasCoin(GasCoin),
     Input(Input),
     Result(TxResult),
@@ -24,6 +24,7 @@ impl Validator {
     async fn address(&self) -> Address {
         Address {
             address: SuiAddress::from(self.validator_summary.sui_address),
+            checkpoint_viewed_at: None,
         }
     }
 
@@ -87,7 +88,7 @@ impl Validator {
     /// can then update the reference gas price and tallying rule on behalf of the validator.
     async fn operation_cap(&self, ctx: &Context<'_>) -> Result<Option<MoveObject>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_move_obj(self.operation_cap_id(), None)
+            .fetch_move_obj(self.operation_cap_id(), None, None)
             .await
             .extend()
     }
@@ -96,7 +97,7 @@ impl Validator {
     /// and to compound staking rewards.
     async fn staking_pool(&self, ctx: &Context<'_>) -> Result<Option<MoveObject>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_move_obj(self.staking_pool_id(), None)
+            .fetch_move_obj(self.staking_pool_id(), None, None)
             .await
             .extend()
     }
@@ -105,7 +106,7 @@ impl Validator {
     /// the amount of SUI tokens that each past SUI staker can withdraw in the future.
     async fn exchange_rates(&self, ctx: &Context<'_>) -> Result<Option<MoveObject>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_move_obj(self.exchange_rates_id(), None)
+            .fetch_move_obj(self.exchange_rates_id(), None, None)
             .await
             .extend()
     }
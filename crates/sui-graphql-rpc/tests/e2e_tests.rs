@@ -3,6 +3,8 @@
 
 #[cfg(feature = "pg_integration")]
 mod tests {
+    use diesel::{Connection, PgConnection, RunQueryDsl};
+    use fastcrypto::encoding::{Base64, Encoding};
     use rand::rngs::StdRng;
     use rand::SeedableRng;
     use serde_json::json;
@@ -13,8 +15,24 @@ mod tests {
     use sui_graphql_rpc::client::simple_client::GraphqlQueryVariable;
     use sui_graphql_rpc::client::ClientError;
     use sui_graphql_rpc::config::ConnectionConfig;
-    use sui_graphql_rpc::test_infra::cluster::DEFAULT_INTERNAL_DATA_SOURCE_PORT;
+    use sui_graphql_rpc::test_infra::cluster::{
+        ExecutorCluster, DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+    };
+    use sui_indexer::models_v2::{
+        address_metrics::StoredAddressMetrics, network_metrics::StoredEpochPeakTps,
+        tx_count_metrics::StoredTxCountMetrics,
+    };
+    use sui_indexer::schema_v2::{address_metrics, epoch_peak_tps, tx_count_metrics};
+    use sui_types::base_types::ObjectID;
     use sui_types::digests::ChainIdentifier;
+    use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+    use sui_types::gas_coin::MIST_PER_SUI;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::storage::ObjectStore;
+    use sui_types::transaction::{
+        Argument, Command, GasData, ObjectArg, ProgrammableTransaction, Transaction,
+        TransactionData, TransactionKind,
+    };
     use sui_types::DEEPBOOK_ADDRESS;
     use sui_types::SUI_FRAMEWORK_ADDRESS;
     use tokio::time::sleep;
@@ -293,6 +311,277 @@ mod tests {
         }
     }
 
+    async fn query_data(cluster: &ExecutorCluster, query: String) -> serde_json::Value {
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query, true, vec![], vec![])
+            .await
+            .unwrap();
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        res.response_body().data.clone().into_json().unwrap()
+    }
+
+    async fn network_metrics(cluster: &ExecutorCluster, query: &str) -> serde_json::Value {
+        query_data(cluster, query.to_string()).await["networkMetrics"].clone()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_network_metrics() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        sim.create_checkpoint();
+        sim.create_checkpoint();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let db_url = connection_config.db_url();
+        let cluster = sui_graphql_rpc::test_infra::cluster::serve_executor(
+            connection_config,
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+        )
+        .await;
+        cluster
+            .wait_for_checkpoint_catchup(2, Duration::from_secs(10))
+            .await;
+
+        let query = r#"
+            {
+                networkMetrics {
+                    currentTps
+                    tps30Days
+                    currentEpoch
+                    currentCheckpoint
+                    transactionCountMetricsConnection(last: 1) {
+                        nodes { checkpoint totalTransactionBlocks totalSuccessfulTransactions }
+                    }
+                    addressMetricsConnection(filter: { firstCheckpoint: 2 }) {
+                        nodes { checkpoint cumulativeAddresses }
+                    }
+                }
+            }
+        "#;
+
+        // Until the indexer's analytics have been computed, the metrics derived from them are 0.
+        assert_eq!(
+            network_metrics(&cluster, query).await,
+            json!({
+                "currentTps": 0.0,
+                "tps30Days": 0.0,
+                "currentEpoch": 0,
+                "currentCheckpoint": 2,
+                "transactionCountMetricsConnection": { "nodes": [] },
+                "addressMetricsConnection": { "nodes": [] },
+            })
+        );
+
+        // Fill in the analytics, as the indexer's analytical worker would.
+        let mut conn = PgConnection::establish(&db_url).unwrap();
+        diesel::insert_into(tx_count_metrics::table)
+            .values(vec![
+                StoredTxCountMetrics {
+                    checkpoint_sequence_number: 1,
+                    epoch: 0,
+                    timestamp_ms: 1_000,
+                    total_transaction_blocks: 4,
+                    total_successful_transaction_blocks: 4,
+                    total_successful_transactions: 10,
+                },
+                StoredTxCountMetrics {
+                    checkpoint_sequence_number: 2,
+                    epoch: 0,
+                    timestamp_ms: 3_000,
+                    total_transaction_blocks: 6,
+                    total_successful_transaction_blocks: 5,
+                    total_successful_transactions: 12,
+                },
+            ])
+            .execute(&mut conn)
+            .unwrap();
+        diesel::insert_into(address_metrics::table)
+            .values(
+                [1, 2, 3]
+                    .into_iter()
+                    .map(|checkpoint| StoredAddressMetrics {
+                        checkpoint,
+                        epoch: 0,
+                        timestamp_ms: checkpoint * 1_000,
+                        cumulative_addresses: checkpoint * 10,
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .execute(&mut conn)
+            .unwrap();
+        diesel::insert_into(epoch_peak_tps::table)
+            .values(StoredEpochPeakTps {
+                epoch: 0,
+                peak_tps: 7.5,
+                peak_tps_30d: 8.5,
+            })
+            .execute(&mut conn)
+            .unwrap();
+
+        // The current TPS is computed over the latest checkpoints: 12 transactions in 2 seconds.
+        assert_eq!(
+            network_metrics(&cluster, query).await,
+            json!({
+                "currentTps": 6.0,
+                "tps30Days": 8.5,
+                "currentEpoch": 0,
+                "currentCheckpoint": 2,
+                "transactionCountMetricsConnection": {
+                    "nodes": [{
+                        "checkpoint": 2,
+                        "totalTransactionBlocks": 6,
+                        "totalSuccessfulTransactions": 12,
+                    }],
+                },
+                "addressMetricsConnection": {
+                    "nodes": [
+                        { "checkpoint": 2, "cumulativeAddresses": 20 },
+                        { "checkpoint": 3, "cumulativeAddresses": 30 },
+                    ],
+                },
+            })
+        );
+    }
+
+    /// Execute `pt` from the first account in `sim`'s keystore, paying for gas with `gas`, and
+    /// seal it in a checkpoint of its own.
+    fn execute_in_checkpoint(
+        sim: &mut Simulacrum,
+        gas: ObjectID,
+        pt: ProgrammableTransaction,
+    ) -> TransactionEffects {
+        let (sender, key) = sim.keystore().accounts().next().unwrap();
+        let gas_data = GasData {
+            payment: vec![sim
+                .get_object(&gas)
+                .unwrap()
+                .unwrap()
+                .compute_object_reference()],
+            owner: *sender,
+            price: sim.reference_gas_price(),
+            budget: MIST_PER_SUI,
+        };
+
+        let kind = TransactionKind::ProgrammableTransaction(pt);
+        let tx_data = TransactionData::new_with_gas_data(kind, *sender, gas_data);
+        let tx = Transaction::from_data_and_signer(tx_data, vec![key]);
+
+        let (effects, error) = sim.execute_transaction(tx).unwrap();
+        assert!(error.is_none(), "{error:?}");
+        sim.create_checkpoint();
+        effects
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_object_at_checkpoint() {
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        let sender = *sim.keystore().accounts().next().unwrap().0;
+        let gas = sim
+            .store()
+            .owned_objects(sender)
+            .filter(|o| o.is_gas_coin())
+            .max_by_key(|o| o.get_coin_value_unsafe())
+            .unwrap();
+        let gas_id = gas.id();
+        let gas_v0 = gas.version().value();
+
+        // Checkpoint 1: Split a new coin off the gas coin.
+        let split = execute_in_checkpoint(&mut sim, gas_id, {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.transfer_sui(sender, Some(MIST_PER_SUI));
+            builder.finish()
+        });
+        let (coin_id, coin_v1, _) = split.created()[0].0;
+        let gas_v1 = split.gas_object().0 .1.value();
+
+        // Checkpoint 2: Merge the new coin back into the gas coin, deleting it.
+        let coin = sim.get_object(&coin_id).unwrap().unwrap();
+        let merge = execute_in_checkpoint(&mut sim, gas_id, {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let coin = builder
+                .obj(ObjectArg::ImmOrOwnedObject(coin.compute_object_reference()))
+                .unwrap();
+            builder.command(Command::MergeCoins(Argument::GasCoin, vec![coin]));
+            builder.finish()
+        });
+        let gas_v2 = merge.gas_object().0 .1.value();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster = sui_graphql_rpc::test_infra::cluster::serve_executor(
+            connection_config,
+            DEFAULT_INTERNAL_DATA_SOURCE_PORT,
+            Arc::new(sim),
+        )
+        .await;
+        cluster
+            .wait_for_checkpoint_catchup(2, Duration::from_secs(10))
+            .await;
+
+        let query = format!(
+            r#"
+            {{
+                gas0: object(address: "{gas_id}", checkpoint: 0) {{ version }}
+                gas1: object(address: "{gas_id}", checkpoint: 1) {{ version }}
+                gas2: object(address: "{gas_id}", checkpoint: 2) {{ version }}
+                coin0: object(address: "{coin_id}", checkpoint: 0) {{ version }}
+                coin1: object(address: "{coin_id}", checkpoint: 1) {{ version }}
+                coin2: object(address: "{coin_id}", checkpoint: 2) {{ version }}
+                coin: object(address: "{coin_id}") {{ version }}
+                sender1: address(address: "{sender}", checkpoint: 1) {{
+                    balance {{ coinObjectCount }}
+                }}
+                sender2: address(address: "{sender}", checkpoint: 2) {{
+                    balance {{ coinObjectCount }}
+                }}
+            }}
+            "#
+        );
+
+        let data = query_data(&cluster, query).await;
+
+        // Past versions of a live object are served from its history.
+        assert_eq!(data["gas0"], json!({ "version": gas_v0 }));
+        assert_eq!(data["gas1"], json!({ "version": gas_v1 }));
+        assert_eq!(data["gas2"], json!({ "version": gas_v2 }));
+
+        // A deleted object is only visible at the checkpoints where it was live.
+        assert_eq!(data["coin0"], json!(null));
+        assert_eq!(data["coin1"], json!({ "version": coin_v1.value() }));
+        assert_eq!(data["coin2"], json!(null));
+        assert_eq!(data["coin"], json!(null));
+
+        // Balances owned by an address are consistent with the objects at that checkpoint.
+        let count =
+            |sender: &serde_json::Value| sender["balance"]["coinObjectCount"].as_u64().unwrap();
+        assert_eq!(count(&data["sender1"]), count(&data["sender2"]) + 1);
+
+        // Checkpoints that have not been indexed yet are rejected.
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(
+                format!(r#"{{ object(address: "{gas_id}", checkpoint: 99) {{ version }} }}"#),
+                true,
+                vec![],
+                vec![],
+            )
+            .await
+            .unwrap();
+        let errors = res.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "Checkpoint 99 has not been indexed yet. The latest checkpoint indexed is 2"
+        );
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_transaction_execution() {
@@ -388,6 +677,120 @@ mod tests {
         assert_eq!(sender_read, sender.to_string());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_dry_run_transaction_block() {
+        let _guard = telemetry_subscribers::TelemetryConfig::new()
+            .with_env()
+            .init();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+
+        let cluster =
+            sui_graphql_rpc::test_infra::cluster::start_cluster(connection_config, None).await;
+
+        let addresses = cluster.validator_fullnode_handle.wallet.get_addresses();
+
+        let recipient = addresses[1];
+        let tx = cluster
+            .validator_fullnode_handle
+            .test_transaction_builder()
+            .await
+            .transfer_sui(Some(1_000), recipient)
+            .build();
+        let tx_bytes = Base64::encode(bcs::to_bytes(&tx).unwrap());
+
+        let query = r#"
+            {
+                dryRunTransactionBlock(txBytes: $tx) {
+                    error
+                    effects {
+                        status
+                        balanceChanges {
+                            amount
+                        }
+                    }
+                    results {
+                        returnValues {
+                            bcs
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = vec![GraphqlQueryVariable {
+            name: "tx".to_string(),
+            ty: "String!".to_string(),
+            value: json!(tx_bytes),
+        }];
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query.to_string(), true, variables.clone(), vec![])
+            .await
+            .unwrap();
+
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        let binding = res.response_body().data.clone().into_json().unwrap();
+        let res = binding.get("dryRunTransactionBlock").unwrap();
+
+        assert!(res.get("error").unwrap().is_null());
+        let effects = res.get("effects").unwrap();
+        assert_eq!(effects.get("status").unwrap().as_str().unwrap(), "SUCCESS");
+        // The sender pays, and the recipient receives.
+        assert_eq!(
+            effects
+                .get("balanceChanges")
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        // One result per command: splitting the gas coin, and transferring the split coin.
+        assert_eq!(res.get("results").unwrap().as_array().unwrap().len(), 2);
+
+        // Dev inspecting the same transaction reports its results, but no balance changes.
+        let query = r#"
+            {
+                dryRunTransactionBlock(txBytes: $tx, skipChecks: true) {
+                    error
+                    effects {
+                        status
+                        balanceChanges {
+                            amount
+                        }
+                    }
+                    results {
+                        returnValues {
+                            bcs
+                        }
+                    }
+                }
+            }
+        "#;
+        let res = cluster
+            .graphql_client
+            .execute_to_graphql(query.to_string(), true, variables, vec![])
+            .await
+            .unwrap();
+
+        assert!(res.errors().is_empty(), "{:?}", res.errors());
+        let binding = res.response_body().data.clone().into_json().unwrap();
+        let res = binding.get("dryRunTransactionBlock").unwrap();
+
+        assert!(res.get("error").unwrap().is_null());
+        let effects = res.get("effects").unwrap();
+        assert_eq!(effects.get("status").unwrap().as_str().unwrap(), "SUCCESS");
+        assert!(effects
+            .get("balanceChanges")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert_eq!(res.get("results").unwrap().as_array().unwrap().len(), 2);
+    }
+
     use sui_graphql_rpc::server::builder::tests::*;
 
     #[tokio::test]
@@ -425,4 +828,10 @@ mod tests {
     async fn test_query_complexity_metrics() {
         test_query_complexity_metrics_impl().await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_subscription() {
+        test_subscription_impl().await;
+    }
 }
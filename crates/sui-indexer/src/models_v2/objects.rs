@@ -102,6 +102,55 @@ impl From<StoredObject> for StoredHistoryObject {
     }
 }
 
+impl TryFrom<StoredHistoryObject> for StoredObject {
+    type Error = IndexerError;
+
+    /// Recover the live representation of an object from a row of its history.  Fails if the row
+    /// records the object being wrapped or deleted, as there is no live object to recover.
+    fn try_from(o: StoredHistoryObject) -> Result<Self, Self::Error> {
+        let corrupt = |column: &str| {
+            IndexerError::PersistentStorageDataCorruptionError(format!(
+                "History of active object {:?} at version {} is missing {column}",
+                o.object_id, o.object_version,
+            ))
+        };
+
+        if o.object_status != ObjectStatus::Active as i16 {
+            return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Object {:?} at version {} is wrapped or deleted",
+                o.object_id, o.object_version,
+            )));
+        }
+
+        let object_digest = o
+            .object_digest
+            .clone()
+            .ok_or_else(|| corrupt("object_digest"))?;
+        let owner_type = o.owner_type.ok_or_else(|| corrupt("owner_type"))?;
+        let serialized_object = o
+            .serialized_object
+            .clone()
+            .ok_or_else(|| corrupt("serialized_object"))?;
+
+        Ok(Self {
+            object_id: o.object_id,
+            object_version: o.object_version,
+            object_digest,
+            checkpoint_sequence_number: o.checkpoint_sequence_number,
+            owner_type,
+            owner_id: o.owner_id,
+            object_type: o.object_type,
+            serialized_object,
+            coin_type: o.coin_type,
+            coin_balance: o.coin_balance,
+            df_kind: o.df_kind,
+            df_name: o.df_name,
+            df_object_type: o.df_object_type,
+            df_object_id: o.df_object_id,
+        })
+    }
+}
+
 #[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
 #[diesel(table_name = objects, primary_key(object_id))]
 pub struct StoredDeletedObject {
@@ -419,6 +468,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_history_object_round_trip() {
+        let test_obj = Object::new_gas_for_testing();
+        let stored_obj = StoredObject::from(IndexedObject::from_object(1, test_obj, None));
+
+        let history_obj = StoredHistoryObject::from(stored_obj.clone());
+        let live_obj = StoredObject::try_from(history_obj).unwrap();
+
+        assert_eq!(live_obj.object_id, stored_obj.object_id);
+        assert_eq!(live_obj.object_version, stored_obj.object_version);
+        assert_eq!(live_obj.object_digest, stored_obj.object_digest);
+        assert_eq!(live_obj.owner_type, stored_obj.owner_type);
+        assert_eq!(live_obj.serialized_object, stored_obj.serialized_object);
+        assert_eq!(live_obj.coin_balance, stored_obj.coin_balance);
+    }
+
+    #[test]
+    fn test_wrapped_or_deleted_history_object_is_not_live() {
+        let test_obj = Object::new_gas_for_testing();
+        let stored_obj = StoredObject::from(IndexedObject::from_object(1, test_obj, None));
+
+        let deleted = StoredHistoryObject {
+            object_status: ObjectStatus::WrappedOrDeleted as i16,
+            object_digest: None,
+            owner_type: None,
+            serialized_object: None,
+            ..StoredHistoryObject::from(stored_obj.clone())
+        };
+        assert!(StoredObject::try_from(deleted).is_err());
+
+        // Active rows must have all the columns of a live object.
+        let corrupt = StoredHistoryObject {
+            serialized_object: None,
+            ..StoredHistoryObject::from(stored_obj)
+        };
+        assert!(StoredObject::try_from(corrupt).is_err());
+    }
+
     #[test]
     fn test_convert_stored_obj_to_sui_coin() {
         let test_obj = Object::new_gas_for_testing();
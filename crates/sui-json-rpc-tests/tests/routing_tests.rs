@@ -5,15 +5,20 @@ use async_trait::async_trait;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
 use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::BatchRequestBuilder;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClientBuilder;
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::WsClientBuilder;
 use jsonrpsee::RpcModule;
 use prometheus::Registry;
+use serde_json::{json, Value};
 use std::env;
+use std::time::Duration;
 use sui_config::local_ip_utils;
-use sui_json_rpc::{JsonRpcServerBuilder, SuiRpcModule};
+use sui_json_rpc::axum_router::{BatchConfig, TOO_BIG_BATCH_REQUEST_CODE};
+use sui_json_rpc::{JsonRpcServerBuilder, ServerType, SuiRpcModule};
 use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
@@ -132,71 +137,162 @@ async fn test_disable_routing() {
 }
 
 // TODO(chris): clean up this after March 27th, 2023
-// #[tokio::test]
-// async fn test_rpc_backward_compatibility_batched_request() {
-//     let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
-//     builder.register_module(TestApiModule).unwrap();
-
-//     let port = get_available_port("0.0.0.0");
-//     let handle = builder
-//         .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
-//         .await
-//         .unwrap();
-//     let url = format!("http://0.0.0.0:{}", port);
-
-//     // Test with un-versioned client
-//     let client = HttpClientBuilder::default().build(&url).unwrap();
-
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(3, response.num_successful_calls());
-
-//     // try to access old method directly should fail
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo_1_5", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(2, response.num_successful_calls());
-
-//     // One malformed request shouldn't fail the whole batch
-//     let client = Client::new();
-//     let response = client
-//         .post(format!("http://127.0.0.1:{}/", port))
-//         .json(&vec![
-//             json!(&Request {
-//                 jsonrpc: Default::default(),
-//                 id: Id::Number(1),
-//                 method: "test_foo".into(),
-//                 params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
-//             }),
-//             json!("Bad json input"),
-//         ])
-//         .send()
-//         .await
-//         .unwrap();
-
-//     let responses = response.text().await.unwrap();
-//     let responses: Vec<&JsonRawValue> = serde_json::from_str(&responses).unwrap();
-
-//     // Should have 2 results
-//     assert_eq!(2, responses.len());
-
-//     // First response should success
-//     let response = serde_json::from_str::<Response<String>>(responses[0].get());
-//     assert!(matches!(response, Ok(result) if result.result == "Some string"));
-
-//     // Second response should fail
-//     let response = serde_json::from_str::<ErrorResponse>(responses[1].get());
-//     assert!(matches!(response, Ok(result) if result.error_object().message() == "Invalid request"));
-
-//     handle.stop().unwrap()
-// }
+#[tokio::test]
+async fn test_rpc_backward_compatibility_batched_request() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+
+    // Test with un-versioned client
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+
+    let response = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(3, response.num_successful_calls());
+
+    // try to access old method directly should fail
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo_1_5", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+
+    let response = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(2, response.num_successful_calls());
+
+    // Every call of the batch is routed according to the client version
+    let mut versioned_header = HeaderMap::new();
+    versioned_header.insert(
+        CLIENT_TARGET_API_VERSION_HEADER,
+        HeaderValue::from_static("1.5"),
+    );
+    let client_with_new_header = HttpClientBuilder::default()
+        .set_headers(versioned_header)
+        .build(&url)
+        .unwrap();
+
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo", rpc_params!("string")).unwrap();
+    batch.insert("test_foo", rpc_params!("string")).unwrap();
+
+    let response = client_with_new_header
+        .batch_request::<String>(batch)
+        .await
+        .unwrap();
+    let results: Vec<_> = response.into_ok().unwrap().collect();
+    assert_eq!(
+        vec!["Some string from old method", "Some string from old method"],
+        results
+    );
+
+    // One malformed request shouldn't fail the whole batch
+    let responses = post_raw(
+        &url,
+        json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "test_foo", "params": [true]},
+            "Bad json input",
+        ]),
+    )
+    .await;
+    let responses = responses.as_array().unwrap();
+
+    // Should have 2 results
+    assert_eq!(2, responses.len());
+
+    // First response should success
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"], "Some string");
+
+    // Second response should fail
+    assert_eq!(responses[1]["error"]["message"], "Invalid request");
+
+    // An empty batch is an invalid request
+    let response = post_raw(&url, json!([])).await;
+    assert_eq!(response["error"]["message"], "Invalid request");
+}
+
+#[tokio::test]
+async fn test_batch_request_limits() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_batch_config(BatchConfig {
+        max_batch_size: 2,
+        max_concurrent_calls: 1,
+    });
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder.start(address, None, None).await.unwrap();
+    let url = format!("http://0.0.0.0:{}", address.port());
+    let call =
+        |id: u64| json!({"jsonrpc": "2.0", "id": id, "method": "test_foo", "params": [true]});
+
+    // Responses are in the order of the calls
+    let responses = post_raw(&url, json!([call(1), call(2)])).await;
+    let ids: Vec<_> = responses
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|response| response["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(vec![1, 2], ids);
+
+    let response = post_raw(&url, json!([call(1), call(2), call(3)])).await;
+    assert_eq!(response["error"]["code"], TOO_BIG_BATCH_REQUEST_CODE);
+}
+
+#[tokio::test]
+async fn test_ws_batch_request_limits() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_batch_config(BatchConfig {
+        max_batch_size: 2,
+        max_concurrent_calls: 1,
+    });
+
+    let address = local_ip_utils::new_local_tcp_socket_for_testing();
+    let _handle = builder
+        .start(address, None, Some(ServerType::WebSocket))
+        .await
+        .unwrap();
+    let url = format!("ws://0.0.0.0:{}", address.port());
+    let client = WsClientBuilder::default().build(&url).await.unwrap();
+
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    let response = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(2, response.num_successful_calls());
+
+    let mut batch = BatchRequestBuilder::default();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    batch.insert("test_foo", rpc_params!(true)).unwrap();
+    let response = tokio::time::timeout(
+        Duration::from_secs(10),
+        client.batch_request::<String>(batch),
+    )
+    .await
+    .expect("the server should reject the batch");
+    assert!(response.is_err());
+}
+
+async fn post_raw(url: &str, body: Value) -> Value {
+    reqwest::Client::new()
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
 
 #[open_rpc(namespace = "test")]
 #[rpc(server, client, namespace = "test")]
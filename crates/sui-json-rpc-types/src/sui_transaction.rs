@@ -262,60 +262,68 @@ impl Display for SuiTransactionBlockResponse {
         }
 
         if let Some(object_changes) = &self.object_changes {
-            let mut builder = TableBuilder::default();
-            let (
-                mut created,
-                mut deleted,
-                mut mutated,
-                mut published,
-                mut transferred,
-                mut wrapped,
-            ) = (vec![], vec![], vec![], vec![], vec![], vec![]);
-
-            for obj in object_changes {
-                match obj {
-                    ObjectChange::Created { .. } => created.push(obj),
-                    ObjectChange::Deleted { .. } => deleted.push(obj),
-                    ObjectChange::Mutated { .. } => mutated.push(obj),
-                    ObjectChange::Published { .. } => published.push(obj),
-                    ObjectChange::Transferred { .. } => transferred.push(obj),
-                    ObjectChange::Wrapped { .. } => wrapped.push(obj),
-                };
-            }
-
-            write_obj_changes(created, "Created", &mut builder)?;
-            write_obj_changes(deleted, "Deleted", &mut builder)?;
-            write_obj_changes(mutated, "Mutated", &mut builder)?;
-            write_obj_changes(published, "Published", &mut builder)?;
-            write_obj_changes(transferred, "Transferred", &mut builder)?;
-            write_obj_changes(wrapped, "Wrapped", &mut builder)?;
-
-            let mut table = builder.build();
-            table.with(TablePanel::header("Object Changes"));
-            table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
-                1,
-                TableStyle::modern().get_horizontal(),
-            )]));
-            writeln!(writer, "{}", table)?;
+            write_object_changes_table(writer, object_changes)?;
         }
 
         if let Some(balance_changes) = &self.balance_changes {
-            let mut builder = TableBuilder::default();
-            for balance in balance_changes {
-                builder.push_record(vec![format!("{}", balance)]);
-            }
-            let mut table = builder.build();
-            table.with(TablePanel::header("Balance Changes"));
-            table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
-                1,
-                TableStyle::modern().get_horizontal(),
-            )]));
-            writeln!(writer, "{}", table)?;
+            write_balance_changes_table(writer, balance_changes)?;
         }
         Ok(())
     }
 }
 
+fn write_object_changes_table(
+    writer: &mut Formatter<'_>,
+    object_changes: &[ObjectChange],
+) -> fmt::Result {
+    let mut builder = TableBuilder::default();
+    let (mut created, mut deleted, mut mutated, mut published, mut transferred, mut wrapped) =
+        (vec![], vec![], vec![], vec![], vec![], vec![]);
+
+    for obj in object_changes {
+        match obj {
+            ObjectChange::Created { .. } => created.push(obj),
+            ObjectChange::Deleted { .. } => deleted.push(obj),
+            ObjectChange::Mutated { .. } => mutated.push(obj),
+            ObjectChange::Published { .. } => published.push(obj),
+            ObjectChange::Transferred { .. } => transferred.push(obj),
+            ObjectChange::Wrapped { .. } => wrapped.push(obj),
+        };
+    }
+
+    write_obj_changes(created, "Created", &mut builder)?;
+    write_obj_changes(deleted, "Deleted", &mut builder)?;
+    write_obj_changes(mutated, "Mutated", &mut builder)?;
+    write_obj_changes(published, "Published", &mut builder)?;
+    write_obj_changes(transferred, "Transferred", &mut builder)?;
+    write_obj_changes(wrapped, "Wrapped", &mut builder)?;
+
+    let mut table = builder.build();
+    table.with(TablePanel::header("Object Changes"));
+    table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
+        1,
+        TableStyle::modern().get_horizontal(),
+    )]));
+    writeln!(writer, "{}", table)
+}
+
+fn write_balance_changes_table(
+    writer: &mut Formatter<'_>,
+    balance_changes: &[BalanceChange],
+) -> fmt::Result {
+    let mut builder = TableBuilder::default();
+    for balance in balance_changes {
+        builder.push_record(vec![format!("{}", balance)]);
+    }
+    let mut table = builder.build();
+    table.with(TablePanel::header("Balance Changes"));
+    table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
+        1,
+        TableStyle::modern().get_horizontal(),
+    )]));
+    writeln!(writer, "{}", table)
+}
+
 fn write_obj_changes<T: Display>(
     values: Vec<T>,
     output_string: &str,
@@ -948,6 +956,20 @@ pub struct DryRunTransactionBlockResponse {
     pub input: SuiTransactionBlockData,
 }
 
+impl Display for DryRunTransactionBlockResponse {
+    fn fmt(&self, writer: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            writer,
+            "Dry run of transaction block {}",
+            self.effects.transaction_digest()
+        )?;
+        writeln!(writer, "{}", self.effects)?;
+        writeln!(writer, "{}", self.events)?;
+        write_object_changes_table(writer, &self.object_changes)?;
+        write_balance_changes_table(writer, &self.balance_changes)
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "TransactionBlockEvents", transparent)]
 pub struct SuiTransactionBlockEvents {
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::future::Future;
 use std::sync::Arc;
 
 use axum::extract::Json;
@@ -23,6 +24,27 @@ use sui_json_rpc_api::CLIENT_TARGET_API_VERSION_HEADER;
 
 pub const MAX_RESPONSE_SIZE: u32 = 2 << 30;
 
+pub const TOO_BIG_BATCH_REQUEST_CODE: i32 = -32010;
+pub const TOO_BIG_BATCH_REQUEST_MSG: &str = "The batch request was too large";
+
+/// Limits on JSON-RPC batch requests, i.e. arrays of calls sent in a single request.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchConfig {
+    /// Maximum number of calls in a batch request. Batch requests are rejected if 0.
+    pub max_batch_size: usize,
+    /// Maximum number of calls of a batch request executed concurrently.
+    pub max_concurrent_calls: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 50,
+            max_concurrent_calls: 10,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct JsonRpcService<L> {
     logger: L,
@@ -32,15 +54,22 @@ pub struct JsonRpcService<L> {
     /// Registered server methods.
     methods: Methods,
     rpc_router: RpcRouter,
+    batch_config: BatchConfig,
 }
 
 impl<L> JsonRpcService<L> {
-    pub fn new(methods: Methods, rpc_router: RpcRouter, logger: L) -> Self {
+    pub fn new(
+        methods: Methods,
+        rpc_router: RpcRouter,
+        logger: L,
+        batch_config: BatchConfig,
+    ) -> Self {
         Self {
             methods,
             rpc_router,
             logger,
             id_provider: Arc::new(RandomIntegerIdProvider),
+            batch_config,
         }
     }
 }
@@ -64,6 +93,7 @@ impl<L: Logger> JsonRpcService<L> {
         ws::WsCallData {
             logger: &self.logger,
             methods: &self.methods,
+            rpc_router: &self.rpc_router,
             max_response_body_size: MAX_RESPONSE_SIZE,
             request_start: self.logger.on_request(TransportProtocol::Http),
             bounded_subscriptions,
@@ -108,27 +138,98 @@ pub async fn json_rpc_handler<L: Logger>(
         .and_then(|h| h.to_str().ok());
     let response = process_raw_request(&service, api_version, raw_request.get()).await;
 
-    ok_response(response.result)
+    ok_response(response)
 }
 
 async fn process_raw_request<L: Logger>(
     service: &JsonRpcService<L>,
     api_version: Option<&str>,
     raw_request: &str,
-) -> MethodResponse {
+) -> String {
     if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-        process_request(request, api_version, service.call_data()).await
-    } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-        MethodResponse::error(
-            Id::Null,
-            ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-        )
+        process_request(request, api_version, service.call_data())
+            .await
+            .result
+    } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+        process_batch(service.batch_config, batch, |raw_call| async move {
+            Some(match serde_json::from_str::<Request>(raw_call) {
+                Ok(request) => process_request(request, api_version, service.call_data()).await,
+                Err(_) => batch_call_error(raw_call),
+            })
+        })
+        .await
+        // Calls over HTTP always have a response.
+        .unwrap_or_default()
     } else {
         let (id, code) = prepare_error(raw_request);
-        MethodResponse::error(id, ErrorObject::from(code))
+        MethodResponse::error(id, ErrorObject::from(code)).result
     }
 }
 
+/// Process the calls of a batch request with `process_call`, executing at most
+/// `max_concurrent_calls` of them at a time, and return the batch response. Responses are in the
+/// order of the calls. Returns `None` if none of the calls has a response.
+async fn process_batch<'a, F, Fut>(
+    batch_config: BatchConfig,
+    batch: Vec<&'a RawValue>,
+    process_call: F,
+) -> Option<String>
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = Option<MethodResponse>>,
+{
+    if batch.is_empty() {
+        // As per the spec, an empty batch is an invalid request, rather than an empty batch.
+        return Some(
+            MethodResponse::error(Id::Null, ErrorObject::from(ErrorCode::InvalidRequest)).result,
+        );
+    }
+    if batch_config.max_batch_size == 0 {
+        return Some(
+            MethodResponse::error(
+                Id::Null,
+                ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
+            )
+            .result,
+        );
+    }
+    if batch.len() > batch_config.max_batch_size {
+        return Some(
+            MethodResponse::error(
+                Id::Null,
+                ErrorObject::owned(
+                    TOO_BIG_BATCH_REQUEST_CODE,
+                    TOO_BIG_BATCH_REQUEST_MSG,
+                    Some(format!(
+                        "Exceeded max limit of {}",
+                        batch_config.max_batch_size
+                    )),
+                ),
+            )
+            .result,
+        );
+    }
+
+    let responses: Vec<String> = futures::stream::iter(batch)
+        .map(|raw_call| process_call(raw_call.get()))
+        .buffered(batch_config.max_concurrent_calls.max(1))
+        .filter_map(|response| async move { response.map(|response| response.result) })
+        .collect()
+        .await;
+    if responses.is_empty() {
+        None
+    } else {
+        Some(format!("[{}]", responses.join(",")))
+    }
+}
+
+/// The response to a call of a batch request which isn't a valid request. Unlike a request on its
+/// own, it is valid JSON as part of the batch, so it can't be a parse error.
+fn batch_call_error(raw_call: &str) -> MethodResponse {
+    let (id, _) = prepare_error(raw_call);
+    MethodResponse::error(id, ErrorObject::from(ErrorCode::InvalidRequest))
+}
+
 async fn process_request<L: Logger>(
     req: Request<'_>,
     api_version: Option<&str>,
@@ -246,6 +347,7 @@ pub mod ws {
         pub bounded_subscriptions: BoundedSubscriptions,
         pub id_provider: &'a dyn IdProvider,
         pub methods: &'a Methods,
+        pub rpc_router: &'a RpcRouter,
         pub max_response_body_size: u32,
         pub sink: &'a MethodSink,
         pub logger: &'a L,
@@ -258,11 +360,21 @@ pub mod ws {
     pub async fn ws_json_rpc_upgrade<L: Logger>(
         ws: WebSocketUpgrade,
         State(service): State<JsonRpcService<L>>,
+        headers: HeaderMap,
     ) -> Response {
-        ws.on_upgrade(|ws| ws_json_rpc_handler(ws, service))
+        // Get version from header.
+        let api_version = headers
+            .get(CLIENT_TARGET_API_VERSION_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.to_owned());
+        ws.on_upgrade(|ws| ws_json_rpc_handler(ws, service, api_version))
     }
 
-    async fn ws_json_rpc_handler<L: Logger>(mut socket: WebSocket, service: JsonRpcService<L>) {
+    async fn ws_json_rpc_handler<L: Logger>(
+        mut socket: WebSocket,
+        service: JsonRpcService<L>,
+        api_version: Option<String>,
+    ) {
         #[allow(clippy::disallowed_methods)]
         let (tx, mut rx) = mpsc::unbounded::<String>();
         let sink = MethodSink::new_with_limit(tx, MAX_RESPONSE_SIZE, MAX_RESPONSE_SIZE);
@@ -273,10 +385,16 @@ pub mod ws {
                 maybe_message = socket.recv() => {
                     if let Some(Ok(message)) = maybe_message {
                         if let Message::Text(msg) = message {
-                            let response =
-                                process_raw_request(&service, &msg, bounded_subscriptions.clone(), &sink).await;
+                            let response = process_raw_request(
+                                &service,
+                                api_version.as_deref(),
+                                &msg,
+                                bounded_subscriptions.clone(),
+                                &sink,
+                            )
+                            .await;
                             if let Some(response) = response {
-                                let _ = sink.send_raw(response.result);
+                                let _ = sink.send_raw(response);
                             }
                         }
                     } else {
@@ -292,31 +410,55 @@ pub mod ws {
         }
     }
 
+    /// Subscriptions send their response through the sink themselves, so the response to a batch
+    /// request only contains the responses to its other calls.
     async fn process_raw_request<L: Logger>(
         service: &JsonRpcService<L>,
+        api_version: Option<&str>,
         raw_request: &str,
         bounded_subscriptions: BoundedSubscriptions,
         sink: &MethodSink,
-    ) -> Option<MethodResponse> {
+    ) -> Option<String> {
         if let Ok(request) = serde_json::from_str::<Request>(raw_request) {
-            process_request(request, service.ws_call_data(bounded_subscriptions, sink)).await
-        } else if let Ok(_batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
-            Some(MethodResponse::error(
-                Id::Null,
-                ErrorObject::borrowed(BATCHES_NOT_SUPPORTED_CODE, &BATCHES_NOT_SUPPORTED_MSG, None),
-            ))
+            process_request(
+                request,
+                api_version,
+                service.ws_call_data(bounded_subscriptions, sink),
+            )
+            .await
+            .map(|response| response.result)
+        } else if let Ok(batch) = serde_json::from_str::<Vec<&RawValue>>(raw_request) {
+            process_batch(service.batch_config, batch, |raw_call| {
+                let bounded_subscriptions = bounded_subscriptions.clone();
+                async move {
+                    match serde_json::from_str::<Request>(raw_call) {
+                        Ok(request) => {
+                            process_request(
+                                request,
+                                api_version,
+                                service.ws_call_data(bounded_subscriptions, sink),
+                            )
+                            .await
+                        }
+                        Err(_) => Some(batch_call_error(raw_call)),
+                    }
+                }
+            })
+            .await
         } else {
             let (id, code) = prepare_error(raw_request);
-            Some(MethodResponse::error(id, ErrorObject::from(code)))
+            Some(MethodResponse::error(id, ErrorObject::from(code)).result)
         }
     }
 
     async fn process_request<L: Logger>(
         req: Request<'_>,
+        api_version: Option<&str>,
         call: WsCallData<'_, L>,
     ) -> Option<MethodResponse> {
         let WsCallData {
             methods,
+            rpc_router,
             logger,
             max_response_body_size,
             request_start,
@@ -327,7 +469,7 @@ pub mod ws {
         let conn_id = 0; // unused
 
         let params = Params::new(req.params.map(|params| params.get()));
-        let name = &req.method;
+        let name = rpc_router.route(&req.method, api_version);
         let id = req.id;
 
         let response = match methods.method_with_name(name) {
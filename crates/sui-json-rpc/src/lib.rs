@@ -24,6 +24,7 @@ use sui_json_rpc_api::{
 };
 use sui_open_rpc::{Module, Project};
 
+use crate::axum_router::BatchConfig;
 use crate::error::Error;
 use crate::metrics::MetricsLogger;
 use crate::routing_layer::RpcRouter;
@@ -53,6 +54,7 @@ pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
+    batch_config: BatchConfig,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -79,9 +81,14 @@ impl JsonRpcServerBuilder {
             module: RpcModule::new(()),
             rpc_doc: sui_rpc_doc(version),
             registry: prometheus_registry.clone(),
+            batch_config: BatchConfig::default(),
         }
     }
 
+    pub fn set_batch_config(&mut self, batch_config: BatchConfig) {
+        self.batch_config = batch_config;
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -169,8 +176,12 @@ impl JsonRpcServerBuilder {
             .layer(Self::trace_layer())
             .layer(Self::cors()?);
 
-        let service =
-            crate::axum_router::JsonRpcService::new(module.into(), rpc_router, metrics_logger);
+        let service = crate::axum_router::JsonRpcService::new(
+            module.into(),
+            rpc_router,
+            metrics_logger,
+            self.batch_config,
+        );
 
         let mut router = axum::Router::new();
 
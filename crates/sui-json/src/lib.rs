@@ -821,20 +821,6 @@ pub fn is_receiving_argument(view: &BinaryIndexedView, arg_type: &SignatureToken
     )
 }
 
-fn resolve_call_args(
-    view: &BinaryIndexedView,
-    type_args: &[TypeTag],
-    json_args: &[SuiJsonValue],
-    parameter_types: &[SignatureToken],
-) -> Result<Vec<ResolvedCallArg>, anyhow::Error> {
-    json_args
-        .iter()
-        .zip(parameter_types)
-        .enumerate()
-        .map(|(idx, (arg, param))| resolve_call_arg(view, type_args, idx, arg, param))
-        .collect()
-}
-
 /// Resolve the JSON args of a function into the expected formats to make them usable by Move call
 /// This is because we have special types which we need to specify in other formats
 pub fn resolve_move_function_args(
@@ -844,6 +830,31 @@ pub fn resolve_move_function_args(
     type_args: &[TypeTag],
     combined_args_json: Vec<SuiJsonValue>,
 ) -> Result<Vec<(ResolvedCallArg, SignatureToken)>, anyhow::Error> {
+    resolve_move_function_args_partial(
+        package,
+        module_ident,
+        function,
+        type_args,
+        combined_args_json.into_iter().map(Some).collect(),
+    )?
+    .into_iter()
+    .map(|(arg, expected_type)| {
+        let arg = arg.ok_or_else(|| anyhow!("Unresolved argument of type {expected_type:?}"))?;
+        Ok((arg, expected_type))
+    })
+    .collect()
+}
+
+/// Same as [resolve_move_function_args], but arguments passed as `None` are left unresolved
+/// (their expected type is still returned). This lets callers building programmable
+/// transactions supply those arguments from the results of earlier commands.
+pub fn resolve_move_function_args_partial(
+    package: &MovePackage,
+    module_ident: Identifier,
+    function: Identifier,
+    type_args: &[TypeTag],
+    combined_args_json: Vec<Option<SuiJsonValue>>,
+) -> Result<Vec<(Option<ResolvedCallArg>, SignatureToken)>, anyhow::Error> {
     // Extract the expected function signature
     let module = package.deserialize_module(&module_ident, VERSION_MAX, true)?;
     let function_str = function.as_ident_str();
@@ -878,13 +889,18 @@ pub fn resolve_move_function_args(
         );
     }
     // Check that the args are valid and convert to the correct format
-    let call_args = resolve_call_args(&view, type_args, &combined_args_json, parameters)?;
-    let tupled_call_args = call_args
-        .into_iter()
-        .zip(parameters.iter())
-        .map(|(arg, expected_type)| (arg, expected_type.clone()))
-        .collect::<Vec<_>>();
-    Ok(tupled_call_args)
+    combined_args_json
+        .iter()
+        .zip(parameters)
+        .enumerate()
+        .map(|(idx, (arg, param))| {
+            let arg = arg
+                .as_ref()
+                .map(|arg| resolve_call_arg(&view, type_args, idx, arg, param))
+                .transpose()?;
+            Ok((arg, param.clone()))
+        })
+        .collect()
 }
 
 fn convert_string_to_u256(s: &str) -> Result<U256, anyhow::Error> {
@@ -0,0 +1,39 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal external signer for tests: serves the keys of a file based keystore over the
+//! external signer protocol. Usage: `mock-external-signer <path to sui.keystore>`.
+
+use anyhow::{anyhow, Context};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use sui_keys::external::{handle_request, SignerRequest, SignerResponse};
+use sui_keys::keystore::FileBasedKeystore;
+
+fn main() -> Result<(), anyhow::Error> {
+    let path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("Usage: mock-external-signer <keystore path>"))?;
+    let keystore = FileBasedKeystore::new(&path)?;
+    let keys = keystore.key_pairs();
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<SignerRequest>(&line) {
+            Ok(request) => handle_request(&keys, request),
+            Err(e) => SignerResponse::Error {
+                message: format!("Invalid request: {e}"),
+            },
+        };
+        writeln!(stdout, "{}", serde_json::to_string(&response)?)
+            .context("Cannot write response")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
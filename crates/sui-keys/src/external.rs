@@ -0,0 +1,178 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Line-delimited JSON protocol spoken between the CLI and an external signer process
+//! (e.g. a wrapper around an HSM or a cloud KMS).
+//!
+//! For every request the signer command is spawned, a single JSON request is written to its
+//! stdin followed by a newline, and a single JSON response is read back from its stdout.
+//! Public keys and signatures are exchanged as base64 of `flag || bytes`, the same encoding
+//! used by the file based keystore and by `sui keytool`.
+
+use anyhow::{anyhow, bail, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use sui_types::crypto::{
+    EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature, ToFromBytes,
+};
+
+/// How to reach the external signer. Stored as-is in the client config under
+/// `keystore: External`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExternalSignerConfig {
+    /// The signer executable.
+    pub command: String,
+    /// Extra arguments passed to the signer executable.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Where to persist the aliases of the keys held by the signer. Aliases are kept in
+    /// memory only if this is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aliases_path: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// List the public keys the signer can sign with.
+    Keys,
+    /// Sign an already hashed message with the key identified by `public_key`.
+    SignHashed { public_key: String, msg: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Keys { public_keys: Vec<String> },
+    Signature { signature: String },
+    Error { message: String },
+}
+
+/// Client side of the external signer protocol.
+#[derive(Clone, Debug)]
+pub struct ExternalSigner {
+    config: ExternalSignerConfig,
+}
+
+impl ExternalSigner {
+    pub fn new(config: ExternalSignerConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ExternalSignerConfig {
+        &self.config
+    }
+
+    /// Ask the signer for the public keys it holds.
+    pub fn keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        match self.request(&SignerRequest::Keys)? {
+            SignerResponse::Keys { public_keys } => public_keys
+                .iter()
+                .map(|pk| {
+                    PublicKey::decode_base64(pk)
+                        .map_err(|e| anyhow!("External signer returned an invalid key {pk}: {e}"))
+                })
+                .collect(),
+            SignerResponse::Error { message } => bail!("External signer error: {message}"),
+            other => bail!("Unexpected response from external signer: {other:?}"),
+        }
+    }
+
+    /// Sign `msg` (already hashed) with `public_key`. The returned signature is checked to
+    /// carry the requested public key, so a misbehaving signer cannot sign for another account.
+    pub fn sign_hashed(
+        &self,
+        public_key: &PublicKey,
+        msg: &[u8],
+    ) -> Result<Signature, anyhow::Error> {
+        let request = SignerRequest::SignHashed {
+            public_key: public_key.encode_base64(),
+            msg: Base64::encode(msg),
+        };
+        let signature = match self.request(&request)? {
+            SignerResponse::Signature { signature } => signature,
+            SignerResponse::Error { message } => bail!("External signer error: {message}"),
+            other => bail!("Unexpected response from external signer: {other:?}"),
+        };
+        let bytes = Base64::decode(&signature)
+            .map_err(|e| anyhow!("External signer returned invalid base64: {e}"))?;
+        let signature = <Signature as ToFromBytes>::from_bytes(&bytes)
+            .map_err(|e| anyhow!("External signer returned an invalid signature: {e}"))?;
+        let signer = PublicKey::try_from_bytes(signature.scheme(), signature.public_key_bytes())
+            .map_err(|e| anyhow!("External signer returned an invalid signature: {e}"))?;
+        if &signer != public_key {
+            bail!(
+                "External signer signed with {} instead of {}",
+                signer.encode_base64(),
+                public_key.encode_base64()
+            );
+        }
+        Ok(signature)
+    }
+
+    fn request(&self, request: &SignerRequest) -> Result<SignerResponse, anyhow::Error> {
+        let mut child = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Cannot start external signer: {}", self.config.command))?;
+
+        {
+            let mut stdin = child
+                .stdin
+                .take()
+                .ok_or_else(|| anyhow!("Cannot write to external signer stdin"))?;
+            let mut line = serde_json::to_string(request)?;
+            line.push('\n');
+            stdin.write_all(line.as_bytes())?;
+            // Dropping stdin closes the pipe, letting the signer know there are no more requests.
+        }
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("Cannot read from external signer stdout"))?;
+        let mut line = String::new();
+        BufReader::new(stdout).read_line(&mut line)?;
+        let status = child.wait()?;
+
+        if line.trim().is_empty() {
+            bail!("External signer exited ({status}) without responding");
+        }
+        serde_json::from_str(line.trim())
+            .with_context(|| format!("Cannot parse external signer response: {}", line.trim()))
+    }
+}
+
+/// Answer a request using in-memory keys. This is the whole signer side of the protocol and
+/// backs the `mock-external-signer` binary used in tests.
+pub fn handle_request(keys: &[&SuiKeyPair], request: SignerRequest) -> SignerResponse {
+    match request {
+        SignerRequest::Keys => SignerResponse::Keys {
+            public_keys: keys.iter().map(|k| k.public().encode_base64()).collect(),
+        },
+        SignerRequest::SignHashed { public_key, msg } => {
+            let Some(key) = keys
+                .iter()
+                .find(|k| k.public().encode_base64() == public_key)
+            else {
+                return SignerResponse::Error {
+                    message: format!("Unknown public key: {public_key}"),
+                };
+            };
+            match Base64::decode(&msg) {
+                Ok(msg) => SignerResponse::Signature {
+                    signature: Base64::encode(Signature::new_hashed(&msg, *key).as_ref()),
+                },
+                Err(e) => SignerResponse::Error {
+                    message: format!("Invalid message encoding: {e}"),
+                },
+            }
+        }
+    }
+}
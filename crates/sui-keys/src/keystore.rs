@@ -1,11 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::external::{ExternalSigner, ExternalSignerConfig};
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use crate::random_names::{random_name, random_names};
 use anyhow::{anyhow, bail, ensure, Context};
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
+use fastcrypto::hash::HashFunction;
 use rand::{rngs::StdRng, SeedableRng};
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -17,10 +19,12 @@ use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::get_key_pair_from_rng;
 use sui_types::crypto::{
-    enum_dispatch, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme, SuiKeyPair,
+    enum_dispatch, DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme,
+    SuiKeyPair,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -28,6 +32,7 @@ use sui_types::crypto::{
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    External(ExternalKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -149,6 +154,15 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External")?;
+                write!(
+                    writer,
+                    "Signer Command : {}",
+                    external.signer.config().command
+                )?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -599,6 +613,275 @@ impl InMemKeystore {
     }
 }
 
+/// A keystore whose private keys never enter this process. Key listing and signing are
+/// delegated to an external signer (see [crate::external]), which makes it possible to back
+/// every CLI command with HSM or KMS held keys.
+///
+/// The signer is only started the first time keys are listed or used, so that loading a client
+/// config does not run it. If it cannot be reached, no keys are listed and signing fails with the
+/// reason.
+pub struct ExternalKeystore {
+    signer: ExternalSigner,
+    state: OnceLock<Result<ExternalKeys, String>>,
+}
+
+/// The keys held by the external signer, and their aliases.
+struct ExternalKeys {
+    keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.signer.config().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(ExternalKeystore::new(ExternalSignerConfig::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let loaded = self
+            .loaded()
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        let public_key = loaded.keys.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })?;
+        self.signer
+            .sign_hashed(public_key, msg)
+            .map_err(|e| signature::Error::from_source(e.to_string()))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        // Same digest as `Signature::new_secure`, computed locally so the signer only ever
+        // sees the hash.
+        let mut hasher = DefaultHash::default();
+        hasher.update(
+            &bcs::to_bytes(&IntentMessage::new(intent, msg))
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        );
+        self.sign_hashed(address, &hasher.finalize().digest)
+    }
+
+    fn add_key(
+        &mut self,
+        _alias: Option<String>,
+        _keypair: SuiKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        bail!("Cannot add a private key to an external keystore, import it into the signer instead")
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        match self.loaded() {
+            Ok(loaded) => loaded.keys.values().cloned().collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        if self.loaded()?.keys.contains_key(address) {
+            bail!("The private key for address [{address}] is held by the external signer")
+        }
+        Err(anyhow!("Cannot find key for address: [{address}]"))
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        match self.loaded() {
+            Ok(loaded) => loaded.aliases.values().collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        match self.loaded() {
+            Ok(loaded) => loaded.aliases.iter().collect::<Vec<_>>(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        let _ = self.loaded();
+        match self.state.get_mut() {
+            Some(Ok(loaded)) => loaded.aliases.values_mut().collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Get alias of address
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.loaded()?.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    /// Get the address by its alias
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.loaded()?
+            .aliases
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.alias_exists(&a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .alias_names()
+                    .into_iter()
+                    .map(|x| x.to_string())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+
+    /// Updates an old alias to the new alias and saves it to the aliases file, if any.
+    /// If the new_alias is None, it will generate a new random alias.
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        self.save_aliases()?;
+        Ok(new_alias_name)
+    }
+}
+
+impl ExternalKeystore {
+    /// A keystore backed by the signer described by `config`. The signer is not started until
+    /// its keys are needed.
+    pub fn new(config: ExternalSignerConfig) -> Self {
+        Self {
+            signer: ExternalSigner::new(config),
+            state: OnceLock::new(),
+        }
+    }
+
+    /// Query the signer for its keys and load (or generate) their aliases, the first time this
+    /// is called. Later calls return the outcome of the first one.
+    pub fn load(&self) -> Result<(), anyhow::Error> {
+        self.loaded().map(|_| ())
+    }
+
+    pub fn save_aliases(&self) -> Result<(), anyhow::Error> {
+        save_external_aliases(self.signer.config(), &self.loaded()?.aliases)
+    }
+
+    fn loaded(&self) -> Result<&ExternalKeys, anyhow::Error> {
+        self.state
+            .get_or_init(|| ExternalKeys::load(&self.signer).map_err(|e| format!("{e:#}")))
+            .as_ref()
+            .map_err(|e| anyhow!("Cannot load keys from external signer: {e}"))
+    }
+}
+
+impl ExternalKeys {
+    fn load(signer: &ExternalSigner) -> Result<Self, anyhow::Error> {
+        let config = signer.config();
+        let keys = signer
+            .keys()?
+            .into_iter()
+            .map(|pk| (SuiAddress::from(&pk), pk))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut aliases: BTreeMap<SuiAddress, Alias> = BTreeMap::new();
+        if let Some(path) = config.aliases_path.as_ref().filter(|p| p.exists()) {
+            let reader = BufReader::new(File::open(path).with_context(|| {
+                format!(
+                    "Cannot open aliases file of external keystore: {}",
+                    path.display()
+                )
+            })?);
+            let stored: Vec<Alias> = serde_json::from_reader(reader).with_context(|| {
+                format!(
+                    "Cannot deserialize aliases file of external keystore: {}",
+                    path.display()
+                )
+            })?;
+            for alias in stored {
+                let pk = PublicKey::decode_base64(&alias.public_key_base64).map_err(|e| {
+                    anyhow!(
+                        "Invalid aliases file of external keystore: {}. {}",
+                        path.display(),
+                        e
+                    )
+                })?;
+                let address = SuiAddress::from(&pk);
+                // Aliases of keys the signer no longer holds are dropped.
+                if keys.contains_key(&address) {
+                    aliases.insert(address, alias);
+                }
+            }
+        }
+
+        let missing = keys
+            .iter()
+            .filter(|(address, _)| !aliases.contains_key(*address))
+            .collect::<Vec<_>>();
+        let taken = aliases
+            .values()
+            .map(|a| a.alias.clone())
+            .collect::<HashSet<_>>();
+        let names = random_names(taken, missing.len());
+        for ((address, pk), alias) in missing.into_iter().zip(names) {
+            aliases.insert(
+                *address,
+                Alias {
+                    alias,
+                    public_key_base64: pk.encode_base64(),
+                },
+            );
+        }
+
+        save_external_aliases(config, &aliases)?;
+        Ok(Self { keys, aliases })
+    }
+}
+
+fn save_external_aliases(
+    config: &ExternalSignerConfig,
+    aliases: &BTreeMap<SuiAddress, Alias>,
+) -> Result<(), anyhow::Error> {
+    if let Some(path) = &config.aliases_path {
+        let aliases_store = serde_json::to_string_pretty(&aliases.values().collect::<Vec<_>>())
+            .with_context(|| {
+                format!(
+                    "Cannot serialize aliases of external keystore: {}",
+                    path.display()
+                )
+            })?;
+        fs::write(path, aliases_store)?
+    }
+    Ok(())
+}
+
 fn validate_alias(alias: &str) -> Result<String, anyhow::Error> {
     let re = Regex::new(r"^[A-Za-z][A-Za-z0-9-_]*$")
         .map_err(|_| anyhow!("Cannot build the regex needed to validate the alias naming"))?;
@@ -9,8 +9,12 @@ use fastcrypto::traits::EncodeDecodeBase64;
 use sui_keys::key_derive::generate_new_key;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+use sui_keys::external::ExternalSignerConfig;
+use sui_keys::keystore::{
+    AccountKeystore, ExternalKeystore, FileBasedKeystore, InMemKeystore, Keystore,
+};
+use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignature, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -266,3 +270,125 @@ fn get_alias_by_address_test() {
     let address = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
     assert!(keystore.get_alias_by_address(&address.0).is_err())
 }
+
+fn mock_external_keystore(temp_dir: &TempDir) -> (FileBasedKeystore, ExternalSignerConfig) {
+    let keystore_path = temp_dir.path().join("signer.keystore");
+    let mut signer_keys = FileBasedKeystore::new(&keystore_path).unwrap();
+    signer_keys
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+    signer_keys
+        .generate_and_add_new_key(SignatureScheme::Secp256k1, None, None, None)
+        .unwrap();
+    let config = ExternalSignerConfig {
+        command: env!("CARGO_BIN_EXE_mock-external-signer").to_string(),
+        args: vec![keystore_path.to_str().unwrap().to_string()],
+        aliases_path: Some(temp_dir.path().join("external.aliases")),
+    };
+    (signer_keys, config)
+}
+
+#[test]
+fn external_keystore_lists_signer_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let (signer_keys, config) = mock_external_keystore(&temp_dir);
+    let keystore = Keystore::from(ExternalKeystore::new(config.clone()));
+
+    let mut expected = signer_keys.addresses();
+    let mut addresses = keystore.addresses();
+    expected.sort();
+    addresses.sort();
+    assert_eq!(expected, addresses);
+    assert_eq!(2, keystore.aliases().len());
+    assert!(keystore.to_string().contains("External"));
+
+    // Aliases are persisted and reloaded.
+    let aliases = keystore.alias_names();
+    let reloaded = ExternalKeystore::new(config);
+    assert_eq!(aliases, reloaded.alias_names());
+}
+
+#[test]
+fn external_keystore_signs_with_signer_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let (signer_keys, config) = mock_external_keystore(&temp_dir);
+    let keystore = Keystore::from(ExternalKeystore::new(config));
+
+    let msg = PersonalMessage {
+        message: b"hello".to_vec(),
+    };
+    let intent = Intent::personal_message();
+    for address in signer_keys.addresses() {
+        let signature = keystore
+            .sign_secure(&address, &msg, intent.clone())
+            .unwrap();
+        signature
+            .verify_secure(
+                &IntentMessage::new(intent.clone(), msg.clone()),
+                address,
+                signature.scheme(),
+            )
+            .unwrap();
+        assert!(keystore.get_key(&address).is_err());
+    }
+
+    let (unknown, _, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    assert!(keystore.sign_secure(&unknown, &msg, intent).is_err());
+}
+
+#[test]
+fn external_keystore_rejects_new_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let (_, config) = mock_external_keystore(&temp_dir);
+    let mut keystore = Keystore::from(ExternalKeystore::new(config));
+    assert!(keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .is_err());
+}
+
+#[test]
+fn external_keystore_missing_signer() {
+    let config = ExternalSignerConfig {
+        command: "/nonexistent/external-signer".to_string(),
+        args: vec![],
+        aliases_path: None,
+    };
+    let keystore = ExternalKeystore::new(config);
+    assert!(keystore.load().is_err());
+    assert!(keystore.keys().is_empty());
+
+    let (address, _, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    let msg = PersonalMessage {
+        message: b"hello".to_vec(),
+    };
+    let err = keystore
+        .sign_secure(&address, &msg, Intent::personal_message())
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Cannot load keys from external signer"));
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_starts_signer_lazily() {
+    let temp_dir = TempDir::new().unwrap();
+    let marker = temp_dir.path().join("started");
+    let config = ExternalSignerConfig {
+        command: "/bin/sh".to_string(),
+        args: vec![
+            "-c".to_string(),
+            format!("touch {}", marker.to_str().unwrap()),
+        ],
+        aliases_path: None,
+    };
+
+    // Loading the keystore from a config does not run the signer.
+    let keystore: Keystore =
+        serde_json::from_value(serde_json::json!({ "External": config })).unwrap();
+    assert!(!marker.exists());
+
+    // Listing keys does, and the signer not responding is reported as having no keys.
+    assert!(keystore.keys().is_empty());
+    assert!(marker.exists());
+}
@@ -166,7 +166,30 @@ fn build_anemo_services(out_dir: &Path) {
         )
         .build();
 
+    let state_snapshot = anemo_build::manual::Service::builder()
+        .name("StateSnapshot")
+        .package("sui")
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("get_state_snapshot_availability")
+                .route_name("GetStateSnapshotAvailability")
+                .request_type("()")
+                .response_type("crate::state_snapshot::GetStateSnapshotAvailabilityResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("get_state_snapshot_file_chunk")
+                .route_name("GetStateSnapshotFileChunk")
+                .request_type("crate::state_snapshot::GetStateSnapshotFileChunkRequest")
+                .response_type("Option<crate::state_snapshot::StateSnapshotFileChunk>")
+                .codec_path(codec_path)
+                .build(),
+        )
+        .build();
+
     anemo_build::manual::Builder::new()
         .out_dir(out_dir)
-        .compile(&[discovery, state_sync]);
+        .compile(&[discovery, state_sync, state_snapshot]);
 }
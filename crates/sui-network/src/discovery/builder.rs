@@ -5,6 +5,7 @@ use super::{
     metrics::Metrics, server::Server, Discovery, DiscoveryEventLoop, DiscoveryServer, State,
 };
 use crate::discovery::TrustedPeerChangeEvent;
+use crate::peer_scoring::PeerScorer;
 use anemo::codegen::InboundRequestLayer;
 use anemo_tower::rate_limit;
 use std::{
@@ -22,6 +23,7 @@ use tokio::{
 pub struct Builder {
     config: Option<P2pConfig>,
     metrics: Option<Metrics>,
+    peer_scorer: Option<Arc<PeerScorer>>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
 }
 
@@ -31,6 +33,7 @@ impl Builder {
         Self {
             config: None,
             metrics: None,
+            peer_scorer: None,
             trusted_peer_change_rx,
         }
     }
@@ -45,6 +48,12 @@ impl Builder {
         self
     }
 
+    /// Shares peer scores with other components, e.g. state sync.
+    pub fn peer_scorer(mut self, peer_scorer: Arc<PeerScorer>) -> Self {
+        self.peer_scorer = Some(peer_scorer);
+        self
+    }
+
     pub fn build(self) -> (UnstartedDiscovery, DiscoveryServer<impl Discovery>) {
         let discovery_config = self
             .config
@@ -70,10 +79,12 @@ impl Builder {
         let Builder {
             config,
             metrics,
+            peer_scorer,
             trusted_peer_change_rx,
         } = self;
         let config = config.unwrap();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
+        let peer_scorer = peer_scorer.unwrap_or_default();
         let (sender, receiver) = oneshot::channel();
 
         let handle = Handle {
@@ -100,6 +111,7 @@ impl Builder {
                 state,
                 trusted_peer_change_rx,
                 metrics,
+                peer_scorer,
             },
             server,
         )
@@ -114,6 +126,7 @@ pub struct UnstartedDiscovery {
     pub(super) state: Arc<RwLock<State>>,
     pub(super) trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
     pub(super) metrics: Metrics,
+    pub(super) peer_scorer: Arc<PeerScorer>,
 }
 
 impl UnstartedDiscovery {
@@ -125,6 +138,7 @@ impl UnstartedDiscovery {
             state,
             trusted_peer_change_rx,
             metrics,
+            peer_scorer,
         } = self;
 
         let discovery_config = config.discovery.clone().unwrap_or_default();
@@ -153,6 +167,7 @@ impl UnstartedDiscovery {
                 state,
                 trusted_peer_change_rx,
                 metrics,
+                peer_scorer,
             },
             handle,
         )
@@ -41,6 +41,7 @@ pub use generated::{
 pub use server::GetKnownPeersResponse;
 
 use self::metrics::Metrics;
+use crate::peer_scoring::PeerScorer;
 
 /// The internal discovery state shared between the main event loop and the request handler
 struct State {
@@ -84,6 +85,7 @@ struct DiscoveryEventLoop {
     state: Arc<RwLock<State>>,
     trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
     metrics: Metrics,
+    peer_scorer: Arc<PeerScorer>,
 }
 
 impl DiscoveryEventLoop {
@@ -260,6 +262,21 @@ impl DiscoveryEventLoop {
             .unwrap()
             .known_peers
             .retain(|_k, v| now_unix.saturating_sub(v.timestamp_ms) < ONE_DAY_MILLISECONDS);
+        self.peer_scorer.prune();
+
+        // Disconnect from peers banned for serving invalid data, unless we explicitly configured
+        // them as seed or allowlisted peers.
+        for peer_id in self.network.peers() {
+            if self.peer_scorer.is_banned(&peer_id)
+                && !self.allowlisted_peers.contains_key(&peer_id)
+            {
+                debug!(
+                    "Disconnecting from banned peer {}",
+                    peer_id.short_display(4)
+                );
+                let _ = self.network.disconnect(peer_id);
+            }
+        }
 
         // Clean out the pending_dials
         self.pending_dials.retain(|_k, v| !v.is_finished());
@@ -280,6 +297,7 @@ impl DiscoveryEventLoop {
                 !info.addresses.is_empty() // Peer has addresses we can dial
                 && !state.connected_peers.contains_key(peer_id) // We're not already connected
                 && !self.pending_dials.contains_key(peer_id) // There is no pending dial to this node
+                && !self.peer_scorer.is_banned(peer_id) // The peer isn't banned
             })
             .collect::<Vec<_>>();
 
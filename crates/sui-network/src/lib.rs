@@ -6,6 +6,8 @@ use std::time::Duration;
 
 pub mod api;
 pub mod discovery;
+pub mod peer_scoring;
+pub mod state_snapshot;
 pub mod state_sync;
 pub mod utils;
 
@@ -0,0 +1,152 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::PeerStats;
+use anemo::PeerId;
+use prometheus::{
+    register_gauge_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry, GaugeVec, IntCounter,
+    IntCounterVec, IntGaugeVec, Registry,
+};
+use std::sync::Arc;
+use std::time::Instant;
+use tap::Pipe;
+
+#[derive(Clone)]
+pub(super) struct Metrics(Option<Arc<Inner>>);
+
+impl std::fmt::Debug for Metrics {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Metrics").finish()
+    }
+}
+
+impl Metrics {
+    pub fn enabled(registry: &Registry) -> Self {
+        Metrics(Some(Inner::new(registry)))
+    }
+
+    pub fn disabled() -> Self {
+        Metrics(None)
+    }
+
+    pub fn report_peer_stats(&self, peer_id: &PeerId, stats: &PeerStats) {
+        if let Some(inner) = &self.0 {
+            let peer_id = peer_id.to_string();
+            let labels = [peer_id.as_str()];
+            if let Some(latency) = stats.latency {
+                inner
+                    .peer_latency_ms
+                    .with_label_values(&labels)
+                    .set((latency * 1000.0) as i64);
+            }
+            if let Some(throughput) = stats.throughput {
+                inner
+                    .peer_throughput_bytes_per_sec
+                    .with_label_values(&labels)
+                    .set(throughput as i64);
+            }
+            inner
+                .peer_success_rate
+                .with_label_values(&labels)
+                .set(stats.success_rate);
+            inner
+                .peer_banned
+                .with_label_values(&labels)
+                .set(stats.is_banned(Instant::now()) as i64);
+        }
+    }
+
+    pub fn inc_bytes_served(&self, peer_id: &PeerId, bytes: u64) {
+        if let Some(inner) = &self.0 {
+            inner
+                .peer_bytes_served
+                .with_label_values(&[&peer_id.to_string()])
+                .inc_by(bytes);
+        }
+    }
+
+    pub fn inc_bans(&self) {
+        if let Some(inner) = &self.0 {
+            inner.peer_bans.inc();
+        }
+    }
+
+    pub fn remove_peer(&self, peer_id: &PeerId) {
+        if let Some(inner) = &self.0 {
+            let peer_id = peer_id.to_string();
+            let labels = [peer_id.as_str()];
+            // Labels may not exist if the peer never served a request of some kind.
+            let _ = inner.peer_latency_ms.remove_label_values(&labels);
+            let _ = inner
+                .peer_throughput_bytes_per_sec
+                .remove_label_values(&labels);
+            let _ = inner.peer_success_rate.remove_label_values(&labels);
+            let _ = inner.peer_banned.remove_label_values(&labels);
+            let _ = inner.peer_bytes_served.remove_label_values(&labels);
+        }
+    }
+}
+
+struct Inner {
+    peer_latency_ms: IntGaugeVec,
+    peer_throughput_bytes_per_sec: IntGaugeVec,
+    peer_success_rate: GaugeVec,
+    peer_banned: IntGaugeVec,
+    peer_bytes_served: IntCounterVec,
+    peer_bans: IntCounter,
+}
+
+impl Inner {
+    pub fn new(registry: &Registry) -> Arc<Self> {
+        Self {
+            peer_latency_ms: register_int_gauge_vec_with_registry!(
+                "peer_scoring_latency_ms",
+                "Moving average of the latency of requests served by each peer",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_throughput_bytes_per_sec: register_int_gauge_vec_with_registry!(
+                "peer_scoring_throughput_bytes_per_sec",
+                "Moving average of the throughput of checkpoint contents downloads from each peer",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_success_rate: register_gauge_vec_with_registry!(
+                "peer_scoring_success_rate",
+                "Moving average of the fraction of requests each peer served successfully",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_banned: register_int_gauge_vec_with_registry!(
+                "peer_scoring_banned",
+                "Whether each peer is banned for serving invalid data",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_bytes_served: register_int_counter_vec_with_registry!(
+                "peer_scoring_bytes_served",
+                "Number of bytes of valid data served by each peer",
+                &["peer_id"],
+                registry
+            )
+            .unwrap(),
+
+            peer_bans: register_int_counter_with_registry!(
+                "peer_scoring_bans",
+                "Number of times a peer was banned for serving invalid data",
+                registry
+            )
+            .unwrap(),
+        }
+        .pipe(Arc::new)
+    }
+}
@@ -0,0 +1,323 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scoring of p2p peers based on how well they serve our requests.
+//!
+//! The `PeerScorer` keeps moving averages of the latency, download throughput and success rate of
+//! the requests each peer served, and bans peers that served invalid data. It is shared by state
+//! sync, which ranks peers with it to steer downloads toward the fastest ones, and discovery,
+//! which stops dialing banned peers and disconnects from them.
+//!
+//! Peers we have no statistics for yet are ranked by their connection RTT, which is optimistic so
+//! that new peers get tried and measured.
+
+use anemo::PeerId;
+use prometheus::Registry;
+use std::{
+    collections::HashMap,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+use sui_config::p2p::PeerScoringConfig;
+use tracing::{info, warn};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+use self::metrics::Metrics;
+
+/// Success rate floor, so that a peer that failed a few requests in a row is ranked lower but
+/// still eventually retried.
+const MIN_SUCCESS_RATE: f64 = 0.05;
+
+/// Kind of request a peer served, which determines how it is ranked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestKind {
+    /// Small requests, for which peers are ranked by latency.
+    Latency,
+    /// Bulk downloads, for which peers are ranked by throughput.
+    Throughput,
+}
+
+#[derive(Debug)]
+struct PeerStats {
+    /// Moving average of the latency of successful requests, in seconds.
+    latency: Option<f64>,
+    /// Moving average of the throughput of successful bulk downloads, in bytes per second.
+    throughput: Option<f64>,
+    /// Moving average of the fraction of requests that succeeded.
+    success_rate: f64,
+    bytes_served: u64,
+    offenses: u32,
+    banned_until: Option<Instant>,
+    last_updated: Instant,
+}
+
+impl PeerStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            latency: None,
+            throughput: None,
+            success_rate: 1.0,
+            bytes_served: 0,
+            offenses: 0,
+            banned_until: None,
+            last_updated: now,
+        }
+    }
+
+    fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Snapshot of the statistics of a peer, as returned by `PeerScorer::peer_scores`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerScore {
+    pub peer_id: PeerId,
+    pub latency: Option<Duration>,
+    pub throughput_bytes_per_sec: Option<f64>,
+    pub success_rate: f64,
+    pub bytes_served: u64,
+    pub offenses: u32,
+    /// Remaining ban duration, if the peer is banned.
+    pub banned_for: Option<Duration>,
+}
+
+struct Inner {
+    peers: HashMap<PeerId, PeerStats>,
+    /// Moving average of the size of bulk downloads across all peers, used to compare peers
+    /// with different latency and throughput.
+    average_download_bytes: Option<f64>,
+}
+
+/// Tracks how well each peer serves our requests. See the module documentation.
+pub struct PeerScorer {
+    config: PeerScoringConfig,
+    inner: RwLock<Inner>,
+    metrics: Metrics,
+}
+
+impl std::fmt::Debug for PeerScorer {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("PeerScorer")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl Default for PeerScorer {
+    fn default() -> Self {
+        Self::new(PeerScoringConfig::default())
+    }
+}
+
+impl PeerScorer {
+    pub fn new(config: PeerScoringConfig) -> Self {
+        Self {
+            config,
+            inner: RwLock::new(Inner {
+                peers: HashMap::new(),
+                average_download_bytes: None,
+            }),
+            metrics: Metrics::disabled(),
+        }
+    }
+
+    pub fn with_metrics(mut self, registry: &Registry) -> Self {
+        self.metrics = Metrics::enabled(registry);
+        self
+    }
+
+    /// Records a request `peer_id` served successfully in `latency`, with a response of `bytes`.
+    pub fn report_success(
+        &self,
+        peer_id: PeerId,
+        kind: RequestKind,
+        latency: Duration,
+        bytes: usize,
+    ) {
+        let now = Instant::now();
+        let weight = self.config.moving_average_weight();
+        let mut inner = self.inner.write().unwrap();
+
+        let latency_secs = latency.as_secs_f64();
+        if kind == RequestKind::Throughput {
+            inner.average_download_bytes = Some(moving_average(
+                inner.average_download_bytes,
+                bytes as f64,
+                weight,
+            ));
+        }
+
+        let stats = inner
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerStats::new(now));
+        match kind {
+            RequestKind::Latency => {
+                stats.latency = Some(moving_average(stats.latency, latency_secs, weight));
+            }
+            RequestKind::Throughput => {
+                let throughput = bytes as f64 / latency_secs.max(f64::EPSILON);
+                stats.throughput = Some(moving_average(stats.throughput, throughput, weight));
+            }
+        }
+        stats.success_rate = moving_average(Some(stats.success_rate), 1.0, weight);
+        stats.bytes_served += bytes as u64;
+        stats.last_updated = now;
+
+        self.metrics.report_peer_stats(&peer_id, stats);
+        self.metrics.inc_bytes_served(&peer_id, bytes as u64);
+    }
+
+    /// Records a request `peer_id` failed to serve, because of an error or a timeout.
+    pub fn report_failure(&self, peer_id: PeerId) {
+        let now = Instant::now();
+        let weight = self.config.moving_average_weight();
+        let mut inner = self.inner.write().unwrap();
+
+        let stats = inner
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerStats::new(now));
+        stats.success_rate = moving_average(Some(stats.success_rate), 0.0, weight);
+        stats.last_updated = now;
+
+        self.metrics.report_peer_stats(&peer_id, stats);
+    }
+
+    /// Records that `peer_id` served data that failed verification, and bans it for a duration
+    /// that doubles with each offense. Returns the ban duration.
+    pub fn report_invalid_data(&self, peer_id: PeerId) -> Duration {
+        self.report_invalid_data_at(peer_id, Instant::now())
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        let now = Instant::now();
+        self.inner
+            .read()
+            .unwrap()
+            .peers
+            .get(peer_id)
+            .is_some_and(|stats| stats.is_banned(now))
+    }
+
+    /// Returns the estimated cost of sending a request of `kind` to `peer_id`, in seconds, or
+    /// `None` if the peer is banned. Lower is better. `rtt` is the connection round trip time,
+    /// used for peers that haven't served such requests yet.
+    pub fn cost(&self, peer_id: &PeerId, rtt: Duration, kind: RequestKind) -> Option<f64> {
+        self.cost_at(peer_id, rtt, kind, Instant::now())
+    }
+
+    /// Returns the statistics of all the peers we have exchanged requests with.
+    pub fn peer_scores(&self) -> Vec<PeerScore> {
+        let now = Instant::now();
+        let inner = self.inner.read().unwrap();
+        let mut scores: Vec<_> = inner
+            .peers
+            .iter()
+            .map(|(peer_id, stats)| PeerScore {
+                peer_id: *peer_id,
+                latency: stats.latency.map(Duration::from_secs_f64),
+                throughput_bytes_per_sec: stats.throughput,
+                success_rate: stats.success_rate,
+                bytes_served: stats.bytes_served,
+                offenses: stats.offenses,
+                banned_for: stats
+                    .banned_until
+                    .filter(|until| *until > now)
+                    .map(|until| until - now),
+            })
+            .collect();
+        scores.sort_by_key(|score| score.peer_id);
+        scores
+    }
+
+    /// Drops the statistics of peers that are not banned and that we haven't exchanged any
+    /// request with for longer than the configured retention period.
+    pub fn prune(&self) {
+        self.prune_at(Instant::now())
+    }
+
+    fn report_invalid_data_at(&self, peer_id: PeerId, now: Instant) -> Duration {
+        let mut inner = self.inner.write().unwrap();
+        let stats = inner
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerStats::new(now));
+
+        stats.offenses += 1;
+        let duration = self
+            .config
+            .ban_duration()
+            .saturating_mul(1 << (stats.offenses - 1).min(16))
+            .min(self.config.max_ban_duration());
+        stats.banned_until = Some(now + duration);
+        stats.success_rate = 0.0;
+        stats.last_updated = now;
+        warn!(
+            "Banning peer {} for {duration:?} after it served invalid data {} times",
+            peer_id.short_display(4),
+            stats.offenses
+        );
+
+        self.metrics.report_peer_stats(&peer_id, stats);
+        self.metrics.inc_bans();
+        duration
+    }
+
+    fn cost_at(
+        &self,
+        peer_id: &PeerId,
+        rtt: Duration,
+        kind: RequestKind,
+        now: Instant,
+    ) -> Option<f64> {
+        let inner = self.inner.read().unwrap();
+        let Some(stats) = inner.peers.get(peer_id) else {
+            return Some(rtt.as_secs_f64());
+        };
+        if stats.is_banned(now) {
+            return None;
+        }
+
+        let latency = stats.latency.unwrap_or(rtt.as_secs_f64());
+        let transfer_time = match (kind, stats.throughput, inner.average_download_bytes) {
+            (RequestKind::Throughput, Some(throughput), Some(bytes)) => {
+                bytes / throughput.max(f64::EPSILON)
+            }
+            _ => 0.0,
+        };
+        Some((latency + transfer_time) / stats.success_rate.max(MIN_SUCCESS_RATE))
+    }
+
+    fn prune_at(&self, now: Instant) {
+        let retention = self.config.stats_retention();
+        let mut inner = self.inner.write().unwrap();
+        let metrics = &self.metrics;
+        inner.peers.retain(|peer_id, stats| {
+            let retain = stats.is_banned(now)
+                || now.saturating_duration_since(stats.last_updated) < retention;
+            if retain {
+                // Refreshes the ban status of peers whose ban expired.
+                metrics.report_peer_stats(peer_id, stats);
+            } else {
+                info!(
+                    "Dropping statistics of peer {} after {retention:?} of inactivity",
+                    peer_id.short_display(4)
+                );
+                metrics.remove_peer(peer_id);
+            }
+            retain
+        });
+    }
+}
+
+fn moving_average(current: Option<f64>, sample: f64, weight: f64) -> f64 {
+    match current {
+        Some(current) => current * (1.0 - weight) + sample * weight,
+        None => sample,
+    }
+}
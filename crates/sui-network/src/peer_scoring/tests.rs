@@ -0,0 +1,142 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn peer(i: u8) -> PeerId {
+    PeerId([i; 32])
+}
+
+fn test_config() -> PeerScoringConfig {
+    PeerScoringConfig {
+        ban_duration_ms: Some(10_000),
+        max_ban_duration_ms: Some(25_000),
+        moving_average_weight: Some(0.5),
+        stats_retention_ms: Some(60_000),
+    }
+}
+
+#[test]
+fn unknown_peers_are_ranked_by_rtt() {
+    let scorer = PeerScorer::new(test_config());
+    let rtt = Duration::from_millis(100);
+    assert_eq!(scorer.cost(&peer(1), rtt, RequestKind::Latency), Some(0.1));
+    assert_eq!(
+        scorer.cost(&peer(1), rtt, RequestKind::Throughput),
+        Some(0.1)
+    );
+}
+
+#[test]
+fn peers_are_ranked_by_latency_and_success_rate() {
+    let scorer = PeerScorer::new(test_config());
+    let rtt = Duration::from_millis(10);
+
+    scorer.report_success(peer(1), RequestKind::Latency, Duration::from_millis(100), 1);
+    scorer.report_success(peer(2), RequestKind::Latency, Duration::from_millis(300), 1);
+    let cost = |i| scorer.cost(&peer(i), rtt, RequestKind::Latency).unwrap();
+    assert!((cost(1) - 0.1).abs() < 1e-9);
+    assert!(cost(1) < cost(2));
+
+    // The latency is a moving average.
+    scorer.report_success(peer(1), RequestKind::Latency, Duration::from_millis(500), 1);
+    assert!((cost(1) - 0.3).abs() < 1e-9);
+
+    // Failures make a peer look slower.
+    scorer.report_failure(peer(1));
+    assert!((cost(1) - 0.6).abs() < 1e-9);
+    assert!(cost(2) < cost(1));
+}
+
+#[test]
+fn downloads_are_steered_toward_high_throughput_peers() {
+    let scorer = PeerScorer::new(test_config());
+    let rtt = Duration::from_millis(10);
+
+    // Both peers have the same latency, but peer 2 downloads 10x slower.
+    for i in [1, 2] {
+        scorer.report_success(peer(i), RequestKind::Latency, Duration::from_millis(50), 1);
+    }
+    scorer.report_success(
+        peer(1),
+        RequestKind::Throughput,
+        Duration::from_secs(1),
+        1_000_000,
+    );
+    scorer.report_success(
+        peer(2),
+        RequestKind::Throughput,
+        Duration::from_secs(10),
+        1_000_000,
+    );
+
+    let cost = |i| scorer.cost(&peer(i), rtt, RequestKind::Throughput).unwrap();
+    assert!((cost(1) - 1.05).abs() < 1e-9);
+    assert!((cost(2) - 10.05).abs() < 1e-9);
+
+    // Summaries are still ranked by latency only.
+    assert_eq!(
+        scorer.cost(&peer(1), rtt, RequestKind::Latency),
+        scorer.cost(&peer(2), rtt, RequestKind::Latency)
+    );
+
+    let scores = scorer.peer_scores();
+    assert_eq!(scores.len(), 2);
+    assert_eq!(scores[0].peer_id, peer(1));
+    assert_eq!(scores[0].bytes_served, 1_000_001);
+    assert_eq!(scores[0].throughput_bytes_per_sec, Some(1_000_000.0));
+}
+
+#[test]
+fn peers_serving_invalid_data_are_banned() {
+    let scorer = PeerScorer::new(test_config());
+    let rtt = Duration::from_millis(10);
+    let now = Instant::now();
+
+    assert_eq!(
+        scorer.report_invalid_data_at(peer(1), now),
+        Duration::from_secs(10)
+    );
+    assert!(scorer.is_banned(&peer(1)));
+    assert_eq!(
+        scorer.cost_at(&peer(1), rtt, RequestKind::Latency, now),
+        None
+    );
+    assert!(scorer
+        .cost_at(
+            &peer(1),
+            rtt,
+            RequestKind::Latency,
+            now + Duration::from_secs(10)
+        )
+        .is_some());
+
+    // Repeat offenses double the ban duration, up to the maximum.
+    assert_eq!(
+        scorer.report_invalid_data_at(peer(1), now),
+        Duration::from_secs(20)
+    );
+    assert_eq!(
+        scorer.report_invalid_data_at(peer(1), now),
+        Duration::from_secs(25)
+    );
+    assert_eq!(scorer.peer_scores()[0].offenses, 3);
+}
+
+#[test]
+fn inactive_peers_are_pruned() {
+    let scorer = PeerScorer::new(test_config());
+    let now = Instant::now();
+
+    scorer.report_success(peer(1), RequestKind::Latency, Duration::from_millis(100), 1);
+    scorer.report_invalid_data_at(peer(2), now + Duration::from_secs(55));
+
+    scorer.prune_at(now + Duration::from_secs(30));
+    assert_eq!(scorer.peer_scores().len(), 2);
+
+    // Peer 2 is retained because it is still banned.
+    scorer.prune_at(now + Duration::from_secs(61));
+    let scores = scorer.peer_scores();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].peer_id, peer(2));
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anemo::codegen::InboundRequestLayer;
+use anemo_tower::{inflight_limit, rate_limit};
+use std::path::PathBuf;
+use sui_config::p2p::StateSnapshotConfig;
+
+use super::{server::Server, StateSnapshot, StateSnapshotServer};
+
+pub struct Builder {
+    config: Option<StateSnapshotConfig>,
+    snapshot_dir: Option<PathBuf>,
+}
+
+impl Builder {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            config: None,
+            snapshot_dir: None,
+        }
+    }
+
+    pub fn config(mut self, config: StateSnapshotConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Directory of the state snapshots to serve. Overridden by the `snapshot_dir` of the config,
+    /// if set. If neither is set, the server reports that it has no snapshots.
+    pub fn snapshot_dir(mut self, snapshot_dir: PathBuf) -> Self {
+        self.snapshot_dir = Some(snapshot_dir);
+        self
+    }
+
+    pub fn build(self) -> StateSnapshotServer<impl StateSnapshot> {
+        let config = self.config.clone().unwrap_or_default();
+        let mut state_snapshot_server = StateSnapshotServer::new(self.build_internal());
+
+        // Apply rate limits from configuration. Requests above the inflight limit wait rather
+        // than fail, as clients download several files at once.
+        state_snapshot_server = state_snapshot_server
+            .add_layer_for_get_state_snapshot_file_chunk(InboundRequestLayer::new(
+                rate_limit::RateLimitLayer::new(
+                    governor::Quota::per_second(config.get_file_chunk_rate_limit()),
+                    rate_limit::WaitMode::Block,
+                ),
+            ))
+            .add_layer_for_get_state_snapshot_file_chunk(InboundRequestLayer::new(
+                inflight_limit::InflightLimitLayer::new(
+                    config.get_file_chunk_inflight_limit(),
+                    inflight_limit::WaitMode::Block,
+                ),
+            ));
+
+        state_snapshot_server
+    }
+
+    pub(super) fn build_internal(self) -> Server {
+        let Builder {
+            config,
+            snapshot_dir,
+        } = self;
+        let config = config.unwrap_or_default();
+
+        Server {
+            snapshot_dir: config.snapshot_dir.clone().or(snapshot_dir),
+            max_chunk_size: config.max_chunk_size_bytes(),
+        }
+    }
+}
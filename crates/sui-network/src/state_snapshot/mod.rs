@@ -0,0 +1,289 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer-to-peer distribution of state snapshots.
+//!
+//! Nodes that produce state snapshots at the end of each epoch can serve them to peers through
+//! the StateSnapshot service, so that new fullnodes can bootstrap without access to the object
+//! store the snapshots are uploaded to. The service serves the files of the snapshot directory of
+//! an epoch, as laid out by the state snapshot writer, in chunks. It is oblivious to the format of
+//! the files: clients download them with a [StateSnapshotDownloader] and verify them against the
+//! snapshot MANIFEST and the epoch's accumulator root, exactly as when restoring from an object
+//! store. As the MANIFEST itself is not signed, it is only trusted when most peers agree on it.
+//!
+//! The downloader spreads concurrent file downloads across the peers, preferring the ones with the
+//! highest throughput, and bans peers that served files that failed verification.
+
+use anemo::{PeerId, Request};
+use anyhow::anyhow;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use sui_config::p2p::StateSnapshotConfig;
+use sui_types::committee::EpochId;
+use tracing::{debug, warn};
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/sui.StateSnapshot.rs"));
+}
+mod builder;
+mod server;
+#[cfg(test)]
+mod tests;
+
+pub use builder::Builder;
+pub use generated::{
+    state_snapshot_client::StateSnapshotClient,
+    state_snapshot_server::{StateSnapshot, StateSnapshotServer},
+};
+pub use server::{
+    GetStateSnapshotAvailabilityResponse, GetStateSnapshotFileChunkRequest, StateSnapshotFileChunk,
+};
+
+use crate::peer_scoring::{PeerScorer, RequestKind};
+
+/// Downloads state snapshot files from a set of peers serving the StateSnapshot service.
+pub struct StateSnapshotDownloader {
+    network: anemo::Network,
+    peers: Vec<PeerId>,
+    config: StateSnapshotConfig,
+    peer_scorer: Arc<PeerScorer>,
+    next_peer: AtomicUsize,
+}
+
+impl StateSnapshotDownloader {
+    pub fn new(network: anemo::Network, peers: Vec<PeerId>, config: StateSnapshotConfig) -> Self {
+        Self {
+            network,
+            peers,
+            config,
+            peer_scorer: Arc::default(),
+            next_peer: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn peer_scorer(mut self, peer_scorer: Arc<PeerScorer>) -> Self {
+        self.peer_scorer = peer_scorer;
+        self
+    }
+
+    /// Returns the epochs for which at least one of the peers serves a state snapshot, along with
+    /// the peers serving each of them.
+    pub async fn available_epochs(&self) -> BTreeMap<EpochId, Vec<PeerId>> {
+        let timeout = self.config.chunk_timeout();
+        let responses = futures::future::join_all(self.peers.iter().map(|peer_id| async move {
+            let peer = self.network.peer(*peer_id)?;
+            let request = Request::new(()).with_timeout(timeout);
+            match StateSnapshotClient::new(peer)
+                .get_state_snapshot_availability(request)
+                .await
+            {
+                Ok(response) => Some((*peer_id, response.into_inner().epochs)),
+                Err(e) => {
+                    debug!("Failed to query state snapshot availability of {peer_id}: {e:?}");
+                    None
+                }
+            }
+        }))
+        .await;
+
+        let mut available_epochs: BTreeMap<EpochId, Vec<PeerId>> = BTreeMap::new();
+        for (peer_id, epochs) in responses.into_iter().flatten() {
+            for epoch in epochs {
+                available_epochs.entry(epoch).or_default().push(peer_id);
+            }
+        }
+        available_epochs
+    }
+
+    /// Downloads the file `file_name` of the state snapshot of `epoch`, trying peers in turn
+    /// until one serves a file that `verify` accepts. Peers that serve a file `verify` rejects are
+    /// banned.
+    pub async fn download_file(
+        &self,
+        epoch: EpochId,
+        file_name: &str,
+        verify: impl Fn(&[u8]) -> bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        for peer in self.ranked_peers() {
+            let peer_id = peer.peer_id();
+            let start = Instant::now();
+            let data = match self
+                .download_file_from_peer(StateSnapshotClient::new(peer), epoch, file_name)
+                .await
+            {
+                Ok(Some(data)) => data,
+                Ok(None) => continue,
+                Err(e) => {
+                    debug!("Failed to download {file_name} of epoch {epoch} from {peer_id}: {e}");
+                    self.peer_scorer.report_failure(peer_id);
+                    continue;
+                }
+            };
+
+            if !verify(&data) {
+                warn!("Peer {peer_id} served invalid {file_name} of epoch {epoch}");
+                self.peer_scorer.report_invalid_data(peer_id);
+                continue;
+            }
+            self.peer_scorer.report_success(
+                peer_id,
+                RequestKind::Throughput,
+                start.elapsed(),
+                data.len(),
+            );
+            return Ok(data);
+        }
+
+        Err(anyhow!(
+            "No peer served a valid {file_name} of the state snapshot of epoch {epoch}"
+        ))
+    }
+
+    /// Downloads the file `file_name` of the state snapshot of `epoch` from every peer, and
+    /// returns the contents that a strict majority of the peers serving a file `verify` accepts
+    /// agree on. This is meant for files, like the MANIFEST, that cannot be verified on their
+    /// own: peers that disagree with the majority are only banned once there is one, and nothing
+    /// is returned if the peers are split.
+    pub async fn download_agreed_file(
+        &self,
+        epoch: EpochId,
+        file_name: &str,
+        verify: impl Fn(&[u8]) -> bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        let downloads =
+            futures::future::join_all(self.ranked_peers().into_iter().map(|peer| async move {
+                let peer_id = peer.peer_id();
+                let result = self
+                    .download_file_from_peer(StateSnapshotClient::new(peer), epoch, file_name)
+                    .await;
+                (peer_id, result)
+            }))
+            .await;
+
+        let mut peers_by_contents: BTreeMap<Vec<u8>, Vec<PeerId>> = BTreeMap::new();
+        for (peer_id, result) in downloads {
+            match result {
+                Ok(Some(data)) if verify(&data) => {
+                    peers_by_contents.entry(data).or_default().push(peer_id)
+                }
+                Ok(Some(_)) => {
+                    warn!("Peer {peer_id} served invalid {file_name} of epoch {epoch}");
+                    self.peer_scorer.report_invalid_data(peer_id);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    debug!("Failed to download {file_name} of epoch {epoch} from {peer_id}: {e}");
+                    self.peer_scorer.report_failure(peer_id);
+                }
+            }
+        }
+
+        let responses: usize = peers_by_contents.values().map(Vec::len).sum();
+        let Some(data) = peers_by_contents
+            .iter()
+            .find(|(_, peers)| 2 * peers.len() > responses)
+            .map(|(data, _)| data.clone())
+        else {
+            return Err(anyhow!(
+                "Peers do not agree on the {file_name} of the state snapshot of epoch {epoch}: \
+                 {} different versions served by {responses} peers",
+                peers_by_contents.len(),
+            ));
+        };
+        for (contents, peers) in &peers_by_contents {
+            if *contents != data {
+                for peer_id in peers {
+                    warn!("Peer {peer_id} served a minority {file_name} of epoch {epoch}");
+                    self.peer_scorer.report_invalid_data(*peer_id);
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// Returns the connected peers that are not banned, best first, rotated so that concurrent
+    /// downloads are spread across peers instead of all going to the best one.
+    fn ranked_peers(&self) -> Vec<anemo::Peer> {
+        let mut peers: Vec<_> = self
+            .peers
+            .iter()
+            .filter_map(|peer_id| self.network.peer(*peer_id))
+            .filter_map(|peer| {
+                self.peer_scorer
+                    .cost(
+                        &peer.peer_id(),
+                        peer.connection_rtt(),
+                        RequestKind::Throughput,
+                    )
+                    .map(|cost| (cost, peer))
+            })
+            .collect();
+        peers.sort_by(|(cost_a, _), (cost_b, _)| cost_a.total_cmp(cost_b));
+        if !peers.is_empty() {
+            let start = self.next_peer.fetch_add(1, Ordering::Relaxed) % peers.len();
+            peers.rotate_left(start);
+        }
+        peers.into_iter().map(|(_cost, peer)| peer).collect()
+    }
+
+    /// Downloads a file chunk by chunk from a single peer. Returns `None` if the peer doesn't have
+    /// the file.
+    async fn download_file_from_peer(
+        &self,
+        mut client: StateSnapshotClient<anemo::Peer>,
+        epoch: EpochId,
+        file_name: &str,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let timeout = self.config.chunk_timeout();
+        let max_length = self.config.max_chunk_size_bytes();
+        let max_file_size = self.config.max_file_size_bytes();
+        let mut data = Vec::new();
+        let mut file_size = None;
+        loop {
+            let request = Request::new(GetStateSnapshotFileChunkRequest {
+                epoch,
+                file_name: file_name.to_owned(),
+                offset: data.len() as u64,
+                max_length,
+            })
+            .with_timeout(timeout);
+            let Some(chunk) = client
+                .get_state_snapshot_file_chunk(request)
+                .await
+                .map_err(|e| anyhow!("{e:?}"))?
+                .into_inner()
+            else {
+                return Ok(None);
+            };
+
+            if chunk.file_size > max_file_size {
+                return Err(anyhow!(
+                    "file size of {} bytes is above the limit of {max_file_size} bytes",
+                    chunk.file_size
+                ));
+            }
+            if *file_size.get_or_insert(chunk.file_size) != chunk.file_size {
+                return Err(anyhow!("file size changed during the download"));
+            }
+
+            data.extend_from_slice(&chunk.data);
+            if data.len() as u64 >= chunk.file_size {
+                data.truncate(chunk.file_size as usize);
+                return Ok(Some(data));
+            }
+            if chunk.data.is_empty() {
+                return Err(anyhow!(
+                    "received empty chunk at offset {} of {} bytes",
+                    data.len(),
+                    chunk.file_size
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::StateSnapshot;
+use anemo::{rpc::Status, types::response::StatusCode, Request, Response, Result};
+use serde::{Deserialize, Serialize};
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use sui_types::committee::EpochId;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+pub(super) const MANIFEST_FILE_NAME: &str = "MANIFEST";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetStateSnapshotAvailabilityResponse {
+    /// Epochs for which a complete state snapshot is served, in ascending order.
+    pub epochs: Vec<EpochId>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetStateSnapshotFileChunkRequest {
+    pub epoch: EpochId,
+    /// Name of the file in the snapshot directory of the epoch, e.g. `MANIFEST` or `1_1.obj`.
+    pub file_name: String,
+    pub offset: u64,
+    pub max_length: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshotFileChunk {
+    /// Total size of the file, so that the client knows when it has downloaded all of it.
+    pub file_size: u64,
+    pub data: Vec<u8>,
+}
+
+pub(super) struct Server {
+    pub(super) snapshot_dir: Option<PathBuf>,
+    pub(super) max_chunk_size: u64,
+}
+
+impl Server {
+    fn epoch_dir(snapshot_dir: &std::path::Path, epoch: EpochId) -> PathBuf {
+        snapshot_dir.join(format!("epoch_{epoch}"))
+    }
+}
+
+#[anemo::async_trait]
+impl StateSnapshot for Server {
+    async fn get_state_snapshot_availability(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<GetStateSnapshotAvailabilityResponse>, Status> {
+        let Some(snapshot_dir) = &self.snapshot_dir else {
+            return Ok(Response::new(
+                GetStateSnapshotAvailabilityResponse::default(),
+            ));
+        };
+
+        let mut epochs = Vec::new();
+        let mut entries = match tokio::fs::read_dir(snapshot_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Response::new(
+                    GetStateSnapshotAvailabilityResponse::default(),
+                ));
+            }
+            Err(e) => return Err(Status::internal(e.to_string())),
+        };
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+        {
+            let Some(epoch) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("epoch_"))
+                .and_then(|epoch| epoch.parse::<EpochId>().ok())
+            else {
+                continue;
+            };
+            // The MANIFEST is the last file written, so its presence means the snapshot is
+            // complete.
+            if tokio::fs::try_exists(entry.path().join(MANIFEST_FILE_NAME))
+                .await
+                .unwrap_or(false)
+            {
+                epochs.push(epoch);
+            }
+        }
+        epochs.sort_unstable();
+
+        Ok(Response::new(GetStateSnapshotAvailabilityResponse {
+            epochs,
+        }))
+    }
+
+    async fn get_state_snapshot_file_chunk(
+        &self,
+        request: Request<GetStateSnapshotFileChunkRequest>,
+    ) -> Result<Response<Option<StateSnapshotFileChunk>>, Status> {
+        let Some(snapshot_dir) = &self.snapshot_dir else {
+            return Ok(Response::new(None));
+        };
+        let request = request.into_inner();
+        if !is_snapshot_file_name(&request.file_name) {
+            return Err(Status::new_with_message(
+                StatusCode::BadRequest,
+                format!("invalid state snapshot file name: {}", request.file_name),
+            ));
+        }
+
+        let path = Self::epoch_dir(snapshot_dir, request.epoch).join(&request.file_name);
+        let mut file = match tokio::fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Response::new(None));
+            }
+            Err(e) => return Err(Status::internal(e.to_string())),
+        };
+        let file_size = file
+            .metadata()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .len();
+
+        let offset = request.offset.min(file_size);
+        let length = request
+            .max_length
+            .min(self.max_chunk_size)
+            .min(file_size - offset);
+        let mut data = Vec::with_capacity(length as usize);
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        file.take(length)
+            .read_to_end(&mut data)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(Some(StateSnapshotFileChunk {
+            file_size,
+            data,
+        })))
+    }
+}
+
+/// Returns whether `file_name` is the name of a file of a state snapshot, i.e. `MANIFEST` or
+/// `<bucket>_<partition>.obj|ref`. Anything else, in particular paths escaping the snapshot
+/// directory, is rejected.
+pub(super) fn is_snapshot_file_name(file_name: &str) -> bool {
+    if file_name == MANIFEST_FILE_NAME {
+        return true;
+    }
+    let Some(stem) = file_name
+        .strip_suffix(".obj")
+        .or_else(|| file_name.strip_suffix(".ref"))
+    else {
+        return false;
+    };
+    let Some((bucket, partition)) = stem.split_once('_') else {
+        return false;
+    };
+    [bucket, partition]
+        .iter()
+        .all(|num| !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit()))
+}
@@ -0,0 +1,261 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::server::is_snapshot_file_name;
+use super::*;
+use crate::utils::build_network;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn write_snapshot(dir: &Path, epoch: EpochId, files: &[(&str, &[u8])]) {
+    let epoch_dir = dir.join(format!("epoch_{epoch}"));
+    std::fs::create_dir_all(&epoch_dir).unwrap();
+    for (name, contents) in files {
+        std::fs::write(epoch_dir.join(name), contents).unwrap();
+    }
+}
+
+fn small_chunks_config() -> StateSnapshotConfig {
+    StateSnapshotConfig {
+        max_chunk_size_bytes: Some(3),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn snapshot_file_names() {
+    assert!(is_snapshot_file_name("MANIFEST"));
+    assert!(is_snapshot_file_name("1_1.obj"));
+    assert!(is_snapshot_file_name("1000_12.ref"));
+    assert!(!is_snapshot_file_name("1_1.obj.tmp"));
+    assert!(!is_snapshot_file_name("_1.obj"));
+    assert!(!is_snapshot_file_name("1_.ref"));
+    assert!(!is_snapshot_file_name("../epoch_1/MANIFEST"));
+    assert!(!is_snapshot_file_name("1_1/../../x.obj"));
+}
+
+#[tokio::test]
+async fn server_get_availability_and_chunks() {
+    let dir = tempdir().unwrap();
+    write_snapshot(
+        dir.path(),
+        1,
+        &[("MANIFEST", b"manifest"), ("1_1.obj", b"objects")],
+    );
+    // Incomplete snapshots are not advertised.
+    write_snapshot(dir.path(), 2, &[("1_1.obj", b"objects")]);
+    write_snapshot(dir.path(), 0, &[("MANIFEST", b"manifest")]);
+
+    let server = Builder::new()
+        .config(small_chunks_config())
+        .snapshot_dir(dir.path().to_path_buf())
+        .build_internal();
+
+    let response = server
+        .get_state_snapshot_availability(Request::new(()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.epochs, vec![0, 1]);
+
+    let chunk = |epoch, file_name: &str, offset, max_length| GetStateSnapshotFileChunkRequest {
+        epoch,
+        file_name: file_name.to_owned(),
+        offset,
+        max_length,
+    };
+    let response = server
+        .get_state_snapshot_file_chunk(Request::new(chunk(1, "1_1.obj", 2, 100)))
+        .await
+        .unwrap()
+        .into_inner();
+    // Chunks are capped to the configured size.
+    assert_eq!(
+        response,
+        Some(StateSnapshotFileChunk {
+            file_size: 7,
+            data: b"jec".to_vec(),
+        })
+    );
+
+    let response = server
+        .get_state_snapshot_file_chunk(Request::new(chunk(1, "1_1.obj", 6, 100)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.unwrap().data, b"s".to_vec());
+
+    let response = server
+        .get_state_snapshot_file_chunk(Request::new(chunk(1, "2_1.obj", 0, 100)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response, None);
+
+    server
+        .get_state_snapshot_file_chunk(Request::new(chunk(1, "../epoch_0/MANIFEST", 0, 100)))
+        .await
+        .unwrap_err();
+
+    // A server without a snapshot directory serves nothing.
+    let server = Builder::new().build_internal();
+    let response = server
+        .get_state_snapshot_availability(Request::new(()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.epochs.is_empty());
+}
+
+#[tokio::test]
+async fn download_from_multiple_peers() {
+    let good_dir = tempdir().unwrap();
+    write_snapshot(
+        good_dir.path(),
+        1,
+        &[("MANIFEST", b"manifest"), ("1_1.obj", b"good objects")],
+    );
+    let bad_dir = tempdir().unwrap();
+    write_snapshot(
+        bad_dir.path(),
+        1,
+        &[("MANIFEST", b"manifest"), ("1_1.obj", b"bad objects")],
+    );
+    write_snapshot(bad_dir.path(), 2, &[("MANIFEST", b"manifest")]);
+
+    let serve = |dir: &Path| {
+        let server = Builder::new()
+            .config(small_chunks_config())
+            .snapshot_dir(dir.to_path_buf())
+            .build();
+        build_network(|router| router.add_rpc_service(server))
+    };
+    let good_network = serve(good_dir.path());
+    let bad_network = serve(bad_dir.path());
+    let network = build_network(|router| router);
+    for peer in [&good_network, &bad_network] {
+        network
+            .connect_with_peer_id(peer.local_addr(), peer.peer_id())
+            .await
+            .unwrap();
+    }
+
+    let peer_scorer = Arc::new(PeerScorer::default());
+    let downloader = StateSnapshotDownloader::new(
+        network,
+        vec![good_network.peer_id(), bad_network.peer_id()],
+        small_chunks_config(),
+    )
+    .peer_scorer(peer_scorer.clone());
+
+    let available_epochs = downloader.available_epochs().await;
+    assert_eq!(available_epochs.len(), 2);
+    assert_eq!(available_epochs[&1].len(), 2);
+    assert_eq!(available_epochs[&2], vec![bad_network.peer_id()]);
+
+    // Whichever peer is tried first, only the valid file is accepted and the peer that served an
+    // invalid one is banned.
+    for _ in 0..2 {
+        let data = downloader
+            .download_file(1, "1_1.obj", |data| data == b"good objects")
+            .await
+            .unwrap();
+        assert_eq!(data, b"good objects".to_vec());
+    }
+    assert!(peer_scorer.is_banned(&bad_network.peer_id()));
+    assert!(!peer_scorer.is_banned(&good_network.peer_id()));
+
+    downloader
+        .download_file(1, "2_1.obj", |_| true)
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn download_file_agreed_by_peers() {
+    let dirs: Vec<_> = [b"manifest".as_slice(), b"manifest", b"forged manifest"]
+        .into_iter()
+        .map(|manifest| {
+            let dir = tempdir().unwrap();
+            write_snapshot(dir.path(), 1, &[("MANIFEST", manifest)]);
+            dir
+        })
+        .collect();
+    let peer_networks: Vec<_> = dirs
+        .iter()
+        .map(|dir| {
+            let server = Builder::new()
+                .snapshot_dir(dir.path().to_path_buf())
+                .build();
+            build_network(|router| router.add_rpc_service(server))
+        })
+        .collect();
+    let network = build_network(|router| router);
+    for peer in &peer_networks {
+        network
+            .connect_with_peer_id(peer.local_addr(), peer.peer_id())
+            .await
+            .unwrap();
+    }
+    let peer_ids: Vec<_> = peer_networks.iter().map(|peer| peer.peer_id()).collect();
+
+    // Peers that are split do not get banned.
+    let peer_scorer = Arc::new(PeerScorer::default());
+    let downloader =
+        StateSnapshotDownloader::new(network.clone(), peer_ids[1..].to_vec(), Default::default())
+            .peer_scorer(peer_scorer.clone());
+    downloader
+        .download_agreed_file(1, "MANIFEST", |_| true)
+        .await
+        .unwrap_err();
+    assert!(peer_ids.iter().all(|peer| !peer_scorer.is_banned(peer)));
+
+    // The majority wins, and the peer disagreeing with it is banned.
+    let peer_scorer = Arc::new(PeerScorer::default());
+    let downloader = StateSnapshotDownloader::new(network, peer_ids.clone(), Default::default())
+        .peer_scorer(peer_scorer.clone());
+    let data = downloader
+        .download_agreed_file(1, "MANIFEST", |_| true)
+        .await
+        .unwrap();
+    assert_eq!(data, b"manifest".to_vec());
+    assert!(!peer_scorer.is_banned(&peer_ids[0]));
+    assert!(!peer_scorer.is_banned(&peer_ids[1]));
+    assert!(peer_scorer.is_banned(&peer_ids[2]));
+}
+
+#[tokio::test]
+async fn download_rejects_oversized_files() {
+    let dir = tempdir().unwrap();
+    write_snapshot(
+        dir.path(),
+        1,
+        &[("MANIFEST", b"manifest"), ("1_1.obj", b"too many objects")],
+    );
+    let server = Builder::new()
+        .snapshot_dir(dir.path().to_path_buf())
+        .build();
+    let peer_network = build_network(|router| router.add_rpc_service(server));
+    let network = build_network(|router| router);
+    network
+        .connect_with_peer_id(peer_network.local_addr(), peer_network.peer_id())
+        .await
+        .unwrap();
+
+    let downloader = StateSnapshotDownloader::new(
+        network,
+        vec![peer_network.peer_id()],
+        StateSnapshotConfig {
+            max_file_size_bytes: Some(10),
+            ..Default::default()
+        },
+    );
+    downloader
+        .download_file(1, "MANIFEST", |_| true)
+        .await
+        .unwrap();
+    downloader
+        .download_file(1, "1_1.obj", |_| true)
+        .await
+        .unwrap_err();
+}
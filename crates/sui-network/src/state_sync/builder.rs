@@ -22,6 +22,7 @@ use super::{
     server::{CheckpointContentsDownloadLimitLayer, Server},
     Handle, PeerHeights, StateSync, StateSyncEventLoop, StateSyncMessage, StateSyncServer,
 };
+use crate::peer_scoring::PeerScorer;
 use sui_types::storage::WriteStore;
 
 pub struct Builder<S> {
@@ -29,6 +30,7 @@ pub struct Builder<S> {
     config: Option<StateSyncConfig>,
     metrics: Option<Metrics>,
     archive_readers: Option<ArchiveReaderBalancer>,
+    peer_scorer: Option<Arc<PeerScorer>>,
 }
 
 impl Builder<()> {
@@ -39,6 +41,7 @@ impl Builder<()> {
             config: None,
             metrics: None,
             archive_readers: None,
+            peer_scorer: None,
         }
     }
 }
@@ -50,6 +53,7 @@ impl<S> Builder<S> {
             config: self.config,
             metrics: self.metrics,
             archive_readers: self.archive_readers,
+            peer_scorer: self.peer_scorer,
         }
     }
 
@@ -67,6 +71,12 @@ impl<S> Builder<S> {
         self.archive_readers = Some(archive_readers);
         self
     }
+
+    /// Shares peer scores with other components, e.g. discovery.
+    pub fn peer_scorer(mut self, peer_scorer: Arc<PeerScorer>) -> Self {
+        self.peer_scorer = Some(peer_scorer);
+        self
+    }
 }
 
 impl<S> Builder<S>
@@ -128,11 +138,13 @@ where
             config,
             metrics,
             archive_readers,
+            peer_scorer,
         } = self;
         let store = store.unwrap();
         let config = config.unwrap_or_default();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
         let archive_readers = archive_readers.unwrap_or_default();
+        let peer_scorer = peer_scorer.unwrap_or_default();
 
         let (sender, mailbox) = mpsc::channel(config.mailbox_capacity());
         let (checkpoint_event_sender, _receiver) =
@@ -168,6 +180,7 @@ where
                 checkpoint_event_sender,
                 metrics,
                 archive_readers,
+                peer_scorer,
             },
             server,
         )
@@ -184,6 +197,7 @@ pub struct UnstartedStateSync<S> {
     pub(super) checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     pub(super) metrics: Metrics,
     pub(super) archive_readers: ArchiveReaderBalancer,
+    pub(super) peer_scorer: Arc<PeerScorer>,
 }
 
 impl<S> UnstartedStateSync<S>
@@ -202,6 +216,7 @@ where
             checkpoint_event_sender,
             metrics,
             archive_readers,
+            peer_scorer,
         } = self;
 
         (
@@ -218,6 +233,7 @@ where
                 checkpoint_event_sender,
                 network,
                 metrics,
+                peer_scorer,
                 archive_readers,
                 sync_checkpoint_from_archive_task: None,
             },
@@ -54,7 +54,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::{
     collections::{HashMap, VecDeque},
     sync::{Arc, RwLock},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use sui_config::p2p::StateSyncConfig;
 use sui_types::{
@@ -95,6 +95,7 @@ use sui_archival::reader::ArchiveReaderBalancer;
 use sui_storage::verify_checkpoint;
 
 use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
+use crate::peer_scoring::{PeerScorer, RequestKind};
 
 /// A handle to the StateSync subsystem.
 ///
@@ -267,7 +268,8 @@ impl PeerHeights {
     }
 }
 
-// PeerBalancer is an Iterator that selects peers based on RTT with some added randomness.
+// PeerBalancer is an Iterator that selects peers based on their score with some added
+// randomness, skipping banned peers.
 #[derive(Clone)]
 struct PeerBalancer {
     peers: VecDeque<(anemo::Peer, PeerStateSyncInfo)>,
@@ -285,20 +287,32 @@ impl PeerBalancer {
     pub fn new(
         network: &anemo::Network,
         peer_heights: Arc<RwLock<PeerHeights>>,
+        peer_scorer: &PeerScorer,
         request_type: PeerCheckpointRequestType,
     ) -> Self {
+        let request_kind = match request_type {
+            PeerCheckpointRequestType::Summary => RequestKind::Latency,
+            PeerCheckpointRequestType::Content => RequestKind::Throughput,
+        };
         let mut peers: Vec<_> = peer_heights
             .read()
             .unwrap()
             .peers_on_same_chain()
             // Filter out any peers who we aren't connected with.
             .filter_map(|(peer_id, info)| network.peer(*peer_id).map(|peer| (peer, *info)))
+            // Filter out banned peers.
+            .filter_map(|(peer, info)| {
+                peer_scorer
+                    .cost(&peer.peer_id(), peer.connection_rtt(), request_kind)
+                    .map(|cost| (cost, peer, info))
+            })
             .collect();
-        peers.sort_by(|(peer_a, _), (peer_b, _)| {
-            peer_a.connection_rtt().cmp(&peer_b.connection_rtt())
-        });
+        peers.sort_by(|(cost_a, _, _), (cost_b, _, _)| cost_a.total_cmp(cost_b));
         Self {
-            peers: peers.into(),
+            peers: peers
+                .into_iter()
+                .map(|(_cost, peer, info)| (peer, info))
+                .collect(),
             requested_checkpoint: None,
             request_type,
         }
@@ -367,6 +381,7 @@ struct StateSyncEventLoop<S> {
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     network: anemo::Network,
     metrics: Metrics,
+    peer_scorer: Arc<PeerScorer>,
 
     archive_readers: ArchiveReaderBalancer,
     sync_checkpoint_from_archive_task: Option<AbortHandle>,
@@ -412,6 +427,7 @@ where
             self.network.clone(),
             self.store.clone(),
             self.peer_heights.clone(),
+            self.peer_scorer.clone(),
             self.weak_sender.clone(),
             self.checkpoint_event_sender.clone(),
             self.config.checkpoint_content_download_concurrency(),
@@ -679,6 +695,7 @@ where
                 self.network.clone(),
                 self.store.clone(),
                 self.peer_heights.clone(),
+                self.peer_scorer.clone(),
                 self.metrics.clone(),
                 self.config.pinned_checkpoints.clone(),
                 self.config.checkpoint_header_download_concurrency(),
@@ -936,6 +953,7 @@ async fn sync_to_checkpoint<S>(
     network: anemo::Network,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    peer_scorer: Arc<PeerScorer>,
     metrics: Metrics,
     pinned_checkpoints: Vec<(CheckpointSequenceNumber, CheckpointDigest)>,
     checkpoint_header_download_concurrency: usize,
@@ -962,6 +980,7 @@ where
     let peer_balancer = PeerBalancer::new(
         &network,
         peer_heights.clone(),
+        &peer_scorer,
         PeerCheckpointRequestType::Summary,
     );
     // range of the next sequence_numbers to fetch
@@ -970,6 +989,7 @@ where
         .map(|next| {
             let peers = peer_balancer.clone().with_checkpoint(next);
             let peer_heights = peer_heights.clone();
+            let peer_scorer = peer_scorer.clone();
             let pinned_checkpoints = &pinned_checkpoints;
             async move {
                 if let Some(checkpoint) = peer_heights
@@ -983,12 +1003,17 @@ where
                 // Iterate through peers trying each one in turn until we're able to
                 // successfully get the target checkpoint
                 for mut peer in peers {
+                    let peer_id = peer.inner().peer_id();
                     let request = Request::new(GetCheckpointSummaryRequest::BySequenceNumber(next))
                         .with_timeout(timeout);
+                    let start = Instant::now();
                     if let Some(checkpoint) = peer
                         .get_checkpoint_summary(request)
                         .await
-                        .tap_err(|e| trace!("{e:?}"))
+                        .tap_err(|e| {
+                            trace!("{e:?}");
+                            peer_scorer.report_failure(peer_id);
+                        })
                         .ok()
                         .and_then(Response::into_inner)
                         .tap_none(|| trace!("peer unable to help sync"))
@@ -999,6 +1024,7 @@ where
                                 "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
                                 checkpoint.sequence_number()
                             );
+                            peer_scorer.report_invalid_data(peer_id);
                             continue;
                         }
 
@@ -1014,16 +1040,24 @@ where
                                     pinned_checkpoints[pinned_digest_index].1,
                                     checkpoint_digest
                                 );
+                                peer_scorer.report_invalid_data(peer_id);
                                 continue;
                             }
                         }
 
+                        peer_scorer.report_success(
+                            peer_id,
+                            RequestKind::Latency,
+                            start.elapsed(),
+                            bcs::serialized_size(&checkpoint).unwrap_or_default(),
+                        );
+
                         // Insert in our store in the event that things fail and we need to retry
                         peer_heights
                             .write()
                             .unwrap()
                             .insert_checkpoint(checkpoint.clone());
-                        return (Some(checkpoint), next, Some(peer.inner().peer_id()));
+                        return (Some(checkpoint), next, Some(peer_id));
                     }
                 }
                 (None, next, None)
@@ -1055,9 +1089,10 @@ where
                     // another peer for a different one
                     peer_heights.remove_checkpoint(checkpoint.digest());
 
-                    // Mark peer as not on the same chain as us
+                    // Mark peer as not on the same chain as us, and stop querying it for a while
                     if let Some(peer_id) = maybe_peer_id {
                         peer_heights.mark_peer_as_not_on_same_chain(peer_id);
+                        peer_scorer.report_invalid_data(peer_id);
                     }
 
                     return Err(anyhow::anyhow!(
@@ -1156,6 +1191,7 @@ async fn sync_checkpoint_contents<S>(
     network: anemo::Network,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    peer_scorer: Arc<PeerScorer>,
     sender: mpsc::WeakSender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     checkpoint_content_download_concurrency: usize,
@@ -1226,6 +1262,7 @@ async fn sync_checkpoint_contents<S>(
                             network.clone(),
                             &store,
                             peer_heights.clone(),
+                            peer_scorer.clone(),
                             timeout,
                             checkpoint,
                         ));
@@ -1259,6 +1296,7 @@ async fn sync_checkpoint_contents<S>(
                 network.clone(),
                 &store,
                 peer_heights.clone(),
+                peer_scorer.clone(),
                 timeout,
                 next_checkpoint,
             ));
@@ -1280,6 +1318,7 @@ async fn sync_one_checkpoint_contents<S>(
     network: anemo::Network,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    peer_scorer: Arc<PeerScorer>,
     timeout: Duration,
     checkpoint: VerifiedCheckpoint,
 ) -> Result<(VerifiedCheckpoint, u64), VerifiedCheckpoint>
@@ -1290,10 +1329,12 @@ where
     let peers = PeerBalancer::new(
         &network,
         peer_heights.clone(),
+        &peer_scorer,
         PeerCheckpointRequestType::Content,
     )
     .with_checkpoint(*checkpoint.sequence_number());
-    let Some(contents) = get_full_checkpoint_contents(peers, &store, &checkpoint, timeout).await
+    let Some(contents) =
+        get_full_checkpoint_contents(peers, &store, &peer_scorer, &checkpoint, timeout).await
     else {
         // Delay completion in case of error so we don't hammer the network with retries.
         let duration = peer_heights
@@ -1312,6 +1353,7 @@ where
 async fn get_full_checkpoint_contents<S>(
     peers: PeerBalancer,
     store: S,
+    peer_scorer: &PeerScorer,
     checkpoint: &VerifiedCheckpoint,
     timeout: Duration,
 ) -> Option<FullCheckpointContents>
@@ -1335,22 +1377,38 @@ where
     // Iterate through our selected peers trying each one in turn until we're able to
     // successfully get the target checkpoint
     for mut peer in peers {
+        let peer_id = peer.inner().peer_id();
         let request = Request::new(digest).with_timeout(timeout);
+        let start = Instant::now();
         if let Some(contents) = peer
             .get_checkpoint_contents(request)
             .await
-            .tap_err(|e| trace!("{e:?}"))
+            .tap_err(|e| {
+                trace!("{e:?}");
+                peer_scorer.report_failure(peer_id);
+            })
             .ok()
             .and_then(Response::into_inner)
             .tap_none(|| trace!("peer unable to help sync"))
         {
             if contents.verify_digests(digest).is_ok() {
+                peer_scorer.report_success(
+                    peer_id,
+                    RequestKind::Throughput,
+                    start.elapsed(),
+                    bcs::serialized_size(&contents).unwrap_or_default(),
+                );
                 let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
                 store
                     .insert_checkpoint_contents(checkpoint, verified_contents)
                     .expect("store operation should not fail");
                 return Some(contents);
             }
+            debug!(
+                "peer {} returned checkpoint contents that do not match digest {digest}",
+                peer_id.short_display(4)
+            );
+            peer_scorer.report_invalid_data(peer_id);
         }
     }
     None
@@ -10,8 +10,13 @@ use axum::{
 };
 use humantime::parse_duration;
 use serde::Deserialize;
+use std::fmt::Display;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
 use std::sync::Arc;
+use sui_core::traffic_controller::{TrafficController, TrafficSource};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
 use sui_types::error::SuiError;
 use telemetry_subscribers::TracingHandle;
 use tracing::info;
@@ -47,6 +52,33 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// View the traffic control policy and the currently blocked sources:
+//
+//   $ curl 'http://127.0.0.1:1337/traffic-control'
+//
+// Update some fields of the traffic control policy:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/traffic-control/policy?sender_requests_per_sec=50&deny_duration=1m'
+//
+// Block a client IP or a sender address for 10 minutes, or unblock it:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/traffic-control/block?source=1.2.3.4&duration=10m'
+//   $ curl -X POST 'http://127.0.0.1:1337/traffic-control/unblock?source=1.2.3.4'
+//
+// View the transaction and certificate deny configs currently in effect:
+//
+//   $ curl 'http://127.0.0.1:1337/deny-config'
+//
+// Deny an object, a package, a sender or sponsor address, or a certificate, or allow it again.
+// Changes take effect immediately, and are persisted across restarts:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/deny-config/add?kind=package&id=0x1234'
+//   $ curl -X POST 'http://127.0.0.1:1337/deny-config/remove?kind=package&id=0x1234'
+//
+// Toggle the package publish, package upgrade and shared object kill switches:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/deny-config/switches?package_publish_disabled=true'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -56,6 +88,14 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const TRAFFIC_CONTROL: &str = "/traffic-control";
+const TRAFFIC_CONTROL_POLICY: &str = "/traffic-control/policy";
+const TRAFFIC_CONTROL_BLOCK: &str = "/traffic-control/block";
+const TRAFFIC_CONTROL_UNBLOCK: &str = "/traffic-control/unblock";
+const DENY_CONFIG: &str = "/deny-config";
+const DENY_CONFIG_ADD: &str = "/deny-config/add";
+const DENY_CONFIG_REMOVE: &str = "/deny-config/remove";
+const DENY_CONFIG_SWITCHES: &str = "/deny-config/switches";
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -86,6 +126,14 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
+        .route(TRAFFIC_CONTROL, get(traffic_control))
+        .route(TRAFFIC_CONTROL_POLICY, post(set_traffic_control_policy))
+        .route(TRAFFIC_CONTROL_BLOCK, post(traffic_control_block))
+        .route(TRAFFIC_CONTROL_UNBLOCK, post(traffic_control_unblock))
+        .route(DENY_CONFIG, get(deny_config))
+        .route(DENY_CONFIG_ADD, post(deny_config_add))
+        .route(DENY_CONFIG_REMOVE, post(deny_config_remove))
+        .route(DENY_CONFIG_SWITCHES, post(set_deny_config_switches))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -288,3 +336,341 @@ async fn force_close_epoch(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+fn get_traffic_controller(
+    state: &AppState,
+) -> Result<&Arc<TrafficController>, (StatusCode, String)> {
+    state.node.traffic_controller().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            "traffic control is not enabled on this node\n".to_string(),
+        )
+    })
+}
+
+async fn traffic_control(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let traffic_controller = match get_traffic_controller(&state) {
+        Ok(traffic_controller) => traffic_controller,
+        Err(err) => return err,
+    };
+
+    let mut output = format!("{:#?}\n", traffic_controller.policy());
+    for denied in traffic_controller.deny_list() {
+        output.push_str(&format!(
+            "{} blocked for {:?} ({} offenses)\n",
+            denied.source, denied.remaining, denied.offenses
+        ));
+    }
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct TrafficControlPolicyUpdate {
+    client_ip_requests_per_sec: Option<u32>,
+    client_ip_request_burst: Option<u32>,
+    sender_requests_per_sec: Option<u32>,
+    sender_request_burst: Option<u32>,
+    errors_per_min: Option<u32>,
+    error_burst: Option<u32>,
+    deny_duration: Option<String>,
+    max_deny_duration: Option<String>,
+    deny_decay_period: Option<String>,
+    max_tracked_sources: Option<usize>,
+}
+
+async fn set_traffic_control_policy(
+    State(state): State<Arc<AppState>>,
+    update: Query<TrafficControlPolicyUpdate>,
+) -> (StatusCode, String) {
+    let traffic_controller = match get_traffic_controller(&state) {
+        Ok(traffic_controller) => traffic_controller,
+        Err(err) => return err,
+    };
+    let Query(update) = update;
+
+    let parse = |duration: Option<String>| duration.map(|d| parse_duration(&d)).transpose();
+    let (Ok(deny_duration), Ok(max_deny_duration), Ok(deny_decay_period)) = (
+        parse(update.deny_duration),
+        parse(update.max_deny_duration),
+        parse(update.deny_decay_period),
+    ) else {
+        return (StatusCode::BAD_REQUEST, "invalid duration\n".to_string());
+    };
+
+    let mut policy = traffic_controller.policy();
+    policy.client_ip_requests_per_sec = update
+        .client_ip_requests_per_sec
+        .unwrap_or(policy.client_ip_requests_per_sec);
+    policy.client_ip_request_burst = update
+        .client_ip_request_burst
+        .unwrap_or(policy.client_ip_request_burst);
+    policy.sender_requests_per_sec = update
+        .sender_requests_per_sec
+        .unwrap_or(policy.sender_requests_per_sec);
+    policy.sender_request_burst = update
+        .sender_request_burst
+        .unwrap_or(policy.sender_request_burst);
+    policy.errors_per_min = update.errors_per_min.unwrap_or(policy.errors_per_min);
+    policy.error_burst = update.error_burst.unwrap_or(policy.error_burst);
+    policy.deny_duration = deny_duration.unwrap_or(policy.deny_duration);
+    policy.max_deny_duration = max_deny_duration.unwrap_or(policy.max_deny_duration);
+    policy.deny_decay_period = deny_decay_period.unwrap_or(policy.deny_decay_period);
+    policy.max_tracked_sources = update
+        .max_tracked_sources
+        .unwrap_or(policy.max_tracked_sources);
+
+    traffic_controller.set_policy(policy.clone());
+    (StatusCode::OK, format!("{:#?}\n", policy))
+}
+
+#[derive(Deserialize)]
+struct TrafficControlBlock {
+    source: String,
+    duration: String,
+}
+
+async fn traffic_control_block(
+    State(state): State<Arc<AppState>>,
+    block: Query<TrafficControlBlock>,
+) -> (StatusCode, String) {
+    let traffic_controller = match get_traffic_controller(&state) {
+        Ok(traffic_controller) => traffic_controller,
+        Err(err) => return err,
+    };
+    let Query(TrafficControlBlock { source, duration }) = block;
+
+    let source = match source.parse::<TrafficSource>() {
+        Ok(source) => source,
+        Err(err) => return (StatusCode::BAD_REQUEST, format!("{err}\n")),
+    };
+    let Ok(duration) = parse_duration(&duration) else {
+        return (StatusCode::BAD_REQUEST, "invalid duration\n".to_string());
+    };
+
+    traffic_controller.block(source, duration);
+    (
+        StatusCode::OK,
+        format!("{source} blocked for {duration:?}\n"),
+    )
+}
+
+#[derive(Deserialize)]
+struct TrafficControlUnblock {
+    source: String,
+}
+
+async fn traffic_control_unblock(
+    State(state): State<Arc<AppState>>,
+    unblock: Query<TrafficControlUnblock>,
+) -> (StatusCode, String) {
+    let traffic_controller = match get_traffic_controller(&state) {
+        Ok(traffic_controller) => traffic_controller,
+        Err(err) => return err,
+    };
+    let Query(TrafficControlUnblock { source }) = unblock;
+
+    let source = match source.parse::<TrafficSource>() {
+        Ok(source) => source,
+        Err(err) => return (StatusCode::BAD_REQUEST, format!("{err}\n")),
+    };
+
+    if traffic_controller.unblock(&source) {
+        (StatusCode::OK, format!("{source} unblocked\n"))
+    } else {
+        (StatusCode::NOT_FOUND, format!("{source} is not blocked\n"))
+    }
+}
+
+async fn deny_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let authority_state = state.node.state();
+    let transaction_deny_config = authority_state.transaction_deny_config();
+    let certificate_deny_config = authority_state.certificate_deny_config();
+
+    fn sorted<T: Ord + Display>(items: impl IntoIterator<Item = T>) -> String {
+        let mut items: Vec<_> = items.into_iter().collect();
+        items.sort();
+        items.iter().map(|item| format!("  {item}\n")).collect()
+    }
+
+    let output = format!(
+        "package_publish_disabled: {}\n\
+         package_upgrade_disabled: {}\n\
+         shared_object_disabled: {}\n\
+         user_transaction_disabled: {}\n\
+         denied objects:\n{}\
+         denied packages:\n{}\
+         denied addresses:\n{}\
+         denied certificates:\n{}",
+        transaction_deny_config.package_publish_disabled(),
+        transaction_deny_config.package_upgrade_disabled(),
+        transaction_deny_config.shared_object_disabled(),
+        transaction_deny_config.user_transaction_disabled(),
+        sorted(transaction_deny_config.get_object_deny_set()),
+        sorted(transaction_deny_config.get_package_deny_set()),
+        sorted(transaction_deny_config.get_address_deny_set()),
+        sorted(certificate_deny_config.certificate_deny_set()),
+    );
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DenyKind {
+    Object,
+    Package,
+    Address,
+    Certificate,
+}
+
+#[derive(Deserialize)]
+struct DenyConfigEntry {
+    kind: DenyKind,
+    id: String,
+}
+
+enum DenyConfigId {
+    Object(ObjectID),
+    Package(ObjectID),
+    Address(SuiAddress),
+    Certificate(TransactionDigest),
+}
+
+impl DenyConfigEntry {
+    fn parse(self) -> Result<DenyConfigId, (StatusCode, String)> {
+        fn parse<T: FromStr>(id: &str, what: &str) -> Result<T, (StatusCode, String)> {
+            id.parse()
+                .map_err(|_| (StatusCode::BAD_REQUEST, format!("invalid {what} '{id}'\n")))
+        }
+        Ok(match self.kind {
+            DenyKind::Object => DenyConfigId::Object(parse(&self.id, "object ID")?),
+            DenyKind::Package => DenyConfigId::Package(parse(&self.id, "package ID")?),
+            DenyKind::Address => DenyConfigId::Address(parse(&self.id, "address")?),
+            DenyKind::Certificate => {
+                DenyConfigId::Certificate(parse(&self.id, "transaction digest")?)
+            }
+        })
+    }
+}
+
+async fn update_deny_config_entry(
+    state: &AppState,
+    entry: DenyConfigEntry,
+    add: bool,
+) -> (StatusCode, String) {
+    let id = match entry.parse() {
+        Ok(id) => id,
+        Err(err) => return err,
+    };
+
+    let result = state
+        .node
+        .update_deny_config(
+            |transaction_deny_config, certificate_deny_config| match (&id, add) {
+                (DenyConfigId::Object(id), true) => transaction_deny_config.add_denied_object(*id),
+                (DenyConfigId::Object(id), false) => {
+                    transaction_deny_config.remove_denied_object(id)
+                }
+                (DenyConfigId::Package(id), true) => {
+                    transaction_deny_config.add_denied_package(*id)
+                }
+                (DenyConfigId::Package(id), false) => {
+                    transaction_deny_config.remove_denied_package(id)
+                }
+                (DenyConfigId::Address(address), true) => {
+                    transaction_deny_config.add_denied_address(*address)
+                }
+                (DenyConfigId::Address(address), false) => {
+                    transaction_deny_config.remove_denied_address(address)
+                }
+                (DenyConfigId::Certificate(digest), true) => {
+                    certificate_deny_config.add_certificate_deny(*digest)
+                }
+                (DenyConfigId::Certificate(digest), false) => {
+                    certificate_deny_config.remove_certificate_deny(digest)
+                }
+            },
+        )
+        .await;
+
+    let id = match id {
+        DenyConfigId::Object(id) => format!("object {id}"),
+        DenyConfigId::Package(id) => format!("package {id}"),
+        DenyConfigId::Address(address) => format!("address {address}"),
+        DenyConfigId::Certificate(digest) => format!("certificate {digest}"),
+    };
+    match (result, add) {
+        (Ok(true), true) => (StatusCode::OK, format!("{id} denied\n")),
+        (Ok(false), true) => (StatusCode::OK, format!("{id} is already denied\n")),
+        (Ok(true), false) => (StatusCode::OK, format!("{id} allowed\n")),
+        (Ok(false), false) => (StatusCode::NOT_FOUND, format!("{id} is not denied\n")),
+        (Err(err), _) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("can't persist deny config: {err:?}\n"),
+        ),
+    }
+}
+
+async fn deny_config_add(
+    State(state): State<Arc<AppState>>,
+    entry: Query<DenyConfigEntry>,
+) -> (StatusCode, String) {
+    let Query(entry) = entry;
+    update_deny_config_entry(&state, entry, true).await
+}
+
+async fn deny_config_remove(
+    State(state): State<Arc<AppState>>,
+    entry: Query<DenyConfigEntry>,
+) -> (StatusCode, String) {
+    let Query(entry) = entry;
+    update_deny_config_entry(&state, entry, false).await
+}
+
+#[derive(Deserialize)]
+struct DenyConfigSwitches {
+    package_publish_disabled: Option<bool>,
+    package_upgrade_disabled: Option<bool>,
+    shared_object_disabled: Option<bool>,
+}
+
+async fn set_deny_config_switches(
+    State(state): State<Arc<AppState>>,
+    switches: Query<DenyConfigSwitches>,
+) -> (StatusCode, String) {
+    let Query(DenyConfigSwitches {
+        package_publish_disabled,
+        package_upgrade_disabled,
+        shared_object_disabled,
+    }) = switches;
+
+    let result = state
+        .node
+        .update_deny_config(|transaction_deny_config, _| {
+            if let Some(disabled) = package_publish_disabled {
+                transaction_deny_config.set_package_publish_disabled(disabled);
+            }
+            if let Some(disabled) = package_upgrade_disabled {
+                transaction_deny_config.set_package_upgrade_disabled(disabled);
+            }
+            if let Some(disabled) = shared_object_disabled {
+                transaction_deny_config.set_shared_object_disabled(disabled);
+            }
+            format!(
+                "package_publish_disabled: {}\n\
+                 package_upgrade_disabled: {}\n\
+                 shared_object_disabled: {}\n",
+                transaction_deny_config.package_publish_disabled(),
+                transaction_deny_config.package_upgrade_disabled(),
+                transaction_deny_config.shared_object_disabled(),
+            )
+        })
+        .await;
+
+    match result {
+        Ok(output) => (StatusCode::OK, output),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("can't persist deny config: {err:?}\n"),
+        ),
+    }
+}
@@ -49,9 +49,11 @@ use narwhal_network::metrics::MetricsMakeCallbackHandler;
 use narwhal_network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
 use sui_archival::reader::ArchiveReaderBalancer;
 use sui_archival::writer::ArchiveWriter;
-use sui_config::node::{ConsensusProtocol, DBCheckpointConfig};
+use sui_config::certificate_deny_config::CertificateDenyConfig;
+use sui_config::node::{ConsensusProtocol, DBCheckpointConfig, DenyConfigOverride};
 use sui_config::node_config_metrics::NodeConfigMetrics;
-use sui_config::{ConsensusConfig, NodeConfig};
+use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_config::{Config, ConsensusConfig, NodeConfig, PersistedConfig};
 use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::epoch_start_configuration::EpochStartConfigTrait;
@@ -80,11 +82,13 @@ use sui_core::module_cache_metrics::ResolverMetrics;
 use sui_core::signature_verifier::SignatureVerifierMetrics;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_core::storage::RocksDbStore;
+use sui_core::traffic_controller::{TrafficController, TrafficControllerMetrics};
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_core::{
     authority::{AuthorityState, AuthorityStore},
     authority_client::NetworkAuthorityClient,
 };
+use sui_json_rpc::axum_router::BatchConfig;
 use sui_json_rpc::coin_api::CoinReadApi;
 use sui_json_rpc::governance_api::GovernanceReadApi;
 use sui_json_rpc::indexer_api::IndexerApi;
@@ -98,6 +102,8 @@ use sui_macros::{fail_point_async, replay_log};
 use sui_network::api::ValidatorServer;
 use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
+use sui_network::peer_scoring::PeerScorer;
+use sui_network::state_snapshot;
 use sui_network::state_sync;
 use sui_protocol_config::{Chain, ProtocolConfig, SupportedProtocolVersions};
 use sui_snapshot::uploader::StateSnapshotUploader;
@@ -216,6 +222,13 @@ pub struct SuiNode {
     accumulator: Arc<StateAccumulator>,
     connection_monitor_status: Arc<ConnectionMonitorStatus>,
 
+    /// Per-client rate limiting of the validator gRPC server, if enabled in the config.
+    traffic_controller: Option<Arc<TrafficController>>,
+
+    /// Deny configs updated through the admin API, persisted so that they survive restarts.
+    deny_config_override: Mutex<PersistedConfig<DenyConfigOverride>>,
+    node_config_metrics: Arc<NodeConfigMetrics>,
+
     /// Broadcast channel to send the starting system state for the next epoch.
     end_of_epoch_channel: broadcast::Sender<SuiSystemState>,
 
@@ -392,8 +405,25 @@ impl SuiNode {
         registry_service: RegistryService,
         custom_rpc_runtime: Option<Handle>,
     ) -> Result<Arc<SuiNode>> {
-        NodeConfigMetrics::new(&registry_service.default_registry()).record_metrics(config);
         let mut config = config.clone();
+        let deny_config_override_path = config.deny_config_override_path();
+        let deny_config_override = if deny_config_override_path.exists() {
+            let deny_config_override = DenyConfigOverride::load(&deny_config_override_path)?;
+            warn!(
+                "Overriding transaction and certificate deny configs with the ones persisted in {}",
+                deny_config_override_path.display()
+            );
+            config.transaction_deny_config = deny_config_override.transaction_deny_config.clone();
+            config.certificate_deny_config = deny_config_override.certificate_deny_config.clone();
+            deny_config_override
+        } else {
+            DenyConfigOverride {
+                transaction_deny_config: config.transaction_deny_config.clone(),
+                certificate_deny_config: config.certificate_deny_config.clone(),
+            }
+        };
+        let node_config_metrics = NodeConfigMetrics::new(&registry_service.default_registry());
+        node_config_metrics.record_metrics(&config);
         if config.supported_protocol_versions.is_none() {
             info!(
                 "populating config.supported_protocol_versions with default {:?}",
@@ -680,6 +710,13 @@ impl SuiNode {
         let connection_monitor_status = Arc::new(connection_monitor_status);
         let sui_node_metrics = Arc::new(SuiNodeMetrics::new(&registry_service.default_registry()));
 
+        let traffic_controller = config.traffic_control_config.clone().map(|policy| {
+            Arc::new(TrafficController::new(
+                policy,
+                TrafficControllerMetrics::new(&registry_service.default_registry()),
+            ))
+        });
+
         let validator_components = if state.is_validator(&epoch_store) {
             let components = Self::construct_validator_components(
                 &config,
@@ -690,6 +727,7 @@ impl SuiNode {
                 state_sync_handle.clone(),
                 accumulator.clone(),
                 connection_monitor_status.clone(),
+                traffic_controller.clone(),
                 &registry_service,
                 sui_node_metrics.clone(),
             )
@@ -717,6 +755,11 @@ impl SuiNode {
             accumulator,
             end_of_epoch_channel,
             connection_monitor_status,
+            traffic_controller,
+            deny_config_override: Mutex::new(
+                deny_config_override.persisted(&deny_config_override_path),
+            ),
+            node_config_metrics,
             trusted_peer_change_tx,
 
             _db_checkpoint_handle: db_checkpoint_handle,
@@ -897,21 +940,42 @@ impl SuiNode {
         archive_readers: ArchiveReaderBalancer,
         prometheus_registry: &Registry,
     ) -> Result<(Network, discovery::Handle, state_sync::Handle)> {
+        let peer_scorer = Arc::new(
+            PeerScorer::new(config.p2p_config.peer_scoring.clone().unwrap_or_default())
+                .with_metrics(prometheus_registry),
+        );
+
         let (state_sync, state_sync_server) = state_sync::Builder::new()
             .config(config.p2p_config.state_sync.clone().unwrap_or_default())
             .store(state_sync_store)
             .archive_readers(archive_readers)
             .with_metrics(prometheus_registry)
+            .peer_scorer(peer_scorer.clone())
             .build();
 
         let (discovery, discovery_server) = discovery::Builder::new(trusted_peer_change_rx)
             .config(config.p2p_config.clone())
+            .peer_scorer(peer_scorer)
             .build();
 
+        // Serve the state snapshots this node writes, if they are kept on local disk.
+        let mut state_snapshot_builder = state_snapshot::Builder::new()
+            .config(config.p2p_config.state_snapshot.clone().unwrap_or_default());
+        if let Some(ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(snapshot_dir),
+            ..
+        }) = &config.state_snapshot_write_config.object_store_config
+        {
+            state_snapshot_builder = state_snapshot_builder.snapshot_dir(snapshot_dir.clone());
+        }
+        let state_snapshot_server = state_snapshot_builder.build();
+
         let p2p_network = {
             let routes = anemo::Router::new()
                 .add_rpc_service(discovery_server)
-                .add_rpc_service(state_sync_server);
+                .add_rpc_service(state_sync_server)
+                .add_rpc_service(state_snapshot_server);
 
             let inbound_network_metrics =
                 NetworkMetrics::new("sui", "inbound", prometheus_registry);
@@ -1011,6 +1075,7 @@ impl SuiNode {
         state_sync_handle: state_sync::Handle,
         accumulator: Arc<StateAccumulator>,
         connection_monitor_status: Arc<ConnectionMonitorStatus>,
+        traffic_controller: Option<Arc<TrafficController>>,
         registry_service: &RegistryService,
         sui_node_metrics: Arc<SuiNodeMetrics>,
     ) -> Result<ValidatorComponents> {
@@ -1071,6 +1136,7 @@ impl SuiNode {
             config,
             state.clone(),
             consensus_adapter.clone(),
+            traffic_controller,
             &registry_service.default_registry(),
         )
         .await?;
@@ -1261,12 +1327,14 @@ impl SuiNode {
         config: &NodeConfig,
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
+        traffic_controller: Option<Arc<TrafficController>>,
         prometheus_registry: &Registry,
     ) -> Result<tokio::task::JoinHandle<Result<()>>> {
         let validator_service = ValidatorService::new(
             state.clone(),
             consensus_adapter,
             Arc::new(ValidatorServiceMetrics::new(prometheus_registry)),
+            traffic_controller,
         );
 
         let mut server_conf = mysten_network::config::Config::new();
@@ -1292,6 +1360,34 @@ impl SuiNode {
         self.state.clone()
     }
 
+    pub fn traffic_controller(&self) -> Option<&Arc<TrafficController>> {
+        self.traffic_controller.as_ref()
+    }
+
+    /// Applies `update` to the transaction and certificate deny configs, persists them, and
+    /// makes them take effect immediately. Nothing is changed if persisting fails.
+    pub async fn update_deny_config<R>(
+        &self,
+        update: impl FnOnce(&mut TransactionDenyConfig, &mut CertificateDenyConfig) -> R,
+    ) -> Result<R> {
+        let mut deny_config_override = self.deny_config_override.lock().await;
+        let mut updated = (**deny_config_override).clone();
+        let result = update(
+            &mut updated.transaction_deny_config,
+            &mut updated.certificate_deny_config,
+        );
+        updated.save(deny_config_override.path())?;
+
+        self.node_config_metrics
+            .record_transaction_deny_config(&updated.transaction_deny_config);
+        self.state
+            .set_transaction_deny_config(updated.transaction_deny_config.clone());
+        self.state
+            .set_certificate_deny_config(updated.certificate_deny_config.clone());
+        **deny_config_override = updated;
+        Ok(result)
+    }
+
     // Only used for testing because of how epoch store is loaded.
     pub fn reference_gas_price_for_testing(&self) -> Result<u64, anyhow::Error> {
         self.state.reference_gas_price_for_testing()
@@ -1521,6 +1617,7 @@ impl SuiNode {
                             self.state_sync.clone(),
                             self.accumulator.clone(),
                             self.connection_monitor_status.clone(),
+                            self.traffic_controller.clone(),
                             &self.registry_service,
                             self.metrics.clone(),
                         )
@@ -1725,6 +1822,17 @@ pub fn build_http_server(
 
     let json_rpc_router = {
         let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+        if let Some(batch_config) = &config.json_rpc_batch_config {
+            let defaults = BatchConfig::default();
+            server.set_batch_config(BatchConfig {
+                max_batch_size: batch_config
+                    .max_batch_size
+                    .unwrap_or(defaults.max_batch_size),
+                max_concurrent_calls: batch_config
+                    .max_concurrent_calls
+                    .unwrap_or(defaults.max_concurrent_calls),
+            });
+        }
 
         let kv_store = build_kv_store(&state, config, prometheus_registry)?;
 
@@ -0,0 +1,309 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::{Duration, Instant};
+
+use crate::config::{AggregationConfig, AggregationMethod, SubmissionPolicy};
+
+/// The result of aggregating the values from a feed's data sources.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Aggregate {
+    pub value: f64,
+    /// Names of the data sources whose values were rejected as outliers.
+    pub outliers: Vec<String>,
+}
+
+/// Combine `values`, keyed by the name of the data source they came from, into a single value,
+/// after rejecting outliers.
+pub(crate) fn aggregate(
+    config: &AggregationConfig,
+    values: &[(String, f64)],
+) -> anyhow::Result<Aggregate> {
+    let mut outliers = vec![];
+    let mut accepted: Vec<f64> = values.iter().map(|(_, value)| *value).collect();
+
+    if let (Some(max_deviation), Some(median)) = (config.max_deviation, median(&accepted)) {
+        accepted.clear();
+        for (source, value) in values {
+            if deviation(*value, median) > max_deviation {
+                outliers.push(source.clone());
+            } else {
+                accepted.push(*value);
+            }
+        }
+    }
+
+    if accepted.len() < config.min_sources.max(1) {
+        anyhow::bail!(
+            "Not enough values to aggregate: {} accepted, {} rejected as outliers, {} required",
+            accepted.len(),
+            outliers.len(),
+            config.min_sources.max(1),
+        );
+    }
+
+    let value = match config.method {
+        AggregationMethod::Median => median(&accepted),
+        AggregationMethod::TrimmedMean { trim_ratio } => trimmed_mean(&accepted, trim_ratio),
+    }
+    .expect("Bug, aggregating no values");
+
+    // Values are checked as they are retrieved, but a bad aggregate must never be submitted.
+    anyhow::ensure!(
+        value.is_finite() && value > 0.0,
+        "Aggregated value {value} is not a finite, positive number"
+    );
+
+    Ok(Aggregate { value, outliers })
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    let sorted = sorted(values);
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+fn trimmed_mean(values: &[f64], trim_ratio: f64) -> Option<f64> {
+    let sorted = sorted(values);
+    let trim = (sorted.len() as f64 * trim_ratio.clamp(0.0, 0.5)).floor() as usize;
+    // Always keep at least one value, even if the ratio would trim them all.
+    let kept = if 2 * trim < sorted.len() {
+        &sorted[trim..sorted.len() - trim]
+    } else {
+        &sorted[..]
+    };
+
+    if kept.is_empty() {
+        return None;
+    }
+
+    Some(kept.iter().sum::<f64>() / kept.len() as f64)
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// How far `value` is from `reference`, as a fraction of `reference`.
+fn deviation(value: f64, reference: f64) -> f64 {
+    if reference == 0.0 {
+        if value == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY
+        }
+    } else {
+        ((value - reference) / reference).abs()
+    }
+}
+
+/// Decides whether an aggregated value should be submitted on-chain, according to a feed's
+/// submission policy and the last value it submitted.
+#[derive(Debug)]
+pub(crate) struct SubmissionTracker {
+    policy: SubmissionPolicy,
+    last_submission: Option<(f64, Instant)>,
+}
+
+impl SubmissionTracker {
+    pub fn new(policy: SubmissionPolicy) -> Self {
+        Self {
+            policy,
+            last_submission: None,
+        }
+    }
+
+    /// Whether `value` should be submitted at `now`. Nothing is recorded until the submission
+    /// succeeds, see `record`.
+    pub fn should_submit(&self, value: f64, now: Instant) -> bool {
+        match (self.last_submission, &self.policy) {
+            (None, _) => true,
+            (
+                _,
+                SubmissionPolicy {
+                    deviation_threshold: None,
+                    heartbeat: None,
+                },
+            ) => true,
+            (Some((last_value, last_time)), policy) => {
+                let deviated = policy
+                    .deviation_threshold
+                    .is_some_and(|threshold| deviation(value, last_value) > threshold);
+                let heartbeat_due = policy
+                    .heartbeat
+                    .is_some_and(|heartbeat| now.saturating_duration_since(last_time) >= heartbeat);
+                deviated || heartbeat_due
+            }
+        }
+    }
+
+    /// Record that `value`, aggregated at `at`, was submitted on-chain.
+    pub fn record(&mut self, value: f64, at: Instant) {
+        self.last_submission = Some((value, at));
+    }
+}
+
+/// A value retrieved from a data source.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Reading {
+    pub value: f64,
+    pub retrieved_at: Instant,
+}
+
+impl Reading {
+    /// Whether this reading can still be used at `now`, if it is allowed to be up to
+    /// `max_staleness` old.
+    pub fn is_fresh(&self, now: Instant, max_staleness: Duration) -> bool {
+        now.saturating_duration_since(self.retrieved_at) <= max_staleness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(values: &[(&str, f64)]) -> Vec<(String, f64)> {
+        values
+            .iter()
+            .map(|(source, value)| (source.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_median() {
+        let config = AggregationConfig::default();
+
+        let odd = aggregate(&config, &values(&[("a", 3.0), ("b", 1.0), ("c", 2.0)])).unwrap();
+        assert_eq!(odd.value, 2.0);
+
+        let even = aggregate(
+            &config,
+            &values(&[("a", 4.0), ("b", 1.0), ("c", 2.0), ("d", 3.0)]),
+        )
+        .unwrap();
+        assert_eq!(even.value, 2.5);
+    }
+
+    #[test]
+    fn test_trimmed_mean() {
+        let config = AggregationConfig {
+            method: AggregationMethod::TrimmedMean { trim_ratio: 0.25 },
+            ..Default::default()
+        };
+
+        let aggregate = aggregate(
+            &config,
+            &values(&[("a", 1.0), ("b", 2.0), ("c", 4.0), ("d", 100.0)]),
+        )
+        .unwrap();
+        assert_eq!(aggregate.value, 3.0);
+    }
+
+    #[test]
+    fn test_outlier_rejection() {
+        let config = AggregationConfig {
+            max_deviation: Some(0.1),
+            ..Default::default()
+        };
+
+        let aggregate = aggregate(
+            &config,
+            &values(&[("a", 10.0), ("b", 10.4), ("c", 9.8), ("flaky", 1000.0)]),
+        )
+        .unwrap();
+        assert_eq!(aggregate.outliers, vec!["flaky".to_string()]);
+        assert_eq!(aggregate.value, 10.0);
+    }
+
+    #[test]
+    fn test_not_enough_sources() {
+        let config = AggregationConfig {
+            min_sources: 3,
+            max_deviation: Some(0.1),
+            ..Default::default()
+        };
+
+        assert!(aggregate(&config, &values(&[])).is_err());
+        assert!(aggregate(&config, &values(&[("a", 10.0), ("b", 10.0)])).is_err());
+        // Outliers do not count towards the minimum.
+        assert!(aggregate(
+            &config,
+            &values(&[("a", 10.0), ("b", 10.0), ("c", 10.0), ("d", 50.0)])
+        )
+        .is_ok());
+        assert!(aggregate(&config, &values(&[("a", 10.0), ("b", 10.0), ("c", 50.0)])).is_err());
+    }
+
+    #[test]
+    fn test_non_finite_aggregate_is_rejected() {
+        let config = AggregationConfig::default();
+
+        assert!(aggregate(&config, &values(&[("a", f64::NAN)])).is_err());
+        assert!(aggregate(&config, &values(&[("a", f64::INFINITY), ("b", 1.0)])).is_err());
+        assert!(aggregate(&config, &values(&[("a", -1.0)])).is_err());
+    }
+
+    #[test]
+    fn test_submit_every_value_by_default() {
+        let mut tracker = SubmissionTracker::new(SubmissionPolicy::default());
+        let now = Instant::now();
+
+        assert!(tracker.should_submit(1.0, now));
+        tracker.record(1.0, now);
+        assert!(tracker.should_submit(1.0, now));
+    }
+
+    #[test]
+    fn test_submit_on_deviation_or_heartbeat() {
+        let mut tracker = SubmissionTracker::new(SubmissionPolicy {
+            deviation_threshold: Some(0.01),
+            heartbeat: Some(Duration::from_secs(60)),
+        });
+        let start = Instant::now();
+
+        // The first value is always submitted.
+        assert!(tracker.should_submit(100.0, start));
+        tracker.record(100.0, start);
+
+        // Small changes are not submitted until the heartbeat expires.
+        assert!(!tracker.should_submit(100.5, start + Duration::from_secs(10)));
+        assert!(!tracker.should_submit(99.5, start + Duration::from_secs(59)));
+        assert!(tracker.should_submit(99.5, start + Duration::from_secs(60)));
+        tracker.record(99.5, start + Duration::from_secs(60));
+
+        // Deviations are compared to the last submitted value, and submitted immediately.
+        assert!(!tracker.should_submit(100.0, start + Duration::from_secs(61)));
+        assert!(tracker.should_submit(101.0, start + Duration::from_secs(62)));
+    }
+
+    #[test]
+    fn test_failed_submissions_are_not_recorded() {
+        let tracker = SubmissionTracker::new(SubmissionPolicy {
+            deviation_threshold: Some(0.01),
+            heartbeat: None,
+        });
+        let now = Instant::now();
+
+        // Until a submission is recorded, the value is still due.
+        assert!(tracker.should_submit(100.0, now));
+        assert!(tracker.should_submit(100.0, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_reading_staleness() {
+        let start = Instant::now();
+        let reading = Reading {
+            value: 1.0,
+            retrieved_at: start,
+        };
+
+        assert!(reading.is_fresh(start + Duration::from_secs(5), Duration::from_secs(5)));
+        assert!(!reading.is_fresh(start + Duration::from_secs(6), Duration::from_secs(5)));
+    }
+}
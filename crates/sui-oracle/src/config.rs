@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::time::Duration;
 use sui_config::Config;
@@ -18,11 +18,91 @@ pub struct DataSourceConfig {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UploadFeedConfig {
+    /// How often the data sources are polled, and a new value considered for submission.
     pub submission_interval: Duration,
-    pub data_source_config: DataSourceConfig,
+    /// A single, unnamed data source. Kept for compatibility with configs that predate
+    /// `data-sources`, and equivalent to a data source named "default".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source_config: Option<DataSourceConfig>,
+    /// Data sources whose values are aggregated into the feed's value, keyed by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data_sources: BTreeMap<String, DataSourceConfig>,
+    #[serde(default)]
+    pub aggregation: AggregationConfig,
+    #[serde(default)]
+    pub submission_policy: SubmissionPolicy,
     pub upload_parameters: UploadParameters,
 }
 
+impl UploadFeedConfig {
+    /// All the feed's data sources, keyed by name.
+    pub fn data_sources(&self) -> BTreeMap<String, DataSourceConfig> {
+        let mut sources = self.data_sources.clone();
+        if let Some(source) = &self.data_source_config {
+            sources.insert("default".to_string(), source.clone());
+        }
+        sources
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AggregationMethod {
+    #[default]
+    Median,
+    /// The mean of the values left after dropping the `trim-ratio` fraction of lowest values and
+    /// the same fraction of highest values.
+    #[serde(rename_all = "kebab-case")]
+    TrimmedMean { trim_ratio: f64 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AggregationConfig {
+    #[serde(default)]
+    pub method: AggregationMethod,
+    /// The minimum number of fresh, non-outlier values needed to produce a value for the feed.
+    #[serde(default = "default_min_sources")]
+    pub min_sources: usize,
+    /// How long a value retrieved from a data source can still be used for, if later attempts to
+    /// retrieve it fail. Defaults to only using values from the latest attempt.
+    #[serde(default)]
+    pub max_staleness: Option<Duration>,
+    /// Values that deviate from the median of all fresh values by more than this fraction of the
+    /// median are rejected as outliers. Defaults to not rejecting any values.
+    #[serde(default)]
+    pub max_deviation: Option<f64>,
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            method: AggregationMethod::default(),
+            min_sources: default_min_sources(),
+            max_staleness: None,
+            max_deviation: None,
+        }
+    }
+}
+
+fn default_min_sources() -> usize {
+    1
+}
+
+/// When an aggregated value is submitted on-chain. If neither of the fields are set, every value
+/// is submitted.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SubmissionPolicy {
+    /// Submit a value if it deviates from the last submitted value by more than this fraction of
+    /// the last submitted value.
+    #[serde(default)]
+    pub deviation_threshold: Option<f64>,
+    /// Submit a value if nothing has been submitted for this long, even if it has not deviated.
+    #[serde(default)]
+    pub heartbeat: Option<Duration>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UploadParameters {
@@ -1,17 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use aggregation::{aggregate, Reading, SubmissionTracker};
 use chrono::{DateTime, Utc};
-use config::{DownloadFeedConfigs, UploadFeedConfig, UploadParameters};
+use config::{DataSourceConfig, DownloadFeedConfigs, UploadFeedConfig, UploadParameters};
+use futures::future::join_all;
 use metrics::OracleMetrics;
 use mysten_metrics::monitored_scope;
 use once_cell::sync::OnceCell;
 use prometheus::Registry;
 use std::ops::Add;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
-use std::{collections::HashMap, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use sui_json_rpc_types::{
     SuiObjectDataOptions, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
@@ -37,6 +42,7 @@ use tap::tap::TapFallible;
 use sui_sdk::wallet_context::WalletContext;
 use sui_types::base_types::{random_object_ref, ObjectID, ObjectRef};
 use tracing::{debug, error, info, warn};
+mod aggregation;
 pub mod config;
 mod metrics;
 
@@ -117,7 +123,7 @@ impl OracleNode {
 }
 
 struct DataProviderRunner {
-    providers: Vec<Arc<DataProvider>>,
+    providers: Vec<DataProvider>,
     uploader: OnChainDataUploader,
 }
 
@@ -141,14 +147,13 @@ impl DataProviderRunner {
                     data_feed.submission_interval,
                 );
                 let oracle_obj_id = data_feed.upload_parameters.write_data_provider_object_id;
-                let data_provider = DataProvider {
-                    feed_name: feed_name.clone(),
-                    source_name: source_name.clone(),
-                    upload_feed: Arc::new(data_feed),
-                    sender: sender.clone(),
-                    metrics: metrics.clone(),
-                };
-                providers.push(Arc::new(data_provider));
+                providers.push(DataProvider::new(
+                    feed_name.clone(),
+                    source_name.clone(),
+                    data_feed,
+                    sender.clone(),
+                    metrics.clone(),
+                ));
                 if let std::collections::hash_map::Entry::Vacant(e) =
                     oracle_object_args.entry(oracle_obj_id)
                 {
@@ -226,12 +231,46 @@ struct DataProvider {
     pub feed_name: String,
     pub source_name: String,
     pub upload_feed: Arc<UploadFeedConfig>,
+    pub data_sources: BTreeMap<String, DataSourceConfig>,
     pub sender: tokio::sync::mpsc::Sender<DataPoint>,
+    client: reqwest::Client,
+    /// The latest value successfully retrieved from each data source.
+    readings: HashMap<String, Reading>,
+    /// Shared with the data points sent to the uploader, which record them once submitted.
+    tracker: Arc<Mutex<SubmissionTracker>>,
     metrics: Arc<OracleMetrics>,
 }
 
 impl DataProvider {
-    pub async fn run(&self) {
+    fn new(
+        feed_name: String,
+        source_name: String,
+        upload_feed: UploadFeedConfig,
+        sender: tokio::sync::mpsc::Sender<DataPoint>,
+        metrics: Arc<OracleMetrics>,
+    ) -> Self {
+        let data_sources = upload_feed.data_sources();
+        assert!(
+            !data_sources.is_empty(),
+            "No data sources configured for feed: {}",
+            make_onchain_feed_name(&feed_name, &source_name)
+        );
+        Self {
+            feed_name,
+            source_name,
+            data_sources,
+            sender,
+            client: reqwest::Client::new(),
+            readings: HashMap::new(),
+            tracker: Arc::new(Mutex::new(SubmissionTracker::new(
+                upload_feed.submission_policy.clone(),
+            ))),
+            upload_feed: Arc::new(upload_feed),
+            metrics,
+        }
+    }
+
+    pub async fn run(mut self) {
         info!(
             feed_name = self.feed_name,
             source_name = self.source_name,
@@ -246,76 +285,151 @@ impl DataProvider {
         }
     }
 
-    async fn run_once(&self) {
+    async fn run_once(&mut self) {
         debug!(
             feed_name = self.feed_name,
             source_name = self.source_name,
             "Running data provider once."
         );
-        let value = self.retrieve_from_data_source().await;
-        if value.is_err() {
-            error!(
-                feed_name = self.feed_name,
-                source_name = self.source_name,
-                "Failed to retrieve data from data source: {:?}",
-                value
-            );
-            self.metrics
-                .data_source_errors
-                .with_label_values(&[&self.feed_name, &self.source_name])
-                .inc();
-            return;
-        }
+        let onchain_feed_name = make_onchain_feed_name(&self.feed_name, &self.source_name);
+        let now = Instant::now();
 
-        self.metrics
-            .data_source_successes
-            .with_label_values(&[&self.feed_name, &self.source_name])
-            .inc();
+        let client = &self.client;
+        let results = join_all(
+            self.data_sources
+                .iter()
+                .map(|(data_source, config)| async move {
+                    let start = Instant::now();
+                    let value = retrieve_from_data_source(client, config).await;
+                    (data_source.clone(), value, start.elapsed())
+                }),
+        )
+        .await;
 
-        // TODO: allow more flexible multiplers and data types
-        let value = (value.unwrap() * METRICS_MULTIPLIER) as u64;
-        self.send_to_uploader(value).await;
-    }
+        for (data_source, value, latency) in results {
+            let labels = [onchain_feed_name.as_str(), data_source.as_str()];
+            let provider_labels = [self.feed_name.as_str(), self.source_name.as_str()];
+            self.metrics
+                .data_source_latency_ms
+                .with_label_values(&labels)
+                .observe(latency.as_millis() as u64);
 
-    async fn retrieve_from_data_source(&self) -> anyhow::Result<f64> {
-        // TODO: support websocket
-        let url = &self.upload_feed.data_source_config.url;
-        let json_path = &self.upload_feed.data_source_config.json_path;
-        let response = reqwest::Client::new().get(url).send().await?;
+            match value {
+                Ok(value) => {
+                    self.metrics
+                        .data_source_successes
+                        .with_label_values(&provider_labels)
+                        .inc();
+                    self.metrics
+                        .data_source_values
+                        .with_label_values(&labels)
+                        .set(value);
+                    self.readings.insert(
+                        data_source,
+                        Reading {
+                            value,
+                            retrieved_at: now,
+                        },
+                    );
+                }
+                Err(err) => {
+                    error!(
+                        feed_name = self.feed_name,
+                        source_name = self.source_name,
+                        data_source,
+                        "Failed to retrieve data from data source: {:?}",
+                        err
+                    );
+                    self.metrics
+                        .data_source_errors
+                        .with_label_values(&provider_labels)
+                        .inc();
+                }
+            }
+        }
+
+        // Only use values retrieved in this run, unless older values are allowed.
+        let max_staleness = self
+            .upload_feed
+            .aggregation
+            .max_staleness
+            .unwrap_or(Duration::ZERO);
+        let values: Vec<_> = self
+            .readings
+            .iter()
+            .filter(|(_, reading)| reading.is_fresh(now, max_staleness))
+            .map(|(data_source, reading)| (data_source.clone(), reading.value))
+            .collect();
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to fetch data: {:?}", response);
+        for data_source in self.data_sources.keys() {
+            let up = values.iter().any(|(fresh, _)| fresh == data_source);
+            self.metrics
+                .data_source_up
+                .with_label_values(&[&onchain_feed_name, data_source])
+                .set(i64::from(up));
         }
 
-        let json_blob: serde_json::Value = response.json().await.unwrap();
-        let data = jsonpath_lib::select(&json_blob, json_path)?;
+        let aggregate = match aggregate(&self.upload_feed.aggregation, &values) {
+            Ok(aggregate) => aggregate,
+            Err(err) => {
+                error!(
+                    feed_name = self.feed_name,
+                    source_name = self.source_name,
+                    "Failed to aggregate data: {:?}",
+                    err
+                );
+                self.metrics
+                    .aggregation_errors
+                    .with_label_values(&[&onchain_feed_name])
+                    .inc();
+                return;
+            }
+        };
 
-        if data.is_empty() {
-            anyhow::bail!(
-                "Failed to find data from json blob: {:?} with json path: {:?}",
-                json_blob,
-                json_path
+        for data_source in &aggregate.outliers {
+            warn!(
+                feed_name = self.feed_name,
+                source_name = self.source_name,
+                data_source,
+                aggregate = aggregate.value,
+                "Rejected outlier value from data source."
             );
+            let labels = [onchain_feed_name.as_str(), data_source.as_str()];
+            self.metrics
+                .data_source_outliers
+                .with_label_values(&labels)
+                .inc();
+            self.metrics
+                .data_source_up
+                .with_label_values(&labels)
+                .set(0);
         }
-        // Assume there is one single value per request
-        match data[0].as_str() {
-            Some(value_str) => match value_str.parse::<f64>() {
-                Ok(value) => Ok(value),
-                Err(_) => anyhow::bail!(
-                    "Failed to parse data {:?} as f64 from json blob: {:?}",
-                    data[0],
-                    json_blob
-                ),
-            },
-            None => anyhow::bail!(
-                "Failed to parse data {:?} as string from json blob: {:?}",
-                data[0],
-                json_blob
-            ),
+
+        if !self
+            .tracker
+            .lock()
+            .unwrap()
+            .should_submit(aggregate.value, now)
+        {
+            debug!(
+                feed_name = self.feed_name,
+                source_name = self.source_name,
+                value = aggregate.value,
+                "Value has not deviated enough to submit."
+            );
+            self.metrics
+                .skipped_submissions
+                .with_label_values(&[&onchain_feed_name])
+                .inc();
+            return;
         }
+
+        // TODO: allow more flexible multiplers and data types
+        let value = (aggregate.value * METRICS_MULTIPLIER) as u64;
+        self.send_to_uploader(value, aggregate.value, now).await;
     }
 
-    async fn send_to_uploader(&self, value: u64) {
+    async fn send_to_uploader(&self, value: u64, aggregate: f64, aggregated_at: Instant) {
         let _ = self
             .sender
             .send(DataPoint {
@@ -324,12 +438,63 @@ impl DataProvider {
                 value,
                 retrieval_timestamp: SystemTime::now(),
                 retrieval_instant: Instant::now(),
+                submission: Submission {
+                    tracker: self.tracker.clone(),
+                    aggregate,
+                    aggregated_at,
+                },
             })
             .await
             .tap_err(|err| error!("Failed to send data point to uploader: {:?}", err));
     }
 }
 
+async fn retrieve_from_data_source(
+    client: &reqwest::Client,
+    config: &DataSourceConfig,
+) -> anyhow::Result<f64> {
+    // TODO: support websocket
+    let url = &config.url;
+    let json_path = &config.json_path;
+    let response = client.get(url).send().await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch data: {:?}", response);
+    }
+
+    let json_blob: serde_json::Value = response.json().await?;
+    let data = jsonpath_lib::select(&json_blob, json_path)?;
+
+    if data.is_empty() {
+        anyhow::bail!(
+            "Failed to find data from json blob: {:?} with json path: {:?}",
+            json_blob,
+            json_path
+        );
+    }
+    // Assume there is one single value per request
+    match data[0].as_str() {
+        Some(value_str) => match value_str.parse::<f64>() {
+            Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+            Ok(value) => anyhow::bail!(
+                "Data {:?} from json blob: {:?} is not a finite, positive number",
+                value,
+                json_blob
+            ),
+            Err(_) => anyhow::bail!(
+                "Failed to parse data {:?} as f64 from json blob: {:?}",
+                data[0],
+                json_blob
+            ),
+        },
+        None => anyhow::bail!(
+            "Failed to parse data {:?} as string from json blob: {:?}",
+            data[0],
+            json_blob
+        ),
+    }
+}
+
 fn make_onchain_feed_name(feed_name: &str, source_name: &str) -> String {
     format!(
         "{}-{}",
@@ -518,6 +683,7 @@ impl OnChainDataUploader {
         // Update metrics
         for data_point in &data_points {
             if success {
+                data_point.submission.record();
                 self.metrics
                     .upload_successes
                     .with_label_values(&[&data_point.feed_name])
@@ -624,6 +790,25 @@ struct DataPoint {
     value: u64,
     retrieval_timestamp: SystemTime,
     retrieval_instant: Instant,
+    submission: Submission,
+}
+
+/// The aggregate a data point was derived from, recorded in its feed's submission tracker only
+/// once the data point is uploaded.
+#[derive(Debug)]
+struct Submission {
+    tracker: Arc<Mutex<SubmissionTracker>>,
+    aggregate: f64,
+    aggregated_at: Instant,
+}
+
+impl Submission {
+    fn record(&self) {
+        self.tracker
+            .lock()
+            .unwrap()
+            .record(self.aggregate, self.aggregated_at);
+    }
 }
 
 struct OnChainDataReader {
@@ -709,3 +894,230 @@ async fn get_object_arg(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    use crate::config::{AggregationConfig, SubmissionPolicy};
+
+    use super::*;
+
+    /// Serve `body` as JSON to every request, returning the URL to request it from.
+    async fn mock_source(status: u16, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len(),
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{addr}/price")
+    }
+
+    fn source(url: String) -> DataSourceConfig {
+        DataSourceConfig {
+            url,
+            json_path: "$.price".to_string(),
+        }
+    }
+
+    fn provider(
+        data_sources: BTreeMap<String, DataSourceConfig>,
+        aggregation: AggregationConfig,
+        submission_policy: SubmissionPolicy,
+    ) -> (DataProvider, mpsc::Receiver<DataPoint>) {
+        let (sender, receiver) = mpsc::channel(100);
+        let upload_feed = UploadFeedConfig {
+            submission_interval: Duration::from_secs(1),
+            data_source_config: None,
+            data_sources,
+            aggregation,
+            submission_policy,
+            upload_parameters: UploadParameters {
+                write_package_id: ObjectID::ZERO,
+                write_module_name: "simple_oracle".to_string(),
+                write_function_name: "submit_data".to_string(),
+                write_data_provider_object_id: ObjectID::ZERO,
+            },
+        };
+        let metrics = Arc::new(OracleMetrics::new(&Registry::new()));
+        let provider = DataProvider::new(
+            "SUIUSD".to_string(),
+            "mock".to_string(),
+            upload_feed,
+            sender,
+            metrics,
+        );
+        (provider, receiver)
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_from_data_source() {
+        let client = reqwest::Client::new();
+
+        let ok = source(mock_source(200, r#"{"price": "1.25"}"#).await);
+        assert_eq!(retrieve_from_data_source(&client, &ok).await.unwrap(), 1.25);
+
+        let unavailable = source(mock_source(503, r#"{}"#).await);
+        assert!(retrieve_from_data_source(&client, &unavailable)
+            .await
+            .is_err());
+
+        let malformed = source(mock_source(200, r#"{"price": "#).await);
+        assert!(retrieve_from_data_source(&client, &malformed)
+            .await
+            .is_err());
+
+        let missing = source(mock_source(200, r#"{"cost": "1.25"}"#).await);
+        assert!(retrieve_from_data_source(&client, &missing).await.is_err());
+
+        for price in [
+            r#"{"price": "NaN"}"#,
+            r#"{"price": "inf"}"#,
+            r#"{"price": "-1"}"#,
+        ] {
+            let invalid = source(mock_source(200, price).await);
+            assert!(retrieve_from_data_source(&client, &invalid).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_sources_rejecting_outliers() {
+        let data_sources = BTreeMap::from([
+            (
+                "a".to_string(),
+                source(mock_source(200, r#"{"price": "1.00"}"#).await),
+            ),
+            (
+                "b".to_string(),
+                source(mock_source(200, r#"{"price": "1.02"}"#).await),
+            ),
+            (
+                "c".to_string(),
+                source(mock_source(200, r#"{"price": "0.99"}"#).await),
+            ),
+            (
+                "flaky".to_string(),
+                source(mock_source(200, r#"{"price": "250"}"#).await),
+            ),
+            ("down".to_string(), source(mock_source(500, r#"{}"#).await)),
+        ]);
+        let aggregation = AggregationConfig {
+            min_sources: 3,
+            max_deviation: Some(0.05),
+            ..Default::default()
+        };
+        let (mut provider, mut receiver) =
+            provider(data_sources, aggregation, SubmissionPolicy::default());
+
+        provider.run_once().await;
+
+        let data_point = receiver.try_recv().unwrap();
+        assert_eq!(data_point.feed_name, "suiusd-mock");
+        assert_eq!(data_point.value, 1_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_not_enough_sources() {
+        let data_sources = BTreeMap::from([
+            (
+                "a".to_string(),
+                source(mock_source(200, r#"{"price": "1.00"}"#).await),
+            ),
+            ("down".to_string(), source(mock_source(500, r#"{}"#).await)),
+        ]);
+        let aggregation = AggregationConfig {
+            min_sources: 2,
+            ..Default::default()
+        };
+        let (mut provider, mut receiver) =
+            provider(data_sources, aggregation, SubmissionPolicy::default());
+
+        provider.run_once().await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stale_values() {
+        let data_sources = BTreeMap::from([(
+            "a".to_string(),
+            source(mock_source(200, r#"{"price": "1.00"}"#).await),
+        )]);
+        let aggregation = AggregationConfig {
+            max_staleness: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        let (mut provider, mut receiver) =
+            provider(data_sources, aggregation, SubmissionPolicy::default());
+
+        provider.run_once().await;
+        assert_eq!(receiver.try_recv().unwrap().value, 1_000_000);
+
+        // The source goes down, but its last value is still recent enough to use.
+        provider
+            .data_sources
+            .insert("a".to_string(), source(mock_source(500, r#"{}"#).await));
+        provider.run_once().await;
+        assert_eq!(receiver.try_recv().unwrap().value, 1_000_000);
+
+        // Once it is too old, nothing is submitted.
+        let reading = provider.readings.get_mut("a").unwrap();
+        let Some(stale) = reading.retrieved_at.checked_sub(Duration::from_secs(61)) else {
+            // The clock started too recently to go back that far.
+            return;
+        };
+        reading.retrieved_at = stale;
+        provider.run_once().await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_submit_on_deviation() {
+        let data_sources = BTreeMap::from([(
+            "a".to_string(),
+            source(mock_source(200, r#"{"price": "1.00"}"#).await),
+        )]);
+        let submission_policy = SubmissionPolicy {
+            deviation_threshold: Some(0.01),
+            heartbeat: Some(Duration::from_secs(3600)),
+        };
+        let (mut provider, mut receiver) = provider(
+            data_sources,
+            AggregationConfig::default(),
+            submission_policy,
+        );
+
+        provider.run_once().await;
+        assert_eq!(receiver.try_recv().unwrap().value, 1_000_000);
+
+        // Until the upload succeeds, the value is still due for submission.
+        provider.run_once().await;
+        let data_point = receiver.try_recv().unwrap();
+        assert_eq!(data_point.value, 1_000_000);
+        data_point.submission.record();
+
+        // Unchanged values are not submitted again before the heartbeat.
+        provider.run_once().await;
+        assert!(receiver.try_recv().is_err());
+
+        provider.data_sources.insert(
+            "a".to_string(),
+            source(mock_source(200, r#"{"price": "1.05"}"#).await),
+        );
+        provider.run_once().await;
+        assert_eq!(receiver.try_recv().unwrap().value, 1_050_000);
+    }
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_int_counter_vec_with_registry, register_int_counter_with_registry, IntCounter,
-    IntCounterVec, Registry,
+    register_gauge_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry, GaugeVec, IntCounter,
+    IntCounterVec, IntGaugeVec, Registry,
 };
 
 use mysten_metrics::histogram::{Histogram, HistogramVec};
@@ -12,7 +13,13 @@ use mysten_metrics::histogram::{Histogram, HistogramVec};
 pub struct OracleMetrics {
     pub(crate) data_source_successes: IntCounterVec,
     pub(crate) data_source_errors: IntCounterVec,
+    pub(crate) data_source_outliers: IntCounterVec,
+    pub(crate) data_source_up: IntGaugeVec,
+    pub(crate) data_source_values: GaugeVec,
+    pub(crate) data_source_latency_ms: HistogramVec,
     pub(crate) data_staleness: IntCounterVec,
+    pub(crate) aggregation_errors: IntCounterVec,
+    pub(crate) skipped_submissions: IntCounterVec,
     pub(crate) upload_successes: IntCounterVec,
     pub(crate) upload_data_errors: IntCounterVec,
     pub(crate) download_successes: IntCounterVec,
@@ -43,6 +50,33 @@ impl OracleMetrics {
                 registry,
             )
             .unwrap(),
+            data_source_outliers: register_int_counter_vec_with_registry!(
+                "oracle_data_source_outliers",
+                "Total number of values from data sources that are rejected as outliers",
+                &["feed", "source"],
+                registry,
+            )
+            .unwrap(),
+            data_source_up: register_int_gauge_vec_with_registry!(
+                "oracle_data_source_up",
+                "Whether the latest value from a data source was used in the feed's aggregate",
+                &["feed", "source"],
+                registry,
+            )
+            .unwrap(),
+            data_source_values: register_gauge_vec_with_registry!(
+                "oracle_data_source_values",
+                "Latest value retrieved from a data source",
+                &["feed", "source"],
+                registry,
+            )
+            .unwrap(),
+            data_source_latency_ms: HistogramVec::new_in_registry(
+                "oracle_data_source_latency_ms",
+                "Latency of data retrieval requests to data sources, in milliseconds",
+                &["feed", "source"],
+                registry,
+            ),
             data_staleness: register_int_counter_vec_with_registry!(
                 "oracle_data_staleness",
                 "Total number of stale data that are skipped",
@@ -50,6 +84,20 @@ impl OracleMetrics {
                 registry,
             )
             .unwrap(),
+            aggregation_errors: register_int_counter_vec_with_registry!(
+                "oracle_aggregation_errors",
+                "Total number of times there were not enough values from data sources to aggregate",
+                &["feed"],
+                registry,
+            )
+            .unwrap(),
+            skipped_submissions: register_int_counter_vec_with_registry!(
+                "oracle_skipped_submissions",
+                "Total number of aggregated values not submitted, as they did not deviate enough",
+                &["feed"],
+                registry,
+            )
+            .unwrap(),
             upload_successes: register_int_counter_vec_with_registry!(
                 "oracle_upload_successes",
                 "Total number of successful data upload",
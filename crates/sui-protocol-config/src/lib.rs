@@ -362,6 +362,11 @@ struct FeatureFlags {
     // If true allow calling receiving_object_id function
     #[serde(skip_serializing_if = "is_false")]
     allow_receiving_object_id: bool,
+
+    // How the cost of the transactions touching a shared object is estimated to limit the load
+    // of each shared object in a consensus commit.
+    #[serde(skip_serializing_if = "PerObjectCongestionControlMode::is_none")]
+    per_object_congestion_control_mode: PerObjectCongestionControlMode,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -388,6 +393,25 @@ impl ConsensusTransactionOrdering {
     }
 }
 
+/// How the execution cost of a transaction is estimated, to limit the total cost of the
+/// transactions scheduled on each shared object in one consensus commit.
+#[derive(Default, Copy, Clone, PartialEq, Eq, Serialize, Debug)]
+pub enum PerObjectCongestionControlMode {
+    /// No congestion control.
+    #[default]
+    None,
+    /// The cost of a transaction is its gas budget.
+    TotalGasBudget,
+    /// Every transaction has a cost of 1.
+    TotalTxCount,
+}
+
+impl PerObjectCongestionControlMode {
+    pub fn is_none(&self) -> bool {
+        matches!(self, PerObjectCongestionControlMode::None)
+    }
+}
+
 /// Constants that change the behavior of the protocol.
 ///
 /// The value of each constant here must be fixed for a given protocol version. To change the value
@@ -865,6 +889,17 @@ pub struct ProtocolConfig {
     /// Maximum allowed precision loss when reducing voting weights for the random beacon
     /// protocol.
     random_beacon_reduction_allowed_delta: Option<u16>,
+
+    /// === Congestion control ===
+
+    /// Maximum estimated cost of the transactions scheduled on one shared object in a consensus
+    /// commit, see `per_object_congestion_control_mode`. Transactions over the limit are deferred
+    /// to a later commit.
+    max_accumulated_txn_cost_per_object_in_checkpoint: Option<u64>,
+
+    /// Maximum number of consensus rounds a transaction can be deferred because of congestion,
+    /// after which it is scheduled regardless of the load on its shared objects.
+    max_deferral_rounds_for_congestion_control: Option<u64>,
 }
 
 // feature flags
@@ -1075,6 +1110,10 @@ impl ProtocolConfig {
     pub fn hardened_otw_check(&self) -> bool {
         self.feature_flags.hardened_otw_check
     }
+
+    pub fn per_object_congestion_control_mode(&self) -> PerObjectCongestionControlMode {
+        self.feature_flags.per_object_congestion_control_mode
+    }
 }
 
 #[cfg(not(msim))]
@@ -1451,6 +1490,10 @@ impl ProtocolConfig {
 
             random_beacon_reduction_allowed_delta: None,
 
+            max_accumulated_txn_cost_per_object_in_checkpoint: None,
+
+            max_deferral_rounds_for_congestion_control: None,
+
             // When adding a new constant, set it to None in the earliest version, like this:
             // new_constant: None,
         };
@@ -1779,6 +1822,12 @@ impl ProtocolConfig {
     pub fn set_consensus_bad_nodes_stake_threshold(&mut self, val: u64) {
         self.consensus_bad_nodes_stake_threshold = Some(val);
     }
+    pub fn set_per_object_congestion_control_mode_for_testing(
+        &mut self,
+        val: PerObjectCongestionControlMode,
+    ) {
+        self.feature_flags.per_object_congestion_control_mode = val;
+    }
     pub fn set_receive_object_for_testing(&mut self, val: bool) {
         self.feature_flags.receive_objects = val
     }
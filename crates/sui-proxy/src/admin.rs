@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::config::{PeerValidationConfig, RemoteWriteConfig};
+use crate::forwarder::Forwarder;
 use crate::handlers::publish_metrics;
 use crate::histogram_relay::HistogramRelay;
 use crate::middleware::{
@@ -58,7 +59,7 @@ pub async fn shutdown_signal(h: axum_server::Handle) {
     h.graceful_shutdown(Some(Duration::from_secs(grace)))
 }
 
-/// Reqwest client holds the global client for remote_push api calls
+/// Reqwest client holds the client for remote_push api calls to a remote_write target
 /// it also holds the username and password.  The client has an underlying
 /// connection pool.  See reqwest documentation for details
 #[derive(Clone)]
@@ -89,7 +90,7 @@ pub struct Labels {
 /// App will configure our routes. This fn is also used to instrument our tests
 pub fn app(
     labels: Labels,
-    client: ReqwestClient,
+    forwarder: Forwarder,
     relay: HistogramRelay,
     allower: Option<SuiNodeProvider>,
 ) -> Router {
@@ -111,7 +112,7 @@ pub fn app(
     router
         .layer(Extension(relay))
         .layer(Extension(labels))
-        .layer(Extension(client))
+        .layer(Extension(forwarder))
         .layer(
             ServiceBuilder::new().layer(
                 TraceLayer::new_for_http().on_response(
@@ -3,8 +3,9 @@
 use anyhow::{Context, Result};
 use core::time::Duration;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_with::{serde_as, DurationSeconds};
+use serde_with::{serde_as, DurationSeconds, OneOrMany};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::debug;
 
 #[serde_as]
@@ -14,7 +15,12 @@ pub struct ProxyConfig {
     pub network: String,
     pub inventory_hostname: String,
     pub listen_address: SocketAddr,
-    pub remote_write: RemoteWriteConfig,
+    /// one or more remote_write targets, each of which receives every metric we relay
+    #[serde_as(as = "OneOrMany<_>")]
+    pub remote_write: Vec<RemoteWriteConfig>,
+    /// on-disk buffering of the requests to remote_write targets
+    #[serde(default)]
+    pub remote_write_buffer: RemoteWriteBufferConfig,
     pub json_rpc: PeerValidationConfig,
     pub metrics_address: String,
     pub histogram_address: String,
@@ -36,6 +42,80 @@ pub struct RemoteWriteConfig {
     /// <https://docs.rs/reqwest/latest/reqwest/struct.ClientBuilder.html#method.pool_max_idle_per_host>
     #[serde(default = "pool_max_idle_per_host_default")]
     pub pool_max_idle_per_host: usize,
+
+    /// the name of this target in logs and metrics, also used to name its buffer directory.
+    /// defaults to the url with non alphanumeric characters replaced
+    #[serde(default)]
+    pub name: Option<String>,
+    /// rules applied in order to the labels of every timeseries we send to this target
+    #[serde(default)]
+    pub relabel: Vec<RelabelRule>,
+}
+
+impl RemoteWriteConfig {
+    /// the name of this target, see `name`
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.url
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect()
+        })
+    }
+}
+
+/// RelabelRule rewrites the labels of the timeseries sent to a remote_write target.
+/// Regexes are anchored, eg. `sui_.*` matches `sui_epoch` but not `my_sui_epoch`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum RelabelRule {
+    /// set label to value, adding the label if it's missing
+    Set { label: String, value: String },
+    /// rename the from label to to, overwriting to if it exists
+    Rename { from: String, to: String },
+    /// remove label
+    Remove { label: String },
+    /// drop the timeseries whose label value matches regex. a missing label has the empty value
+    Drop { label: String, regex: String },
+    /// drop the timeseries whose label value doesn't match regex
+    Keep { label: String, regex: String },
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteWriteBufferConfig {
+    /// requests to each remote_write target are queued in a subdirectory of this directory
+    /// until the target accepts them, so they survive target outages and proxy restarts
+    #[serde(default = "buffer_directory_default")]
+    pub directory: PathBuf,
+    /// the maximum size of the queue of each target. the oldest requests are dropped beyond it
+    #[serde(default = "buffer_max_bytes_per_target_default")]
+    pub max_bytes_per_target: u64,
+    /// how long we wait before retrying a target after its first failure
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "buffer_initial_backoff_default")]
+    pub initial_backoff: Duration,
+    /// the wait before retrying doubles after every consecutive failure, up to this duration
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "buffer_max_backoff_default")]
+    pub max_backoff: Duration,
+    /// how many queued requests we send to each target at once. with more than one, requests
+    /// may reach the target out of order
+    #[serde(default = "buffer_max_concurrent_requests_default")]
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for RemoteWriteBufferConfig {
+    fn default() -> Self {
+        Self {
+            directory: buffer_directory_default(),
+            max_bytes_per_target: buffer_max_bytes_per_target_default(),
+            initial_backoff: buffer_initial_backoff_default(),
+            max_backoff: buffer_max_backoff_default(),
+            max_concurrent_requests: buffer_max_concurrent_requests_default(),
+        }
+    }
 }
 
 #[serde_as]
@@ -74,6 +154,31 @@ fn remote_write_url() -> String {
     "http://metrics-gw.testnet.sui.io/api/v1/push".to_string()
 }
 
+/// the default directory to buffer remote_write requests in
+fn buffer_directory_default() -> PathBuf {
+    PathBuf::from("/opt/sui-proxy/remote-write-buffer")
+}
+
+/// the default size of the buffer of each remote_write target, 1GiB
+fn buffer_max_bytes_per_target_default() -> u64 {
+    1024 * 1024 * 1024
+}
+
+/// the default wait before retrying a remote_write target
+fn buffer_initial_backoff_default() -> Duration {
+    Duration::from_secs(1)
+}
+
+/// the default maximum wait before retrying a remote_write target
+fn buffer_max_backoff_default() -> Duration {
+    Duration::from_secs(60)
+}
+
+/// the default number of requests sent to a remote_write target at once
+fn buffer_max_concurrent_requests_default() -> usize {
+    4
+}
+
 /// load our config file from a path
 pub fn load<P: AsRef<std::path::Path>, T: DeserializeOwned + Serialize>(path: P) -> Result<T> {
     let path = path.as_ref();
@@ -92,4 +197,35 @@ mod tests {
 
         let _template: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
+
+    #[test]
+    fn config_load_multiple_remote_writes() {
+        const TEMPLATE: &str = include_str!("./data/config_multiple_remote_writes.yaml");
+
+        let config: ProxyConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        assert_eq!(config.remote_write.len(), 2);
+        assert_eq!(config.remote_write[0].name(), "primary");
+        assert_eq!(
+            config.remote_write[1].name(),
+            "http___backup_abcd_io_api_v1_push"
+        );
+        assert_eq!(
+            config.remote_write[0].relabel,
+            vec![
+                RelabelRule::Set {
+                    label: "cluster".into(),
+                    value: "joenet".into()
+                },
+                RelabelRule::Keep {
+                    label: "__name__".into(),
+                    regex: "sui_.*".into()
+                },
+            ]
+        );
+        assert_eq!(config.remote_write_buffer.max_bytes_per_target, 1048576);
+        assert_eq!(
+            config.remote_write_buffer.max_backoff,
+            Duration::from_secs(60)
+        );
+    }
 }
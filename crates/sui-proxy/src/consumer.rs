@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::admin::ReqwestClient;
+use crate::forwarder::Forwarder;
 use crate::prom_to_mimir::Mimir;
 use crate::remote_write::WriteRequest;
 use anyhow::Result;
@@ -139,7 +140,8 @@ pub fn populate_labels(
     data
 }
 
-fn encode_compress(request: &WriteRequest) -> Result<Vec<u8>, (StatusCode, &'static str)> {
+/// encode_compress encodes a remote_write request to protobuf and compresses it with snappy
+pub fn encode_compress(request: &WriteRequest) -> Result<Vec<u8>, (StatusCode, &'static str)> {
     let observe = || {
         let timer = CONSUMER_ENCODE_COMPRESS_DURATION
             .with_label_values(&["encode_compress"])
@@ -184,20 +186,25 @@ fn encode_compress(request: &WriteRequest) -> Result<Vec<u8>, (StatusCode, &'sta
     Ok(compressed)
 }
 
-async fn check_response(
-    request: WriteRequest,
-    response: reqwest::Response,
-) -> Result<(), (StatusCode, &'static str)> {
+/// SendError classifies the failures to send a request to a remote_write target
+#[derive(Debug)]
+pub enum SendError {
+    /// the request may succeed if we send it again later, eg. the target is unavailable
+    Retryable(String),
+    /// the target will never accept the request, eg. it is malformed
+    Permanent(String),
+}
+
+async fn check_response(response: reqwest::Response) -> Result<(), SendError> {
     match response.status() {
-        reqwest::StatusCode::OK => {
+        code if code.is_success() => {
             CONSUMER_OPS
                 .with_label_values(&["check_response", "OK"])
                 .inc();
-            debug!("({}) SUCCESS: {:?}", reqwest::StatusCode::OK, request);
+            debug!("({}) SUCCESS", code);
             Ok(())
         }
         reqwest::StatusCode::BAD_REQUEST => {
-            error!("TRIED: {:?}", request);
             let body = response
                 .text()
                 .await
@@ -210,22 +217,19 @@ async fn check_response(
                     .with_label_values(&["check_response", "BAD_REQUEST"])
                     .inc();
                 error!("({}) ERROR: {:?}", reqwest::StatusCode::BAD_REQUEST, body);
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "IGNORING METRICS due to err-mimir-sample-out-of-order",
+                return Err(SendError::Permanent(
+                    "IGNORING METRICS due to err-mimir-sample-out-of-order".into(),
                 ));
             }
             CONSUMER_OPS
                 .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
                 .inc();
             error!("({}) ERROR: {:?}", reqwest::StatusCode::BAD_REQUEST, body);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "unknown bad request error encountered in remote_push",
-            ))
+            Err(SendError::Permanent(format!(
+                "bad request error encountered in remote_push: {body}"
+            )))
         }
         code => {
-            error!("TRIED: {:?}", request);
             let body = response
                 .text()
                 .await
@@ -234,14 +238,51 @@ async fn check_response(
                 .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
                 .inc();
             error!("({}) ERROR: {:?}", code, body);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "unknown error encountered in remote_push",
-            ))
+            let error = format!("({code}) error encountered in remote_push: {body}");
+            // the target is overloaded or unavailable, anything else won't get better with time
+            if code == reqwest::StatusCode::TOO_MANY_REQUESTS || code.is_server_error() {
+                Err(SendError::Retryable(error))
+            } else {
+                Err(SendError::Permanent(error))
+            }
         }
     }
 }
 
+/// post a compressed remote_write request, as encoded by encode_compress, to a remote_write target
+pub async fn post(rc: &ReqwestClient, compressed: Vec<u8>) -> Result<(), SendError> {
+    let timer = CONSUMER_OPERATION_DURATION
+        .with_label_values(&["post"])
+        .start_timer();
+    let response = match rc
+        .client
+        .post(rc.settings.url.to_owned())
+        .header(reqwest::header::CONTENT_ENCODING, "snappy")
+        .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+        .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+        .basic_auth(
+            rc.settings.username.to_owned(),
+            Some(rc.settings.password.to_owned()),
+        )
+        .body(compressed)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(error) => {
+            CONSUMER_OPS
+                .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
+                .inc();
+            error!("post error: {error}");
+            timer.stop_and_discard();
+            return Err(SendError::Retryable(format!("post error: {error}")));
+        }
+    };
+    let result = check_response(response).await;
+    timer.observe_duration();
+    result
+}
+
 async fn convert(
     mfs: Vec<MetricFamily>,
 ) -> Result<impl Iterator<Item = WriteRequest>, (StatusCode, &'static str)> {
@@ -267,72 +308,42 @@ async fn convert(
     Ok(result)
 }
 
-/// convert_to_remote_write is an expensive method due to the time it takes to submit to mimir.
-/// other operations here are optimized for async, within reason.  The post process uses a single
-/// connection to mimir and thus incurs the seriliaztion delay for each metric family sent. Possible
-/// future optimizations would be to use multiple tcp connections to mimir, within reason. Nevertheless
-/// we await on each post of each metric family so it shouldn't block any other async work in a
-/// significant way.
+/// convert_to_remote_write converts the node metrics to remote_write requests and queues them
+/// for every remote_write target of the forwarder. Submitting them to the targets is expensive,
+/// so it happens in the background, see Forwarder. The node receives a response as soon as the
+/// requests are durably queued for at least one target.
 pub async fn convert_to_remote_write(
-    rc: ReqwestClient,
+    forwarder: Forwarder,
     node_metric: NodeMetric,
 ) -> (StatusCode, &'static str) {
     let timer = CONSUMER_OPERATION_DURATION
         .with_label_values(&["convert_to_remote_write"])
         .start_timer();
 
-    let remote_write_protos = match convert(node_metric.data).await {
-        Ok(v) => v,
+    let remote_write_protos: Vec<WriteRequest> = match convert(node_metric.data).await {
+        Ok(v) => v.collect(),
         Err(err) => {
             timer.stop_and_discard();
             return err;
         }
     };
+    let mf_cnt = remote_write_protos.len();
 
-    // a counter so we don't iterate the node data 2x
-    let mut mf_cnt = 0;
-    for request in remote_write_protos {
-        mf_cnt += 1;
-        let compressed = match encode_compress(&request) {
-            Ok(compressed) => compressed,
-            Err(error) => return error,
-        };
-
-        let response = match rc
-            .client
-            .post(rc.settings.url.to_owned())
-            .header(reqwest::header::CONTENT_ENCODING, "snappy")
-            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
-            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
-            .basic_auth(
-                rc.settings.username.to_owned(),
-                Some(rc.settings.password.to_owned()),
-            )
-            .body(compressed)
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(error) => {
-                CONSUMER_OPS
-                    .with_label_values(&["check_response", "INTERNAL_SERVER_ERROR"])
-                    .inc();
-                error!("DROPPING METRICS due to post error: {error}");
-                timer.stop_and_discard();
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "DROPPING METRICS due to post error",
-                );
-            }
-        };
-
-        match check_response(request, response).await {
-            Ok(_) => (),
+    // queueing writes to disk, keep it off the async workers
+    let queued =
+        match tokio::task::spawn_blocking(move || forwarder.enqueue(remote_write_protos)).await {
+            Ok(queued) => queued,
             Err(err) => {
-                timer.stop_and_discard();
-                return err;
+                error!("unable to queue remote_write requests; {err}");
+                0
             }
-        }
+        };
+    if queued == 0 {
+        timer.stop_and_discard();
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DROPPING METRICS; unable to queue remote_write requests",
+        );
     }
     CONSUMER_OPS_SUBMITTED.inc_by(mf_cnt as f64);
     timer.observe_duration();
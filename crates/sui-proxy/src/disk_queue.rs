@@ -0,0 +1,287 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use anyhow::{bail, Context, Result};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// suffix of entries being written, which are ignored (and removed) when opening a queue
+const TMP_SUFFIX: &str = ".tmp";
+
+/// DiskQueue is a bounded FIFO of payloads persisted in a directory, so that they survive
+/// restarts of the proxy. Every entry is a file named after an increasing sequence number.
+/// Entries are written to a temporary file which is then renamed, so a crash never leaves a
+/// partially written entry behind. When the queue is full, the oldest entries are evicted to
+/// make room for new ones.
+#[derive(Debug)]
+pub struct DiskQueue {
+    directory: PathBuf,
+    max_bytes: u64,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// (sequence number, size in bytes) of our entries, oldest first
+    entries: VecDeque<(u64, u64)>,
+    next_sequence: u64,
+    bytes: u64,
+}
+
+/// the oldest entry of a DiskQueue, see DiskQueue::peek
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub sequence: u64,
+    pub payload: Vec<u8>,
+}
+
+impl DiskQueue {
+    /// open the queue persisted in directory, creating it if needed
+    pub fn open<P: AsRef<Path>>(directory: P, max_bytes: u64) -> Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory).context(format!("cannot create {:?}", directory))?;
+        let mut entries = vec![];
+        for file in fs::read_dir(&directory).context(format!("cannot read {:?}", directory))? {
+            let file = file?;
+            let name = file.file_name();
+            let name = name.to_string_lossy();
+            if name.ends_with(TMP_SUFFIX) {
+                fs::remove_file(file.path())?;
+                continue;
+            }
+            match name.parse::<u64>() {
+                Ok(sequence) => entries.push((sequence, file.metadata()?.len())),
+                Err(_) => warn!("ignoring unexpected file {:?} in {:?}", name, directory),
+            }
+        }
+        entries.sort_unstable();
+        let inner = Inner {
+            next_sequence: entries.last().map_or(0, |(sequence, _)| sequence + 1),
+            bytes: entries.iter().map(|(_, size)| size).sum(),
+            entries: entries.into(),
+        };
+        let queue = Self {
+            directory,
+            max_bytes,
+            inner: Mutex::new(inner),
+        };
+        // the bound may have been lowered since the queue was written
+        queue.evict(&mut queue.inner.lock().unwrap(), 0)?;
+        Ok(queue)
+    }
+
+    /// push appends payload to the queue, returning the number of entries evicted to make room
+    pub fn push(&self, payload: &[u8]) -> Result<usize> {
+        let size = payload.len() as u64;
+        if size > self.max_bytes {
+            bail!(
+                "entry of {size} bytes exceeds the queue size of {} bytes",
+                self.max_bytes
+            );
+        }
+        let mut inner = self.inner.lock().unwrap();
+        let evicted = self.evict(&mut inner, size)?;
+        let sequence = inner.next_sequence;
+        let path = self.path(sequence);
+        let tmp = path.with_extension(&TMP_SUFFIX[1..]);
+        fs::write(&tmp, payload).context(format!("cannot write {:?}", tmp))?;
+        fs::rename(&tmp, &path).context(format!("cannot rename {:?}", tmp))?;
+        inner.entries.push_back((sequence, size));
+        inner.next_sequence += 1;
+        inner.bytes += size;
+        Ok(evicted)
+    }
+
+    /// peek returns the oldest entry without removing it
+    pub fn peek(&self) -> Result<Option<Entry>> {
+        Ok(self.peek_oldest(1)?.pop())
+    }
+
+    /// peek_oldest returns up to count of the oldest entries, oldest first, without removing
+    /// them. it only fails if not even the oldest entry can be read
+    pub fn peek_oldest(&self, count: usize) -> Result<Vec<Entry>> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut entries = vec![];
+        while entries.len() < count {
+            let Some(&(sequence, size)) = inner.entries.get(entries.len()) else {
+                break;
+            };
+            match fs::read(self.path(sequence)) {
+                Ok(payload) => entries.push(Entry { sequence, payload }),
+                // someone removed the file from under us, skip it
+                Err(error) if error.kind() == ErrorKind::NotFound => {
+                    warn!("entry {sequence} of {:?} is missing", self.directory);
+                    inner.entries.remove(entries.len());
+                    inner.bytes -= size;
+                }
+                Err(error) if entries.is_empty() => {
+                    return Err(error).context(format!("cannot read entry {sequence}"));
+                }
+                // return the entries read so far, the next peek reports the error
+                Err(_) => break,
+            }
+        }
+        Ok(entries)
+    }
+
+    /// remove the entry sequence, returned by peek. it's a no-op if the entry was evicted since
+    pub fn remove(&self, sequence: u64) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.entries.binary_search_by_key(&sequence, |(s, _)| *s) {
+            Ok(index) => self.remove_at(&mut inner, index),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// the number of entries in the queue
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the size of the entries in the queue, in bytes
+    pub fn bytes(&self) -> u64 {
+        self.inner.lock().unwrap().bytes
+    }
+
+    /// evict the oldest entries until an entry of size bytes fits in the queue
+    fn evict(&self, inner: &mut Inner, size: u64) -> Result<usize> {
+        let mut evicted = 0;
+        while inner.bytes + size > self.max_bytes && !inner.entries.is_empty() {
+            self.remove_at(inner, 0)?;
+            evicted += 1;
+        }
+        Ok(evicted)
+    }
+
+    fn remove_at(&self, inner: &mut Inner, index: usize) -> Result<()> {
+        let Some((sequence, size)) = inner.entries.remove(index) else {
+            return Ok(());
+        };
+        inner.bytes -= size;
+        match fs::remove_file(self.path(sequence)) {
+            Err(error) if error.kind() != ErrorKind::NotFound => {
+                Err(error).context(format!("cannot remove entry {sequence}"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn path(&self, sequence: u64) -> PathBuf {
+        // zero padded so entries sort by name too, which eases debugging
+        self.directory.join(format!("{sequence:020}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = DiskQueue::open(dir.path(), 1024).unwrap();
+        assert_eq!(queue.peek().unwrap(), None);
+
+        queue.push(b"first").unwrap();
+        queue.push(b"second").unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.bytes(), 11);
+
+        let entry = queue.peek().unwrap().unwrap();
+        assert_eq!(entry.payload, b"first");
+        // peek doesn't remove the entry
+        assert_eq!(queue.peek().unwrap().unwrap(), entry);
+        queue.remove(entry.sequence).unwrap();
+        // removing twice is a no-op
+        queue.remove(entry.sequence).unwrap();
+
+        assert_eq!(queue.peek().unwrap().unwrap().payload, b"second");
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.bytes(), 6);
+    }
+
+    #[test]
+    fn reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let queue = DiskQueue::open(dir.path(), 1024).unwrap();
+            queue.push(b"first").unwrap();
+            queue.push(b"second").unwrap();
+            let entry = queue.peek().unwrap().unwrap();
+            queue.remove(entry.sequence).unwrap();
+        }
+        // a write interrupted by a crash
+        fs::write(dir.path().join(format!("{:020}.tmp", 7)), b"partial").unwrap();
+
+        let queue = DiskQueue::open(dir.path(), 1024).unwrap();
+        assert_eq!(queue.len(), 1);
+        queue.push(b"third").unwrap();
+        let entry = queue.peek().unwrap().unwrap();
+        assert_eq!(entry.payload, b"second");
+        queue.remove(entry.sequence).unwrap();
+        assert_eq!(queue.peek().unwrap().unwrap().payload, b"third");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn peek_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = DiskQueue::open(dir.path(), 1024).unwrap();
+        assert!(queue.peek_oldest(2).unwrap().is_empty());
+
+        for payload in [b"first", b"secnd", b"third"] {
+            queue.push(payload).unwrap();
+        }
+        let entries = queue.peek_oldest(2).unwrap();
+        let payloads: Vec<_> = entries.iter().map(|e| e.payload.as_slice()).collect();
+        assert_eq!(payloads, [b"first", b"secnd"]);
+
+        // entries may be removed out of order
+        queue.remove(entries[1].sequence).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.bytes(), 10);
+        let payloads: Vec<_> = queue
+            .peek_oldest(3)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.payload)
+            .collect();
+        assert_eq!(payloads, [b"first".to_vec(), b"third".to_vec()]);
+
+        // missing files are skipped
+        fs::remove_file(dir.path().join(format!("{:020}", entries[0].sequence))).unwrap();
+        assert_eq!(queue.peek().unwrap().unwrap().payload, b"third");
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = DiskQueue::open(dir.path(), 10).unwrap();
+        assert_eq!(queue.push(b"1234").unwrap(), 0);
+        assert_eq!(queue.push(b"5678").unwrap(), 0);
+        // the oldest entry is evicted
+        assert_eq!(queue.push(b"90").unwrap(), 0);
+        assert_eq!(queue.push(b"abc").unwrap(), 1);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.bytes(), 9);
+        assert_eq!(queue.peek().unwrap().unwrap().payload, b"5678");
+
+        // entries larger than the queue are rejected
+        queue.push(b"this is too large").unwrap_err();
+        assert_eq!(queue.len(), 3);
+
+        // a lower bound evicts entries when reopening
+        drop(queue);
+        let queue = DiskQueue::open(dir.path(), 5).unwrap();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.peek().unwrap().unwrap().payload, b"90");
+    }
+}
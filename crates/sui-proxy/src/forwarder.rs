@@ -0,0 +1,306 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::admin::{make_reqwest_client, ReqwestClient};
+use crate::config::{RemoteWriteBufferConfig, RemoteWriteConfig};
+use crate::consumer::{encode_compress, post, SendError};
+use crate::disk_queue::DiskQueue;
+use crate::relabel::Relabeler;
+use crate::remote_write::WriteRequest;
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use prometheus::{register_counter_vec, register_int_gauge_vec};
+use prometheus::{CounterVec, IntGaugeVec};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+
+static TARGET_HEALTHY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "remote_write_target_healthy",
+        "1 if the last request sent to the remote_write target succeeded, 0 if it must be retried.",
+        &["target"]
+    )
+    .unwrap()
+});
+static TARGET_QUEUE_ENTRIES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "remote_write_target_queue_entries",
+        "The number of requests queued on disk for the remote_write target.",
+        &["target"]
+    )
+    .unwrap()
+});
+static TARGET_QUEUE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "remote_write_target_queue_bytes",
+        "The size of the requests queued on disk for the remote_write target.",
+        &["target"]
+    )
+    .unwrap()
+});
+static TARGET_REQUESTS: Lazy<CounterVec> = Lazy::new(|| {
+    register_counter_vec!(
+        "remote_write_target_requests",
+        "Outcomes of the requests for the remote_write target: sent, retried, or dropped because the target rejected them, the queue was full, or they couldn't be queued.",
+        &["target", "status"]
+    )
+    .unwrap()
+});
+
+/// Forwarder relays remote_write requests to all our remote_write targets. Requests are first
+/// written to an on-disk queue per target, which a task per target drains oldest first, a few
+/// requests at a time, retrying with exponential backoff while the target is unavailable. An
+/// unavailable target thus doesn't lose metrics, up to the size of its queue, nor delay the
+/// other targets.
+#[derive(Clone)]
+pub struct Forwarder {
+    targets: Arc<Vec<Arc<Target>>>,
+}
+
+struct Target {
+    name: String,
+    client: ReqwestClient,
+    relabeler: Relabeler,
+    queue: DiskQueue,
+    /// wakes up the sender when the queue is no longer empty
+    notify: Notify,
+}
+
+impl Forwarder {
+    /// start a forwarder to the given remote_write targets, spawning a sender task per target
+    pub fn start(
+        settings: Vec<RemoteWriteConfig>,
+        buffer: RemoteWriteBufferConfig,
+        user_agent: &str,
+    ) -> Result<Self> {
+        if settings.is_empty() {
+            bail!("at least one remote_write target is required");
+        }
+        if buffer.max_concurrent_requests == 0 {
+            bail!("max-concurrent-requests must be at least 1");
+        }
+        let mut names = HashSet::new();
+        let mut targets = vec![];
+        for settings in settings {
+            let name = settings.name();
+            if !names.insert(name.clone()) {
+                bail!("duplicate remote_write target name {name}");
+            }
+            let relabeler = Relabeler::new(&settings.relabel).context(format!(
+                "invalid relabel rules for remote_write target {name}"
+            ))?;
+            let queue = DiskQueue::open(buffer.directory.join(&name), buffer.max_bytes_per_target)?;
+            info!(
+                "remote_write target {name} sends to {}, {} requests queued",
+                settings.url,
+                queue.len()
+            );
+            let target = Arc::new(Target {
+                client: make_reqwest_client(settings, user_agent),
+                name,
+                relabeler,
+                queue,
+                notify: Notify::new(),
+            });
+            target.update_queue_metrics();
+            TARGET_HEALTHY.with_label_values(&[&target.name]).set(1);
+            tokio::spawn(target.clone().run(
+                buffer.initial_backoff,
+                buffer.max_backoff,
+                buffer.max_concurrent_requests,
+            ));
+            targets.push(target);
+        }
+        Ok(Self {
+            targets: Arc::new(targets),
+        })
+    }
+
+    /// enqueue requests for every target. this does blocking io. returns the number of targets
+    /// that queued all of the requests
+    pub fn enqueue(&self, requests: Vec<WriteRequest>) -> usize {
+        self.targets
+            .iter()
+            .filter(|target| target.enqueue(&requests))
+            .count()
+    }
+}
+
+impl Target {
+    fn enqueue(&self, requests: &[WriteRequest]) -> bool {
+        let mut queued_all = true;
+        for request in requests {
+            let compressed = if self.relabeler.is_empty() {
+                encode_compress(request)
+            } else {
+                let mut request = request.clone();
+                self.relabeler.relabel(&mut request);
+                if request.timeseries.is_empty() {
+                    continue;
+                }
+                encode_compress(&request)
+            };
+            let Ok(compressed) = compressed else {
+                self.inc_requests("dropped_encode", 1);
+                queued_all = false;
+                continue;
+            };
+            match self.queue.push(&compressed) {
+                Ok(evicted) => {
+                    if evicted > 0 {
+                        warn!(
+                            "remote_write target {} queue is full, dropped the {evicted} oldest requests",
+                            self.name
+                        );
+                        self.inc_requests("dropped_queue_full", evicted);
+                    }
+                }
+                Err(error) => {
+                    error!(
+                        "DROPPING METRICS; unable to queue request for remote_write target {}: {error:#}",
+                        self.name
+                    );
+                    self.inc_requests("dropped_queue_error", 1);
+                    queued_all = false;
+                }
+            }
+        }
+        self.update_queue_metrics();
+        self.notify.notify_one();
+        queued_all
+    }
+
+    /// send the queued requests oldest first, up to max_concurrent_requests at once, forever
+    async fn run(
+        self: Arc<Self>,
+        initial_backoff: Duration,
+        max_backoff: Duration,
+        max_concurrent_requests: usize,
+    ) {
+        let mut backoff = initial_backoff;
+        loop {
+            let entries = match self
+                .blocking(move |target| target.queue.peek_oldest(max_concurrent_requests))
+                .await
+            {
+                Ok(entries) if entries.is_empty() => {
+                    self.notify.notified().await;
+                    continue;
+                }
+                Ok(entries) => entries,
+                Err(error) => {
+                    error!(
+                        "unable to read queue of remote_write target {}: {error:#}",
+                        self.name
+                    );
+                    tokio::time::sleep(backoff).await;
+                    continue;
+                }
+            };
+
+            let mut posts = JoinSet::new();
+            for entry in entries {
+                let target = self.clone();
+                posts.spawn(
+                    async move { (entry.sequence, post(&target.client, entry.payload).await) },
+                );
+            }
+            // requests that were sent or that the target will never accept, the others are
+            // retried
+            let mut done = vec![];
+            let mut unavailable = None;
+            while let Some(result) = posts.join_next().await {
+                let (sequence, result) = match result {
+                    Ok(result) => result,
+                    Err(error) => {
+                        error!(
+                            "request to remote_write target {} failed: {error}",
+                            self.name
+                        );
+                        continue;
+                    }
+                };
+                match result {
+                    Ok(()) => {
+                        self.inc_requests("sent", 1);
+                        done.push(sequence);
+                    }
+                    Err(SendError::Permanent(reason)) => {
+                        // the target is up, it just won't ever accept this request
+                        error!(
+                            "DROPPING METRICS; remote_write target {} rejected request: {reason}",
+                            self.name
+                        );
+                        self.inc_requests("dropped_rejected", 1);
+                        done.push(sequence);
+                    }
+                    Err(SendError::Retryable(reason)) => {
+                        self.inc_requests("retried", 1);
+                        unavailable = Some(reason);
+                    }
+                }
+            }
+
+            if !done.is_empty() {
+                self.blocking(move |target| {
+                    for sequence in done {
+                        if let Err(error) = target.queue.remove(sequence) {
+                            error!(
+                                "unable to remove request from queue of remote_write target {}: {error:#}",
+                                target.name
+                            );
+                        }
+                    }
+                    target.update_queue_metrics();
+                })
+                .await;
+            }
+
+            match unavailable {
+                None => {
+                    TARGET_HEALTHY.with_label_values(&[&self.name]).set(1);
+                    backoff = initial_backoff;
+                }
+                Some(reason) => {
+                    TARGET_HEALTHY.with_label_values(&[&self.name]).set(0);
+                    warn!(
+                        "remote_write target {} is unavailable, retrying in {backoff:?}: {reason}",
+                        self.name
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                }
+            }
+        }
+    }
+
+    /// run f, which does blocking io on our queue, off the async workers
+    async fn blocking<R, F>(self: &Arc<Self>, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&Target) -> R + Send + 'static,
+    {
+        let target = self.clone();
+        tokio::task::spawn_blocking(move || f(&target))
+            .await
+            .expect("remote_write queue task panicked")
+    }
+
+    fn inc_requests(&self, status: &str, count: usize) {
+        TARGET_REQUESTS
+            .with_label_values(&[&self.name, status])
+            .inc_by(count as f64);
+    }
+
+    fn update_queue_metrics(&self) {
+        TARGET_QUEUE_ENTRIES
+            .with_label_values(&[&self.name])
+            .set(self.queue.len() as i64);
+        TARGET_QUEUE_BYTES
+            .with_label_values(&[&self.name])
+            .set(self.queue.bytes() as i64);
+    }
+}
@@ -1,7 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use crate::admin::{Labels, ReqwestClient};
+use crate::admin::Labels;
 use crate::consumer::{convert_to_remote_write, populate_labels, NodeMetric};
+use crate::forwarder::Forwarder;
 use crate::histogram_relay::HistogramRelay;
 use crate::middleware::LenDelimProtobuf;
 use crate::peers::SuiPeer;
@@ -40,10 +41,10 @@ static HTTP_HANDLER_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
 /// Publish handler which receives metrics from nodes.  Nodes will call us at this endpoint
 /// and we relay them to the upstream tsdb
 ///
-/// Clients will receive a response after successfully relaying the metrics upstream
+/// Clients will receive a response after the metrics are queued for relaying upstream
 pub async fn publish_metrics(
     Extension(labels): Extension<Labels>,
-    Extension(client): Extension<ReqwestClient>,
+    Extension(forwarder): Extension<Forwarder>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Extension(SuiPeer {
         name, public_key, ..
@@ -60,7 +61,7 @@ pub async fn publish_metrics(
     let data = populate_labels(name, labels.network, labels.inventory_hostname, data);
     relay.submit(data.clone());
     let response = convert_to_remote_write(
-        client.clone(),
+        forwarder,
         NodeMetric {
             data,
             peer_addr: Multiaddr::from(addr.ip()),
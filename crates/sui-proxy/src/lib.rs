@@ -3,12 +3,15 @@
 pub mod admin;
 pub mod config;
 pub mod consumer;
+pub mod disk_queue;
+pub mod forwarder;
 pub mod handlers;
 pub mod histogram_relay;
 pub mod metrics;
 pub mod middleware;
 pub mod peers;
 pub mod prom_to_mimir;
+pub mod relabel;
 pub mod remote_write;
 
 /// var extracts environment variables at runtime with a default fallback value
@@ -38,15 +41,22 @@ mod tests {
     use crate::histogram_relay::HistogramRelay;
     use crate::prom_to_mimir::tests::*;
 
+    use crate::config::{RelabelRule, RemoteWriteBufferConfig};
+    use crate::forwarder::Forwarder;
+    use crate::remote_write::{Label, Sample, TimeSeries, WriteRequest};
     use crate::{admin::CertKeyPair, config::RemoteWriteConfig, peers::SuiNodeProvider};
+    use axum::body::Bytes;
     use axum::http::{header, StatusCode};
     use axum::routing::post;
-    use axum::Router;
+    use axum::{Extension, Router};
     use multiaddr::Multiaddr;
     use prometheus::Encoder;
     use prometheus::PROTOBUF_FORMAT;
+    use prost::Message;
     use protobuf::RepeatedField;
     use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
     use sui_tls::{CertVerifier, TlsAcceptor};
 
@@ -67,9 +77,155 @@ mod tests {
             .unwrap();
     }
 
+    /// a mock remote_write that fails the first `failures` requests, then records the requests
+    #[derive(Default)]
+    struct MockRemoteWrite {
+        failures: AtomicUsize,
+        received: Mutex<Vec<WriteRequest>>,
+    }
+
+    async fn run_mock_remote_write(listener: TcpListener, mock: Arc<MockRemoteWrite>) {
+        async fn handler(
+            Extension(mock): Extension<Arc<MockRemoteWrite>>,
+            body: Bytes,
+        ) -> StatusCode {
+            if mock
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| f.checked_sub(1))
+                .is_ok()
+            {
+                return StatusCode::SERVICE_UNAVAILABLE;
+            }
+            let decompressed = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+            let request = WriteRequest::decode(decompressed.as_slice()).unwrap();
+            mock.received.lock().unwrap().push(request);
+            StatusCode::OK
+        }
+
+        let app = Router::new()
+            .route("/v1/push", post(handler))
+            .layer(Extension(mock));
+
+        axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    }
+
+    /// forwarder_fanout_and_retry sends a request to a target that is temporarily unavailable
+    /// and to a target that is down. The first one receives it, relabeled, once it recovers, while
+    /// it stays queued on disk for the second one.
+    #[tokio::test]
+    async fn forwarder_fanout_and_retry() {
+        let mock_listener = std::net::TcpListener::bind("localhost:0").unwrap();
+        let mock_url = format!(
+            "http://localhost:{}/v1/push",
+            mock_listener.local_addr().unwrap().port()
+        );
+        let mock = Arc::new(MockRemoteWrite {
+            failures: AtomicUsize::new(2),
+            ..Default::default()
+        });
+        let _mock_remote_write = tokio::spawn(run_mock_remote_write(mock_listener, mock.clone()));
+
+        // nothing listens on this port once the listener is dropped
+        let down_url = {
+            let listener = std::net::TcpListener::bind("localhost:0").unwrap();
+            format!(
+                "http://localhost:{}/v1/push",
+                listener.local_addr().unwrap().port()
+            )
+        };
+
+        let buffer_dir = tempfile::tempdir().unwrap();
+        let forwarder = Forwarder::start(
+            vec![
+                RemoteWriteConfig {
+                    name: Some("flaky".into()),
+                    url: mock_url,
+                    relabel: vec![
+                        RelabelRule::Set {
+                            label: "cluster".into(),
+                            value: "unittest".into(),
+                        },
+                        RelabelRule::Remove {
+                            label: "host".into(),
+                        },
+                    ],
+                    ..Default::default()
+                },
+                RemoteWriteConfig {
+                    name: Some("down".into()),
+                    url: down_url,
+                    ..Default::default()
+                },
+            ],
+            RemoteWriteBufferConfig {
+                directory: buffer_dir.path().to_path_buf(),
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(50),
+                ..Default::default()
+            },
+            "dummy user agent",
+        )
+        .unwrap();
+
+        let label = |name: &str, value: &str| Label {
+            name: name.into(),
+            value: value.into(),
+        };
+        let request = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![
+                    label("__name__", "foo_metric"),
+                    label("host", "validator-0"),
+                ],
+                samples: vec![Sample {
+                    value: 2046.0,
+                    timestamp: 1,
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(forwarder.enqueue(vec![request]), 2);
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while mock.received.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the flaky target should receive the request once it recovers");
+
+        let received = mock.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].timeseries[0].labels,
+            vec![
+                label("__name__", "foo_metric"),
+                label("cluster", "unittest")
+            ]
+        );
+        assert_eq!(mock.failures.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            std::fs::read_dir(buffer_dir.path().join("flaky"))
+                .unwrap()
+                .count(),
+            0
+        );
+        assert_eq!(
+            std::fs::read_dir(buffer_dir.path().join("down"))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
     /// axum_acceptor is a basic e2e test that creates a mock remote_write post endpoint and has a simple
     /// sui-node client that posts data to the proxy using the protobuf format.  The server processes this
-    /// data and sends it to the mock remote_write which accepts everything.  Future work is to make this more
+    /// data and queues it for the mock remote_write which accepts everything.  Future work is to make this more
     /// robust and expand the scope of coverage, probabaly moving this test elsewhere and renaming it.
     #[tokio::test]
     async fn axum_acceptor() {
@@ -97,22 +253,28 @@ mod tests {
             )
             .unwrap();
 
-        let client = admin::make_reqwest_client(
-            RemoteWriteConfig {
+        let buffer_dir = tempfile::tempdir().unwrap();
+        let forwarder = Forwarder::start(
+            vec![RemoteWriteConfig {
                 url: dummy_remote_write_url.to_owned(),
                 username: "bar".into(),
                 password: "foo".into(),
                 ..Default::default()
+            }],
+            RemoteWriteBufferConfig {
+                directory: buffer_dir.path().to_path_buf(),
+                ..Default::default()
             },
             "dummy user agent",
-        );
+        )
+        .unwrap();
 
         let app = admin::app(
             Labels {
                 network: "unittest-network".into(),
                 inventory_hostname: "ansible_inventory_name".into(),
             },
-            client,
+            forwarder,
             HistogramRelay::new(),
             Some(allower.clone()),
         );
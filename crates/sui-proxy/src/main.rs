@@ -6,10 +6,10 @@ use clap::Parser;
 use sui_proxy::config::ProxyConfig;
 use sui_proxy::{
     admin::{
-        app, create_server_cert_default_allow, create_server_cert_enforce_peer,
-        make_reqwest_client, server, Labels,
+        app, create_server_cert_default_allow, create_server_cert_enforce_peer, server, Labels,
     },
     config::load,
+    forwarder::Forwarder,
     histogram_relay, metrics,
 };
 use sui_tls::TlsAcceptor;
@@ -67,7 +67,12 @@ async fn main() -> Result<()> {
 
     info!(
         "listen on {:?} send to {:?}",
-        config.listen_address, config.remote_write.url
+        config.listen_address,
+        config
+            .remote_write
+            .iter()
+            .map(|remote_write| &remote_write.url)
+            .collect::<Vec<_>>()
     );
 
     let listener = std::net::TcpListener::bind(config.listen_address).unwrap();
@@ -86,7 +91,11 @@ async fn main() -> Result<()> {
     let histogram_listener = std::net::TcpListener::bind(config.histogram_address).unwrap();
     let metrics_listener = std::net::TcpListener::bind(config.metrics_address).unwrap();
     let acceptor = TlsAcceptor::new(tls_config);
-    let client = make_reqwest_client(config.remote_write, APP_USER_AGENT);
+    let forwarder = Forwarder::start(
+        config.remote_write,
+        config.remote_write_buffer,
+        APP_USER_AGENT,
+    )?;
     let histogram_relay = histogram_relay::start_prometheus_server(histogram_listener);
     let registry_service = metrics::start_prometheus_server(metrics_listener);
     let prometheus_registry = registry_service.default_registry();
@@ -102,7 +111,7 @@ async fn main() -> Result<()> {
             network: config.network,
             inventory_hostname: config.inventory_hostname,
         },
-        client,
+        forwarder,
         histogram_relay,
         allower,
     );
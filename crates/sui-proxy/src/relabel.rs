@@ -0,0 +1,188 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+use crate::config::RelabelRule;
+use crate::remote_write::{Label, TimeSeries, WriteRequest};
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// a RelabelRule with its regex compiled
+#[derive(Clone, Debug)]
+enum Rule {
+    Set { label: String, value: String },
+    Rename { from: String, to: String },
+    Remove { label: String },
+    Drop { label: String, regex: Regex },
+    Keep { label: String, regex: Regex },
+}
+
+/// Relabeler applies the relabel rules of a remote_write target to the requests we send it
+#[derive(Clone, Debug, Default)]
+pub struct Relabeler {
+    rules: Vec<Rule>,
+}
+
+impl Relabeler {
+    pub fn new(rules: &[RelabelRule]) -> Result<Self> {
+        // anchor regexes so they match whole label values, as prometheus does
+        let anchored = |regex: &str| {
+            Regex::new(&format!("^(?:{regex})$")).context(format!("invalid relabel regex {regex}"))
+        };
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Ok(match rule.clone() {
+                    RelabelRule::Set { label, value } => Rule::Set { label, value },
+                    RelabelRule::Rename { from, to } => Rule::Rename { from, to },
+                    RelabelRule::Remove { label } => Rule::Remove { label },
+                    RelabelRule::Drop { label, regex } => Rule::Drop {
+                        regex: anchored(&regex)?,
+                        label,
+                    },
+                    RelabelRule::Keep { label, regex } => Rule::Keep {
+                        regex: anchored(&regex)?,
+                        label,
+                    },
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// relabel rewrites the timeseries of request in place, dropping the ones filtered out by
+    /// our rules. labels are kept sorted by name, as remote_write requires.
+    pub fn relabel(&self, request: &mut WriteRequest) {
+        if self.is_empty() {
+            return;
+        }
+        request
+            .timeseries
+            .retain_mut(|ts| self.relabel_timeseries(ts));
+    }
+
+    /// returns false if the timeseries must be dropped
+    fn relabel_timeseries(&self, ts: &mut TimeSeries) -> bool {
+        let value = |labels: &[Label], name: &str| {
+            labels
+                .iter()
+                .find(|l| l.name == name)
+                .map(|l| l.value.clone())
+                .unwrap_or_default()
+        };
+        for rule in &self.rules {
+            match rule {
+                Rule::Set { label, value } => {
+                    ts.labels.retain(|l| &l.name != label);
+                    ts.labels.push(Label {
+                        name: label.clone(),
+                        value: value.clone(),
+                    });
+                }
+                Rule::Rename { from, to } => {
+                    if ts.labels.iter().any(|l| &l.name == from) {
+                        ts.labels.retain(|l| &l.name != to);
+                        for l in ts.labels.iter_mut().filter(|l| &l.name == from) {
+                            l.name = to.clone();
+                        }
+                    }
+                }
+                Rule::Remove { label } => ts.labels.retain(|l| &l.name != label),
+                Rule::Drop { label, regex } => {
+                    if regex.is_match(&value(&ts.labels, label)) {
+                        return false;
+                    }
+                }
+                Rule::Keep { label, regex } => {
+                    if !regex.is_match(&value(&ts.labels, label)) {
+                        return false;
+                    }
+                }
+            }
+        }
+        ts.labels.sort_by(|a, b| a.name.cmp(&b.name));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeseries(labels: Vec<(&str, &str)>) -> TimeSeries {
+        TimeSeries {
+            labels: labels
+                .into_iter()
+                .map(|(name, value)| Label {
+                    name: name.into(),
+                    value: value.into(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn relabel() {
+        let relabeler = Relabeler::new(&[
+            RelabelRule::Keep {
+                label: "__name__".into(),
+                regex: "sui_.*".into(),
+            },
+            RelabelRule::Drop {
+                label: "host".into(),
+                regex: "validator-(1|2)".into(),
+            },
+            RelabelRule::Rename {
+                from: "host".into(),
+                to: "instance".into(),
+            },
+            RelabelRule::Remove {
+                label: "network".into(),
+            },
+            RelabelRule::Set {
+                label: "cluster".into(),
+                value: "mainnet".into(),
+            },
+        ])
+        .unwrap();
+
+        let mut request = WriteRequest {
+            timeseries: vec![
+                timeseries(vec![
+                    ("__name__", "sui_epoch"),
+                    ("host", "validator-0"),
+                    ("network", "mainnet"),
+                ]),
+                // dropped by keep, the regex is anchored
+                timeseries(vec![("__name__", "my_sui_epoch"), ("host", "validator-0")]),
+                // dropped by drop
+                timeseries(vec![("__name__", "sui_epoch"), ("host", "validator-2")]),
+                // dropped by keep, a missing label has the empty value
+                timeseries(vec![("host", "validator-0")]),
+            ],
+            ..Default::default()
+        };
+        relabeler.relabel(&mut request);
+
+        assert_eq!(
+            request.timeseries,
+            vec![timeseries(vec![
+                ("__name__", "sui_epoch"),
+                ("cluster", "mainnet"),
+                ("instance", "validator-0"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn invalid_regex() {
+        Relabeler::new(&[RelabelRule::Drop {
+            label: "host".into(),
+            regex: "(".into(),
+        }])
+        .unwrap_err();
+    }
+}
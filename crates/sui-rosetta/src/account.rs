@@ -7,16 +7,16 @@ use axum_extra::extract::WithRejection;
 use futures::StreamExt;
 
 use sui_sdk::rpc_types::StakeStatus;
-use sui_sdk::{SuiClient, SUI_COIN_TYPE};
+use sui_sdk::SuiClient;
 use sui_types::base_types::SuiAddress;
 use tracing::info;
 
 use crate::errors::Error;
 use crate::types::{
     AccountBalanceRequest, AccountBalanceResponse, AccountCoinsRequest, AccountCoinsResponse,
-    Amount, Coin, SubAccount, SubAccountType, SubBalance,
+    Amount, Coin, CoinMetadataCache, Currency, SubAccount, SubAccountType, SubBalance,
 };
-use crate::{OnlineServerContext, SuiEnv};
+use crate::{OnlineServerContext, SuiEnv, SUI};
 use std::time::Duration;
 
 /// Get an array of all AccountBalances for an AccountIdentifier and the BlockIdentifier
@@ -68,14 +68,11 @@ pub async fn balance(
         }
         Err(Error::RetryExhausted(String::from("retry")))
     } else {
+        let currencies = requested_currencies(&ctx.coin_metadata_cache, request.currencies).await?;
+
         // Get current live balance
         while retry_attempts > 0 {
-            let balances_first = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_first = get_balances(&ctx.client, address, &currencies).await?;
 
             // Get current latest checkpoint
             let checkpoint1 = ctx
@@ -101,12 +98,7 @@ pub async fn balance(
             }
 
             // Get live balance again
-            let balances_second = ctx
-                .client
-                .coin_read_api()
-                .get_balance(address, Some(SUI_COIN_TYPE.to_string()))
-                .await?
-                .total_balance as i128;
+            let balances_second = get_balances(&ctx.client, address, &currencies).await?;
 
             // if those two live balances are equal then that is the current balance for checkpoint2
             if balances_first.eq(&balances_second) {
@@ -116,7 +108,7 @@ pub async fn balance(
                 );
                 return Ok(AccountBalanceResponse {
                     block_identifier: ctx.blocks().create_block_identifier(checkpoint2).await?,
-                    balances: vec![Amount::new(balances_first)],
+                    balances: balances_first,
                 });
             } else {
                 // balances are different so we need to try again.
@@ -131,6 +123,54 @@ pub async fn balance(
     }
 }
 
+/// The currencies a request asked for, defaulting to SUI if it did not ask for any. The symbol
+/// and decimals of each currency must match those of its coin type, so that balances are not
+/// reported under the wrong currency.
+async fn requested_currencies(
+    coin_metadata_cache: &CoinMetadataCache,
+    currencies: Vec<Currency>,
+) -> Result<Vec<Currency>, Error> {
+    if currencies.is_empty() {
+        return Ok(vec![SUI.clone()]);
+    }
+
+    let mut resolved = Vec::with_capacity(currencies.len());
+    for currency in currencies {
+        let expected = coin_metadata_cache
+            .get_currency(&currency.coin_type()?)
+            .await?;
+        if currency.symbol != expected.symbol || currency.decimals != expected.decimals {
+            return Err(Error::InvalidInput(format!(
+                "Currency {} ({} decimals) does not match coin type [{}]: expected {} ({} decimals)",
+                currency.symbol,
+                currency.decimals,
+                currency.coin_type_str(),
+                expected.symbol,
+                expected.decimals
+            )));
+        }
+        resolved.push(expected);
+    }
+    Ok(resolved)
+}
+
+async fn get_balances(
+    client: &SuiClient,
+    address: SuiAddress,
+    currencies: &[Currency],
+) -> Result<Vec<Amount>, Error> {
+    let mut amounts = vec![];
+    for currency in currencies {
+        let balance = client
+            .coin_read_api()
+            .get_balance(address, Some(currency.coin_type_str().to_string()))
+            .await?
+            .total_balance as i128;
+        amounts.push(Amount::new_with_currency(balance, currency.clone()));
+    }
+    Ok(amounts)
+}
+
 async fn get_sub_account_balances(
     account_type: SubAccountType,
     client: &SuiClient,
@@ -201,16 +241,20 @@ pub async fn coins(
     WithRejection(Json(request), _): WithRejection<Json<AccountCoinsRequest>, Error>,
 ) -> Result<AccountCoinsResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let coins = context
-        .client
-        .coin_read_api()
-        .get_coins_stream(
-            request.account_identifier.address,
-            Some(SUI_COIN_TYPE.to_string()),
-        )
-        .map(Coin::from)
-        .collect()
-        .await;
+    let mut coins = vec![];
+    for currency in requested_currencies(&context.coin_metadata_cache, request.currencies).await? {
+        let currency_coins: Vec<_> = context
+            .client
+            .coin_read_api()
+            .get_coins_stream(
+                request.account_identifier.address,
+                Some(currency.coin_type_str().to_string()),
+            )
+            .map(|coin| Coin::new(coin, currency.clone()))
+            .collect()
+            .await;
+        coins.extend(currency_coins);
+    }
 
     Ok(AccountCoinsResponse {
         block_identifier: context.blocks().current_block_identifier().await?,
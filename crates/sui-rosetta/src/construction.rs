@@ -217,12 +217,31 @@ pub async fn metadata(
     gas_price += 100;
 
     // Get amount, objects, for the operation
-    let (total_required_amount, objects) = match &option.internal_operation {
+    let (total_required_amount, objects, currency) = match &option.internal_operation {
         InternalOperation::PaySui { amounts, .. } => {
             let amount = amounts.iter().sum::<u64>();
-            (Some(amount), vec![])
+            (Some(amount), vec![], None)
         }
-        InternalOperation::Stake { amount, .. } => (*amount, vec![]),
+        InternalOperation::PayCoin {
+            amounts, currency, ..
+        } => {
+            let amount = amounts.iter().sum::<u64>();
+            let coins = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    sender,
+                    Some(currency.coin_type_str().to_string()),
+                    amount.into(),
+                    vec![],
+                )
+                .await?;
+            let coin_refs = coins.into_iter().map(|c| c.object_ref()).collect();
+
+            // Only gas is paid in SUI.
+            (Some(0), coin_refs, Some(currency.clone()))
+        }
+        InternalOperation::Stake { amount, .. } => (*amount, vec![], None),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
                 // unstake all
@@ -261,7 +280,7 @@ pub async fn metadata(
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(SuiError::from)?;
 
-            (Some(0), stake_refs)
+            (Some(0), stake_refs, None)
         }
     };
 
@@ -278,6 +297,7 @@ pub async fn metadata(
             gas_price,
             // MAX BUDGET
             budget: 50_000_000_000,
+            currency: currency.clone(),
         })?;
 
     let dry_run = context
@@ -334,6 +354,7 @@ pub async fn metadata(
             total_coin_value,
             gas_price,
             budget,
+            currency,
         },
         suggested_fee: vec![Amount::new(budget as i128)],
     })
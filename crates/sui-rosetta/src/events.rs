@@ -0,0 +1,86 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::Error;
+use crate::types::{
+    BlockEvent, BlockEventType, BlockIdentifier, EventsBlocksRequest, EventsBlocksResponse,
+};
+use crate::{OnlineServerContext, SuiEnv};
+
+/// This module implements the [Rosetta Events API](https://www.rosetta-api.org/docs/EventsApi.html)
+
+/// Maximum number of block events returned in one request.
+const MAX_LIMIT: u64 = 100;
+
+/// Get a range of block events. Each Sui checkpoint is a block, and checkpoints are final, so the
+/// event stream only contains `block_added` events, whose sequence is the checkpoint's sequence
+/// number.
+///
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/EventsApi.html#eventsblocks)
+pub async fn blocks(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<EventsBlocksRequest>, Error>,
+) -> Result<EventsBlocksResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+
+    let limit = match request.limit {
+        Some(0) => return Err(Error::InvalidInput("Limit must be positive".into())),
+        Some(limit) => limit.min(MAX_LIMIT),
+        None => MAX_LIMIT,
+    };
+
+    let max_sequence = context
+        .client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+
+    // Without an offset, return the last `limit` events, up to the tip.
+    let start = request
+        .offset
+        .unwrap_or_else(|| (max_sequence + 1).saturating_sub(limit));
+
+    let mut events = vec![];
+    let mut cursor = start.checked_sub(1).map(Into::into);
+    while start <= max_sequence && (events.len() as u64) < limit {
+        let page = context
+            .client
+            .read_api()
+            .get_checkpoints(cursor, Some((limit - events.len() as u64) as usize), false)
+            .await?;
+
+        events.extend(
+            page.data
+                .into_iter()
+                .filter(|checkpoint| checkpoint.sequence_number <= max_sequence)
+                .map(|checkpoint| BlockEvent {
+                    sequence: checkpoint.sequence_number,
+                    block_identifier: BlockIdentifier {
+                        index: checkpoint.sequence_number,
+                        hash: checkpoint.digest,
+                    },
+                    type_: BlockEventType::BlockAdded,
+                }),
+        );
+
+        // Checkpoints may have been created since `max_sequence` was read, but they are not part
+        // of this response.
+        let reached_max = events
+            .last()
+            .is_some_and(|event| event.sequence >= max_sequence);
+        if reached_max || !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+
+    Ok(EventsBlocksResponse {
+        max_sequence,
+        events,
+    })
+}
@@ -0,0 +1,27 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::errors::Error;
+use crate::types::{MempoolResponse, NetworkRequest};
+use crate::SuiEnv;
+
+/// This module implements the [Rosetta Mempool API](https://www.rosetta-api.org/docs/MempoolApi.html)
+
+/// Get all transaction identifiers in the mempool.
+///
+/// Sui does not have a mempool: Transactions are executed as soon as they are certified, or
+/// sequenced by consensus if they touch shared objects, so this is always empty.
+///
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempool)
+pub async fn mempool(
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<NetworkRequest>, Error>,
+) -> Result<MempoolResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    Ok(MempoolResponse {
+        transaction_identifiers: vec![],
+    })
+}
@@ -1,14 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Not;
 use std::str::FromStr;
 use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
@@ -30,8 +30,8 @@ use sui_types::transaction::TransactionData;
 use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, CoinMetadataCache,
+    Currency, InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
 use crate::Error;
 
@@ -90,6 +90,10 @@ impl Operations {
         self
     }
 
+    pub fn iter(&self) -> std::slice::Iter<'_, Operation> {
+        self.0.iter()
+    }
+
     pub fn type_(&self) -> Option<OperationType> {
         self.0.first().map(|op| op.type_)
     }
@@ -101,6 +105,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -108,11 +113,56 @@ impl Operations {
     }
 
     fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_internal()?;
+        if currency.is_some_and(|currency| !currency.is_sui()) {
+            return Err(Error::InvalidInput(
+                "PaySui can only pay SUI, use PayCoin to pay other currencies.".into(),
+            ));
+        }
+        Ok(InternalOperation::PaySui {
+            sender,
+            recipients,
+            amounts,
+        })
+    }
+
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_internal()?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        if currency.is_sui() {
+            return Err(Error::InvalidInput(
+                "PayCoin cannot pay SUI, use PaySui instead.".into(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
+    /// Extract the sender, recipients and amounts paid from pay operations, along with the
+    /// currency they are paid in, which must be the same for all operations.
+    #[allow(clippy::type_complexity)]
+    fn pay_ops_to_internal(
+        self,
+    ) -> Result<(SuiAddress, Vec<SuiAddress>, Vec<u64>, Option<Currency>), Error> {
         let mut recipients = vec![];
         let mut amounts = vec![];
         let mut sender = None;
+        let mut currency: Option<Currency> = None;
         for op in self {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                match &currency {
+                    Some(currency) if *currency != amount.currency => {
+                        return Err(Error::MalformedOperationError(
+                            "All pay operations should be in the same currency.".into(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => currency = Some(amount.currency.clone()),
+                }
                 if amount.value.is_negative() {
                     sender = Some(account.address)
                 } else {
@@ -128,11 +178,7 @@ impl Operations {
             }
         }
         let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
-        Ok(InternalOperation::PaySui {
-            sender,
-            recipients,
-            amounts,
-        })
+        Ok((sender, recipients, amounts, currency))
     }
 
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
@@ -213,14 +259,19 @@ impl Operations {
         Ok(InternalOperation::WithdrawStake { sender, stake_ids })
     }
 
+    /// Parse the operations of a transaction. `currency` is the currency of the coins the
+    /// transaction takes as inputs, if it is known from its effects: Inputs are not trusted to
+    /// identify the currency themselves, so without it only SUI payments (from the gas coin) are
+    /// recognised.
     fn from_transaction(
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
         status: Option<OperationStatus>,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionBlockKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, status, pt, currency)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
@@ -230,6 +281,7 @@ impl Operations {
         sender: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransactionBlock,
+        currency: Option<Currency>,
     ) -> Result<Vec<Operation>, Error> {
         #[derive(Debug)]
         enum KnownValue {
@@ -261,6 +313,12 @@ impl Operations {
                 // Might not be a SUI coin
                 SuiArgument::Input(_) => return None,
             };
+            split_amounts(inputs, amounts)
+        }
+        fn split_amounts(
+            inputs: &[SuiCallArg],
+            amounts: &[SuiArgument],
+        ) -> Option<Vec<KnownValue>> {
             let amounts = amounts
                 .iter()
                 .map(|amount| {
@@ -366,11 +424,24 @@ impl Operations {
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
+        // The input coin that a PayCoin transaction merges its input coins into and pays from.
+        let mut pay_coin_input = None;
+        let mut paid_from_gas = false;
+        let mut pays_from = |i: u16| *pay_coin_input.get_or_insert(i) == i;
         for command in commands {
             let result = match command {
+                SuiCommand::SplitCoins(SuiArgument::Input(i), amounts) if currency.is_some() => {
+                    pays_from(*i)
+                        .then(|| split_amounts(inputs, amounts))
+                        .flatten()
+                }
                 SuiCommand::SplitCoins(coin, amounts) => {
+                    paid_from_gas = true;
                     split_coins(inputs, &known_results, *coin, amounts)
                 }
+                SuiCommand::MergeCoins(SuiArgument::Input(i), _) if currency.is_some() => {
+                    pays_from(*i).then(Vec::new)
+                }
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
                     inputs,
@@ -408,16 +479,24 @@ impl Operations {
             }
         }
 
+        // Payments from both the gas coin and input coins can't be told apart.
+        if paid_from_gas && pay_coin_input.is_some() {
+            needs_generic = true;
+        }
+
         if !needs_generic && !aggregated_recipients.is_empty() {
             let total_paid: u64 = aggregated_recipients.values().copied().sum();
+            let currency = currency.filter(|_| pay_coin_input.is_some());
+            let pay = |address, amount| match &currency {
+                Some(currency) => Operation::pay_coin(status, address, amount, currency.clone()),
+                None => Operation::pay_sui(status, address, amount),
+            };
             operations.extend(
                 aggregated_recipients
                     .into_iter()
-                    .map(|(recipient, amount)| {
-                        Operation::pay_sui(status, recipient, amount.into())
-                    }),
+                    .map(|(recipient, amount)| pay(recipient, amount.into())),
             );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            operations.push(pay(sender, -(total_paid as i128)));
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -455,57 +534,46 @@ impl Operations {
             && tx.function == WITHDRAW_STAKE_FUN_NAME.as_str()
     }
 
-    fn process_balance_change(
+    async fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
-    ) -> impl Iterator<Item = Operation> {
-        let mut balances = balance_changes
-            .iter()
-            .fold(balances, |mut balances, balance_change| {
-                // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
-                    }
-                }
-                balances
-            });
+        mut balances: HashMap<(SuiAddress, TypeTag), i128>,
+        cache: &CoinMetadataCache,
+    ) -> Result<Vec<Operation>, Error> {
+        for balance_change in balance_changes {
+            // Rosetta only care about address owner
+            if let Owner::AddressOwner(owner) = balance_change.owner {
+                *balances
+                    .entry((owner, balance_change.coin_type.clone()))
+                    .or_default() += balance_change.amount;
+            }
+        }
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
-
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        *balances.entry((gas_owner, GAS::type_tag())).or_default() -= gas_used;
 
-        let gas = if gas_used != 0 {
-            vec![Operation::gas(gas_owner, gas_used)]
-        } else {
-            // Gas can be 0 for system tx
-            vec![]
-        };
-        balance_change.chain(gas)
-    }
-}
+        let mut operations = vec![];
+        for ((addr, coin_type), amount) in balances {
+            if amount != 0 {
+                let currency = cache.get_currency(&coin_type).await?;
+                operations.push(Operation::balance_change(status, addr, amount, currency));
+            }
+        }
 
-impl TryFrom<SuiTransactionBlockData> for Operations {
-    type Error = Error;
-    fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
-        let sender = *data.sender();
-        Ok(Self::new(Self::from_transaction(
-            data.transaction().clone(),
-            sender,
-            None,
-        )?))
+        // Gas can be 0 for system tx
+        if gas_used != 0 {
+            operations.push(Operation::gas(gas_owner, gas_used));
+        }
+        Ok(operations)
     }
-}
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+    /// Parse the operations of an executed transaction, including the balance changes it caused
+    /// in every currency, whose metadata is looked up in `cache`.
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -519,23 +587,43 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             - gas_summary.storage_cost as i128
             - gas_summary.computation_cost as i128;
 
+        let balance_changes = response
+            .balance_changes
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?;
+
+        // The only currency other than SUI whose balances changed, if there is exactly one, is
+        // the currency of any coins paid from the transaction's inputs.
+        let coin_types = balance_changes
+            .iter()
+            .map(|change| &change.coin_type)
+            .filter(|coin_type| **coin_type != GAS::type_tag())
+            .collect::<HashSet<_>>();
+        let currency = match coin_types.into_iter().collect::<Vec<_>>()[..] {
+            [coin_type] => Some(cache.get_currency(coin_type).await?),
+            _ => None,
+        };
+
         let status = Some(effect.into_status().into());
-        let ops: Operations = tx.data.try_into()?;
+        let ops = Operations::new(Self::from_transaction(
+            tx.data.transaction().clone(),
+            sender,
+            None,
+            currency,
+        )?);
         let ops = ops.set_status(status).into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
         // change amount extracted from event to prevent double counting.
-        let mut accounted_balances =
-            ops.as_ref()
-                .iter()
-                .fold(HashMap::new(), |mut balances, op| {
-                    if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
-                        (&op.account, &op.amount, &op.status)
-                    {
-                        *balances.entry(acc.address).or_default() -= amount.value;
-                    }
-                    balances
-                });
+        let mut accounted_balances = HashMap::new();
+        for op in ops.as_ref() {
+            if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
+                (&op.account, &op.amount, &op.status)
+            {
+                *accounted_balances
+                    .entry((acc.address, amount.currency.coin_type()?))
+                    .or_default() -= amount.value;
+            }
+        }
 
         let mut principal_amounts = 0;
         let mut reward_amounts = 0;
@@ -564,8 +652,11 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            let sender_sui = accounted_balances
+                .entry((sender, GAS::type_tag()))
+                .or_default();
+            *sender_sui -= principal_amounts;
+            *sender_sui -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -578,12 +669,12 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            &balance_changes,
             status,
             accounted_balances,
-        );
+            cache,
+        )
+        .await?;
 
         Ok(ops
             .into_iter()
@@ -593,6 +684,19 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
     }
 }
 
+impl TryFrom<SuiTransactionBlockData> for Operations {
+    type Error = Error;
+    fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
+        let sender = *data.sender();
+        Ok(Self::new(Self::from_transaction(
+            data.transaction().clone(),
+            sender,
+            None,
+            None,
+        )?))
+    }
+}
+
 fn is_unstake_event(tag: &StructTag) -> bool {
     tag.address == SUI_SYSTEM_ADDRESS
         && tag.module.as_ident_str() == ident_str!("validator")
@@ -602,6 +706,17 @@ fn is_unstake_event(tag: &StructTag) -> bool {
 impl TryFrom<TransactionData> for Operations {
     type Error = Error;
     fn try_from(data: TransactionData) -> Result<Self, Self::Error> {
+        Self::from_transaction_data(data, None)
+    }
+}
+
+impl Operations {
+    /// Parse the operations of unexecuted transaction data, paying from input coins of
+    /// `currency`, if it is known (see `Operations::from_transaction`).
+    fn from_transaction_data(
+        data: TransactionData,
+        currency: Option<Currency>,
+    ) -> Result<Self, Error> {
         struct NoOpsModuleResolver;
         impl ModuleResolver for NoOpsModuleResolver {
             type Error = Error;
@@ -610,7 +725,14 @@ impl TryFrom<TransactionData> for Operations {
             }
         }
         // Rosetta don't need the call args to be parsed into readable format
-        SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?.try_into()
+        let data = SuiTransactionBlockData::try_from(data, &&mut NoOpsModuleResolver)?;
+        let sender = *data.sender();
+        Ok(Self::new(Self::from_transaction(
+            data.transaction().clone(),
+            sender,
+            None,
+            currency,
+        )?))
     }
 }
 
@@ -698,13 +820,35 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(
+        status: Option<OperationStatus>,
+        addr: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(Amount::new_with_currency(amount, currency)),
             coin_change: None,
             metadata: None,
         }
@@ -0,0 +1,499 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashSet, VecDeque};
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use move_core_types::language_storage::TypeTag;
+
+use sui_json_rpc_types::{
+    Checkpoint, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions, SuiTransactionBlockResponseQuery, TransactionFilter,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+
+use crate::errors::Error;
+use crate::operations::{Operation, Operations};
+use crate::types::{
+    AccountIdentifier, BlockIdentifier, BlockTransaction, CoinIdentifier, OperationStatus,
+    OperationType, Operator, SearchTransactionsRequest, SearchTransactionsResponse, Transaction,
+    TransactionIdentifier,
+};
+use crate::{OnlineServerContext, SuiEnv};
+
+/// This module implements the [Rosetta Search API](https://www.rosetta-api.org/docs/SearchApi.html)
+
+/// Maximum number of transactions returned in one page of search results.
+const MAX_LIMIT: u64 = 100;
+
+/// Maximum number of transactions scanned by one request. Searches that match few transactions
+/// return what they found so far once they have scanned this many, with a `next_offset` to
+/// continue from.
+const MAX_SCANNED: usize = 1_000;
+
+/// Number of checkpoints fetched at a time while scanning.
+const CHECKPOINT_PAGE_SIZE: usize = 10;
+
+/// Maximum number of transactions fetched from the fullnode in one call.
+const TRANSACTION_PAGE_SIZE: usize = 50;
+
+/// Search for transactions matching a set of conditions, most recent first.
+///
+/// Transactions are searched block by block, from `max_block` down, and `offset` is the number of
+/// blocks below `max_block` that have already been searched. A client paging through results with
+/// a fixed `max_block` therefore resumes each page where the last one stopped, and sees a stable
+/// sequence of results. Each request scans at most `MAX_SCANNED` transactions, and sets
+/// `next_offset` while there are blocks left to search, even if it found no matches. Pages end on
+/// block boundaries, so a block with more than `limit` matching transactions is returned whole, and
+/// `total_count` only counts the transactions in this page.
+///
+/// Searches that must match an account or address only go through the transactions sent or
+/// received by that address, as indexed by the fullnode, rather than through every block.
+///
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/SearchApi.html#searchtransactions)
+pub async fn transactions(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<SearchTransactionsRequest>, Error>,
+) -> Result<SearchTransactionsResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+
+    let offset = request.offset.unwrap_or(0);
+    let limit = match request.limit {
+        Some(0) => return Err(Error::InvalidInput("Limit must be positive".into())),
+        Some(limit) => limit.min(MAX_LIMIT) as usize,
+        None => MAX_LIMIT as usize,
+    };
+
+    let latest = context
+        .client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+    let max_block = request.max_block.map_or(latest, |max| max.min(latest));
+
+    let filter = SearchFilter::new(request)?;
+
+    // Only one transaction can match, so there is no need to scan for it.
+    if let (Operator::And, Some(digest)) = (filter.operator, filter.transaction) {
+        let transactions = if offset == 0 {
+            search_transaction(&context, &filter, digest, max_block).await?
+        } else {
+            vec![]
+        };
+
+        return Ok(SearchTransactionsResponse {
+            total_count: transactions.len() as u64,
+            transactions,
+            next_offset: None,
+        });
+    }
+
+    if let Some(address) = filter.indexed_address() {
+        return search_address(&context, &filter, address, max_block, offset, limit).await;
+    }
+
+    let mut transactions = vec![];
+    let mut scanned = 0;
+    let mut checkpoints = VecDeque::new();
+    let mut next = max_block.checked_sub(offset);
+
+    while let Some(sequence_number) = next {
+        if transactions.len() >= limit {
+            break;
+        }
+
+        if checkpoints.is_empty() {
+            // Checkpoints are fetched from the one after the cursor, in descending order.
+            let page = context
+                .client
+                .read_api()
+                .get_checkpoints(
+                    Some((sequence_number + 1).into()),
+                    Some(CHECKPOINT_PAGE_SIZE),
+                    true,
+                )
+                .await?;
+            checkpoints.extend(page.data);
+        }
+
+        let Some(checkpoint) = checkpoints.pop_front() else {
+            break;
+        };
+
+        if scanned > 0 && scanned + checkpoint.transactions.len() > MAX_SCANNED {
+            break;
+        }
+
+        let found = search_checkpoint(&context, &filter, &checkpoint).await?;
+        if !transactions.is_empty() && transactions.len() + found.len() > limit {
+            break;
+        }
+
+        scanned += checkpoint.transactions.len();
+        transactions.extend(found);
+        next = checkpoint.sequence_number.checked_sub(1);
+    }
+
+    Ok(SearchTransactionsResponse {
+        next_offset: next.map(|sequence_number| max_block - sequence_number),
+        total_count: transactions.len() as u64,
+        transactions,
+    })
+}
+
+/// The transactions sent or received by `address` that match `filter`, paged block by block like
+/// a scan of every block would be.
+async fn search_address(
+    context: &OnlineServerContext,
+    filter: &SearchFilter,
+    address: SuiAddress,
+    max_block: u64,
+    offset: u64,
+    limit: usize,
+) -> Result<SearchTransactionsResponse, Error> {
+    let mut transactions = vec![];
+    let mut next_offset = None;
+
+    if let Some(first_block) = max_block.checked_sub(offset) {
+        let mut candidates = AddressTransactions::new(&context.client, address);
+        let mut scanned = 0;
+        let mut block: Option<BlockIdentifier> = None;
+
+        while let Some(tx) = candidates.next().await? {
+            // Skip transactions that have not been included in a checkpoint yet, or that are in
+            // blocks that have already been searched.
+            let Some(checkpoint) = tx.checkpoint.filter(|cp| *cp <= first_block) else {
+                continue;
+            };
+
+            let block_identifier = match block {
+                Some(block) if block.index == checkpoint => block,
+                _ => {
+                    // Pages end on block boundaries.
+                    if let Some(block) = block {
+                        if transactions.len() >= limit || scanned >= MAX_SCANNED {
+                            next_offset = Some(max_block - (block.index - 1));
+                            break;
+                        }
+                    }
+                    let block_identifier =
+                        context.blocks().create_block_identifier(checkpoint).await?;
+                    block = Some(block_identifier);
+                    block_identifier
+                }
+            };
+
+            scanned += 1;
+            if let Some(transaction) = matching_transaction(context, filter, tx).await? {
+                transactions.push(BlockTransaction {
+                    block_identifier,
+                    transaction,
+                });
+            }
+        }
+    }
+
+    Ok(SearchTransactionsResponse {
+        total_count: transactions.len() as u64,
+        transactions,
+        next_offset,
+    })
+}
+
+/// The transaction with `digest`, if it exists, has been included in a block no later than
+/// `max_block`, and matches `filter`.
+async fn search_transaction(
+    context: &OnlineServerContext,
+    filter: &SearchFilter,
+    digest: TransactionDigest,
+    max_block: u64,
+) -> Result<Vec<BlockTransaction>, Error> {
+    // Unlike fetching a single transaction, fetching many does not fail for unknown digests.
+    let transactions = context
+        .client
+        .read_api()
+        .multi_get_transactions_with_options(vec![digest], response_options())
+        .await?;
+
+    let mut found = vec![];
+    for tx in transactions {
+        let Some(checkpoint) = tx.checkpoint.filter(|cp| *cp <= max_block) else {
+            continue;
+        };
+
+        if let Some(transaction) = matching_transaction(context, filter, tx).await? {
+            found.push(BlockTransaction {
+                block_identifier: context.blocks().create_block_identifier(checkpoint).await?,
+                transaction,
+            });
+        }
+    }
+
+    Ok(found)
+}
+
+/// The transactions in `checkpoint` that match `filter`.
+async fn search_checkpoint(
+    context: &OnlineServerContext,
+    filter: &SearchFilter,
+    checkpoint: &Checkpoint,
+) -> Result<Vec<BlockTransaction>, Error> {
+    let block_identifier = BlockIdentifier {
+        index: checkpoint.sequence_number,
+        hash: checkpoint.digest,
+    };
+
+    let mut found = vec![];
+    for digests in checkpoint.transactions.chunks(TRANSACTION_PAGE_SIZE) {
+        let transactions = context
+            .client
+            .read_api()
+            .multi_get_transactions_with_options(digests.to_vec(), response_options())
+            .await?;
+
+        for tx in transactions {
+            if let Some(transaction) = matching_transaction(context, filter, tx).await? {
+                found.push(BlockTransaction {
+                    block_identifier,
+                    transaction,
+                });
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+async fn matching_transaction(
+    context: &OnlineServerContext,
+    filter: &SearchFilter,
+    tx: SuiTransactionBlockResponse,
+) -> Result<Option<Transaction>, Error> {
+    let digest = tx.digest;
+    let success = tx.effects.as_ref().map(|effects| effects.status().is_ok());
+    let operations = Operations::try_from_response(tx, &context.coin_metadata_cache).await?;
+    if !filter.matches(digest, success, &operations) {
+        return Ok(None);
+    }
+
+    Ok(Some(Transaction {
+        transaction_identifier: TransactionIdentifier { hash: digest },
+        operations,
+        related_transactions: vec![],
+        metadata: None,
+    }))
+}
+
+/// The conditions from a search request, combined with the request's operator. Conditions on
+/// operations are met if any of the transaction's operations meets them.
+struct SearchFilter {
+    operator: Operator,
+    transaction: Option<TransactionDigest>,
+    account: Option<AccountIdentifier>,
+    address: Option<SuiAddress>,
+    coin: Option<CoinIdentifier>,
+    currency: Option<TypeTag>,
+    status: Option<OperationStatus>,
+    type_: Option<OperationType>,
+    success: Option<bool>,
+}
+
+impl SearchFilter {
+    fn new(request: SearchTransactionsRequest) -> Result<Self, Error> {
+        Ok(Self {
+            operator: request.operator,
+            transaction: request.transaction_identifier.map(|id| id.hash),
+            account: request.account_identifier,
+            address: request.address,
+            coin: request.coin_identifier,
+            currency: request
+                .currency
+                .map(|currency| currency.coin_type())
+                .transpose()?,
+            status: request.status,
+            type_: request.type_,
+            success: request.success,
+        })
+    }
+
+    /// The address all matching transactions must have sent or received, if any, which the
+    /// fullnode's transaction indices can narrow the search down to.
+    fn indexed_address(&self) -> Option<SuiAddress> {
+        if self.operator != Operator::And {
+            return None;
+        }
+        self.account
+            .as_ref()
+            .map(|account| account.address)
+            .or(self.address)
+    }
+
+    fn matches(
+        &self,
+        digest: TransactionDigest,
+        success: Option<bool>,
+        operations: &Operations,
+    ) -> bool {
+        let any_op = |pred: &dyn Fn(&Operation) -> bool| operations.iter().any(pred);
+
+        let conditions = [
+            self.transaction.map(|tx| tx == digest),
+            self.account
+                .as_ref()
+                .map(|account| any_op(&|op| op.account.as_ref() == Some(account))),
+            self.address.map(|address| {
+                any_op(&|op| op.account.as_ref().is_some_and(|a| a.address == address))
+            }),
+            self.coin.as_ref().map(|coin| {
+                any_op(&|op| {
+                    op.coin_change
+                        .as_ref()
+                        .is_some_and(|change| change.coin_identifier == *coin)
+                })
+            }),
+            self.currency.as_ref().map(|currency| {
+                any_op(&|op| {
+                    op.amount
+                        .as_ref()
+                        .and_then(|amount| amount.currency.coin_type().ok())
+                        .is_some_and(|coin_type| coin_type == *currency)
+                })
+            }),
+            self.status
+                .map(|status| any_op(&|op| op.status == Some(status))),
+            self.type_.map(|type_| any_op(&|op| op.type_ == type_)),
+            self.success.map(|s| success == Some(s)),
+        ];
+
+        // A search without any conditions matches every transaction.
+        let mut conditions = conditions.into_iter().flatten().peekable();
+        if conditions.peek().is_none() {
+            return true;
+        }
+
+        match self.operator {
+            Operator::And => conditions.all(|c| c),
+            Operator::Or => conditions.any(|c| c),
+        }
+    }
+}
+
+/// The transactions sent or received by an address, most recent first, merged from the fullnode's
+/// separate indices in checkpoint order and de-duplicated.
+struct AddressTransactions<'a> {
+    from: TransactionStream<'a>,
+    to: TransactionStream<'a>,
+    /// The checkpoint of the last transaction returned.
+    checkpoint: Option<u64>,
+    /// Transactions already returned from `checkpoint`.
+    seen: HashSet<TransactionDigest>,
+}
+
+impl<'a> AddressTransactions<'a> {
+    fn new(client: &'a SuiClient, address: SuiAddress) -> Self {
+        Self {
+            from: TransactionStream::new(client, TransactionFilter::FromAddress(address)),
+            to: TransactionStream::new(client, TransactionFilter::ToAddress(address)),
+            checkpoint: None,
+            seen: HashSet::new(),
+        }
+    }
+
+    async fn next(&mut self) -> Result<Option<SuiTransactionBlockResponse>, Error> {
+        loop {
+            // Transactions that are not in a checkpoint yet are the most recent.
+            let from_cp = self
+                .from
+                .peek()
+                .await?
+                .map(|tx| tx.checkpoint.unwrap_or(u64::MAX));
+            let to_cp = self
+                .to
+                .peek()
+                .await?
+                .map(|tx| tx.checkpoint.unwrap_or(u64::MAX));
+
+            let next = match (from_cp, to_cp) {
+                (None, None) => return Ok(None),
+                (Some(f), Some(t)) if f >= t => self.from.next().await?,
+                (Some(_), None) => self.from.next().await?,
+                (_, Some(_)) => self.to.next().await?,
+            };
+
+            let Some(tx) = next else {
+                return Ok(None);
+            };
+
+            if self.checkpoint != tx.checkpoint {
+                self.checkpoint = tx.checkpoint;
+                self.seen.clear();
+            }
+
+            if self.seen.insert(tx.digest) {
+                return Ok(Some(tx));
+            }
+        }
+    }
+}
+
+/// Pages through the transactions matching a filter on the fullnode, most recent first.
+struct TransactionStream<'a> {
+    client: &'a SuiClient,
+    query: SuiTransactionBlockResponseQuery,
+    cursor: Option<TransactionDigest>,
+    buffer: VecDeque<SuiTransactionBlockResponse>,
+    exhausted: bool,
+}
+
+impl<'a> TransactionStream<'a> {
+    fn new(client: &'a SuiClient, filter: TransactionFilter) -> Self {
+        Self {
+            client,
+            query: SuiTransactionBlockResponseQuery::new(Some(filter), Some(response_options())),
+            cursor: None,
+            buffer: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    async fn peek(&mut self) -> Result<Option<&SuiTransactionBlockResponse>, Error> {
+        self.fill().await?;
+        Ok(self.buffer.front())
+    }
+
+    async fn next(&mut self) -> Result<Option<SuiTransactionBlockResponse>, Error> {
+        self.fill().await?;
+        Ok(self.buffer.pop_front())
+    }
+
+    async fn fill(&mut self) -> Result<(), Error> {
+        while self.buffer.is_empty() && !self.exhausted {
+            let page = self
+                .client
+                .read_api()
+                .query_transaction_blocks(
+                    self.query.clone(),
+                    self.cursor,
+                    Some(TRANSACTION_PAGE_SIZE),
+                    true,
+                )
+                .await?;
+            self.cursor = page.next_cursor;
+            self.exhausted = !page.has_next_page;
+            self.buffer.extend(page.data);
+        }
+        Ok(())
+    }
+}
+
+fn response_options() -> SuiTransactionBlockResponseOptions {
+    SuiTransactionBlockResponseOptions::new()
+        .with_input()
+        .with_effects()
+        .with_balance_changes()
+        .with_events()
+}
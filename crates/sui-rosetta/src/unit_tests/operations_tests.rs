@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_core_types::annotated_value::MoveTypeLayout;
+use serde_json::json;
 use sui_json_rpc_types::SuiCallArg;
-use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
 use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, OperationType};
+use crate::SUI;
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -44,12 +46,191 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
         total_coin_value: 0,
         gas_price,
         budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        currency: None,
     };
     let parsed_data = ops.into_internal()?.try_into_data(metadata)?;
     assert_eq!(data, parsed_data);
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pay_coin_operation_data_parsing() -> Result<(), anyhow::Error> {
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let coins = (0..3)
+        .map(|_| {
+            (
+                ObjectID::random(),
+                SequenceNumber::new(),
+                ObjectDigest::random(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = json!({
+        "symbol": "TEST",
+        "decimals": 6,
+        "metadata": { "coin_type": "0x1234::test::TEST" }
+    });
+
+    let ops: Operations = serde_json::from_value(json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "PayCoin",
+            "account": { "address": recipient.to_string() },
+            "amount": { "value": "10000", "currency": currency }
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "type": "PayCoin",
+            "account": { "address": sender.to_string() },
+            "amount": { "value": "-10000", "currency": currency }
+        }
+    ]))?;
+
+    let gas_price = 10;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins,
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        currency: Some(serde_json::from_value(currency)?),
+    };
+
+    let data = ops.clone().into_internal()?.try_into_data(metadata)?;
+
+    // The currency of the coins paid is only known from the effects of the transaction.
+    let parsed = Operations::try_from(data.clone())?;
+    assert!(parsed
+        .into_iter()
+        .all(|op| op.type_ == OperationType::ProgrammableTransaction));
+
+    let currency = ops
+        .clone()
+        .into_iter()
+        .next()
+        .unwrap()
+        .amount
+        .unwrap()
+        .currency;
+    assert_eq!(
+        ops,
+        Operations::from_transaction_data(data, Some(currency))?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pay_coin_requires_metadata_for_its_currency() {
+    let sender = SuiAddress::random_for_testing_only();
+    let currency = json!({
+        "symbol": "TEST",
+        "decimals": 6,
+        "metadata": { "coin_type": "0x1234::test::TEST" }
+    });
+    let ops: Operations = serde_json::from_value(json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "PayCoin",
+            "account": { "address": SuiAddress::random_for_testing_only().to_string() },
+            "amount": { "value": "10000", "currency": currency }
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "type": "PayCoin",
+            "account": { "address": sender.to_string() },
+            "amount": { "value": "-10000", "currency": currency }
+        }
+    ]))
+    .unwrap();
+
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![random_object_ref()],
+        objects: vec![random_object_ref()],
+        total_coin_value: 0,
+        gas_price: 10,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * 10,
+        // Coins were selected for SUI, not the currency being paid.
+        currency: None,
+    };
+
+    assert!(ops
+        .into_internal()
+        .unwrap()
+        .try_into_data(metadata)
+        .is_err());
+}
+
+#[test]
+fn test_inputs_do_not_determine_currency() {
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = json!({
+        "symbol": "TEST",
+        "decimals": 6,
+        "metadata": { "coin_type": "0x1234::test::TEST" }
+    });
+
+    // A SUI payment, with an unused input claiming to be the currency of the payment.
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay_sui(vec![recipient], vec![10000]).unwrap();
+        builder.pure(currency.to_string()).unwrap();
+        builder.finish()
+    };
+    let gas_price = 10;
+    let data = TransactionData::new_programmable(
+        sender,
+        vec![random_object_ref()],
+        pt,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        gas_price,
+    );
+
+    let ops = Operations::try_from(data).unwrap();
+    assert!(ops.into_iter().all(|op| {
+        op.type_ == OperationType::PaySui && op.amount.is_some_and(|a| a.currency == *SUI)
+    }));
+}
+
+fn random_object_ref() -> ObjectRef {
+    (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    )
+}
+
+#[test]
+fn test_pay_sui_rejects_other_currencies() {
+    let sender = SuiAddress::random_for_testing_only();
+    let ops: Operations = serde_json::from_value(json!([{
+        "operation_identifier": { "index": 0 },
+        "type": "PaySui",
+        "account": { "address": sender.to_string() },
+        "amount": {
+            "value": "-10000",
+            "currency": {
+                "symbol": "TEST",
+                "decimals": 6,
+                "metadata": { "coin_type": "0x1234::test::TEST" }
+            }
+        }
+    }]))
+    .unwrap();
+
+    assert!(ops.into_internal().is_err());
+}
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
@@ -10,11 +10,13 @@ use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_keys::keystore::AccountKeystore;
 use sui_rosetta::operations::Operations;
 use sui_rosetta::types::{
-    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, NetworkIdentifier,
-    SubAccount, SubAccountType, SuiEnv,
+    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, BlockEventType,
+    CoinMetadataCache, EventsBlocksResponse, MempoolResponse, NetworkIdentifier,
+    SearchTransactionsResponse, SubAccount, SubAccountType, SuiEnv,
 };
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
+use sui_types::base_types::TransactionDigest;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::utils::to_sender_signed_transaction;
 use test_cluster::TestClusterBuilder;
@@ -174,7 +176,9 @@ async fn test_stake() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -234,7 +238,9 @@ async fn test_stake_all() {
         tx.effects.as_ref().unwrap().status()
     );
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -348,7 +354,9 @@ async fn test_withdraw_stake() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -417,7 +425,9 @@ async fn test_pay_sui() {
     );
     println!("Sui TX: {tx:?}");
 
-    let ops2 = Operations::try_from(tx).unwrap();
+    let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+        .await
+        .unwrap();
     assert!(
         ops2.contains(&ops),
         "Operation mismatch. expecting:{}, got:{}",
@@ -426,6 +436,229 @@ async fn test_pay_sui() {
     );
 }
 
+#[tokio::test]
+async fn test_search_transactions() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    let ops = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PaySui",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "1000000000" , "currency": { "symbol": "SUI", "decimals": 9}}
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PaySui",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-1000000000" , "currency": { "symbol": "SUI", "decimals": 9}}
+        }]
+    ))
+    .unwrap();
+
+    let response = rosetta_client.rosetta_flow(&ops, keystore).await;
+    let digest = response.transaction_identifier.hash;
+
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "account_identifier": { "address": recipient.to_string() },
+        "type": "PaySui",
+        "limit": 10
+    });
+
+    // Wait for the transaction to be included in a checkpoint.
+    let mut found = None;
+    for _ in 0..20 {
+        let response: SearchTransactionsResponse = rosetta_client
+            .call(RosettaEndpoint::SearchTransactions, &request)
+            .await;
+        found = response
+            .transactions
+            .into_iter()
+            .find(|tx| tx.transaction.transaction_identifier.hash == digest);
+        if found.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let found = found.expect("Transaction not found by search");
+    assert!(
+        found.transaction.operations.contains(&ops),
+        "Operation mismatch. expecting:{}, got:{}",
+        serde_json::to_string(&ops).unwrap(),
+        serde_json::to_string(&found.transaction.operations).unwrap()
+    );
+
+    // The transaction can be looked up by its digest.
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "transaction_identifier": { "hash": digest },
+    });
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &request)
+        .await;
+    assert_eq!(response.transactions.len(), 1);
+    assert_eq!(
+        response.transactions[0].block_identifier.index,
+        found.block_identifier.index
+    );
+    assert_eq!(response.next_offset, None);
+
+    // Searching for an unknown transaction finds nothing.
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "transaction_identifier": { "hash": TransactionDigest::random() },
+    });
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &request)
+        .await;
+    assert!(response.transactions.is_empty());
+    assert_eq!(response.next_offset, None);
+
+    // Paging through every transaction up to the one found, one block at a time, ends at genesis.
+    let max_block = found.block_identifier.index;
+    let mut offset = Some(0);
+    let mut blocks = vec![];
+    while let Some(o) = offset {
+        let request = json!({
+            "network_identifier": { "blockchain": "sui", "network": "localnet" },
+            "max_block": max_block,
+            "offset": o,
+            "limit": 1,
+        });
+        let response: SearchTransactionsResponse = rosetta_client
+            .call(RosettaEndpoint::SearchTransactions, &request)
+            .await;
+        blocks.extend(
+            response
+                .transactions
+                .iter()
+                .map(|tx| tx.block_identifier.index),
+        );
+        offset = response.next_offset;
+    }
+    assert_eq!(blocks.first(), Some(&max_block));
+    assert_eq!(blocks.last(), Some(&0));
+    assert!(blocks.windows(2).all(|w| w[0] >= w[1]));
+
+    // Paging through the recipient's transactions, one block at a time, also finds it.
+    let mut offset = Some(0);
+    let mut digests = vec![];
+    let mut blocks = vec![];
+    while let Some(o) = offset {
+        let request = json!({
+            "network_identifier": { "blockchain": "sui", "network": "localnet" },
+            "account_identifier": { "address": recipient.to_string() },
+            "max_block": max_block,
+            "offset": o,
+            "limit": 1,
+        });
+        let response: SearchTransactionsResponse = rosetta_client
+            .call(RosettaEndpoint::SearchTransactions, &request)
+            .await;
+        for tx in response.transactions {
+            digests.push(tx.transaction.transaction_identifier.hash);
+            blocks.push(tx.block_identifier.index);
+        }
+        offset = response.next_offset;
+    }
+    assert!(digests.contains(&digest));
+    assert_eq!(blocks.first(), Some(&max_block));
+    assert!(blocks.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[tokio::test]
+async fn test_events_blocks() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = test_cluster.wallet.get_client().await.unwrap();
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    // Wait for a few checkpoints to be created.
+    for _ in 0..20 {
+        let latest = client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .unwrap();
+        if latest >= 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "offset": 1,
+        "limit": 2,
+    });
+    let response: EventsBlocksResponse = rosetta_client
+        .call(RosettaEndpoint::EventsBlocks, &request)
+        .await;
+    assert!(response.max_sequence >= 3);
+    assert_eq!(
+        response
+            .events
+            .iter()
+            .map(|event| (event.sequence, event.block_identifier.index, event.type_))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, 1, BlockEventType::BlockAdded),
+            (2, 2, BlockEventType::BlockAdded),
+        ]
+    );
+
+    // Without an offset, the latest events are returned.
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "limit": 2,
+    });
+    let response: EventsBlocksResponse = rosetta_client
+        .call(RosettaEndpoint::EventsBlocks, &request)
+        .await;
+    let sequences = response
+        .events
+        .iter()
+        .map(|event| event.sequence)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sequences,
+        vec![response.max_sequence - 1, response.max_sequence]
+    );
+
+    // Past the tip, there are no events.
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+        "offset": response.max_sequence + 100,
+    });
+    let response: EventsBlocksResponse = rosetta_client
+        .call(RosettaEndpoint::EventsBlocks, &request)
+        .await;
+    assert!(response.events.is_empty());
+}
+
+#[tokio::test]
+async fn test_mempool() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = test_cluster.wallet.get_client().await.unwrap();
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    let request = json!({
+        "network_identifier": { "blockchain": "sui", "network": "localnet" },
+    });
+    let response: MempoolResponse = rosetta_client
+        .call(RosettaEndpoint::Mempool, &request)
+        .await;
+    assert!(response.transaction_identifiers.is_empty());
+}
+
 #[tokio::test]
 async fn test_pay_sui_multiple_times() {
     let test_cluster = TestClusterBuilder::new()
@@ -475,7 +708,9 @@ async fn test_pay_sui_multiple_times() {
             tx.effects.as_ref().unwrap().status()
         );
 
-        let ops2 = Operations::try_from(tx).unwrap();
+        let ops2 = Operations::try_from_response(tx, &CoinMetadataCache::new(client.clone()))
+            .await
+            .unwrap();
         assert!(
             ops2.contains(&ops),
             "Operation mismatch. expecting:{}, got:{}",
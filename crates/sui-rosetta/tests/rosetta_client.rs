@@ -209,6 +209,9 @@ pub enum RosettaEndpoint {
     Submit,
     Metadata,
     Status,
+    EventsBlocks,
+    Mempool,
+    SearchTransactions,
 }
 
 impl RosettaEndpoint {
@@ -229,6 +232,9 @@ impl RosettaEndpoint {
             RosettaEndpoint::Submit => "construction/submit",
             RosettaEndpoint::Metadata => "construction/metadata",
             RosettaEndpoint::Status => "network/status",
+            RosettaEndpoint::EventsBlocks => "events/blocks",
+            RosettaEndpoint::Mempool => "mempool",
+            RosettaEndpoint::SearchTransactions => "search/transactions",
         }
     }
 
@@ -248,7 +254,10 @@ impl RosettaEndpoint {
             | RosettaEndpoint::Transaction
             | RosettaEndpoint::Submit
             | RosettaEndpoint::Metadata
-            | RosettaEndpoint::Status => true,
+            | RosettaEndpoint::Status
+            | RosettaEndpoint::EventsBlocks
+            | RosettaEndpoint::Mempool
+            | RosettaEndpoint::SearchTransactions => true,
         }
     }
 }
@@ -24,7 +24,7 @@ use sui_json_rpc_types::{
     SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
     SuiPastObjectResponse, SuiTransactionBlockEffects, SuiTransactionBlockResponse,
     SuiTransactionBlockResponseOptions, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    TransactionFilter, ValidatorApys,
 };
 use sui_json_rpc_types::{CheckpointPage, SuiLoadedChildObjectsResponse};
 use sui_types::balance::Supply;
@@ -1201,4 +1201,10 @@ impl GovernanceApi {
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
         Ok(*self.api.http.get_reference_gas_price().await?)
     }
+
+    /// Return the [ValidatorApys] of the active validators for the current epoch, or an error
+    /// upon failure.
+    pub async fn get_validators_apy(&self) -> SuiRpcResult<ValidatorApys> {
+        Ok(self.api.http.get_validators_apy().await?)
+    }
 }
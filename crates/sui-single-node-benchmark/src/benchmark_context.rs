@@ -4,34 +4,43 @@
 use crate::command::Component;
 use crate::mock_account::{batch_create_account_and_gas, Account};
 use crate::mock_storage::InMemoryObjectStore;
+use crate::replay::ReplayData;
 use crate::single_node::SingleValidator;
 use crate::tx_generator::{RootObjectCreateTxGenerator, TxGenerator};
 use crate::workload::Workload;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
-use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::effects::{InputSharedObject, TransactionEffects, TransactionEffectsAPI};
+use sui_types::error::SuiResult;
 use sui_types::messages_grpc::HandleTransactionResponse;
 use sui_types::mock_checkpoint_builder::ValidatorKeypairProvider;
-use sui_types::transaction::{CertifiedTransaction, SignedTransaction, Transaction};
-use tracing::info;
+use sui_types::storage::ObjectStore;
+use sui_types::transaction::{
+    CertifiedTransaction, InputObjectKind, SignedTransaction, Transaction, TransactionDataAPI,
+};
+use tracing::{info, warn};
 
 pub struct BenchmarkContext {
     validator: SingleValidator,
     user_accounts: BTreeMap<SuiAddress, Account>,
     admin_account: Account,
     benchmark_component: Component,
+    /// Set when replaying real transactions, whose objects and original effects it holds.
+    replay: Option<ReplayData>,
 }
 
 impl BenchmarkContext {
     pub(crate) async fn new(
-        workload: Workload,
+        workload: &Workload,
         benchmark_component: Component,
         checkpoint_size: usize,
+        reference_gas_price: Option<u64>,
     ) -> Self {
         // Increase by 2 so that we could generate one extra sample transaction before benchmarking.
         // as well as reserve 1 account for package publishing.
@@ -49,14 +58,20 @@ impl BenchmarkContext {
         let (_, admin_account) = user_accounts.pop_last().unwrap();
 
         info!("Initializing validator");
-        let validator =
-            SingleValidator::new(&genesis_gas_objects, benchmark_component, checkpoint_size).await;
+        let validator = SingleValidator::new(
+            &genesis_gas_objects,
+            benchmark_component,
+            checkpoint_size,
+            reference_gas_price,
+        )
+        .await;
 
         Self {
             validator,
             user_accounts,
             admin_account,
             benchmark_component,
+            replay: None,
         }
     }
 
@@ -127,6 +142,113 @@ impl BenchmarkContext {
         root_objects
     }
 
+    /// Loads the objects of a replay workload into the validator and assigns each transaction the
+    /// shared object versions it originally read, so that transactions can be executed
+    /// independently of each other and without consensus. Returns the transactions to replay.
+    pub(crate) async fn prepare_replay(&mut self, mut replay: ReplayData) -> Vec<Transaction> {
+        let validator = self.validator.get_validator().clone();
+        let store = &validator.database;
+
+        // Objects that already exist at genesis, i.e. system packages and system objects, are
+        // kept as created by the benchmark's genesis, which the validator's committee and epoch
+        // are derived from.
+        let mut genesis_object_ids = HashSet::new();
+        replay.objects.retain(|object| {
+            if genesis_object_ids.contains(&object.id())
+                || store.get_object(&object.id()).unwrap().is_some()
+            {
+                genesis_object_ids.insert(object.id());
+                return false;
+            }
+            true
+        });
+        info!("Inserting {} objects", replay.objects.len());
+        store
+            .insert_objects_unsafe_for_testing_only(&replay.objects)
+            .await
+            .unwrap();
+
+        // The validator components sequence shared object transactions through the mock
+        // consensus, which assigns versions of its own.
+        let skip_shared_object_transactions = matches!(
+            self.benchmark_component,
+            Component::ValidatorWithoutConsensus | Component::ValidatorWithFakeConsensus
+        );
+        let mut transactions = vec![];
+        let mut num_missing_inputs = 0;
+        let mut num_shared_object_transactions = 0;
+        for tx in std::mem::take(&mut replay.transactions) {
+            let input_shared_objects = replay.effects[tx.digest()].input_shared_objects();
+            if skip_shared_object_transactions && !input_shared_objects.is_empty() {
+                num_shared_object_transactions += 1;
+                continue;
+            }
+
+            let shared_object_versions: Option<Vec<_>> = input_shared_objects
+                .into_iter()
+                .map(|shared_object| match shared_object {
+                    // System objects are read at their genesis version.
+                    InputSharedObject::Mutate((id, _, _))
+                    | InputSharedObject::ReadOnly((id, _, _))
+                        if genesis_object_ids.contains(&id) =>
+                    {
+                        Some((id, store.get_object(&id).unwrap()?.version()))
+                    }
+                    InputSharedObject::Mutate((id, version, _))
+                    | InputSharedObject::ReadOnly((id, version, _)) => Some((id, version)),
+                    // Deleted shared objects can't be recreated.
+                    InputSharedObject::ReadDeleted(..) | InputSharedObject::MutateDeleted(..) => {
+                        None
+                    }
+                })
+                .collect();
+            let owned_inputs_available =
+                tx.transaction_data()
+                    .input_objects()
+                    .is_ok_and(|input_objects| {
+                        input_objects.iter().all(|kind| match kind {
+                            InputObjectKind::MovePackage(id) => {
+                                store.get_object(id).unwrap().is_some()
+                            }
+                            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                                store.get_object_by_key(id, *version).unwrap().is_some()
+                            }
+                            InputObjectKind::SharedMoveObject { .. } => true,
+                        })
+                    });
+            let Some(shared_object_versions) =
+                shared_object_versions.filter(|_| owned_inputs_available)
+            else {
+                num_missing_inputs += 1;
+                continue;
+            };
+
+            if !shared_object_versions.is_empty() {
+                self.validator
+                    .get_epoch_store()
+                    .set_shared_object_versions_for_testing(tx.digest(), &shared_object_versions)
+                    .unwrap();
+            }
+            transactions.push(tx);
+        }
+
+        info!(
+            "Replaying {} transactions, skipped {} whose inputs are not available",
+            transactions.len(),
+            num_missing_inputs
+        );
+        if num_shared_object_transactions > 0 {
+            info!(
+                "Skipped {} transactions with shared objects, which can't be replayed through \
+                the validator's consensus path",
+                num_shared_object_transactions
+            );
+        }
+        assert!(!transactions.is_empty(), "No transaction to replay");
+        self.replay = Some(replay);
+        transactions
+    }
+
     pub(crate) async fn generate_transactions(
         &self,
         tx_generator: Arc<dyn TxGenerator>,
@@ -172,11 +294,13 @@ impl BenchmarkContext {
 
     pub(crate) async fn benchmark_transaction_execution(&self, transactions: Vec<Transaction>) {
         let mut transactions = self.certify_transactions(transactions).await;
-        self.execute_sample_transaction(transactions.pop().unwrap().into_unsigned())
-            .await;
+        if self.replay.is_none() {
+            self.execute_sample_transaction(transactions.pop().unwrap().into_unsigned())
+                .await;
+        }
 
         let tx_count = transactions.len();
-        let start_time = std::time::Instant::now();
+        let start_time = Instant::now();
         info!(
             "Started executing {} transactions. You can now attach a profiler",
             transactions.len()
@@ -187,13 +311,16 @@ impl BenchmarkContext {
             .map(|tx| {
                 let validator = self.validator();
                 let component = self.benchmark_component;
-                tokio::spawn(async move { validator.execute_certificate(tx, component).await })
+                tokio::spawn(async move {
+                    let start_time = Instant::now();
+                    let result = validator.execute_certificate(tx, component).await;
+                    (start_time.elapsed(), result)
+                })
             })
             .collect();
         let results: Vec<_> = tasks.collect().await;
-        results.into_iter().for_each(|r| {
-            r.unwrap();
-        });
+        let (latencies, results): (Vec<_>, Vec<_>) =
+            results.into_iter().map(|r| r.unwrap()).unzip();
 
         let elapsed = start_time.elapsed().as_millis() as f64 / 1000f64;
         info!(
@@ -201,18 +328,22 @@ impl BenchmarkContext {
             elapsed,
             tx_count as f64 / elapsed
         );
+        log_latency_percentiles(latencies);
+        self.check_execution_results(&results);
     }
 
     pub(crate) async fn benchmark_transaction_execution_in_memory(
         &self,
         mut transactions: Vec<Transaction>,
     ) {
-        self.execute_sample_transaction(transactions.pop().unwrap())
-            .await;
+        if self.replay.is_none() {
+            self.execute_sample_transaction(transactions.pop().unwrap())
+                .await;
+        }
 
         let tx_count = transactions.len();
-        let in_memory_store = self.validator.create_in_memory_store();
-        let start_time = std::time::Instant::now();
+        let in_memory_store = self.create_in_memory_store();
+        let start_time = Instant::now();
         info!(
             "Started executing {} transactions. You can now attach a profiler",
             transactions.len()
@@ -224,16 +355,17 @@ impl BenchmarkContext {
                 let validator = self.validator();
                 let in_memory_store = in_memory_store.clone();
                 tokio::spawn(async move {
-                    validator
+                    let start_time = Instant::now();
+                    let result = validator
                         .execute_transaction_in_memory(in_memory_store, tx)
-                        .await
+                        .await;
+                    (start_time.elapsed(), result)
                 })
             })
             .collect();
         let results: Vec<_> = tasks.collect().await;
-        results.into_iter().for_each(|r| {
-            r.unwrap();
-        });
+        let (latencies, results): (Vec<_>, Vec<_>) =
+            results.into_iter().map(|r| r.unwrap()).unzip();
 
         let elapsed = start_time.elapsed().as_millis() as f64 / 1000f64;
         info!(
@@ -242,6 +374,8 @@ impl BenchmarkContext {
             tx_count as f64 / elapsed,
             in_memory_store.get_num_object_reads() as f64 / tx_count as f64
         );
+        log_latency_percentiles(latencies);
+        self.check_execution_results(&results);
     }
 
     /// Print out a sample transaction and its effects so that we can get a rough idea
@@ -256,20 +390,78 @@ impl BenchmarkContext {
         assert!(effects.status().is_ok());
     }
 
+    /// Synthetic transactions must all execute successfully. Replayed transactions can be
+    /// rejected, e.g. if they read dynamic fields that are not part of the replayed checkpoints,
+    /// or have a different outcome than originally, since they run on this binary's protocol
+    /// version, so they are only counted.
+    fn check_execution_results(&self, results: &[SuiResult<TransactionEffects>]) {
+        let Some(replay) = &self.replay else {
+            for result in results {
+                assert!(result.as_ref().unwrap().status().is_ok());
+            }
+            return;
+        };
+
+        let mut num_rejected = 0;
+        let mut num_failed = 0;
+        let mut num_changed_status = 0;
+        for result in results {
+            match result {
+                Ok(effects) => {
+                    if !effects.status().is_ok() {
+                        num_failed += 1;
+                    }
+                    if effects.status() != replay.effects[effects.transaction_digest()].status() {
+                        num_changed_status += 1;
+                    }
+                }
+                Err(e) => {
+                    if num_rejected == 0 {
+                        warn!("Sample rejected transaction: {e}");
+                    }
+                    num_rejected += 1;
+                }
+            }
+        }
+        info!(
+            "Replayed {} transactions: {} rejected, {} failed during execution, {} with a different \
+            status than when originally executed",
+            results.len(),
+            num_rejected,
+            num_failed,
+            num_changed_status
+        );
+    }
+
     /// Benchmark parallel signing a vector of transactions and measure the TPS.
     pub(crate) async fn benchmark_transaction_signing(&self, transactions: Vec<Transaction>) {
         let sample_transaction = &transactions[0];
         info!("Sample transaction: {:?}", sample_transaction.data());
 
         let tx_count = transactions.len();
-        let start_time = std::time::Instant::now();
-        self.validator_sign_transactions(transactions).await;
+        let start_time = Instant::now();
+        let (latencies, results): (Vec<_>, Vec<_>) = self
+            .validator_sign_transactions(transactions)
+            .await
+            .into_iter()
+            .unzip();
         let elapsed = start_time.elapsed().as_millis() as f64 / 1000f64;
         info!(
             "Transaction signing finished in {}s, TPS={}.",
             elapsed,
             tx_count as f64 / elapsed,
         );
+        log_latency_percentiles(latencies);
+
+        let num_rejected = results.iter().filter(|result| result.is_err()).count();
+        if self.replay.is_some() {
+            info!(
+                "Signed {} transactions: {} rejected",
+                tx_count, num_rejected
+            );
+        } else {
+            assert_eq!(num_rejected, 0);
+        }
     }
 
     pub(crate) async fn benchmark_checkpoint_executor(
@@ -277,18 +469,25 @@ impl BenchmarkContext {
         mut transactions: Vec<Transaction>,
         checkpoint_size: usize,
     ) {
-        self.execute_sample_transaction(transactions.pop().unwrap())
-            .await;
+        if self.replay.is_none() {
+            self.execute_sample_transaction(transactions.pop().unwrap())
+                .await;
+        }
 
         info!("Executing all transactions to generate effects");
-        let tx_count = transactions.len();
-        let in_memory_store = self.validator.create_in_memory_store();
-        let effects: BTreeMap<_, _> = self
+        let in_memory_store = self.create_in_memory_store();
+        let results = self
             .execute_transactions_in_memory(in_memory_store.clone(), transactions.clone())
-            .await
+            .await;
+        self.check_execution_results(&results);
+        // Rejected transactions have no effects, and can't be part of a checkpoint.
+        let effects: BTreeMap<_, _> = results
             .into_iter()
+            .flatten()
             .map(|e| (*e.transaction_digest(), e))
             .collect();
+        transactions.retain(|tx| effects.contains_key(tx.digest()));
+        let tx_count = transactions.len();
 
         info!("Building checkpoints");
         let validator = self.validator();
@@ -317,7 +516,7 @@ impl BenchmarkContext {
                 .unwrap();
             checkpoint_sender.send(checkpoint).unwrap();
         }
-        let start_time = std::time::Instant::now();
+        let start_time = Instant::now();
         info!("Starting checkpoint execution. You can now attach a profiler");
         checkpoint_executor
             .run_epoch(validator.get_epoch_store().clone())
@@ -349,7 +548,7 @@ impl BenchmarkContext {
         &self,
         store: InMemoryObjectStore,
         transactions: Vec<Transaction>,
-    ) -> Vec<TransactionEffects> {
+    ) -> Vec<SuiResult<TransactionEffects>> {
         let tasks: FuturesUnordered<_> = transactions
             .into_iter()
             .map(|tx| {
@@ -381,10 +580,22 @@ impl BenchmarkContext {
             account.gas_objects = Arc::new(refreshed_gas_objects);
         }
     }
+
+    /// Creates an in-memory store with the validator's objects, including all the versions read
+    /// by replayed transactions.
+    fn create_in_memory_store(&self) -> InMemoryObjectStore {
+        let replay_objects = self
+            .replay
+            .as_ref()
+            .map_or(&[][..], |replay| &replay.objects[..]);
+        self.validator.create_in_memory_store(replay_objects)
+    }
+
+    /// Signs the transactions in parallel, returning the result and latency of each of them.
     pub(crate) async fn validator_sign_transactions(
         &self,
         transactions: Vec<Transaction>,
-    ) -> Vec<HandleTransactionResponse> {
+    ) -> Vec<(Duration, SuiResult<HandleTransactionResponse>)> {
         info!(
             "Started signing {} transactions. You can now attach a profiler",
             transactions.len(),
@@ -393,10 +604,32 @@ impl BenchmarkContext {
             .into_iter()
             .map(|tx| {
                 let validator = self.validator();
-                tokio::spawn(async move { validator.sign_transaction(tx).await })
+                tokio::spawn(async move {
+                    let start_time = Instant::now();
+                    let result = validator.sign_transaction(tx).await;
+                    (start_time.elapsed(), result)
+                })
             })
             .collect();
         let results: Vec<_> = tasks.collect().await;
         results.into_iter().map(|r| r.unwrap()).collect()
     }
 }
+
+/// Logs percentiles of the per-transaction latencies. Latencies are measured from when the task of
+/// a transaction starts running, so they include the time spent waiting on other transactions,
+/// e.g. for locks or in the transaction manager.
+fn log_latency_percentiles(mut latencies: Vec<Duration>) {
+    if latencies.is_empty() {
+        return;
+    }
+    latencies.sort_unstable();
+    let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+    info!(
+        "Latency: p50={:?}, p90={:?}, p99={:?}, max={:?}",
+        percentile(50),
+        percentile(90),
+        percentile(99),
+        latencies[latencies.len() - 1]
+    );
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 use strum_macros::EnumIter;
 
 #[derive(Parser)]
@@ -66,7 +67,7 @@ pub enum Component {
     CheckpointExecutor,
 }
 
-#[derive(Subcommand, Clone, Copy)]
+#[derive(Subcommand, Clone)]
 pub enum WorkloadKind {
     NoMove,
     Move {
@@ -86,4 +87,21 @@ pub enum WorkloadKind {
         )]
         computation: u8,
     },
+    /// Replay the user transactions of a range of real checkpoints, read from a local data
+    /// ingestion directory (`{seq}.chk` files). Each transaction is executed against the object
+    /// versions it originally read, including shared objects. Packages and immutable objects are
+    /// taken from all checkpoints of the directory up to the end of the range, so the directory
+    /// should start early enough to contain the packages the transactions call; transactions
+    /// whose inputs are not available are skipped. `--tx-count` is ignored.
+    Replay {
+        #[arg(
+            long,
+            help = "Data ingestion directory containing the checkpoints to replay"
+        )]
+        path: PathBuf,
+        #[arg(long, help = "First checkpoint to replay")]
+        start_checkpoint: u64,
+        #[arg(long, help = "Last checkpoint to replay, inclusive")]
+        end_checkpoint: u64,
+    },
 }
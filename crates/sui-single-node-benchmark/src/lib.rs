@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::benchmark_context::BenchmarkContext;
-use crate::command::Component;
+use crate::command::{Component, WorkloadKind};
+use crate::replay::ReplayData;
 use crate::workload::Workload;
 
 pub(crate) mod benchmark_context;
@@ -10,6 +11,7 @@ pub mod command;
 pub(crate) mod mock_account;
 pub(crate) mod mock_consensus;
 pub(crate) mod mock_storage;
+pub(crate) mod replay;
 pub(crate) mod single_node;
 pub(crate) mod tx_generator;
 pub mod workload;
@@ -19,9 +21,24 @@ pub mod workload;
 /// \checkpoint_size represents both the size of a consensus commit, and size of a checkpoint
 /// if we are benchmarking the checkpoint.
 pub async fn run_benchmark(workload: Workload, component: Component, checkpoint_size: usize) {
-    let mut ctx = BenchmarkContext::new(workload, component, checkpoint_size).await;
-    let tx_generator = workload.create_tx_generator(&mut ctx).await;
-    let transactions = ctx.generate_transactions(tx_generator).await;
+    let replay = match &workload.workload_kind {
+        WorkloadKind::Replay {
+            path,
+            start_checkpoint,
+            end_checkpoint,
+        } => Some(ReplayData::load(path, *start_checkpoint, *end_checkpoint)),
+        _ => None,
+    };
+    let reference_gas_price = replay.as_ref().and_then(ReplayData::reference_gas_price);
+    let mut ctx =
+        BenchmarkContext::new(&workload, component, checkpoint_size, reference_gas_price).await;
+    let transactions = match replay {
+        Some(replay) => ctx.prepare_replay(replay).await,
+        None => {
+            let tx_generator = workload.create_tx_generator(&mut ctx).await;
+            ctx.generate_transactions(tx_generator).await
+        }
+    };
     match component {
         Component::TxnSigning => {
             ctx.benchmark_transaction_signing(transactions).await;
@@ -6,7 +6,7 @@ use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::language_storage::ModuleId;
 use once_cell::unsync::OnceCell;
 use prometheus::core::{Atomic, AtomicU64};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use sui_storage::package_object_cache::PackageObjectCache;
 use sui_types::base_types::{
@@ -21,17 +21,26 @@ use sui_types::storage::{
 use sui_types::transaction::{InputObjectKind, InputObjects, ObjectReadResult};
 
 // TODO: We won't need a special purpose InMemoryObjectStore once the InMemoryCache is ready.
+/// Read-only object store holding all versions of the objects it is created with, so that replayed
+/// transactions can read the versions they originally read.
 #[derive(Clone)]
 pub(crate) struct InMemoryObjectStore {
-    objects: Arc<HashMap<ObjectID, Object>>,
+    objects: Arc<HashMap<ObjectID, BTreeMap<SequenceNumber, Object>>>,
     package_cache: Arc<PackageObjectCache>,
     num_object_reads: Arc<AtomicU64>,
 }
 
 impl InMemoryObjectStore {
-    pub(crate) fn new(objects: HashMap<ObjectID, Object>) -> Self {
+    pub(crate) fn new(objects: impl IntoIterator<Item = Object>) -> Self {
+        let mut versions: HashMap<_, BTreeMap<_, _>> = HashMap::new();
+        for object in objects {
+            versions
+                .entry(object.id())
+                .or_default()
+                .insert(object.version(), object);
+        }
         Self {
-            objects: Arc::new(objects),
+            objects: Arc::new(versions),
             package_cache: PackageObjectCache::new(),
             num_object_reads: Arc::new(AtomicU64::new(0)),
         }
@@ -115,7 +124,11 @@ impl InMemoryObjectStore {
 impl ObjectStore for InMemoryObjectStore {
     fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
         self.num_object_reads.inc_by(1);
-        Ok(self.objects.get(object_id).cloned())
+        Ok(self
+            .objects
+            .get(object_id)
+            .and_then(|versions| versions.values().next_back())
+            .cloned())
     }
 
     fn get_object_by_key(
@@ -123,13 +136,12 @@ impl ObjectStore for InMemoryObjectStore {
         object_id: &ObjectID,
         version: VersionNumber,
     ) -> Result<Option<Object>, SuiError> {
-        Ok(self.get_object(object_id).unwrap().and_then(|o| {
-            if o.version() == version {
-                Some(o.clone())
-            } else {
-                None
-            }
-        }))
+        self.num_object_reads.inc_by(1);
+        Ok(self
+            .objects
+            .get(object_id)
+            .and_then(|versions| versions.get(&version))
+            .cloned())
     }
 }
 
@@ -146,15 +158,18 @@ impl ChildObjectResolver for InMemoryObjectStore {
         child: &ObjectID,
         child_version_upper_bound: SequenceNumber,
     ) -> SuiResult<Option<Object>> {
-        Ok(self.get_object(child).unwrap().and_then(|o| {
-            if o.version() <= child_version_upper_bound
-                && o.owner == Owner::ObjectOwner((*parent).into())
-            {
-                Some(o.clone())
-            } else {
-                None
-            }
-        }))
+        self.num_object_reads.inc_by(1);
+        Ok(self
+            .objects
+            .get(child)
+            .and_then(|versions| {
+                versions
+                    .range(..=child_version_upper_bound)
+                    .next_back()
+                    .map(|(_, o)| o)
+            })
+            .filter(|o| o.owner == Owner::ObjectOwner((*parent).into()))
+            .cloned())
     }
 
     fn get_object_received_at_version(
@@ -0,0 +1,129 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use sui_storage::blob::Blob;
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::effects::TransactionEffects;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::Object;
+use sui_types::transaction::{Transaction, TransactionDataAPI};
+use tracing::info;
+
+/// The transactions of a range of real checkpoints, along with every object version they read.
+/// Unlike the synthetic workloads, transactions don't depend on each other: each of them is
+/// executed against the exact object versions it originally read.
+pub(crate) struct ReplayData {
+    /// User transactions of the checkpoint range, in checkpoint order.
+    pub transactions: Vec<Transaction>,
+    /// Effects each transaction had when it was originally executed.
+    pub effects: HashMap<TransactionDigest, TransactionEffects>,
+    /// All versions of the objects read or written by the transactions, as well as the packages
+    /// and immutable objects created by earlier checkpoints of the directory.
+    pub objects: Vec<Object>,
+}
+
+impl ReplayData {
+    /// Loads checkpoints `start_checkpoint..=end_checkpoint` from the data ingestion directory
+    /// `path`. Checkpoints of the directory preceding the range are only used to collect packages
+    /// and immutable objects, which are not part of the recorded inputs of a transaction.
+    pub(crate) fn load(
+        path: &Path,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+    ) -> Self {
+        assert!(
+            start_checkpoint <= end_checkpoint,
+            "Invalid checkpoint range {start_checkpoint}..={end_checkpoint}"
+        );
+        let checkpoint_files: BTreeMap<CheckpointSequenceNumber, _> = std::fs::read_dir(path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()))
+            .map(|entry| entry.unwrap().path())
+            .filter_map(|file| {
+                let sequence_number = file
+                    .file_name()?
+                    .to_str()?
+                    .strip_suffix(".chk")?
+                    .parse()
+                    .ok()?;
+                (sequence_number <= end_checkpoint).then_some((sequence_number, file))
+            })
+            .collect();
+        for sequence_number in start_checkpoint..=end_checkpoint {
+            assert!(
+                checkpoint_files.contains_key(&sequence_number),
+                "Checkpoint {sequence_number} is missing from {}",
+                path.display()
+            );
+        }
+
+        info!(
+            "Loading checkpoints {}..={} from {}",
+            checkpoint_files.keys().next().unwrap(),
+            end_checkpoint,
+            path.display()
+        );
+        let mut transactions = vec![];
+        let mut effects = HashMap::new();
+        let mut objects: HashMap<(ObjectID, SequenceNumber), Object> = HashMap::new();
+        for (sequence_number, file) in checkpoint_files {
+            let bytes = std::fs::read(&file)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {e}", file.display()));
+            let checkpoint_data: CheckpointData = Blob::from_bytes(&bytes)
+                .unwrap_or_else(|e| panic!("Failed to decode {}: {e}", file.display()));
+
+            if sequence_number < start_checkpoint {
+                // Packages are immutable too.
+                for object in checkpoint_data.output_objects() {
+                    if object.is_immutable() {
+                        objects.insert((object.id(), object.version()), object.clone());
+                    }
+                }
+                continue;
+            }
+
+            for checkpoint_transaction in checkpoint_data.transactions {
+                // System transactions can only be executed in the context they were sequenced in.
+                if checkpoint_transaction.transaction.is_system_tx() {
+                    continue;
+                }
+                // Output objects are kept too, so that dynamic fields that a transaction only
+                // reads are available if an earlier transaction of the range wrote them.
+                for object in checkpoint_transaction
+                    .input_objects
+                    .into_iter()
+                    .chain(checkpoint_transaction.output_objects)
+                {
+                    objects.insert((object.id(), object.version()), object);
+                }
+                effects.insert(
+                    *checkpoint_transaction.transaction.digest(),
+                    checkpoint_transaction.effects,
+                );
+                transactions.push(checkpoint_transaction.transaction);
+            }
+        }
+        info!(
+            "Loaded {} transactions and {} objects",
+            transactions.len(),
+            objects.len()
+        );
+
+        Self {
+            transactions,
+            effects,
+            objects: objects.into_values().collect(),
+        }
+    }
+
+    /// The reference gas price to run the validator with, so that no transaction is rejected for
+    /// paying less than it.
+    pub(crate) fn reference_gas_price(&self) -> Option<u64> {
+        self.transactions
+            .iter()
+            .map(|tx| tx.transaction_data().gas_price())
+            .min()
+    }
+}
@@ -4,7 +4,7 @@
 use crate::command::Component;
 use crate::mock_consensus::{ConsensusMode, MockConsensusClient};
 use crate::mock_storage::InMemoryObjectStore;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
@@ -22,6 +22,7 @@ use sui_types::base_types::{AuthorityName, ObjectRef, SuiAddress, TransactionDig
 use sui_types::committee::Committee;
 use sui_types::crypto::{AccountKeyPair, AuthoritySignature, Signer};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::error::SuiResult;
 use sui_types::executable_transaction::VerifiedExecutableTransaction;
 use sui_types::messages_checkpoint::{
     EndOfEpochData, VerifiedCheckpoint, VerifiedCheckpointContents,
@@ -46,14 +47,17 @@ impl SingleValidator {
         genesis_objects: &[Object],
         component: Component,
         checkpoint_size: usize,
+        reference_gas_price: Option<u64>,
     ) -> Self {
-        let validator = TestAuthorityBuilder::new()
+        let mut builder = TestAuthorityBuilder::new()
             .disable_indexer()
             .with_starting_objects(genesis_objects)
             // This is needed to properly run checkpoint executor.
-            .insert_genesis_checkpoint()
-            .build()
-            .await;
+            .insert_genesis_checkpoint();
+        if let Some(reference_gas_price) = reference_gas_price {
+            builder = builder.with_reference_gas_price(reference_gas_price);
+        }
+        let validator = builder.build().await;
         let epoch_store = validator.epoch_store_for_testing().clone();
         let consensus_mode = match component {
             Component::ValidatorWithFakeConsensus => {
@@ -76,6 +80,7 @@ impl SingleValidator {
             validator,
             consensus_adapter,
             Arc::new(ValidatorServiceMetrics::new_for_tests()),
+            None,
         ));
         Self {
             validator_service,
@@ -128,11 +133,13 @@ impl SingleValidator {
         effects
     }
 
+    /// Executes a certificate through the given component. Errors are returned rather than
+    /// panicking on, since replayed transactions can legitimately be rejected.
     pub async fn execute_certificate(
         &self,
         cert: CertifiedTransaction,
         component: Component,
-    ) -> TransactionEffects {
+    ) -> SuiResult<TransactionEffects> {
         let effects = match component {
             Component::Baseline => {
                 let cert = VerifiedExecutableTransaction::new_from_certificate(
@@ -140,54 +147,55 @@ impl SingleValidator {
                 );
                 self.get_validator()
                     .try_execute_immediately(&cert, None, &self.epoch_store)
-                    .await
-                    .unwrap()
+                    .await?
                     .0
             }
             Component::WithTxManager => {
                 let cert = VerifiedCertificate::new_unchecked(cert);
+                if cert.contains_shared_object() {
+                    // Shared object versions have been assigned ahead of time, so the certificate
+                    // can be enqueued without going through consensus.
+                    self.get_validator().enqueue_certificates_for_execution(
+                        vec![cert.clone()],
+                        &self.epoch_store,
+                    )?;
+                }
                 self.get_validator()
                     .execute_certificate(&cert, &self.epoch_store)
-                    .await
-                    .unwrap()
+                    .await?
                     .into_inner()
                     .into_data()
             }
             Component::ValidatorWithoutConsensus | Component::ValidatorWithFakeConsensus => {
                 let response = self
                     .validator_service
-                    .execute_certificate_for_testing(cert)
-                    .await;
+                    .try_execute_certificate_for_testing(cert)
+                    .await?;
                 response.signed_effects.into_data()
             }
             Component::TxnSigning | Component::CheckpointExecutor | Component::ExecutionOnly => {
                 unreachable!()
             }
         };
-        assert!(effects.status().is_ok());
-        effects
+        Ok(effects)
     }
 
     pub(crate) async fn execute_transaction_in_memory(
         &self,
         store: InMemoryObjectStore,
         transaction: Transaction,
-    ) -> TransactionEffects {
+    ) -> SuiResult<TransactionEffects> {
         let tx_digest = transaction.digest();
-        let input_objects = transaction.transaction_data().input_objects().unwrap();
+        let input_objects = transaction.transaction_data().input_objects()?;
         let objects = if transaction
             .data()
             .intent_message()
             .value
             .is_end_of_epoch_tx()
         {
-            store
-                .read_objects_for_synchronous_execution(&input_objects)
-                .unwrap()
+            store.read_objects_for_synchronous_execution(&input_objects)?
         } else {
-            store
-                .read_objects_for_execution(&*self.epoch_store, tx_digest, &input_objects)
-                .unwrap()
+            store.read_objects_for_execution(&*self.epoch_store, tx_digest, &input_objects)?
         };
 
         let executable = VerifiedExecutableTransaction::new_from_quorum_execution(
@@ -199,8 +207,7 @@ impl SingleValidator {
             objects,
             self.epoch_store.protocol_config(),
             self.epoch_store.reference_gas_price(),
-        )
-        .unwrap();
+        )?;
         let (kind, signer, gas) = executable.transaction_data().execution_parts();
         let (_, effects, _) = self.epoch_store.executor().execute_transaction_to_effects(
             &store,
@@ -217,13 +224,15 @@ impl SingleValidator {
             signer,
             *executable.digest(),
         );
-        assert!(effects.status().is_ok());
-        effects
+        Ok(effects)
     }
 
-    pub async fn sign_transaction(&self, transaction: Transaction) -> HandleTransactionResponse {
+    pub async fn sign_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> SuiResult<HandleTransactionResponse> {
         self.validator_service
-            .handle_transaction_for_testing(transaction)
+            .try_handle_transaction_for_testing(transaction)
             .await
     }
 
@@ -262,7 +271,9 @@ impl SingleValidator {
         );
         let epoch_effects = self
             .execute_transaction_in_memory(in_memory_store, epoch_tx.clone().into_inner())
-            .await;
+            .await
+            .unwrap();
+        assert!(epoch_effects.status().is_ok());
         builder.push_transaction(epoch_tx, epoch_effects);
         let (checkpoint, _, full_contents) = builder.build_end_of_epoch(
             self,
@@ -293,17 +304,18 @@ impl SingleValidator {
         (checkpoint_executor, ckpt_sender)
     }
 
-    pub(crate) fn create_in_memory_store(&self) -> InMemoryObjectStore {
-        let objects: HashMap<_, _> = self
+    /// Creates an in-memory store with the live objects of the validator, plus `extra_objects`,
+    /// which may be older versions of live objects.
+    pub(crate) fn create_in_memory_store(&self, extra_objects: &[Object]) -> InMemoryObjectStore {
+        let live_objects = self
             .get_validator()
             .database
             .iter_live_object_set(false)
             .map(|o| match o {
-                LiveObject::Normal(object) => (object.id(), object),
+                LiveObject::Normal(object) => object,
                 LiveObject::Wrapped(_) => unreachable!(),
-            })
-            .collect();
-        InMemoryObjectStore::new(objects)
+            });
+        InMemoryObjectStore::new(live_objects.chain(extra_objects.iter().cloned()))
     }
 }
 
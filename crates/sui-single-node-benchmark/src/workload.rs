@@ -6,7 +6,7 @@ use crate::command::WorkloadKind;
 use crate::tx_generator::{MoveTxGenerator, NonMoveTxGenerator, TxGenerator};
 use std::sync::Arc;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Workload {
     pub tx_count: u64,
     pub workload_kind: WorkloadKind,
@@ -23,7 +23,11 @@ impl Workload {
     }
 
     pub(crate) fn num_accounts(&self) -> u64 {
-        self.tx_count
+        match self.workload_kind {
+            // Replayed transactions are signed by their original senders.
+            WorkloadKind::Replay { .. } => 0,
+            _ => self.tx_count,
+        }
     }
 
     pub(crate) fn gas_object_num_per_account(&self) -> u64 {
@@ -59,6 +63,9 @@ impl Workload {
                     root_objects,
                 ))
             }
+            WorkloadKind::Replay { .. } => {
+                unreachable!("Replayed transactions are loaded rather than generated")
+            }
         }
     }
 }
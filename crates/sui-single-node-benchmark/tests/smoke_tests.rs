@@ -1,11 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
 use strum::IntoEnumIterator;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_macros::sim_test;
 use sui_single_node_benchmark::command::{Component, WorkloadKind};
 use sui_single_node_benchmark::run_benchmark;
 use sui_single_node_benchmark::workload::Workload;
+use test_cluster::TestClusterBuilder;
 
 #[sim_test]
 async fn benchmark_simple_transfer_smoke_test() {
@@ -34,3 +37,62 @@ async fn benchmark_move_transactions_smoke_test() {
         .await;
     }
 }
+
+#[sim_test]
+async fn benchmark_replay_smoke_test() {
+    // Record a few checkpoints of real transactions, and make sure they can be replayed.
+    let path = tempfile::tempdir().unwrap().into_path();
+    let test_cluster = TestClusterBuilder::new()
+        .with_data_ingestion_dir(path.clone())
+        .build()
+        .await;
+    let recipient = test_cluster.get_address_1();
+    let mut digests = vec![];
+    for _ in 0..3 {
+        let tx_data = test_cluster
+            .test_transaction_builder()
+            .await
+            .transfer_sui(Some(1), recipient)
+            .build();
+        let response = test_cluster.sign_and_execute_transaction(&tx_data).await;
+        digests.push(response.digest);
+    }
+
+    let mut end_checkpoint = 0;
+    for digest in digests {
+        let checkpoint = loop {
+            let response = test_cluster
+                .fullnode_handle
+                .sui_client
+                .read_api()
+                .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+                .await
+                .unwrap();
+            if let Some(checkpoint) = response.checkpoint {
+                break checkpoint;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        end_checkpoint = end_checkpoint.max(checkpoint);
+    }
+    while !path.join(format!("{end_checkpoint}.chk")).exists() {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    for component in Component::iter() {
+        run_benchmark(
+            Workload::new(
+                0,
+                WorkloadKind::Replay {
+                    path: path.clone(),
+                    start_checkpoint: 1,
+                    end_checkpoint,
+                },
+                1,
+            ),
+            component,
+            1000,
+        )
+        .await;
+    }
+}
@@ -5,6 +5,7 @@
 #[cfg(test)]
 mod tests;
 
+pub mod peer_store;
 pub mod reader;
 pub mod uploader;
 mod writer;
@@ -143,16 +144,15 @@ pub struct FileMetadata {
 }
 
 impl FileMetadata {
-    pub fn file_path(&self, dir_path: &Path) -> Path {
+    pub fn file_name(&self) -> String {
         match self.file_type {
-            FileType::Object => {
-                dir_path.child(&*format!("{}_{}.obj", self.bucket_num, self.part_num))
-            }
-            FileType::Reference => {
-                dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
-            }
+            FileType::Object => format!("{}_{}.obj", self.bucket_num, self.part_num),
+            FileType::Reference => format!("{}_{}.ref", self.bucket_num, self.part_num),
         }
     }
+    pub fn file_path(&self, dir_path: &Path) -> Path {
+        dir_path.child(&*self.file_name())
+    }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
         path_to_filesystem(root_path.to_path_buf(), &self.file_path(dir_path))
     }
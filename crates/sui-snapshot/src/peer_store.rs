@@ -0,0 +1,94 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::reader::StateSnapshotReaderV1;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use object_store::path::Path;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+use sui_network::state_snapshot::StateSnapshotDownloader;
+use sui_storage::object_store::ObjectStoreGetExt;
+
+const MANIFEST_FILE_NAME: &str = "MANIFEST";
+
+/// Read-only object store backed by peers serving state snapshots, which lets
+/// `StateSnapshotReaderV1` restore a snapshot from peers the same way it does from a remote object
+/// store. The MANIFEST is fetched first, from all peers, and only accepted if most of them agree
+/// on it. Every other file is then checked against the digest the MANIFEST lists for it, so that
+/// a peer serving a corrupt file is banned and the file is fetched from another peer.
+pub struct PeerSnapshotStore {
+    downloader: StateSnapshotDownloader,
+    /// Expected sha3 digest of each file of the snapshot, by file name.
+    file_digests: RwLock<HashMap<String, [u8; 32]>>,
+}
+
+impl PeerSnapshotStore {
+    pub fn new(downloader: StateSnapshotDownloader) -> Self {
+        Self {
+            downloader,
+            file_digests: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn parse_path(path: &Path) -> Result<(u64, String)> {
+        let mut parts = path.parts();
+        let (Some(epoch_dir), Some(file_name), None) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(anyhow!("Not a state snapshot file path: {path}"));
+        };
+        let epoch = epoch_dir
+            .as_ref()
+            .strip_prefix("epoch_")
+            .and_then(|epoch| epoch.parse().ok())
+            .ok_or_else(|| anyhow!("Not a state snapshot file path: {path}"))?;
+        Ok((epoch, file_name.as_ref().to_owned()))
+    }
+}
+
+impl fmt::Display for PeerSnapshotStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PeerSnapshotStore")
+    }
+}
+
+#[async_trait]
+impl ObjectStoreGetExt for PeerSnapshotStore {
+    async fn get_bytes(&self, src: &Path) -> Result<Bytes> {
+        let (epoch, file_name) = Self::parse_path(src)?;
+
+        if file_name == MANIFEST_FILE_NAME {
+            let data = self
+                .downloader
+                .download_agreed_file(epoch, &file_name, |data| {
+                    StateSnapshotReaderV1::parse_manifest(data)
+                        .is_ok_and(|manifest| manifest.epoch() == epoch)
+                })
+                .await?;
+            let manifest = StateSnapshotReaderV1::parse_manifest(&data)?;
+            let mut file_digests = self.file_digests.write().unwrap();
+            for file_metadata in manifest.file_metadata() {
+                file_digests.insert(file_metadata.file_name(), file_metadata.sha3_digest);
+            }
+            return Ok(Bytes::from(data));
+        }
+
+        let expected_digest = self
+            .file_digests
+            .read()
+            .unwrap()
+            .get(&file_name)
+            .copied()
+            .ok_or_else(|| anyhow!("{file_name} is not listed in the MANIFEST of epoch {epoch}"))?;
+        let data = self
+            .downloader
+            .download_file(epoch, &file_name, |data| {
+                Sha3_256::digest(data).digest == expected_digest
+            })
+            .await?;
+        Ok(Bytes::from(data))
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::peer_store::PeerSnapshotStore;
 use crate::{
     FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
     OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
@@ -17,14 +18,14 @@ use integer_encoding::VarIntReader;
 use object_store::path::Path;
 use std::collections::BTreeMap;
 use std::fs;
-use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::Read;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
 use sui_core::authority::AuthorityStore;
+use sui_network::state_snapshot::StateSnapshotDownloader;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::http::HttpDownloaderBuilder;
 use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem};
@@ -60,12 +61,53 @@ impl StateSnapshotReaderV1 {
         download_concurrency: NonZeroUsize,
         m: MultiProgress,
     ) -> Result<Self> {
-        let epoch_dir = format!("epoch_{}", epoch);
         let remote_object_store = if remote_store_config.no_sign_request {
             remote_store_config.make_http()?
         } else {
             remote_store_config.make().map(Arc::new)?
         };
+        Self::new_from_store(
+            epoch,
+            remote_object_store,
+            local_store_config,
+            indirect_objects_threshold,
+            download_concurrency,
+            m,
+        )
+        .await
+    }
+
+    /// Creates a reader that downloads the snapshot from peers instead of an object store. Every
+    /// file is verified against the snapshot MANIFEST, and the live object set against the
+    /// accumulator root as usual.
+    pub async fn new_from_peers(
+        epoch: u64,
+        downloader: StateSnapshotDownloader,
+        local_store_config: &ObjectStoreConfig,
+        indirect_objects_threshold: usize,
+        download_concurrency: NonZeroUsize,
+        m: MultiProgress,
+    ) -> Result<Self> {
+        Self::new_from_store(
+            epoch,
+            Arc::new(PeerSnapshotStore::new(downloader)),
+            local_store_config,
+            indirect_objects_threshold,
+            download_concurrency,
+            m,
+        )
+        .await
+    }
+
+    async fn new_from_store(
+        epoch: u64,
+        remote_object_store: Arc<dyn ObjectStoreGetExt>,
+        local_store_config: &ObjectStoreConfig,
+        indirect_objects_threshold: usize,
+        download_concurrency: NonZeroUsize,
+        m: MultiProgress,
+    ) -> Result<Self> {
+        let epoch_dir = format!("epoch_{}", epoch);
         let local_object_store: Arc<dyn ObjectStorePutExt> =
             local_store_config.make().map(Arc::new)?;
         let local_staging_dir_root = local_store_config
@@ -472,22 +514,21 @@ impl StateSnapshotReaderV1 {
     }
 
     fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
-        let manifest_file = File::open(path)?;
-        let manifest_file_size = manifest_file.metadata()?.len() as usize;
-        let mut manifest_reader = BufReader::new(manifest_file);
-        manifest_reader.rewind()?;
-        let magic = manifest_reader.read_u32::<BigEndian>()?;
+        Self::parse_manifest(&fs::read(path)?)
+    }
+
+    /// Parses the contents of a MANIFEST file, checking its checksum.
+    pub(crate) fn parse_manifest(bytes: &[u8]) -> anyhow::Result<Manifest> {
+        if bytes.len() < MAGIC_BYTES + SHA3_BYTES {
+            return Err(anyhow!("MANIFEST is too short: {} bytes", bytes.len()));
+        }
+        let magic = (&bytes[..MAGIC_BYTES]).read_u32::<BigEndian>()?;
         if magic != MANIFEST_FILE_MAGIC {
             return Err(anyhow!("Unexpected magic byte: {}", magic));
         }
-        manifest_reader.seek(SeekFrom::End(-(SHA3_BYTES as i64)))?;
-        let mut sha3_digest = [0u8; SHA3_BYTES];
-        manifest_reader.read_exact(&mut sha3_digest)?;
-        manifest_reader.rewind()?;
-        let mut content_buf = vec![0u8; manifest_file_size - SHA3_BYTES];
-        manifest_reader.read_exact(&mut content_buf)?;
+        let (content_buf, sha3_digest) = bytes.split_at(bytes.len() - SHA3_BYTES);
         let mut hasher = Sha3_256::default();
-        hasher.update(&content_buf);
+        hasher.update(content_buf);
         let computed_digest = hasher.finalize().digest;
         if computed_digest != sha3_digest {
             return Err(anyhow!(
@@ -496,8 +537,6 @@ impl StateSnapshotReaderV1 {
                 sha3_digest
             ));
         }
-        manifest_reader.rewind()?;
-        manifest_reader.seek(SeekFrom::Start(MAGIC_BYTES as u64))?;
         let manifest = bcs::from_bytes(&content_buf[MAGIC_BYTES..])?;
         Ok(manifest)
     }
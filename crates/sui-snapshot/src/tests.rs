@@ -10,6 +10,7 @@ use std::collections::HashSet;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_network::state_snapshot::{self, StateSnapshotDownloader};
 use sui_protocol_config::ProtocolConfig;
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_types::base_types::ObjectID;
@@ -160,3 +161,83 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+fn build_network(private_key: [u8; 32], router: anemo::Router) -> anemo::Network {
+    anemo::Network::bind("localhost:0")
+        .private_key(private_key)
+        .server_name("test")
+        .start(router)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_snapshot_from_peers() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    snapshot_writer
+        .write_internal(0, true, perpetual_db.clone())
+        .await?;
+
+    // Serve the snapshot from a peer, and restore it from there.
+    let server = state_snapshot::Builder::new().snapshot_dir(remote).build();
+    let server_network = build_network([1; 32], anemo::Router::new().add_rpc_service(server));
+    let network = build_network([2; 32], anemo::Router::new());
+    network
+        .connect_with_peer_id(server_network.local_addr(), server_network.peer_id())
+        .await?;
+    let downloader =
+        StateSnapshotDownloader::new(network, vec![server_network.peer_id()], Default::default());
+    assert_eq!(
+        downloader
+            .available_epochs()
+            .await
+            .into_keys()
+            .collect::<Vec<_>>(),
+        vec![0]
+    );
+
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new_from_peers(
+        0,
+        downloader,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    snapshot_reader
+        .read(&restored_perpetual_db, abort_registration, None)
+        .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    Ok(())
+}
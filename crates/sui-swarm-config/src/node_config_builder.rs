@@ -189,6 +189,9 @@ impl ValidatorConfigBuilder {
                 .unwrap_or(3600),
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             overload_threshold_config: self.overload_threshold_config.unwrap_or_default(),
+            traffic_control_config: None,
+            deny_config_override_path: None,
+            json_rpc_batch_config: None,
         }
     }
 
@@ -426,6 +429,9 @@ impl FullnodeConfigBuilder {
             jwk_fetch_interval_seconds: 3600,
             zklogin_oauth_providers: default_zklogin_oauth_providers(),
             overload_threshold_config: Default::default(),
+            traffic_control_config: None,
+            deny_config_override_path: None,
+            json_rpc_batch_config: None,
         }
     }
 }
@@ -214,7 +214,7 @@ async fn faucet_request(
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let result = match payload {
-        FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient }) => {
+        FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient, .. }) => {
             state.faucet.request_sui_coins(recipient).await
         }
         _ => {
@@ -239,7 +239,7 @@ async fn faucet_batch_request(
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let result = match payload {
-        FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient }) => {
+        FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient, .. }) => {
             state.faucet.batch_request_sui_coins(recipient).await
         }
         _ => {
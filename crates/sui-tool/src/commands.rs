@@ -9,7 +9,7 @@ use crate::{
     state_sync_from_archive, verify_archive, verify_archive_by_checksum, ConciseObjectOutput,
     GroupedObjectOutput, VerboseObjectOutput,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::env;
 use std::path::PathBuf;
 use sui_config::genesis::Genesis;
@@ -18,6 +18,7 @@ use sui_protocol_config::Chain;
 use sui_replay::{execute_replay_command, ReplayToolCommand};
 use telemetry_subscribers::TracingHandle;
 
+use sui_types::multiaddr::Multiaddr;
 use sui_types::{base_types::*, object::Owner};
 
 use clap::*;
@@ -299,6 +300,12 @@ pub enum ToolCommand {
         /// Only applicable if `--snapshot-bucket-type` is "file".
         #[clap(long = "snapshot-path")]
         snapshot_path: Option<PathBuf>,
+        /// Address of a peer serving state snapshots over the p2p network, to download the
+        /// snapshot from instead of the snapshot bucket. Can be repeated, in which case most
+        /// peers must agree on the snapshot MANIFEST. Only applicable if `--formal` flag
+        /// specified, and not with `--verify false`.
+        #[clap(long = "snapshot-peer")]
+        snapshot_peers: Vec<Multiaddr>,
         /// Archival bucket name. If not specified, defaults are
         /// based on value of `--network` and `--formal` flags.
         #[clap(long = "archive-bucket")]
@@ -526,11 +533,17 @@ impl ToolCommand {
                 snapshot_bucket,
                 snapshot_bucket_type,
                 snapshot_path,
+                snapshot_peers,
                 archive_bucket,
                 archive_bucket_type,
                 no_sign_request,
                 verbose,
             } => {
+                if !snapshot_peers.is_empty() && !(formal && verify.unwrap_or(true)) {
+                    return Err(anyhow!(
+                        "--snapshot-peer is only supported for verified --formal snapshots"
+                    ));
+                }
                 if !verbose {
                     tracing_handle
                         .update_log("off")
@@ -543,6 +556,7 @@ impl ToolCommand {
                 });
                 let snapshot_bucket =
                     snapshot_bucket.or_else(|| match (formal, network, no_sign_request) {
+                        _ if !snapshot_peers.is_empty() => None,
                         (true, Chain::Mainnet, false) => Some(
                             env::var("MAINNET_FORMAL_SIGNED_BUCKET")
                                 .unwrap_or("mysten-mainnet-formal".to_string()),
@@ -710,11 +724,14 @@ impl ToolCommand {
                     }
                 };
 
-                if let Err(e) = check_completed_snapshot(&snapshot_store_config, epoch).await {
-                    panic!(
-                        "Aborting snapshot restore: {}, snapshot may not be uploaded yet",
-                        e
-                    );
+                // Peers only advertise the snapshots they have completely written.
+                if snapshot_peers.is_empty() {
+                    if let Err(e) = check_completed_snapshot(&snapshot_store_config, epoch).await {
+                        panic!(
+                            "Aborting snapshot restore: {}, snapshot may not be uploaded yet",
+                            e
+                        );
+                    }
                 }
                 if formal {
                     let verify = verify.unwrap_or(true);
@@ -723,6 +740,7 @@ impl ToolCommand {
                         epoch,
                         &genesis,
                         snapshot_store_config,
+                        snapshot_peers,
                         archive_store_config,
                         num_parallel_downloads,
                         network,
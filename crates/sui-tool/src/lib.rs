@@ -16,14 +16,17 @@ use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{fs, io};
+use sui_config::p2p::StateSnapshotConfig;
 use sui_config::{genesis::Genesis, NodeConfig};
 use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
 use sui_network::default_mysten_network_config;
+use sui_network::state_snapshot::StateSnapshotDownloader;
 use sui_protocol_config::Chain;
 use sui_sdk::SuiClientBuilder;
 use sui_storage::object_store::http::HttpDownloaderBuilder;
 use sui_types::accumulator::Accumulator;
-use sui_types::crypto::AuthorityPublicKeyBytes;
+use sui_types::crypto::{get_key_pair, AuthorityPublicKeyBytes, KeypairTraits, NetworkKeyPair};
+use sui_types::digests::ChainIdentifier;
 use sui_types::messages_grpc::LayoutGenerationOption;
 use sui_types::multiaddr::Multiaddr;
 use sui_types::{base_types::*, object::Owner};
@@ -819,11 +822,48 @@ pub async fn check_completed_snapshot(
     }
 }
 
+/// Connects to `peers` over the p2p network of the chain of `genesis`, to download state
+/// snapshots from them.
+async fn state_snapshot_downloader(
+    genesis: &Genesis,
+    peers: &[Multiaddr],
+) -> Result<StateSnapshotDownloader, anyhow::Error> {
+    let chain_identifier = ChainIdentifier::from(*genesis.checkpoint().digest());
+    let server_name = format!("sui-{}", chain_identifier);
+    let (_, network_key_pair): (_, NetworkKeyPair) = get_key_pair();
+    let network = anemo::Network::bind("0.0.0.0:0")
+        .server_name(&server_name)
+        .private_key(network_key_pair.private().0.to_bytes())
+        .start(anemo::Router::new())?;
+
+    let mut peer_ids = vec![];
+    for peer in peers {
+        let address = peer
+            .to_anemo_address()
+            .map_err(|e| anyhow!("Invalid snapshot peer address {peer}: {e:?}"))?;
+        match network.connect(address).await {
+            Ok(peer_id) => peer_ids.push(peer_id),
+            Err(e) => eprintln!("Failed to connect to snapshot peer {peer}: {e}"),
+        }
+    }
+    if peer_ids.is_empty() {
+        return Err(anyhow!("Failed to connect to any snapshot peer"));
+    }
+    Ok(StateSnapshotDownloader::new(
+        network,
+        peer_ids,
+        StateSnapshotConfig::default(),
+    ))
+}
+
+/// Restores the formal snapshot of `epoch` into `path`. The snapshot is downloaded from
+/// `snapshot_peers` if any are given, and from `snapshot_store_config` otherwise.
 pub async fn download_formal_snapshot(
     path: &Path,
     epoch: EpochId,
     genesis: &Path,
     snapshot_store_config: ObjectStoreConfig,
+    snapshot_peers: Vec<Multiaddr>,
     archive_store_config: ObjectStoreConfig,
     num_parallel_downloads: usize,
     network: Chain,
@@ -840,6 +880,11 @@ pub async fn download_formal_snapshot(
     let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&path.join("store"), None));
     let genesis = Genesis::load(genesis).unwrap();
     let genesis_committee = genesis.committee()?;
+    let snapshot_downloader = if snapshot_peers.is_empty() {
+        None
+    } else {
+        Some(state_snapshot_downloader(&genesis, &snapshot_peers).await?)
+    };
     let committee_store = Arc::new(CommitteeStore::new(
         path.join("epochs"),
         &genesis_committee,
@@ -882,15 +927,30 @@ pub async fn download_formal_snapshot(
             directory: Some(snapshot_dir_clone.to_path_buf()),
             ..Default::default()
         };
-        let mut reader = StateSnapshotReaderV1::new(
-            epoch,
-            &snapshot_store_config,
-            &local_store_config,
-            usize::MAX,
-            NonZeroUsize::new(num_parallel_downloads).unwrap(),
-            m,
-        )
-        .await
+        let mut reader = match snapshot_downloader {
+            Some(downloader) => {
+                StateSnapshotReaderV1::new_from_peers(
+                    epoch,
+                    downloader,
+                    &local_store_config,
+                    usize::MAX,
+                    NonZeroUsize::new(num_parallel_downloads).unwrap(),
+                    m,
+                )
+                .await
+            }
+            None => {
+                StateSnapshotReaderV1::new(
+                    epoch,
+                    &snapshot_store_config,
+                    &local_store_config,
+                    usize::MAX,
+                    NonZeroUsize::new(num_parallel_downloads).unwrap(),
+                    m,
+                )
+                .await
+            }
+        }
         .unwrap_or_else(|err| panic!("Failed to create reader: {}", err));
         reader
             .read(&perpetual_db_clone, abort_registration, Some(sender))
@@ -15,7 +15,9 @@ use move_binary_format::file_format_common::VERSION_MAX;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
 
-use sui_json::{is_receiving_argument, resolve_move_function_args, ResolvedCallArg, SuiJsonValue};
+use sui_json::{
+    is_receiving_argument, resolve_move_function_args_partial, ResolvedCallArg, SuiJsonValue,
+};
 use sui_json_rpc_types::{
     RPCTransactionRequestParams, SuiData, SuiObjectDataOptions, SuiObjectResponse, SuiRawData,
     SuiTypeTag,
@@ -28,9 +30,11 @@ use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME
 use sui_types::move_package::MovePackage;
 use sui_types::object::{Object, Owner};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::signature::GenericSignature;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, InputObjectKind, ObjectArg, ProgrammableTransaction, Transaction,
+    TransactionData, TransactionDataAPI, TransactionKind,
 };
 use sui_types::{coin, fp_ensure, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
@@ -51,6 +55,15 @@ pub trait DataReader {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
 }
 
+/// An argument of a Move call added with [TransactionBuilder::programmable_move_call].
+#[derive(Clone, Debug)]
+pub enum ProgrammableMoveCallArg {
+    /// A value resolved against the function signature, as for [TransactionBuilder::move_call].
+    Json(SuiJsonValue),
+    /// The gas coin, or the result of an earlier command of the same transaction.
+    Argument(Argument),
+}
+
 #[derive(Clone)]
 pub struct TransactionBuilder(Arc<dyn DataReader + Sync + Send>);
 
@@ -312,7 +325,15 @@ impl TransactionBuilder {
 
         let call_args = self
             .resolve_and_checks_json_args(
-                builder, package, &module, &function, &type_args, call_args,
+                builder,
+                package,
+                &module,
+                &function,
+                &type_args,
+                call_args
+                    .into_iter()
+                    .map(ProgrammableMoveCallArg::Json)
+                    .collect(),
             )
             .await?;
 
@@ -322,13 +343,145 @@ impl TransactionBuilder {
         Ok(())
     }
 
+    /// Add a Move call to `builder` whose arguments may refer to the gas coin or to results of
+    /// earlier commands, and return the result of the call for use by later commands.
+    pub async fn programmable_move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<SuiTypeTag>,
+        call_args: Vec<ProgrammableMoveCallArg>,
+    ) -> anyhow::Result<Argument> {
+        let module = Identifier::from_str(module)?;
+        let function = Identifier::from_str(function)?;
+
+        let type_args = type_args
+            .into_iter()
+            .map(|ty| ty.try_into())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let call_args = self
+            .resolve_and_checks_json_args(
+                builder, package, &module, &function, &type_args, call_args,
+            )
+            .await?;
+
+        Ok(builder.command(Command::move_call(
+            package, module, function, type_args, call_args,
+        )))
+    }
+
+    /// Resolve `object_id` into an input of a programmable transaction, as an owned or
+    /// immutable object, or as a shared object, accessed mutably if `is_mutable_ref` is set.
+    pub async fn object_arg(
+        &self,
+        object_id: ObjectID,
+        is_mutable_ref: bool,
+    ) -> anyhow::Result<ObjectArg> {
+        self.get_object_arg(
+            object_id,
+            &mut BTreeMap::new(),
+            is_mutable_ref,
+            /* is_receiving */ false,
+        )
+        .await
+    }
+
+    /// Turn a programmable transaction into [TransactionData]. If `gas` is not provided, a gas
+    /// coin of `signer` that is not an input of the transaction is selected.
+    pub async fn programmable_transaction(
+        &self,
+        signer: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let input_objects = pt
+            .input_objects()?
+            .iter()
+            .flat_map(|obj| match obj {
+                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas = self
+            .select_gas(signer, gas, gas_budget, input_objects, gas_price)
+            .await?;
+
+        Ok(TransactionData::new(
+            TransactionKind::programmable(pt),
+            signer,
+            gas,
+            gas_budget,
+            gas_price,
+        ))
+    }
+
+    /// Build [TransactionData] for `kind`, sent by `sender` and with gas paid by `sponsor`. If
+    /// `gas` is empty, a gas coin of `sponsor` that is not an input of the transaction is
+    /// selected. The transaction must be signed by both the sender and the sponsor, see
+    /// [sponsored_transaction_from_signatures].
+    pub async fn sponsored_transaction(
+        &self,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        sponsor: SuiAddress,
+        gas: Vec<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas_payment = if gas.is_empty() {
+            let input_objects = kind
+                .input_objects()?
+                .iter()
+                .flat_map(|obj| match obj {
+                    InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+                    _ => None,
+                })
+                .collect();
+            vec![
+                self.select_gas(sponsor, None, gas_budget, input_objects, gas_price)
+                    .await?,
+            ]
+        } else {
+            if gas_budget < gas_price {
+                bail!("Gas budget {gas_budget} is less than the reference gas price {gas_price}. The gas budget must be at least the current reference gas price of {gas_price}.")
+            }
+            let mut gas_payment = vec![];
+            for id in gas {
+                let object = self
+                    .0
+                    .get_object_with_options(id, SuiObjectDataOptions::new().with_owner())
+                    .await?
+                    .into_object()?;
+                ensure!(
+                    object.owner == Some(Owner::AddressOwner(sponsor)),
+                    "Gas object {id} is not owned by the sponsor {sponsor}"
+                );
+                gas_payment.push(object.object_ref());
+            }
+            gas_payment
+        };
+
+        Ok(TransactionData::new_with_gas_coins_allow_sponsor(
+            kind,
+            sender,
+            gas_payment,
+            gas_budget,
+            gas_price,
+            sponsor,
+        ))
+    }
+
     async fn get_object_arg(
         &self,
         id: ObjectID,
         objects: &mut BTreeMap<ObjectID, Object>,
         is_mutable_ref: bool,
-        view: &BinaryIndexedView<'_>,
-        arg_type: &SignatureToken,
+        is_receiving: bool,
     ) -> Result<ObjectArg, anyhow::Error> {
         let response = self
             .0
@@ -339,7 +492,7 @@ impl TransactionBuilder {
         let obj_ref = obj.compute_object_reference();
         let owner = obj.owner;
         objects.insert(id, obj);
-        if is_receiving_argument(view, arg_type) {
+        if is_receiving {
             return Ok(ObjectArg::Receiving(obj_ref));
         }
         Ok(match owner {
@@ -363,7 +516,7 @@ impl TransactionBuilder {
         module: &Identifier,
         function: &Identifier,
         type_args: &[TypeTag],
-        json_args: Vec<SuiJsonValue>,
+        args: Vec<ProgrammableMoveCallArg>,
     ) -> Result<Vec<Argument>, anyhow::Error> {
         let object = self
             .0
@@ -385,7 +538,16 @@ impl TransactionBuilder {
             package.linkage_table,
         )?;
 
-        let json_args_and_tokens = resolve_move_function_args(
+        // Arguments that already are PTB arguments don't need resolving, but still count
+        // towards the function arity.
+        let (json_args, mut ptb_args): (Vec<_>, Vec<_>) = args
+            .into_iter()
+            .map(|arg| match arg {
+                ProgrammableMoveCallArg::Json(json) => (Some(json), None),
+                ProgrammableMoveCallArg::Argument(arg) => (None, Some(arg)),
+            })
+            .unzip();
+        let json_args_and_tokens = resolve_move_function_args_partial(
             &package,
             module.clone(),
             function.clone(),
@@ -397,7 +559,15 @@ impl TransactionBuilder {
         let mut objects = BTreeMap::new();
         let module = package.deserialize_module(module, VERSION_MAX, true)?;
         let view = BinaryIndexedView::Module(&module);
-        for (arg, expected_type) in json_args_and_tokens {
+        for (idx, (arg, expected_type)) in json_args_and_tokens.into_iter().enumerate() {
+            let Some(arg) = arg else {
+                args.push(
+                    ptb_args[idx].take().ok_or_else(|| {
+                        anyhow!("Missing argument {idx} of type {expected_type:?}")
+                    })?,
+                );
+                continue;
+            };
             args.push(match arg {
                 ResolvedCallArg::Pure(p) => builder.input(CallArg::Pure(p)),
 
@@ -408,8 +578,7 @@ impl TransactionBuilder {
                         // Is mutable if passed by mutable reference or by value
                         matches!(expected_type, SignatureToken::MutableReference(_))
                             || !expected_type.is_reference(),
-                        &view,
-                        &expected_type,
+                        is_receiving_argument(&view, &expected_type),
                     )
                     .await?,
                 )),
@@ -422,8 +591,7 @@ impl TransactionBuilder {
                                 id,
                                 &mut objects,
                                 /* is_mutable_ref */ false,
-                                &view,
-                                &expected_type,
+                                is_receiving_argument(&view, &expected_type),
                             )
                             .await?,
                         )
@@ -790,3 +958,106 @@ impl TransactionBuilder {
         Ok((object.object_ref(), object.object_type()?))
     }
 }
+
+/// Build the [Transaction] for sponsored `data` from the signatures of its sender and its sponsor,
+/// checking that each signature was made by the right account. The signatures themselves are
+/// verified by validators when the transaction is executed.
+pub fn sponsored_transaction_from_signatures(
+    data: TransactionData,
+    sender_signature: GenericSignature,
+    sponsor_signature: GenericSignature,
+) -> anyhow::Result<Transaction> {
+    let sender = data.sender();
+    let sponsor = data.gas_owner();
+    ensure!(
+        sender != sponsor,
+        "Transaction is not sponsored, its gas is paid by the sender {sender}"
+    );
+    ensure!(
+        SuiAddress::try_from(&sender_signature)? == sender,
+        "Sender signature is not from the sender {sender}"
+    );
+    ensure!(
+        SuiAddress::try_from(&sponsor_signature)? == sponsor,
+        "Sponsor signature is not from the sponsor {sponsor}"
+    );
+    Ok(Transaction::from_generic_sig_data(
+        data,
+        vec![sender_signature, sponsor_signature],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::random_object_ref;
+    use sui_types::crypto::{get_key_pair, AccountKeyPair};
+
+    use super::*;
+
+    fn signature(data: &TransactionData, key: &AccountKeyPair) -> GenericSignature {
+        Transaction::from_data_and_signer(data.clone(), vec![key])
+            .data()
+            .tx_signatures()[0]
+            .clone()
+    }
+
+    #[test]
+    fn test_sponsored_transaction_from_signatures() {
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+        let (sponsor, sponsor_key): (_, AccountKeyPair) = get_key_pair();
+        let data = TransactionData::new_with_gas_coins_allow_sponsor(
+            TransactionKind::ProgrammableTransaction(
+                ProgrammableTransactionBuilder::new().finish(),
+            ),
+            sender,
+            vec![random_object_ref()],
+            1_000_000,
+            1_000,
+            sponsor,
+        );
+        let sender_signature = signature(&data, &sender_key);
+        let sponsor_signature = signature(&data, &sponsor_key);
+
+        let transaction = sponsored_transaction_from_signatures(
+            data.clone(),
+            sender_signature.clone(),
+            sponsor_signature.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            transaction.data().tx_signatures(),
+            &[sender_signature.clone(), sponsor_signature.clone()]
+        );
+
+        // Signatures must come from the right accounts, in the right roles.
+        assert!(sponsored_transaction_from_signatures(
+            data.clone(),
+            sponsor_signature.clone(),
+            sender_signature.clone()
+        )
+        .is_err());
+        assert!(sponsored_transaction_from_signatures(
+            data,
+            sender_signature.clone(),
+            sender_signature.clone()
+        )
+        .is_err());
+
+        // Transactions paid for by their sender have no sponsor.
+        let unsponsored = TransactionData::new_with_gas_coins_allow_sponsor(
+            TransactionKind::ProgrammableTransaction(
+                ProgrammableTransactionBuilder::new().finish(),
+            ),
+            sender,
+            vec![random_object_ref()],
+            1_000_000,
+            1_000,
+            sender,
+        );
+        let signature = signature(&unsponsored, &sender_key);
+        assert!(
+            sponsored_transaction_from_signatures(unsponsored, signature.clone(), signature)
+                .is_err()
+        );
+    }
+}
@@ -595,6 +595,21 @@ pub enum SuiError {
 
     #[error("Failed to get JWK")]
     JWKRetrievalError,
+
+    #[error("Validator is overloaded and rejecting {load_shedding_percentage}% of new transactions, retry after {retry_after_secs} seconds")]
+    ValidatorOverloadedRetryAfter {
+        load_shedding_percentage: u32,
+        retry_after_secs: u64,
+    },
+
+    #[error("Too many requests from {traffic_source}")]
+    TooManyRequests { traffic_source: String },
+
+    #[error("Requests from {traffic_source} are blocked, retry after {retry_after_secs} seconds")]
+    TrafficControlDenied {
+        traffic_source: String,
+        retry_after_secs: u64,
+    },
 }
 
 #[repr(u64)]
@@ -744,6 +759,9 @@ impl SuiError {
             SuiError::TooManyTransactionsPendingOnObject { .. } => (true, true),
             SuiError::TooOldTransactionPendingOnObject { .. } => (true, true),
             SuiError::TooManyTransactionsPendingConsensus => (true, true),
+            SuiError::ValidatorOverloadedRetryAfter { .. } => (true, true),
+            SuiError::TooManyRequests { .. } => (true, true),
+            SuiError::TrafficControlDenied { .. } => (true, true),
 
             // Non retryable error
             SuiError::ExecutionError(..) => (false, true),
@@ -779,6 +797,7 @@ impl SuiError {
                 | SuiError::TooManyTransactionsPendingOnObject { .. }
                 | SuiError::TooOldTransactionPendingOnObject { .. }
                 | SuiError::TooManyTransactionsPendingConsensus
+                | SuiError::ValidatorOverloadedRetryAfter { .. }
         )
     }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    collections::BTreeMap,
     fmt::{Debug, Display, Formatter, Write},
     path::PathBuf,
     sync::Arc,
@@ -25,14 +26,16 @@ use serde_json::{json, Value};
 use sui_move::build::resolve_lock_file_path;
 use sui_protocol_config::ProtocolConfig;
 use sui_source_validation::{BytecodeSourceVerifier, SourceMode};
+use sui_transaction_builder::sponsored_transaction_from_signatures;
 
 use shared_crypto::intent::Intent;
 use sui_execution::verifier::VerifierOverrides;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DelegatedStake, DryRunTransactionBlockResponse, DynamicFieldPage, StakeStatus, SuiData,
+    SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedData,
+    SuiProtocolConfigValue, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions, SuiTypeTag, ValidatorApys,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
 use sui_keys::keystore::AccountKeystore;
@@ -56,7 +59,10 @@ use sui_types::{
     object::Owner,
     parse_sui_type_tag,
     signature::GenericSignature,
-    transaction::{SenderSignedData, Transaction, TransactionData, TransactionDataAPI},
+    transaction::{
+        ProgrammableTransaction, SenderSignedData, Transaction, TransactionData,
+        TransactionDataAPI, TransactionKind,
+    },
 };
 
 use tabled::{
@@ -68,31 +74,94 @@ use tabled::{
 };
 use tracing::info;
 
+use crate::client_ptb::{parse_ptb_commands, PtbBuilder};
 use crate::key_identity::{get_identity_address, KeyIdentity};
 
+/// Only used to estimate the gas budget, added on top of the gas consumed by the dry run,
+/// in multiples of the gas price.
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+/// Build a transaction with `$build`, binding `$budget` to the gas budget. If no budget is
+/// given, the transaction is built once with a placeholder budget (gas selection needs one),
+/// dry run to estimate the budget, and built again with the estimate.
+macro_rules! build_with_gas_budget {
+    ($context:expr, $gas_budget:expr, |$budget:ident| $build:expr) => {{
+        match $gas_budget {
+            Some($budget) => $build,
+            None => {
+                let $budget = $context.get_reference_gas_price().await?;
+                let probe = $build;
+                let $budget = estimate_gas_budget($context, &probe).await?;
+                $build
+            }
+        }
+    }};
+}
+
 macro_rules! serialize_or_execute {
-    ($tx_data:expr, $serialize_unsigned:expr, $serialize_signed:expr, $context:expr, $result_variant:ident) => {{
+    ($tx_data:expr, $dry_run:expr, $serialize_unsigned:expr, $serialize_signed:expr, $context:expr, $result_variant:ident) => {{
         assert!(
             !$serialize_unsigned || !$serialize_signed,
             "Cannot specify both --serialize-unsigned-transaction and --serialize-signed-transaction"
         );
-        if $serialize_unsigned {
+        ensure!(
+            !$dry_run || (!$serialize_unsigned && !$serialize_signed),
+            "Cannot specify --dry-run together with --serialize-unsigned-transaction or --serialize-signed-transaction"
+        );
+        if $dry_run {
+            let response = $context
+                .get_client()
+                .await?
+                .read_api()
+                .dry_run_transaction_block($tx_data)
+                .await?;
+            SuiClientCommandResult::DryRun(response)
+        } else if $serialize_unsigned {
             SuiClientCommandResult::SerializedUnsignedTransaction($tx_data)
         } else {
-            let signature = $context.config.keystore.sign_secure(
-                &$tx_data.sender(),
-                &$tx_data,
-                Intent::sui_transaction(),
-            )?;
-            let sender_signed_data = SenderSignedData::new_from_sender_signature(
-                $tx_data,
-                Intent::sui_transaction(),
-                signature,
-            );
+            let sender_signature: GenericSignature = $context
+                .config
+                .keystore
+                .sign_secure(&$tx_data.sender(), &$tx_data, Intent::sui_transaction())?
+                .into();
+            let sponsor = $tx_data.gas_owner();
+            let sponsor_signature: Option<GenericSignature> = if sponsor != $tx_data.sender() {
+                match $context
+                    .config
+                    .keystore
+                    .sign_secure(&sponsor, &$tx_data, Intent::sui_transaction())
+                {
+                    Ok(signature) => Some(signature.into()),
+                    // The sponsor adds its own signature later, e.g. through `execute-signed-tx`.
+                    Err(_) if $serialize_signed => None,
+                    Err(e) => {
+                        return Err(anyhow!(
+                            "Cannot sign as the sponsor {sponsor}: {e}. Use \
+                             --serialize-signed-transaction to only sign as the sender"
+                        ))
+                    }
+                }
+            } else {
+                None
+            };
             if $serialize_signed {
-                SuiClientCommandResult::SerializedSignedTransaction(sender_signed_data)
+                let signatures = std::iter::once(sender_signature)
+                    .chain(sponsor_signature)
+                    .collect();
+                SuiClientCommandResult::SerializedSignedTransaction(SenderSignedData::new(
+                    $tx_data,
+                    Intent::sui_transaction(),
+                    signatures,
+                ))
             } else {
-                let transaction = Transaction::new(sender_signed_data);
+                let transaction = match sponsor_signature {
+                    Some(sponsor_signature) => sponsored_transaction_from_signatures(
+                        $tx_data,
+                        sender_signature,
+                        sponsor_signature,
+                    )?,
+                    None => Transaction::from_generic_sig_data($tx_data, vec![sender_signature]),
+                };
                 let response = $context.execute_transaction_may_fail(transaction).await?;
                 let effects = response.effects.as_ref().ok_or_else(|| {
                     anyhow!("Effects from SuiTransactionBlockResult should not be empty")
@@ -151,9 +220,10 @@ pub enum SuiClientCommands {
         /// If not provided, a gas object with at least gas_budget value will be selected
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -164,6 +234,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Query the chain identifier from the rpc endpoint.
@@ -193,7 +268,8 @@ pub enum SuiClientCommands {
         #[clap(long)]
         tx_bytes: String,
 
-        /// A list of Base64 encoded signatures `flag || signature || pubkey`.
+        /// A list of Base64 encoded signatures `flag || signature || pubkey`. Sponsored
+        /// transactions need the signatures of both the sender and the sponsor.
         #[clap(long)]
         signatures: Vec<String>,
     },
@@ -220,9 +296,10 @@ pub enum SuiClientCommands {
         /// If not provided, a gas object with at least gas_budget value will be selected.
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -233,6 +310,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Generate new address and keypair with keypair scheme flag {ed25519 | secp256k1 | secp256r1}
@@ -300,9 +382,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for this transaction
+        /// Gas budget for this transaction.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -313,6 +396,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Pay all residual SUI coins to the recipient with input coins, after deducting the gas cost.
@@ -326,9 +414,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         recipient: KeyIdentity,
 
-        /// Gas budget for this transaction
+        /// Gas budget for this transaction.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -339,6 +428,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Pay SUI coins to recipients following following specified amounts, with input coins.
@@ -358,9 +452,64 @@ pub enum SuiClientCommands {
         #[clap(long, num_args(1..))]
         amounts: Vec<u64>,
 
-        /// Gas budget for this transaction
+        /// Gas budget for this transaction.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
+        /// (TransactionData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_unsigned_transaction: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the signed transaction data
+        /// (SenderSignedData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
+    },
+
+    /// Compose a programmable transaction block (PTB) out of several commands (Move calls,
+    /// coin splits and merges, transfers, vectors, publish and upgrade), whose arguments can
+    /// refer to the gas coin (`gas`) and to the results of earlier commands (`$0`, `$0.1`).
+    #[clap(name = "ptb")]
+    #[clap(group(ArgGroup::new("ptb-commands").required(true).args(&["commands", "file"])))]
+    Ptb {
+        /// The commands of the PTB, as a JSON array, e.g.
+        /// '[{"split_coins": {"coin": "gas", "amounts": [1000]}},
+        ///   {"transfer_objects": {"objects": ["$0.0"], "recipient": "0x..."}}]'
+        #[clap(long)]
+        commands: Option<String>,
+        /// A file holding the commands of the PTB, in the same format as `--commands`.
         #[clap(long)]
-        gas_budget: u64,
+        file: Option<PathBuf>,
+        /// The sender of the transaction. Defaults to the owner of the gas object if provided
+        /// and there is no sponsor, or to the active address.
+        #[clap(long)]
+        sender: Option<KeyIdentity>,
+        /// Address paying for the gas of the transaction on behalf of the sender. The
+        /// transaction is executed if both keys are in the keystore, otherwise use
+        /// --serialize-signed-transaction to sign as the sender and have the sponsor sign
+        /// separately.
+        #[clap(long)]
+        sponsor: Option<KeyIdentity>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string, owned by the sponsor
+        /// if there is one.
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this transaction.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Print the programmable transaction that would be built, without executing it.
+        #[clap(long, required = false)]
+        preview: bool,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -371,6 +520,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Publish Move modules
@@ -389,9 +543,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for running module initializers
+        /// Gas budget for running module initializers.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Publish the package without checking whether compiling dependencies from source results
         /// in bytecode matching the dependencies found on-chain.
@@ -411,6 +566,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Split a coin object into multiple coins.
@@ -429,9 +589,10 @@ pub enum SuiClientCommands {
         /// If not provided, a gas object with at least gas_budget value will be selected
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -442,6 +603,59 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
+    },
+
+    /// Stake SUI with a validator. The coins are merged and the requested amount (or all of
+    /// it) is staked, returning a StakedSui object to the sender.
+    #[clap(name = "stake")]
+    Stake {
+        /// Address of the validator to stake with
+        #[clap(long)]
+        validator: SuiAddress,
+        /// The SUI coins to stake
+        #[clap(long, num_args(1..))]
+        coins: Vec<ObjectID>,
+        /// The amount to stake, if not specified, the total balance of the coins is staked.
+        #[clap(long)]
+        amount: Option<u64>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this transaction.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
+        /// (TransactionData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_unsigned_transaction: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the signed transaction data
+        /// (SenderSignedData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
+    },
+
+    /// List the stakes owned by an address, with their estimated rewards and the APY of the
+    /// validators they are staked with.
+    #[clap(name = "stakes")]
+    Stakes {
+        /// Address owning the stakes. If no address is provided, it will show the stakes of
+        /// `sui client active-address`.
+        #[clap(name = "owner_address")]
+        address: Option<KeyIdentity>,
     },
 
     /// Switch active address and network(e.g., devnet, local rpc server).
@@ -481,9 +695,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for this transfer
+        /// Gas budget for this transfer.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -494,6 +709,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Transfer SUI, and pay gas with the same SUI coin object.
@@ -509,9 +729,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         sui_coin_object_id: ObjectID,
 
-        /// Gas budget for this transfer
+        /// Gas budget for this transfer.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
@@ -526,6 +747,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Upgrade Move modules
@@ -548,9 +774,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for running module initializers
+        /// Gas budget for running module initializers.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Publish the package without checking whether compiling dependencies from source results
         /// in bytecode matching the dependencies found on-chain.
@@ -570,6 +797,11 @@ pub enum SuiClientCommands {
         /// (SenderSignedData) using base64 encoding, and print out the string.
         #[clap(long, required = false)]
         serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
     },
 
     /// Run the bytecode verifier on the package
@@ -609,6 +841,37 @@ pub enum SuiClientCommands {
         address_override: Option<ObjectID>,
     },
 
+    /// Withdraw a stake, returning the principal and the rewards earned to the sender.
+    #[clap(name = "withdraw-stake")]
+    WithdrawStake {
+        /// ID of the StakedSui object to withdraw
+        #[clap(long)]
+        staked_sui: ObjectID,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this transaction.
+        /// If not provided, the budget is estimated from a dry run of the transaction.
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
+        /// (TransactionData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_unsigned_transaction: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the signed transaction data
+        /// (SenderSignedData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_signed_transaction: bool,
+
+        /// Execute the transaction against the current state of the network without
+        /// committing it, and print its effects, object changes and balance changes.
+        #[clap(long, required = false)]
+        dry_run: bool,
+    },
+
     /// Replay a given transaction to view transaction effects. Set environment variable MOVE_VM_STEP=1 to debug.
     #[clap(name = "replay-transaction")]
     ReplayTransaction {
@@ -730,6 +993,7 @@ impl SuiClientCommands {
                 with_unpublished_dependencies,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
@@ -785,23 +1049,27 @@ impl SuiClientCommands {
                 let upgrade_policy = upgrade_cap.policy;
                 let package_digest =
                     compiled_package.get_package_digest(with_unpublished_dependencies);
-
-                let data = client
-                    .transaction_builder()
-                    .upgrade(
-                        sender,
-                        package_id,
-                        compiled_modules,
-                        dependencies.published.into_values().collect(),
-                        upgrade_capability,
-                        upgrade_policy,
-                        package_digest.to_vec(),
-                        gas,
-                        gas_budget,
-                    )
-                    .await?;
+                let dep_ids: Vec<ObjectID> = dependencies.published.into_values().collect();
+
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .upgrade(
+                            sender,
+                            package_id,
+                            compiled_modules.clone(),
+                            dep_ids.clone(),
+                            upgrade_capability,
+                            upgrade_policy,
+                            package_digest.to_vec(),
+                            gas,
+                            gas_budget,
+                        )
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -817,6 +1085,7 @@ impl SuiClientCommands {
                 with_unpublished_dependencies,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 if build_config.test_mode {
                     return Err(SuiError::ModulePublishFailure {
@@ -846,18 +1115,23 @@ impl SuiClientCommands {
                 )
                 .await?;
 
-                let data = client
-                    .transaction_builder()
-                    .publish(
-                        sender,
-                        compiled_modules,
-                        dependencies.published.into_values().collect(),
-                        gas,
-                        gas_budget,
-                    )
-                    .await?;
+                let dep_ids: Vec<ObjectID> = dependencies.published.into_values().collect();
+
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .publish(
+                            sender,
+                            compiled_modules.clone(),
+                            dep_ids.clone(),
+                            gas,
+                            gas_budget,
+                        )
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -944,6 +1218,7 @@ impl SuiClientCommands {
                 args,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 let tx_data = construct_move_call_transaction(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
@@ -951,6 +1226,7 @@ impl SuiClientCommands {
                 .await?;
                 serialize_or_execute!(
                     tx_data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -965,16 +1241,20 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
                 let to = get_identity_address(Some(to), context)?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .transfer_object(from, object_id, gas, gas_budget, to)
-                    .await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .transfer_object(from, object_id, gas, gas_budget, to)
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -989,16 +1269,20 @@ impl SuiClientCommands {
                 amount,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
                 let to = get_identity_address(Some(to), context)?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .transfer_sui(from, object_id, gas_budget, to, amount)
-                    .await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .transfer_sui(from, object_id, gas_budget, to, amount)
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -1014,6 +1298,7 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -1038,12 +1323,22 @@ impl SuiClientCommands {
                     .map_err(|e| anyhow!("{e}"))?;
                 let from = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .pay(from, input_coins, recipients, amounts, gas, gas_budget)
-                    .await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .pay(
+                            from,
+                            input_coins.clone(),
+                            recipients.clone(),
+                            amounts.clone(),
+                            gas,
+                            gas_budget,
+                        )
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -1058,6 +1353,7 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -1082,12 +1378,21 @@ impl SuiClientCommands {
                     .map_err(|e| anyhow!("{e}"))?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
-                    .await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .pay_sui(
+                            signer,
+                            input_coins.clone(),
+                            recipients.clone(),
+                            amounts.clone(),
+                            gas_budget,
+                        )
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -1101,6 +1406,7 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -1109,13 +1415,16 @@ impl SuiClientCommands {
                 let recipient = get_identity_address(Some(recipient), context)?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
-                let data = client
-                    .transaction_builder()
-                    .pay_all_sui(signer, input_coins, recipient, gas_budget)
-                    .await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .pay_all_sui(signer, input_coins.clone(), recipient, gas_budget)
+                        .await?
+                });
 
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -1123,6 +1432,70 @@ impl SuiClientCommands {
                 )
             }
 
+            SuiClientCommands::Ptb {
+                commands,
+                file,
+                sender,
+                sponsor,
+                gas,
+                gas_budget,
+                preview,
+                dry_run,
+                serialize_unsigned_transaction,
+                serialize_signed_transaction,
+            } => {
+                let ptb_commands = parse_ptb_commands(commands, file)?;
+                let sponsor = sponsor
+                    .map(|sponsor| get_identity_address(Some(sponsor), context))
+                    .transpose()?;
+                let sender = match sender {
+                    Some(sender) => get_identity_address(Some(sender), context)?,
+                    None if sponsor.is_some() => context.active_address()?,
+                    None => context
+                        .try_get_object_owner(&gas)
+                        .await?
+                        .unwrap_or(context.active_address()?),
+                };
+                let pt = PtbBuilder::new(context, sender)
+                    .await?
+                    .build(ptb_commands)
+                    .await?;
+                if preview {
+                    return Ok(SuiClientCommandResult::PtbPreview(pt));
+                }
+
+                let client = context.get_client().await?;
+                let tx_data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    let builder = client.transaction_builder();
+                    match sponsor {
+                        Some(sponsor) => {
+                            builder
+                                .sponsored_transaction(
+                                    sender,
+                                    TransactionKind::programmable(pt.clone()),
+                                    sponsor,
+                                    gas.into_iter().collect(),
+                                    gas_budget,
+                                )
+                                .await?
+                        }
+                        None => {
+                            builder
+                                .programmable_transaction(sender, pt.clone(), gas, gas_budget)
+                                .await?
+                        }
+                    }
+                });
+                serialize_or_execute!(
+                    tx_data,
+                    dry_run,
+                    serialize_unsigned_transaction,
+                    serialize_signed_transaction,
+                    context,
+                    Ptb
+                )
+            }
+
             SuiClientCommands::Objects { address } => {
                 let address = get_identity_address(address, context)?;
                 let client = context.get_client().await?;
@@ -1204,24 +1577,29 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 let signer = context.get_object_owner(&coin_id).await?;
                 let client = context.get_client().await?;
                 let data = match (amounts, count) {
                     (Some(amounts), None) => {
-                        client
-                            .transaction_builder()
-                            .split_coin(signer, coin_id, amounts, gas, gas_budget)
-                            .await?
+                        build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                            client
+                                .transaction_builder()
+                                .split_coin(signer, coin_id, amounts.clone(), gas, gas_budget)
+                                .await?
+                        })
                     }
                     (None, Some(count)) => {
                         if count == 0 {
                             return Err(anyhow!("Coin split count must be greater than 0"));
                         }
-                        client
-                            .transaction_builder()
-                            .split_coin_equal(signer, coin_id, count, gas, gas_budget)
-                            .await?
+                        build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                            client
+                                .transaction_builder()
+                                .split_coin_equal(signer, coin_id, count, gas, gas_budget)
+                                .await?
+                        })
                     }
                     _ => {
                         return Err(anyhow!("Exactly one of `count` and `amounts` must be present for split-coin command."));
@@ -1229,6 +1607,7 @@ impl SuiClientCommands {
                 };
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
@@ -1242,21 +1621,95 @@ impl SuiClientCommands {
                 gas_budget,
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
+                dry_run,
             } => {
                 let client = context.get_client().await?;
                 let signer = context.get_object_owner(&primary_coin).await?;
-                let data = client
-                    .transaction_builder()
-                    .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
-                    .await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
+                        .await?
+                });
                 serialize_or_execute!(
                     data,
+                    dry_run,
                     serialize_unsigned_transaction,
                     serialize_signed_transaction,
                     context,
                     MergeCoin
                 )
             }
+            SuiClientCommands::Stake {
+                validator,
+                coins,
+                amount,
+                gas,
+                gas_budget,
+                serialize_unsigned_transaction,
+                serialize_signed_transaction,
+                dry_run,
+            } => {
+                ensure!(
+                    !coins.is_empty(),
+                    "Stake transaction requires a non-empty list of coins"
+                );
+                let signer = context.get_object_owner(&coins[0]).await?;
+                let client = context.get_client().await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .request_add_stake(
+                            signer,
+                            coins.clone(),
+                            amount,
+                            validator,
+                            gas,
+                            gas_budget,
+                        )
+                        .await?
+                });
+                serialize_or_execute!(
+                    data,
+                    dry_run,
+                    serialize_unsigned_transaction,
+                    serialize_signed_transaction,
+                    context,
+                    Stake
+                )
+            }
+            SuiClientCommands::Stakes { address } => {
+                let address = get_identity_address(address, context)?;
+                let client = context.get_client().await?;
+                let stakes = client.governance_api().get_stakes(address).await?;
+                let apys = client.governance_api().get_validators_apy().await?;
+                SuiClientCommandResult::Stakes(StakesOutput { stakes, apys })
+            }
+            SuiClientCommands::WithdrawStake {
+                staked_sui,
+                gas,
+                gas_budget,
+                serialize_unsigned_transaction,
+                serialize_signed_transaction,
+                dry_run,
+            } => {
+                let signer = context.get_object_owner(&staked_sui).await?;
+                let client = context.get_client().await?;
+                let data = build_with_gas_budget!(context, gas_budget, |gas_budget| {
+                    client
+                        .transaction_builder()
+                        .request_withdraw_stake(signer, staked_sui, gas, gas_budget)
+                        .await?
+                });
+                serialize_or_execute!(
+                    data,
+                    dry_run,
+                    serialize_unsigned_transaction,
+                    serialize_signed_transaction,
+                    context,
+                    WithdrawStake
+                )
+            }
             SuiClientCommands::Switch { address, env } => {
                 let mut addr = None;
 
@@ -1403,7 +1856,7 @@ fn compile_package_simple(
     )?)
 }
 
-async fn compile_package(
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1660,6 +2113,15 @@ impl Display for SuiClientCommandResult {
                     fastcrypto::encoding::Base64::encode(bcs::to_bytes(sender_signed_tx).unwrap())
                 )?;
             }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", response)?;
+            }
+            SuiClientCommandResult::PtbPreview(pt) => {
+                write!(writer, "{}", pt)?;
+            }
+            SuiClientCommandResult::DryRun(response) => {
+                write!(writer, "{}", response)?;
+            }
             SuiClientCommandResult::Transfer(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -1687,6 +2149,55 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::MergeCoin(response) => {
                 write!(writer, "{}", response)?;
             }
+            SuiClientCommandResult::Stake(response)
+            | SuiClientCommandResult::WithdrawStake(response) => {
+                write!(writer, "{}", response)?;
+            }
+            SuiClientCommandResult::Stakes(StakesOutput { stakes, apys }) => {
+                if stakes.iter().all(|s| s.stakes.is_empty()) {
+                    write!(f, "No stakes are owned by this address")?;
+                    return Ok(());
+                }
+                let epoch = apys.epoch;
+                let apys: BTreeMap<_, _> = apys.apys.iter().map(|a| (a.address, a.apy)).collect();
+
+                let mut builder = TableBuilder::default();
+                builder.set_header(vec![
+                    "stakedSuiId",
+                    "validator",
+                    "validatorApy",
+                    "principal",
+                    "status",
+                    "estimatedReward",
+                ]);
+                for delegated in stakes {
+                    let apy = apys
+                        .get(&delegated.validator_address)
+                        .map(|apy| format!("{:.2}%", apy * 100.0))
+                        .unwrap_or_else(|| "-".to_string());
+                    for stake in &delegated.stakes {
+                        let (status, reward) = match &stake.status {
+                            StakeStatus::Pending => ("Pending", "-".to_string()),
+                            StakeStatus::Active { estimated_reward } => {
+                                ("Active", estimated_reward.to_string())
+                            }
+                            StakeStatus::Unstaked => ("Unstaked", "-".to_string()),
+                        };
+                        builder.push_record(vec![
+                            stake.staked_sui_id.to_string(),
+                            delegated.validator_address.to_string(),
+                            apy.clone(),
+                            stake.principal.to_string(),
+                            status.to_string(),
+                            reward,
+                        ]);
+                    }
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                table.with(TablePanel::header(format!("Stakes as of epoch {epoch}")));
+                write!(f, "{}", table)?
+            }
             SuiClientCommandResult::Switch(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -1768,33 +2279,79 @@ async fn construct_move_call_transaction(
     function: &str,
     type_args: Vec<TypeTag>,
     gas: Option<ObjectID>,
-    gas_budget: u64,
+    gas_budget: Option<u64>,
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<TransactionData, anyhow::Error> {
     // Convert all numeric input to String, this will allow number input from the CLI without failing SuiJSON's checks.
-    let args = args
+    let args: Vec<SuiJsonValue> = args
         .into_iter()
         .map(|value| SuiJsonValue::new(convert_number_to_string(value.to_json_value())))
         .collect::<Result<_, _>>()?;
 
-    let type_args = type_args
+    let type_args: Vec<SuiTypeTag> = type_args
         .into_iter()
         .map(|arg| arg.try_into())
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect::<Result<_, _>>()?;
     let gas_owner = context.try_get_object_owner(&gas).await?;
     let sender = gas_owner.unwrap_or(context.active_address()?);
 
     let client = context.get_client().await?;
-    client
-        .transaction_builder()
-        .move_call(
-            sender, package, module, function, type_args, args, gas, gas_budget,
-        )
-        .await
+    Ok(build_with_gas_budget!(context, gas_budget, |gas_budget| {
+        client
+            .transaction_builder()
+            .move_call(
+                sender,
+                package,
+                module,
+                function,
+                type_args.clone(),
+                args.clone(),
+                gas,
+                gas_budget,
+            )
+            .await?
+    }))
+}
+
+/// Estimate the gas budget of `tx_data` by dry running it with the maximum budget allowed by
+/// the protocol, and adding a safety margin to the gas it used.
+async fn estimate_gas_budget(
+    context: &WalletContext,
+    tx_data: &TransactionData,
+) -> Result<u64, anyhow::Error> {
+    let client = context.get_client().await?;
+    let protocol_config = client.read_api().get_protocol_config(None).await?;
+    let Some(Some(SuiProtocolConfigValue::U64(max_tx_gas))) =
+        protocol_config.attributes.get("max_tx_gas")
+    else {
+        return Err(anyhow!("Cannot read max_tx_gas from the protocol config"));
+    };
+
+    // With an empty gas payment the fullnode dry runs against a mock gas coin owned by the
+    // sender, so the estimate does not depend on the balance of the coins selected for gas.
+    let mut probe = tx_data.clone();
+    let gas_price = probe.gas_price();
+    let sender = probe.sender();
+    let gas_data = probe.gas_data_mut();
+    gas_data.payment = vec![];
+    gas_data.owner = sender;
+    gas_data.budget = *max_tx_gas;
+
+    let response = client.read_api().dry_run_transaction_block(probe).await?;
+    if let SuiExecutionStatus::Failure { error } = response.effects.status() {
+        return Err(anyhow!(
+            "Cannot estimate the gas budget, the dry run failed: {error}"
+        ));
+    }
+    let gas_cost = response.effects.gas_cost_summary();
+    let overhead = GAS_SAFE_OVERHEAD * gas_price;
+    let computation_cost = gas_cost.computation_cost + overhead;
+    let net_gas_usage = gas_cost.net_gas_usage() + overhead as i64;
+    Ok(computation_cost.max(net_gas_usage.max(0) as u64))
 }
 
-fn convert_number_to_string(value: Value) -> Value {
+pub(crate) fn convert_number_to_string(value: Value) -> Value {
     match value {
         Value::Number(n) => Value::String(n.to_string()),
         Value::Array(a) => Value::Array(a.into_iter().map(convert_number_to_string).collect()),
@@ -1867,7 +2424,7 @@ impl SuiClientCommandResult {
         match self {
             Upgrade(b) | Publish(b) | TransactionBlock(b) | Call(b) | Transfer(b)
             | TransferSui(b) | Pay(b) | PaySui(b) | PayAllSui(b) | SplitCoin(b) | MergeCoin(b)
-            | ExecuteSignedTx(b) => Some(b),
+            | ExecuteSignedTx(b) | Ptb(b) | Stake(b) | WithdrawStake(b) => Some(b),
             _ => None,
         }
     }
@@ -1880,6 +2437,13 @@ pub struct AddressesOutput {
     pub addresses: Vec<(String, SuiAddress)>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakesOutput {
+    pub stakes: Vec<DelegatedStake>,
+    pub apys: ValidatorApys,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DynamicFieldOutput {
@@ -2006,6 +2570,7 @@ pub enum SuiClientCommandResult {
     Addresses(AddressesOutput),
     Call(SuiTransactionBlockResponse),
     ChainIdentifier(String),
+    DryRun(DryRunTransactionBlockResponse),
     DynamicFieldQuery(DynamicFieldPage),
     Envs(Vec<SuiEnv>, Option<String>),
     ExecuteSignedTx(SuiTransactionBlockResponse),
@@ -2018,11 +2583,15 @@ pub enum SuiClientCommandResult {
     Pay(SuiTransactionBlockResponse),
     PayAllSui(SuiTransactionBlockResponse),
     PaySui(SuiTransactionBlockResponse),
+    Ptb(SuiTransactionBlockResponse),
+    PtbPreview(ProgrammableTransaction),
     Publish(SuiTransactionBlockResponse),
     RawObject(SuiObjectResponse),
     SerializedSignedTransaction(SenderSignedData),
     SerializedUnsignedTransaction(TransactionData),
     SplitCoin(SuiTransactionBlockResponse),
+    Stake(SuiTransactionBlockResponse),
+    Stakes(StakesOutput),
     Switch(SwitchResponse),
     SyncClientState,
     TransactionBlock(SuiTransactionBlockResponse),
@@ -2036,6 +2605,7 @@ pub enum SuiClientCommandResult {
         used_module_ticks: u128,
     },
     VerifySource,
+    WithdrawStake(SuiTransactionBlockResponse),
     ReplayTransaction,
     ReplayBatch,
     ReplayCheckpoints,
@@ -0,0 +1,425 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Composition of programmable transaction blocks for `sui client ptb`.
+//!
+//! A PTB is described as a JSON array of commands, for example:
+//!
+//! ```json
+//! [
+//!   { "split_coins": { "coin": "gas", "amounts": [1000, 2000] } },
+//!   { "transfer_objects": { "objects": ["$0.0", "$0.1"], "recipient": "0x42" } }
+//! ]
+//! ```
+//!
+//! Wherever a command takes an argument, `"gas"` refers to the gas coin, `"$i"` to the result of
+//! the `i`-th command and `"$i.j"` to the `j`-th value returned by the `i`-th command. Any other
+//! value is an object ID, an address, or a pure value resolved with `sui-json`.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::language_storage::TypeTag;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde::Deserialize;
+use serde_json::Value;
+use sui_json::{MoveTypeLayout, SuiJsonValue};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTypeTag};
+use sui_sdk::wallet_context::WalletContext;
+use sui_sdk::SuiClient;
+use sui_transaction_builder::ProgrammableMoveCallArg;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    move_package::UpgradeCap,
+    parse_sui_type_tag,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Argument, Command, ObjectArg, ProgrammableTransaction},
+    Identifier, SUI_FRAMEWORK_PACKAGE_ID,
+};
+
+use crate::client_commands::{compile_package, convert_number_to_string};
+use crate::key_identity::{get_identity_address, KeyIdentity};
+
+/// A command of a programmable transaction block, as written by the user.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PtbCommand {
+    /// Call a Move function. Arguments are resolved against the function signature.
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        #[serde(default)]
+        type_args: Vec<String>,
+        #[serde(default)]
+        args: Vec<Value>,
+    },
+    /// Split `amounts` off `coin`, returning one coin per amount.
+    SplitCoins { coin: Value, amounts: Vec<Value> },
+    /// Merge `coins` into `coin`.
+    MergeCoins { coin: Value, coins: Vec<Value> },
+    /// Transfer `objects` to `recipient` (an address or an alias), or to the sender if omitted.
+    TransferObjects {
+        objects: Vec<Value>,
+        #[serde(default)]
+        recipient: Option<String>,
+    },
+    /// Build a `vector<type>` out of `elements`. The type can only be omitted for non-empty
+    /// vectors of objects.
+    MakeMoveVec {
+        #[serde(default, rename = "type")]
+        type_: Option<String>,
+        elements: Vec<Value>,
+    },
+    /// Publish the package at `package_path`, returning its `UpgradeCap`.
+    Publish {
+        package_path: PathBuf,
+        #[serde(default)]
+        with_unpublished_dependencies: bool,
+        #[serde(default)]
+        skip_dependency_verification: bool,
+    },
+    /// Upgrade the package at `package_path` with `upgrade_capability`, keeping its policy.
+    Upgrade {
+        package_path: PathBuf,
+        upgrade_capability: ObjectID,
+        #[serde(default)]
+        with_unpublished_dependencies: bool,
+        #[serde(default)]
+        skip_dependency_verification: bool,
+    },
+}
+
+/// Read the PTB description either inline or from a file.
+pub fn parse_ptb_commands(
+    commands: Option<String>,
+    file: Option<PathBuf>,
+) -> Result<Vec<PtbCommand>, anyhow::Error> {
+    let json = match (commands, file) {
+        (Some(commands), None) => commands,
+        (None, Some(file)) => std::fs::read_to_string(&file)
+            .map_err(|e| anyhow!("Cannot read PTB file {}: {e}", file.display()))?,
+        _ => bail!("Exactly one of `--commands` and `--file` must be provided"),
+    };
+    let commands: Vec<PtbCommand> =
+        serde_json::from_str(&json).map_err(|e| anyhow!("Invalid PTB description: {e}"))?;
+    ensure!(
+        !commands.is_empty(),
+        "A programmable transaction block needs at least one command"
+    );
+    Ok(commands)
+}
+
+/// Parse a reference to the gas coin (`gas`) or to the result of an earlier command (`$i` or
+/// `$i.j`). Returns `None` if `value` is not a reference.
+pub fn parse_ptb_reference(
+    value: &Value,
+    results: &[Argument],
+) -> Result<Option<Argument>, anyhow::Error> {
+    let Some(s) = value.as_str() else {
+        return Ok(None);
+    };
+    if s == "gas" {
+        return Ok(Some(Argument::GasCoin));
+    }
+    let Some(reference) = s.strip_prefix('$') else {
+        return Ok(None);
+    };
+    let (command, nested) = match reference.split_once('.') {
+        Some((command, nested)) => (command, Some(nested)),
+        None => (reference, None),
+    };
+    let command: usize = command
+        .parse()
+        .map_err(|_| anyhow!("Invalid result reference {s}"))?;
+    let Some(Argument::Result(result)) = results.get(command) else {
+        bail!("Result reference {s} does not refer to an earlier command");
+    };
+    Ok(Some(match nested {
+        None => Argument::Result(*result),
+        Some(nested) => Argument::NestedResult(
+            *result,
+            nested
+                .parse()
+                .map_err(|_| anyhow!("Invalid result reference {s}"))?,
+        ),
+    }))
+}
+
+/// Builds a [ProgrammableTransaction] out of [PtbCommand]s, resolving objects and Move call
+/// arguments through the RPC client.
+pub struct PtbBuilder<'a> {
+    context: &'a mut WalletContext,
+    client: SuiClient,
+    sender: SuiAddress,
+    builder: ProgrammableTransactionBuilder,
+    /// The result of each user command, used to resolve `$i` references. A user command can
+    /// expand to several PTB commands (e.g. `upgrade`), so this is not the identity.
+    results: Vec<Argument>,
+}
+
+impl<'a> PtbBuilder<'a> {
+    pub async fn new(
+        context: &'a mut WalletContext,
+        sender: SuiAddress,
+    ) -> Result<PtbBuilder<'a>, anyhow::Error> {
+        let client = context.get_client().await?;
+        Ok(Self {
+            context,
+            client,
+            sender,
+            builder: ProgrammableTransactionBuilder::new(),
+            results: vec![],
+        })
+    }
+
+    pub async fn build(
+        mut self,
+        commands: Vec<PtbCommand>,
+    ) -> Result<ProgrammableTransaction, anyhow::Error> {
+        for (idx, command) in commands.into_iter().enumerate() {
+            let result = self
+                .add_command(command)
+                .await
+                .map_err(|e| anyhow!("Error in PTB command {idx}: {e}"))?;
+            self.results.push(result);
+        }
+        Ok(self.builder.finish())
+    }
+
+    async fn add_command(&mut self, command: PtbCommand) -> Result<Argument, anyhow::Error> {
+        Ok(match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            } => {
+                let type_args = type_args
+                    .iter()
+                    .map(|t| Ok(SuiTypeTag::from(parse_sui_type_tag(t)?)))
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?;
+                let args = args
+                    .into_iter()
+                    .map(|arg| {
+                        Ok(match parse_ptb_reference(&arg, &self.results)? {
+                            Some(arg) => ProgrammableMoveCallArg::Argument(arg),
+                            // Convert all numeric input to String, this will allow number input
+                            // without failing SuiJSON's checks.
+                            None => ProgrammableMoveCallArg::Json(SuiJsonValue::new(
+                                convert_number_to_string(arg),
+                            )?),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?;
+                self.client
+                    .transaction_builder()
+                    .programmable_move_call(
+                        &mut self.builder,
+                        package,
+                        &module,
+                        &function,
+                        type_args,
+                        args,
+                    )
+                    .await?
+            }
+            PtbCommand::SplitCoins { coin, amounts } => {
+                let coin = self.object(&coin).await?;
+                let amounts = amounts
+                    .iter()
+                    .map(|amount| self.amount(amount))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.builder.command(Command::SplitCoins(coin, amounts))
+            }
+            PtbCommand::MergeCoins { coin, coins } => {
+                let coin = self.object(&coin).await?;
+                let coins = self.objects(&coins).await?;
+                self.builder.command(Command::MergeCoins(coin, coins))
+            }
+            PtbCommand::TransferObjects { objects, recipient } => {
+                let objects = self.objects(&objects).await?;
+                let recipient = match recipient {
+                    Some(recipient) => get_identity_address(
+                        Some(KeyIdentity::from_str(&recipient)?),
+                        self.context,
+                    )?,
+                    None => self.sender,
+                };
+                let recipient = self.builder.pure(recipient)?;
+                self.builder
+                    .command(Command::TransferObjects(objects, recipient))
+            }
+            PtbCommand::MakeMoveVec { type_, elements } => {
+                let type_ = type_.as_deref().map(parse_sui_type_tag).transpose()?;
+                ensure!(
+                    type_.is_some() || !elements.is_empty(),
+                    "The type of an empty vector must be specified"
+                );
+                let layout = type_.as_ref().and_then(pure_layout);
+                let mut args = vec![];
+                for element in &elements {
+                    args.push(
+                        match (parse_ptb_reference(element, &self.results)?, &layout) {
+                            (Some(arg), _) => arg,
+                            (None, Some(layout)) => {
+                                let json =
+                                    SuiJsonValue::new(convert_number_to_string(element.clone()))?;
+                                self.builder.pure_bytes(json.to_bcs_bytes(layout)?, false)
+                            }
+                            (None, None) => self.object(element).await?,
+                        },
+                    );
+                }
+                self.builder.command(Command::MakeMoveVec(type_, args))
+            }
+            PtbCommand::Publish {
+                package_path,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let (dependencies, compiled_modules, _, _) = compile_package(
+                    &self.client,
+                    MoveBuildConfig::default(),
+                    package_path,
+                    with_unpublished_dependencies,
+                    skip_dependency_verification,
+                )
+                .await?;
+                self.builder.publish_upgradeable(
+                    compiled_modules,
+                    dependencies.published.into_values().collect(),
+                )
+            }
+            PtbCommand::Upgrade {
+                package_path,
+                upgrade_capability,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let (dependencies, compiled_modules, compiled_package, package_id) =
+                    compile_package(
+                        &self.client,
+                        MoveBuildConfig::default(),
+                        package_path,
+                        with_unpublished_dependencies,
+                        skip_dependency_verification,
+                    )
+                    .await?;
+                let package_id = package_id.map_err(|e| {
+                    anyhow!(
+                        "Invalid 'published-at' field in manifest of package to be upgraded: {e:?}"
+                    )
+                })?;
+                let cap = self.upgrade_cap(upgrade_capability).await?;
+                let cap_arg = self.builder.obj(
+                    self.client
+                        .transaction_builder()
+                        .object_arg(upgrade_capability, /* is_mutable_ref */ true)
+                        .await?,
+                )?;
+                let policy = self.builder.pure(cap.policy)?;
+                let digest = self.builder.pure(
+                    compiled_package
+                        .get_package_digest(with_unpublished_dependencies)
+                        .to_vec(),
+                )?;
+                let ticket = self.builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    Identifier::new("package")?,
+                    Identifier::new("authorize_upgrade")?,
+                    vec![],
+                    vec![cap_arg, policy, digest],
+                );
+                let receipt = self.builder.upgrade(
+                    package_id,
+                    ticket,
+                    dependencies.published.into_values().collect(),
+                    compiled_modules,
+                );
+                self.builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    Identifier::new("package")?,
+                    Identifier::new("commit_upgrade")?,
+                    vec![],
+                    vec![cap_arg, receipt],
+                )
+            }
+        })
+    }
+
+    /// An object argument: a reference, or the ID of an owned, immutable or shared object.
+    async fn object(&mut self, value: &Value) -> Result<Argument, anyhow::Error> {
+        if let Some(arg) = parse_ptb_reference(value, &self.results)? {
+            return Ok(arg);
+        }
+        let id = value
+            .as_str()
+            .ok_or_else(|| anyhow!("Expected an object ID or a result reference, got {value}"))
+            .and_then(|s| Ok(ObjectID::from_hex_literal(s)?))?;
+        // Apart from move calls, whose arguments are resolved against the function's signature,
+        // commands take their object arguments by value.
+        let arg: ObjectArg = self
+            .client
+            .transaction_builder()
+            .object_arg(id, /* is_mutable_ref */ true)
+            .await?;
+        self.builder.obj(arg)
+    }
+
+    async fn objects(&mut self, values: &[Value]) -> Result<Vec<Argument>, anyhow::Error> {
+        let mut args = vec![];
+        for value in values {
+            args.push(self.object(value).await?);
+        }
+        Ok(args)
+    }
+
+    /// A `u64` argument: a reference, or a number (possibly given as a string).
+    fn amount(&mut self, value: &Value) -> Result<Argument, anyhow::Error> {
+        if let Some(arg) = parse_ptb_reference(value, &self.results)? {
+            return Ok(arg);
+        }
+        let amount = match value {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow!("Expected an amount or a result reference, got {value}"))?;
+        self.builder.pure(amount)
+    }
+
+    async fn upgrade_cap(&self, upgrade_capability: ObjectID) -> Result<UpgradeCap, anyhow::Error> {
+        let data = self
+            .client
+            .read_api()
+            .get_object_with_options(upgrade_capability, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .data
+            .ok_or_else(|| anyhow!("Could not find upgrade capability at {upgrade_capability}"))?;
+        Ok(data
+            .bcs
+            .ok_or_else(|| anyhow!("Fetch upgrade capability object but no data was returned"))?
+            .try_as_move()
+            .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
+            .deserialize()?)
+    }
+}
+
+/// The layout of pure (non-object) values of `type_`, if any.
+fn pure_layout(type_: &TypeTag) -> Option<MoveTypeLayout> {
+    Some(match type_ {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Vector(inner) => MoveTypeLayout::Vector(Box::new(pure_layout(inner)?)),
+        TypeTag::Signer | TypeTag::Struct(_) => return None,
+    })
+}
@@ -347,9 +347,10 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
         to: KeyIdentity::Address(SuiAddress::random_for_testing_only()),
         object_id: object_to_send,
         gas: Some(object_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -396,11 +397,12 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -479,9 +481,10 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args,
         gas: None,
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -518,9 +521,10 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -544,9 +548,10 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -572,9 +577,10 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -617,11 +623,12 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -684,11 +691,12 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -718,10 +726,11 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -740,10 +749,11 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         function: "delete".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![SuiJsonValue::from_str(&shared_id.to_string()).unwrap()],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -791,11 +801,12 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -825,10 +836,11 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -863,13 +875,14 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         function: "receiver".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![
             SuiJsonValue::from_str(&parent.object_id.to_string()).unwrap(),
             SuiJsonValue::from_str(&child.object_id.to_string()).unwrap(),
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -917,11 +930,12 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -951,10 +965,11 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -989,13 +1004,14 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         function: "invalid_call_immut_ref".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![
             SuiJsonValue::from_str(&parent.object_id.to_string()).unwrap(),
             SuiJsonValue::from_str(&child.object_id.to_string()).unwrap(),
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1043,11 +1059,12 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1077,10 +1094,11 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1115,13 +1133,14 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         function: "invalid_call_mut_ref".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![
             SuiJsonValue::from_str(&parent.object_id.to_string()).unwrap(),
             SuiJsonValue::from_str(&child.object_id.to_string()).unwrap(),
         ],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1171,11 +1190,12 @@ async fn test_package_publish_command_with_unpublished_dependency_succeeds(
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1239,11 +1259,12 @@ async fn test_package_publish_command_with_unpublished_dependency_fails(
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -1285,11 +1306,12 @@ async fn test_package_publish_command_non_zero_unpublished_dep_fails() -> Result
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -1340,11 +1362,12 @@ async fn test_package_publish_command_failure_invalid() -> Result<(), anyhow::Er
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -1382,11 +1405,12 @@ async fn test_package_publish_nonexistent_dependency() -> Result<(), anyhow::Err
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -1425,11 +1449,12 @@ async fn test_package_publish_test_flag() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await;
@@ -1480,11 +1505,12 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         package_path: package_path.clone(),
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1552,11 +1578,12 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         upgrade_capability: cap.reference.object_id,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1616,9 +1643,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas: Some(gas_obj_id),
         to: KeyIdentity::Address(recipient),
         object_id: obj_id,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -1721,9 +1749,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas: None,
         to: KeyIdentity::Address(recipient),
         object_id: obj_id,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2036,9 +2065,10 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         primary_coin,
         coin_to_merge,
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2091,9 +2121,10 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         primary_coin,
         coin_to_merge,
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2154,12 +2185,13 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2222,12 +2254,13 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test split coin into equal parts
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: None,
         count: Some(3),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2293,12 +2326,13 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with no gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2404,10 +2438,11 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: KeyIdentity::Address(address1),
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: true,
         serialize_signed_transaction: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2415,10 +2450,11 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: KeyIdentity::Address(address1),
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: true,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -2427,16 +2463,270 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: KeyIdentity::Alias(alias1),
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: true,
+        dry_run: false,
     }
     .execute(context)
     .await?;
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb_split_and_transfer() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let commands = json!([
+        { "split_coins": { "coin": "gas", "amounts": [1000, "10"] } },
+        { "transfer_objects": { "objects": ["$0.0"], "recipient": address1.to_string() } },
+        { "transfer_objects": { "objects": ["$0.1"] } },
+    ])
+    .to_string();
+
+    // Preview only builds the PTB.
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(commands.clone()),
+        file: None,
+        sender: None,
+        sponsor: None,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
+        preview: true,
+        dry_run: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::PtbPreview(pt) = resp else {
+        panic!("Expected a PTB preview, got {resp:?}")
+    };
+    assert_eq!(pt.commands.len(), 3);
+
+    // Dry run does not change any state but reports the effects.
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(commands.clone()),
+        file: None,
+        sender: None,
+        sponsor: None,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
+        preview: false,
+        dry_run: true,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(dry_run) = resp else {
+        panic!("Expected a dry run, got {resp:?}")
+    };
+    assert!(dry_run.effects.status().is_ok());
+    assert_eq!(dry_run.effects.created().len(), 2);
+
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(commands),
+        file: None,
+        sender: None,
+        sponsor: None,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
+        preview: false,
+        dry_run: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        panic!("Expected a PTB response, got {resp:?}")
+    };
+    let created = response.effects.as_ref().unwrap().created().to_vec();
+    assert_eq!(created.len(), 2);
+    let values = created
+        .iter()
+        .map(|o| (o.owner, o.reference.object_id))
+        .collect::<Vec<_>>();
+    for (owner, id) in values {
+        let value = get_gas_value(&get_object(id, context).await.unwrap());
+        if owner == Owner::AddressOwner(address1) {
+            assert_eq!(value, 1000);
+        } else {
+            assert_eq!(value, 10);
+        }
+    }
+
+    // References to commands that come later are rejected.
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(json!([{ "transfer_objects": { "objects": ["$1"] } }]).to_string()),
+        file: None,
+        sender: None,
+        sponsor: None,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
+        preview: true,
+        dry_run: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_ptb_sponsored() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let sender = test_cluster.get_address_0();
+    let sponsor = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let client = context.get_client().await?;
+    let sender_coins = client
+        .coin_read_api()
+        .get_coins(sender, None, None, None)
+        .await?
+        .data;
+    let object_id = sender_coins[0].coin_object_id;
+    let sponsor_gas = client
+        .coin_read_api()
+        .get_coins(sponsor, None, None, None)
+        .await?
+        .data[0]
+        .coin_object_id;
+    let commands = json!([
+        { "transfer_objects": { "objects": [object_id.to_string()], "recipient": sponsor.to_string() } },
+    ])
+    .to_string();
+
+    // Both keys are in the keystore, so the transaction is signed by both and executed.
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(commands),
+        file: None,
+        sender: Some(KeyIdentity::Address(sender)),
+        sponsor: Some(KeyIdentity::Address(sponsor)),
+        gas: Some(sponsor_gas),
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        preview: false,
+        dry_run: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        panic!("Expected a PTB response, got {resp:?}")
+    };
+    let effects = response.effects.as_ref().unwrap();
+    assert!(effects.status().is_ok());
+    assert_eq!(effects.gas_object().reference.object_id, sponsor_gas);
+    assert_eq!(effects.gas_object().owner, Owner::AddressOwner(sponsor));
+    let owner = get_object(object_id, context).await.unwrap().owner.unwrap();
+    assert_eq!(owner, Owner::AddressOwner(sponsor));
+
+    // Gas owned by someone else than the sponsor is rejected.
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(json!([{ "split_coins": { "coin": "gas", "amounts": [1] } }]).to_string()),
+        file: None,
+        sender: Some(KeyIdentity::Address(sender)),
+        sponsor: Some(KeyIdentity::Address(sponsor)),
+        gas: Some(sender_coins[1].coin_object_id),
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        preview: false,
+        dry_run: false,
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_dry_run_and_gas_estimation() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let object_refs = client
+        .read_api()
+        .get_owned_objects(
+            address,
+            Some(SuiObjectResponseQuery::new_with_options(
+                SuiObjectDataOptions::new(),
+            )),
+            None,
+            None,
+        )
+        .await?
+        .data;
+    let gas_obj_id = object_refs.first().unwrap().object().unwrap().object_id;
+    let obj_id = object_refs.get(1).unwrap().object().unwrap().object_id;
+
+    // A dry run reports the effects without transferring the object, with the gas budget
+    // estimated since none is given.
+    let resp = SuiClientCommands::Transfer {
+        to: KeyIdentity::Address(recipient),
+        object_id: obj_id,
+        gas: Some(gas_obj_id),
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+        dry_run: true,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(dry_run) = resp else {
+        panic!("Expected a dry run, got {resp:?}")
+    };
+    assert!(dry_run.effects.status().is_ok());
+    let owner = get_object(obj_id, context).await.unwrap().owner.unwrap();
+    assert_eq!(owner, Owner::AddressOwner(address));
+
+    // Dry runs cannot be serialized.
+    let resp = SuiClientCommands::Transfer {
+        to: KeyIdentity::Address(recipient),
+        object_id: obj_id,
+        gas: Some(gas_obj_id),
+        gas_budget: None,
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+        dry_run: true,
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+
+    // Executing with an estimated gas budget succeeds.
+    let resp = SuiClientCommands::Transfer {
+        to: KeyIdentity::Address(recipient),
+        object_id: obj_id,
+        gas: Some(gas_obj_id),
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+        dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Transfer(response) = resp else {
+        panic!("Expected a transfer, got {resp:?}")
+    };
+    assert!(response.status_ok().unwrap());
+    let owner = get_object(obj_id, context).await.unwrap().owner.unwrap();
+    assert_eq!(owner, Owner::AddressOwner(recipient));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -2541,6 +2831,92 @@ async fn test_stake_with_u64_amount() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_stake_stakes_and_withdraw_stake() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let client = context.get_client().await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+    let validator = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?
+        .active_validators[0]
+        .sui_address;
+
+    let resp = SuiClientCommands::Stake {
+        validator,
+        coins: vec![coins[0].coin_object_id],
+        amount: Some(1_000_000_000),
+        gas: Some(coins[1].coin_object_id),
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+        dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Stake(response) = resp else {
+        panic!("Expected a stake response, got {resp:?}")
+    };
+    assert!(response.status_ok().unwrap());
+
+    let resp = SuiClientCommands::Stakes { address: None }
+        .execute(context)
+        .await?;
+    resp.print(true);
+    let SuiClientCommandResult::Stakes(output) = resp else {
+        panic!("Expected stakes, got {resp:?}")
+    };
+    assert_eq!(output.stakes.len(), 1);
+    assert_eq!(output.stakes[0].validator_address, validator);
+    assert_eq!(output.stakes[0].stakes.len(), 1);
+    let stake = &output.stakes[0].stakes[0];
+    assert_eq!(stake.principal, 1_000_000_000);
+    assert!(output.apys.apys.iter().any(|a| a.address == validator));
+
+    // The withdrawal can be serialized for offline (e.g. multisig) signing.
+    let resp = SuiClientCommands::WithdrawStake {
+        staked_sui: stake.staked_sui_id,
+        gas: Some(coins[1].coin_object_id),
+        gas_budget: Some(1_000_000_000),
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+        dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    assert!(matches!(
+        resp,
+        SuiClientCommandResult::SerializedUnsignedTransaction(_)
+    ));
+
+    let resp = SuiClientCommands::WithdrawStake {
+        staked_sui: stake.staked_sui_id,
+        gas: Some(coins[1].coin_object_id),
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+        dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::WithdrawStake(response) = resp else {
+        panic!("Expected a withdraw stake response, got {resp:?}")
+    };
+    assert!(response.status_ok().unwrap());
+
+    let stakes = client.governance_api().get_stakes(address).await?;
+    assert!(stakes.iter().all(|s| s.stakes.is_empty()));
+    Ok(())
+}
+
 async fn test_with_sui_binary(args: &[&str]) -> Result<(), anyhow::Error> {
     let mut cmd = assert_cmd::Command::cargo_bin("sui").unwrap();
     let args = args.iter().map(|s| s.to_string()).collect::<Vec<_>>();